@@ -1,5 +1,11 @@
 #![cfg_attr(feature = "unsized_locals", feature(unsized_locals))]
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 /// The Automaton trait and the FiniteStateAutomaton trait. 
 pub mod automaton;
 /// The RefStateMachine finite state machine implementation. 
@@ -13,4 +19,6 @@ pub mod stateless_mapper;
 /// A pushdown automaton implementation based on finite state machines. 
 pub mod pushdown_automaton;
 /// Combinators for automata. 
-pub mod automata_combinators;
\ No newline at end of file
+pub mod automata_combinators;
+/// State machines defined by explicit transition tables. 
+pub mod transition_table;
\ No newline at end of file
@@ -0,0 +1,252 @@
+use automaton::Automaton;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Row of a transition table: in the given state, on an input of the given 
+/// class, move to the next state and return the action. 
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transition<S, C, A> {
+    /// The state the transition is taken from. 
+    pub from: S,
+    /// The class of the inputs the transition is taken on. 
+    pub on: C,
+    /// The state the transition moves to. 
+    pub to: S,
+    /// The action returned by the transition. 
+    pub action: A
+}
+
+/// State machine written as data, as its initial state and a table of 
+/// transitions keyed by state and input class, so that machines can be 
+/// authored in data files and read back by tooling. 
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TransitionTable<S, C, A> {
+    /// The state the machine starts in. 
+    pub initial: S,
+    /// The transitions of the machine, in the order they were written. 
+    pub transitions: Vec<Transition<S, C, A>>
+}
+
+impl<S, C, A> TransitionTable<S, C, A> {
+    /// Create a new table with the given initial state and no transitions. 
+    pub fn new(initial: S) -> TransitionTable<S, C, A> {
+        TransitionTable {
+            initial: initial,
+            transitions: Vec::new()
+        }
+    }
+
+    /// Add a transition to the end of the table. 
+    pub fn transition(self, from: S, on: C, to: S, action: A) ->
+        TransitionTable<S, C, A>
+    {
+        let mut mut_self = self;
+        mut_self.transitions.push(Transition {
+            from: from,
+            on: on,
+            to: to,
+            action: action
+        });
+        mut_self
+    }
+}
+
+/// Error of building a machine from a transition table. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TableError {
+    /// The transition at the given index of the table is from the same 
+    /// state and on the same input class as an earlier one. 
+    Duplicate(usize)
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TableError::Duplicate(i) => write!(f,
+                "transition {} duplicates an earlier transition", i)
+        }
+    }
+}
+
+/// State machine which runs a transition table, classifying each input 
+/// with its classifier and taking the transition for the current state and 
+/// that class. 
+///
+/// Each transition returns its action; inputs of classes with no transition 
+/// from the current state leave the state as it is and return nothing. 
+pub struct TableMachine<'k, I, S, C, A, F> where 
+    F: Fn(&I) -> C + 'k,
+    S: Eq + Hash + Clone,
+    C: Eq + Hash + Clone,
+    A: Clone,
+    I: 'k
+{
+    table: TransitionTable<S, C, A>,
+    index: HashMap<(S, C), usize>,
+    state: S,
+    classifier: F,
+    _junk: PhantomData<&'k I>
+}
+
+impl<'k, I, S, C, A, F> Clone for TableMachine<'k, I, S, C, A, F> where 
+    F: Fn(&I) -> C + 'k + Clone,
+    S: Eq + Hash + Clone,
+    C: Eq + Hash + Clone,
+    A: Clone,
+    I: 'k
+{
+    fn clone(&self) -> Self {
+        TableMachine {
+            table: self.table.clone(),
+            index: self.index.clone(),
+            state: self.state.clone(),
+            classifier: self.classifier.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<'k, I, S, C, A, F> fmt::Debug for TableMachine<'k, I, S, C, A, F> where 
+    F: Fn(&I) -> C + 'k,
+    S: Eq + Hash + Clone + fmt::Debug,
+    C: Eq + Hash + Clone + fmt::Debug,
+    A: Clone + fmt::Debug,
+    I: 'k
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TableMachine")
+            .field("table", &self.table)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl<'k, I, S, C, A, F> TableMachine<'k, I, S, C, A, F> where 
+    F: Fn(&I) -> C + 'k,
+    S: Eq + Hash + Clone,
+    C: Eq + Hash + Clone,
+    A: Clone,
+    I: 'k
+{
+    /// Create a new machine running the given table from its initial 
+    /// state, failing if the table has two transitions for the same state 
+    /// and input class. 
+    pub fn new(table: TransitionTable<S, C, A>, classifier: F) ->
+        Result<Self, TableError>
+    {
+        let mut index = HashMap::with_capacity(table.transitions.len());
+        for (i, t) in table.transitions.iter().enumerate() {
+            let key = (t.from.clone(), t.on.clone());
+            if index.insert(key, i).is_some() {
+                return Result::Err(TableError::Duplicate(i));
+            }
+        }
+        Result::Ok(TableMachine {
+            state: table.initial.clone(),
+            table: table,
+            index: index,
+            classifier: classifier,
+            _junk: PhantomData
+        })
+    }
+
+    /// Get the current state of the machine. 
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Get the table the machine runs. 
+    pub fn table(&self) -> &TransitionTable<S, C, A> {
+        &self.table
+    }
+
+    /// Get the table the machine runs, with its current state as the 
+    /// initial state, so that the machine can be written out and resumed. 
+    pub fn into_table(self) -> TransitionTable<S, C, A> {
+        TransitionTable {
+            initial: self.state,
+            .. self.table
+        }
+    }
+}
+
+impl<'k, I, S, C, A, F> Automaton<'k> for TableMachine<'k, I, S, C, A, F> where 
+    F: Fn(&I) -> C + 'k,
+    S: Eq + Hash + Clone,
+    C: Eq + Hash + Clone,
+    A: Clone,
+    I: 'k
+{
+    type Input = I;
+    type Action = Option<A>;
+
+    fn transition(&mut self, input: &I) -> Option<A> {
+        let key = (self.state.clone(), (self.classifier)(input));
+        match self.index.get(&key) {
+            Option::Some(&i) => {
+                let row = &self.table.transitions[i];
+                self.state = row.to.clone();
+                Option::Some(row.action.clone())
+            },
+            Option::None => Option::None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automaton::Automaton;
+    use transition_table::{TableError, TableMachine, TransitionTable};
+
+    fn turnstile() -> TransitionTable<String, bool, String> {
+        TransitionTable::new("locked".to_owned())
+            .transition("locked".to_owned(), true, "open".to_owned(),
+                "unlock".to_owned())
+            .transition("open".to_owned(), false, "locked".to_owned(),
+                "lock".to_owned())
+    }
+
+    #[test]
+    fn transition_table_test() {
+        let mut machine = TableMachine::new(turnstile(),
+            |coins: &u32| *coins > 0).unwrap();
+        assert_eq!(machine.transition(&0), Option::None);
+        assert_eq!(machine.transition(&2), Option::Some("unlock".to_owned()));
+        assert_eq!(machine.state(), "open");
+        assert_eq!(machine.transition(&1), Option::None);
+        assert_eq!(machine.transition(&0), Option::Some("lock".to_owned()));
+        machine.transition(&1);
+        let table = machine.into_table();
+        assert_eq!(table.initial, "open");
+        let duplicated = turnstile().transition("open".to_owned(), false,
+            "open".to_owned(), "stay".to_owned());
+        match TableMachine::new(duplicated, |coins: &u32| *coins > 0) {
+            Result::Err(e) => assert_eq!(e, TableError::Duplicate(2)),
+            _ => unreachable!("Expected duplicate transition")
+        };
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn transition_table_serde_test() {
+        let source = r#"{
+            "initial": "locked",
+            "transitions": [
+                {"from": "locked", "on": true, "to": "open",
+                    "action": "unlock"},
+                {"from": "open", "on": false, "to": "locked",
+                    "action": "lock"}
+            ]
+        }"#;
+        let table: TransitionTable<String, bool, String> =
+            ::serde_json::from_str(source).unwrap();
+        assert_eq!(table, turnstile());
+        let written = ::serde_json::to_string(&table).unwrap();
+        assert_eq!(::serde_json::from_str::<TransitionTable<_, _, _>>(
+            &written).unwrap(), table);
+    }
+}
\ No newline at end of file
@@ -1,6 +1,10 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::cell::Cell;
 use stackbt_automata_impl::automaton::Automaton;
+#[cfg(feature = "serde")]
+use persist_state::PersistState;
 
 /// Node whose function is to stall within itself until a function of its 
 /// input return a terminal state, then terminates at that state. 
@@ -84,6 +88,17 @@ impl<I, N, T, C> BehaviorTreeNode for PredicateWait<I, N, T, C> where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<I, N, T, C> PersistState for PredicateWait<I, N, T, C> where 
+    C: Fn(&I) -> Statepoint<N, T>
+{
+    type State = ();
+
+    fn save_state(&self) {}
+
+    fn restore_state(&mut self, _state: ()) {}
+}
+
 /// Node which calls a function wrapper with its input, immediately 
 /// terminating with its return value. 
 /// # Example
@@ -156,6 +171,17 @@ impl<I, O, C> BehaviorTreeNode for Evaluation<I, O, C> where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<I, O, C> PersistState for Evaluation<I, O, C> where 
+    C: Fn(&I) -> O
+{
+    type State = ();
+
+    fn save_state(&self) {}
+
+    fn restore_state(&mut self, _state: ()) {}
+}
+
 #[derive(PartialEq, Debug)]
 pub struct CallLoop<I, O, C> where 
     C: Fn(&I) -> O
@@ -204,8 +230,20 @@ impl<I, O, C> BehaviorTreeNode for CallLoop<I, O, C> where
     }
 }
 
+#[cfg(feature = "serde")]
+impl<I, O, C> PersistState for CallLoop<I, O, C> where 
+    C: Fn(&I) -> O
+{
+    type State = ();
+
+    fn save_state(&self) {}
+
+    fn restore_state(&mut self, _state: ()) {}
+}
+
 /// Node wrapper for an automaton. 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MachineWrapper<M, N, T> where 
     M: Automaton<'static, Action=Statepoint<N, T>> + 'static
 {
@@ -266,6 +304,7 @@ impl<M, N, T> BehaviorTreeNode for MachineWrapper<M, N, T> where
 
 /// Node wrapper for an automaton. 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MachineLoop<M> where 
     M: Automaton<'static> + 'static
 {
@@ -314,6 +353,119 @@ impl<M> BehaviorTreeNode for MachineLoop<M> where
     }
 }
 
+/// Shared state of a family of hysteresis conditions, recording whether the 
+/// condition is currently held. 
+///
+/// Clones of the state are shared, so that the fresh instances of a 
+/// hysteresis condition made each time it is entered see the outcome of 
+/// the earlier ones. 
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct HysteresisState {
+    held: Rc<Cell<bool>>
+}
+
+impl HysteresisState {
+    /// Create a new hysteresis state, with the condition not held. 
+    pub fn new() -> HysteresisState {
+        HysteresisState::default()
+    }
+
+    /// Check whether the condition is currently held. 
+    pub fn is_held(&self) -> bool {
+        self.held.get()
+    }
+}
+
+/// Condition node with separate predicates for entering and exiting the 
+/// held state, so that it does not rapidly toggle between success and 
+/// failure when its input hovers near a boundary. 
+///
+/// While the condition is not held, it becomes held once the enter 
+/// predicate holds, and while it is held, it stops being held once the exit 
+/// predicate holds. The node immediately succeeds if the condition is held 
+/// after this, and fails otherwise. 
+#[derive(PartialEq, Debug)]
+pub struct Hysteresis<I, E, X> where 
+    E: Fn(&I) -> bool,
+    X: Fn(&I) -> bool
+{
+    state: HysteresisState,
+    enter: E,
+    exit: X,
+    _junk: PhantomData<I>
+}
+
+impl<I, E, X> Clone for Hysteresis<I, E, X> where 
+    E: Fn(&I) -> bool + Clone,
+    X: Fn(&I) -> bool + Clone
+{
+    fn clone(&self) -> Self {
+        Hysteresis {
+            state: self.state.clone(),
+            enter: self.enter.clone(),
+            exit: self.exit.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, E, X> Hysteresis<I, E, X> where 
+    E: Fn(&I) -> bool,
+    X: Fn(&I) -> bool
+{
+    /// Create a new hysteresis condition over the given state. 
+    pub fn new(state: HysteresisState, enter: E, exit: X) -> Self {
+        Hysteresis {
+            state: state,
+            enter: enter,
+            exit: exit,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, E, X> BehaviorTreeNode for Hysteresis<I, E, X> where 
+    E: Fn(&I) -> bool,
+    X: Fn(&I) -> bool
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<(), ()>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), Result<(), ()>, Self> {
+        let held = if self.state.is_held() {
+            !(self.exit)(input)
+        } else {
+            (self.enter)(input)
+        };
+        self.state.held.set(held);
+        if held {
+            NodeResult::Terminal(Result::Ok(()))
+        } else {
+            NodeResult::Terminal(Result::Err(()))
+        }
+    }
+}
+
+/// The saved state of a hysteresis condition is whether it is held, which is 
+/// restored into the state it shares with the other instances. 
+#[cfg(feature = "serde")]
+impl<I, E, X> PersistState for Hysteresis<I, E, X> where 
+    E: Fn(&I) -> bool,
+    X: Fn(&I) -> bool
+{
+    type State = bool;
+
+    fn save_state(&self) -> bool {
+        self.state.is_held()
+    }
+
+    fn restore_state(&mut self, state: bool) {
+        self.state.held.set(state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use behavior_tree_node::Statepoint;
@@ -340,6 +492,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hysteresis_test() {
+        use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+        use base_nodes::{Hysteresis, HysteresisState};
+        let state = HysteresisState::new();
+        let condition = || Hysteresis::new(
+            state.clone(),
+            |i: &i64| *i < 20,
+            |i: &i64| *i > 30
+        );
+        for &(input, held) in [(25, false), (15, true), (25, true), (35, false),
+            (25, false)].iter()
+        {
+            match condition().step(&input) {
+                NodeResult::Terminal(t) => assert_eq!(t.is_ok(), held),
+                _ => unreachable!("Node doesn't return nonterminal")
+            };
+        }
+    }
+
     #[test]
     fn evaluation_test() {
         use behavior_tree_node::{BehaviorTreeNode, NodeResult};
@@ -402,4 +574,23 @@ mod tests {
             _ => unreachable!("Expected terminal state"),
         };
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn hysteresis_persist_test() {
+        use base_nodes::{Hysteresis, HysteresisState};
+        use persist_state::PersistState;
+        let condition = |state: &HysteresisState| Hysteresis::new(
+            state.clone(),
+            |i: &i64| *i < 20,
+            |i: &i64| *i > 30
+        );
+        let mut held = condition(&HysteresisState::new());
+        held.restore_state(true);
+        let saved = held.save_state();
+        let mut restored = condition(&HysteresisState::new());
+        assert!(!restored.save_state());
+        restored.restore_state(saved);
+        assert!(restored.save_state());
+    }
 }
\ No newline at end of file
@@ -1,11 +1,15 @@
 #[cfg(feature = "try_trait")]
-use std::ops::Try;
+use std::convert::Infallible;
+use std::fmt;
+#[cfg(feature = "try_trait")]
+use std::ops::{ControlFlow, FromResidual, Residual, Try};
 
 /// A generic enum which are provided to help implementations of certain 
 /// behavior tree nodes choose whether a particular state is nonterminal or 
 /// terminal, and to work with nonterminal or terminal states their children 
 /// have themselves chosen. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Statepoint<N, T> {
     /// A nonterminal state. 
     Nonterminal(N),
@@ -13,25 +17,43 @@ pub enum Statepoint<N, T> {
     Terminal(T),
 }
 
+/// Applying `?` to a statepoint continues with the nonterminal value, and 
+/// returns early with the terminal one. 
 #[cfg(feature = "try_trait")]
 impl<N, T> Try for Statepoint<N, T> {
-    type Ok = N;
-    type Error = T;
+    type Output = N;
+    type Residual = Statepoint<Infallible, T>;
+
+    fn from_output(nonterm: N) -> Self {
+        Statepoint::Nonterminal(nonterm)
+    }
 
-    fn into_result(self) -> Result<N, T> {
+    fn branch(self) -> ControlFlow<Statepoint<Infallible, T>, N> {
         match self {
-            Statepoint::Nonterminal(n) => Result::Ok(n),
-            Statepoint::Terminal(t) => Result::Err(t)
+            Statepoint::Nonterminal(n) => ControlFlow::Continue(n),
+            Statepoint::Terminal(t) => {
+                ControlFlow::Break(Statepoint::Terminal(t))
+            }
         }
     }
+}
 
-    fn from_error(term: T) -> Self {
-        Statepoint::Terminal(term)
+#[cfg(feature = "try_trait")]
+impl<N, T, U> FromResidual<Statepoint<Infallible, U>> for 
+    Statepoint<N, T> where 
+    T: From<U>
+{
+    fn from_residual(residual: Statepoint<Infallible, U>) -> Self {
+        match residual {
+            Statepoint::Nonterminal(i) => match i {},
+            Statepoint::Terminal(u) => Statepoint::Terminal(T::from(u))
+        }
     }
+}
 
-    fn from_ok(nonterm: N) -> Self {
-        Statepoint::Nonterminal(nonterm)
-    }
+#[cfg(feature = "try_trait")]
+impl<N, T> Residual<N> for Statepoint<Infallible, T> {
+    type TryType = Statepoint<N, T>;
 }
 
 /// The return value of behavior tree nodes. To statically prevent further 
@@ -48,24 +70,128 @@ pub enum NodeResult<R, T, N> {
     Terminal(T)
 }
 
+/// Applying `?` to a node result continues with the nonterminal value and 
+/// the node, and returns early with the terminal value. 
 #[cfg(feature = "try_trait")]
 impl<R, T, N> Try for NodeResult<R, T, N> {
-    type Ok = (R, N);
-    type Error = T;
+    type Output = (R, N);
+    type Residual = NodeResult<Infallible, T, Infallible>;
 
-    fn into_result(self) -> Result<(R, N), T> {
+    fn from_output(nonterm: (R, N)) -> Self {
+        NodeResult::Nonterminal(nonterm.0, nonterm.1)
+    }
+
+    fn branch(self) -> ControlFlow<NodeResult<Infallible, T, Infallible>, 
+        (R, N)> 
+    {
         match self {
-            NodeResult::Nonterminal(r, n) => Result::Ok((r, n)),
-            NodeResult::Terminal(t) => Result::Err(t)
+            NodeResult::Nonterminal(r, n) => ControlFlow::Continue((r, n)),
+            NodeResult::Terminal(t) => {
+                ControlFlow::Break(NodeResult::Terminal(t))
+            }
         }
     }
+}
 
-    fn from_error(term: T) -> Self {
-        NodeResult::Terminal(term)
+#[cfg(feature = "try_trait")]
+impl<R, T, N, U> FromResidual<NodeResult<Infallible, U, Infallible>> for 
+    NodeResult<R, T, N> where 
+    T: From<U>
+{
+    fn from_residual(residual: NodeResult<Infallible, U, Infallible>) -> Self {
+        match residual {
+            NodeResult::Nonterminal(i, _) => match i {},
+            NodeResult::Terminal(u) => NodeResult::Terminal(T::from(u))
+        }
     }
+}
 
-    fn from_ok(nonterm: (R, N)) -> Self {
-        NodeResult::Nonterminal(nonterm.0, nonterm.1)
+#[cfg(feature = "try_trait")]
+impl<R, T, N> Residual<(R, N)> for NodeResult<Infallible, T, Infallible> {
+    type TryType = NodeResult<R, T, N>;
+}
+
+/// Node results are serialized as the statepoint they carry, leaving out the 
+/// node, so that the outcomes of steps can be logged or sent elsewhere. 
+#[cfg(feature = "serde")]
+impl<R, T, N> ::serde::Serialize for NodeResult<R, T, N> where 
+    R: ::serde::Serialize,
+    T: ::serde::Serialize
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where 
+        S: ::serde::Serializer
+    {
+        let point = match *self {
+            NodeResult::Nonterminal(ref r, _) => Statepoint::Nonterminal(r),
+            NodeResult::Terminal(ref t) => Statepoint::Terminal(t)
+        };
+        point.serialize(serializer)
+    }
+}
+
+/// The standard outcome of a behavior tree node which either succeeds or 
+/// fails, which the terminals of such nodes can be converted into, for the 
+/// wrappers which only care about success and failure. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BtStatus {
+    /// The node succeeded. 
+    Success,
+    /// The node failed. 
+    Failure
+}
+
+impl BtStatus {
+    /// Whether the status is a success. 
+    pub fn is_success(&self) -> bool {
+        *self == BtStatus::Success
+    }
+
+    /// Swap success and failure. 
+    pub fn invert(self) -> BtStatus {
+        match self {
+            BtStatus::Success => BtStatus::Failure,
+            BtStatus::Failure => BtStatus::Success
+        }
+    }
+
+    /// Convert the status into a success or failure terminal with no value. 
+    pub fn into_result(self) -> Result<(), Failure> {
+        match self {
+            BtStatus::Success => Result::Ok(()),
+            BtStatus::Failure => Result::Err(Failure)
+        }
+    }
+}
+
+/// Error of a node which failed without giving a reason, as the failure 
+/// terminal which a BtStatus is converted into. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Failure;
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "node failed")
+    }
+}
+
+impl<T, F> From<Result<T, F>> for BtStatus {
+    fn from(result: Result<T, F>) -> BtStatus {
+        match result {
+            Result::Ok(_) => BtStatus::Success,
+            Result::Err(_) => BtStatus::Failure
+        }
+    }
+}
+
+impl From<bool> for BtStatus {
+    fn from(success: bool) -> BtStatus {
+        if success {
+            BtStatus::Success
+        } else {
+            BtStatus::Failure
+        }
     }
 }
 
@@ -84,6 +210,188 @@ pub trait BehaviorTreeNode {
     fn step(self, input: &Self::Input) -> 
         NodeResult<Self::Nonterminal, Self::Terminal, Self> where 
         Self: Sized;
+
+    /// Abandon the node before it reaches a terminal state. By default, the 
+    /// node is simply dropped, but nodes which need to release something or 
+    /// pass the abandonment on to their children can override this. 
+    fn abort(self) where 
+        Self: Sized 
+    {}
+}
+
+impl<N> BehaviorTreeNode for Box<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, N::Terminal, 
+        Self> 
+    {
+        match (*self).step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n, 
+                Box::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        (*self).abort()
+    }
+}
+
+/// A node which is one of two nodes sharing the same input, nonterminal, and 
+/// terminal types, and which steps whichever of the two it holds. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Either<A, B> where 
+    A: BehaviorTreeNode,
+    B: BehaviorTreeNode<Input=A::Input, Nonterminal=A::Nonterminal, 
+        Terminal=A::Terminal>
+{
+    /// The first kind of node. 
+    Left(A),
+    /// The second kind of node. 
+    Right(B)
+}
+
+impl<A, B> BehaviorTreeNode for Either<A, B> where 
+    A: BehaviorTreeNode,
+    B: BehaviorTreeNode<Input=A::Input, Nonterminal=A::Nonterminal, 
+        Terminal=A::Terminal>
+{
+    type Input = A::Input;
+    type Nonterminal = A::Nonterminal;
+    type Terminal = A::Terminal;
+
+    #[inline]
+    fn step(self, input: &A::Input) -> NodeResult<A::Nonterminal, A::Terminal, 
+        Self> 
+    {
+        match self {
+            Either::Left(a) => match a.step(input) {
+                NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                    n, 
+                    Either::Left(m)
+                ),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            Either::Right(b) => match b.step(input) {
+                NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                    n, 
+                    Either::Right(m)
+                ),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        match self {
+            Either::Left(a) => a.abort(),
+            Either::Right(b) => b.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{Evaluation, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+
+    #[test]
+    fn box_node_test() {
+        let node = Box::new(PredicateWait::new(|i: &i64| {
+            if *i == 0 {
+                Statepoint::Terminal(*i)
+            } else {
+                Statepoint::Nonterminal(*i)
+            }
+        }));
+        let node_1 = match node.step(&3) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 3);
+                n
+            },
+            _ => unreachable!("Expected nonterminal state")
+        };
+        match node_1.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, 0),
+            _ => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn either_node_test() {
+        use behavior_tree_node::Either;
+        let wait = PredicateWait::new(|i: &i64| {
+            if *i == 0 {
+                Statepoint::Terminal(*i)
+            } else {
+                Statepoint::Nonterminal(())
+            }
+        });
+        let eval = Evaluation::new(|i: &i64| *i * 2);
+        let [left, right] = [Either::Left(wait), Either::Right(eval)];
+        match left.step(&5) {
+            NodeResult::Nonterminal(_, Either::Left(_)) => (),
+            _ => unreachable!("Expected left nonterminal state")
+        };
+        match right.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, 10),
+            _ => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn bt_status_test() {
+        use behavior_tree_node::{BtStatus, Failure};
+        assert_eq!(BtStatus::from(Result::Ok::<i64, ()>(1)), BtStatus::Success);
+        assert_eq!(BtStatus::from(Result::Err::<(), i64>(1)), BtStatus::Failure);
+        assert_eq!(BtStatus::from(false).invert(), BtStatus::Success);
+        assert!(!BtStatus::Failure.is_success());
+        assert_eq!(BtStatus::Failure.into_result(), Result::Err(Failure));
+        assert_eq!(Failure.to_string(), "node failed");
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests_serde {
+    use behavior_tree_node::{NodeResult, Statepoint};
+    use serde_test::{assert_ser_tokens, assert_tokens, Token};
+
+    #[test]
+    fn statepoint_serde_test() {
+        assert_tokens(&Statepoint::Nonterminal::<i64, ()>(5), &[
+            Token::NewtypeVariant {
+                name: "Statepoint",
+                variant: "Nonterminal"
+            },
+            Token::I64(5)
+        ]);
+    }
+
+    #[test]
+    fn node_result_serde_test() {
+        assert_ser_tokens(&NodeResult::Nonterminal::<i64, (), _>(5, "node"), &[
+            Token::NewtypeVariant {
+                name: "Statepoint",
+                variant: "Nonterminal"
+            },
+            Token::I64(5)
+        ]);
+        assert_ser_tokens(&NodeResult::Terminal::<(), bool, ()>(true), &[
+            Token::NewtypeVariant {
+                name: "Statepoint",
+                variant: "Terminal"
+            },
+            Token::Bool(true)
+        ]);
+    }
 }
 
 #[cfg(all(test, feature = "try_trait"))]
@@ -93,23 +401,29 @@ mod tests_try {
     #[test]
     fn statepoint_try_test() {
         use behavior_tree_node::Statepoint;
-        assert_eq!(Statepoint::Nonterminal::<i64, i64>(5).into_result(), 
-            Result::Ok(5));
-        assert_eq!(Statepoint::Terminal::<i64, i64>(5).into_result(), 
-            Result::Err(5));
-        assert_eq!(Statepoint::<i64, i64>::from_error(5), Statepoint::Terminal(5));
-        assert_eq!(Statepoint::<i64, i64>::from_ok(5), Statepoint::Nonterminal(5));
+        fn double(point: Statepoint<i64, i64>) -> Statepoint<i64, i64> {
+            let n = point?;
+            Statepoint::Nonterminal(n * 2)
+        }
+        assert_eq!(double(Statepoint::Nonterminal(5)), 
+            Statepoint::Nonterminal(10));
+        assert_eq!(double(Statepoint::Terminal(5)), Statepoint::Terminal(5));
+        assert_eq!(Statepoint::<i64, i64>::from_output(5), 
+            Statepoint::Nonterminal(5));
     }
     #[test]
     fn node_result_try_test() {
         use behavior_tree_node::NodeResult;
-        assert_eq!(NodeResult::Nonterminal::<i64, i64, i64>(5, 4).into_result(), 
-            Result::Ok((5, 4)));
-        assert_eq!(NodeResult::Terminal::<i64, i64, i64>(5).into_result(), 
-            Result::Err(5));
-        assert_eq!(NodeResult::<i64, i64, i64>::from_error(5), 
-            NodeResult::Terminal(5));
-        assert_eq!(NodeResult::<i64, i64, i64>::from_ok((5, 4)), 
+        fn bump(result: NodeResult<i64, i64, i64>) -> 
+            NodeResult<i64, i64, i64> 
+        {
+            let (r, n) = result?;
+            NodeResult::Nonterminal(r + 1, n)
+        }
+        assert_eq!(bump(NodeResult::Nonterminal(5, 4)), 
+            NodeResult::Nonterminal(6, 4));
+        assert_eq!(bump(NodeResult::Terminal(5)), NodeResult::Terminal(5));
+        assert_eq!(NodeResult::<i64, i64, i64>::from_output((5, 4)), 
             NodeResult::Nonterminal(5, 4));
     }
 }
\ No newline at end of file
@@ -0,0 +1,268 @@
+use bincode::{self, Options};
+use checksum::{self, ChecksumError};
+use migration::Migrations;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use snapshot::Snapshot;
+use std::fmt;
+use std::io;
+
+/// Bytes every encoding starts with. 
+const MAGIC: [u8; 4] = *b"SBTS";
+
+/// Version of the encoding written by this codec, which follows the magic 
+/// bytes. Encodings of other versions are rejected. 
+pub const FORMAT_VERSION: u16 = 2;
+
+const HEADER_LEN: usize = 6;
+
+/// Error of encoding or decoding state. 
+#[derive(Debug)]
+pub enum CodecError {
+    /// The bytes do not start with the magic bytes of the encoding. 
+    Magic,
+    /// The bytes are of the given version of the encoding, which is not the 
+    /// version of this codec. 
+    Version(u16),
+    /// The state, or its encoding, exceeds the size limit of the codec. 
+    TooLarge,
+    /// The checksum of the encoding does not match, as the bytes were 
+    /// truncated or corrupted. 
+    Checksum(ChecksumError),
+    /// The state is of the given version of its layout, from which there is 
+    /// no migration. 
+    Migration(u32),
+    /// The delta is against the keyframe with the given number, which is 
+    /// not the last keyframe applied. 
+    Keyframe(u64),
+    /// The message holding the state failed to be decoded. 
+    #[cfg(feature = "protobuf")]
+    Protobuf(::prost::DecodeError),
+    /// The encoded state failed to be read or written. 
+    Io(io::Error),
+    /// The state failed to be encoded or decoded. 
+    Encoding(bincode::Error)
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodecError::Magic => write!(f, "not an encoding of state"),
+            CodecError::Version(v) => write!(f,
+                "unsupported encoding version {}", v),
+            CodecError::TooLarge => write!(f, "state exceeds the size limit"),
+            CodecError::Checksum(ref e) => write!(f, "corrupt encoding: {}", e),
+            CodecError::Migration(v) => write!(f,
+                "no migration from state version {}", v),
+            CodecError::Keyframe(n) => write!(f,
+                "delta against keyframe {} which was not applied", n),
+            #[cfg(feature = "protobuf")]
+            CodecError::Protobuf(ref e) => write!(f, "protobuf error: {}", e),
+            CodecError::Io(ref e) => write!(f, "i/o error: {}", e),
+            CodecError::Encoding(ref e) => write!(f, "encoding error: {}", e)
+        }
+    }
+}
+
+fn from_bincode(error: bincode::Error) -> CodecError {
+    match *error {
+        bincode::ErrorKind::SizeLimit => CodecError::TooLarge,
+        _ => CodecError::Encoding(error)
+    }
+}
+
+/// Compact binary encoding of state, such as the snapshots of trees, meant 
+/// for frequent autosaves and for sending the state of agents over the 
+/// network. 
+///
+/// Integers are written in a variable length encoding, after a header of 
+/// magic bytes and the version of the encoding, and followed by a CRC-32 
+/// checksum of both, which is verified before anything is decoded so that 
+/// damaged state is never restored into a running agent. Encodings whose 
+/// payload would exceed the size limit of the codec are refused on both 
+/// ends, so that corrupted or hostile input can not make the decoder 
+/// allocate without bound. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BinaryCodec {
+    limit: u64
+}
+
+impl Default for BinaryCodec {
+    fn default() -> BinaryCodec {
+        BinaryCodec::new()
+    }
+}
+
+impl BinaryCodec {
+    /// Create a new codec, with a size limit of one mebibyte. 
+    pub fn new() -> BinaryCodec {
+        BinaryCodec::with_limit(1 << 20)
+    }
+
+    /// Create a new codec with the given size limit of payloads, in bytes. 
+    pub fn with_limit(limit: u64) -> BinaryCodec {
+        BinaryCodec {
+            limit: limit
+        }
+    }
+
+    /// Get the size limit of payloads, in bytes. 
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    fn options(&self) -> impl Options {
+        bincode::DefaultOptions::new().with_limit(self.limit)
+    }
+
+    /// Encode the given state. 
+    pub fn encode<T>(&self, value: &T) -> Result<Vec<u8>, CodecError> where 
+        T: Serialize
+    {
+        let payload = self.encode_payload(value)?;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len() + 
+            checksum::CHECKSUM_LEN);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(FORMAT_VERSION as u8);
+        bytes.push((FORMAT_VERSION >> 8) as u8);
+        bytes.extend(payload);
+        Result::Ok(checksum::seal(bytes))
+    }
+
+    /// Decode state from the given bytes. 
+    pub fn decode<T>(&self, bytes: &[u8]) -> Result<T, CodecError> where 
+        T: DeserializeOwned
+    {
+        if bytes.len() < HEADER_LEN || bytes[..4] != MAGIC {
+            return Result::Err(CodecError::Magic);
+        }
+        let version = bytes[4] as u16 | (bytes[5] as u16) << 8;
+        if version != FORMAT_VERSION {
+            return Result::Err(CodecError::Version(version));
+        }
+        let contents = checksum::unseal(bytes).map_err(CodecError::Checksum)?;
+        self.decode_payload(&contents[HEADER_LEN..])
+    }
+
+    pub(crate) fn encode_payload<T>(&self, value: &T) -> 
+        Result<Vec<u8>, CodecError> where 
+        T: Serialize
+    {
+        self.options().serialize(value).map_err(from_bincode)
+    }
+
+    pub(crate) fn decode_payload<T>(&self, payload: &[u8]) -> 
+        Result<T, CodecError> where 
+        T: DeserializeOwned
+    {
+        if payload.len() as u64 > self.limit {
+            return Result::Err(CodecError::TooLarge);
+        }
+        self.options().deserialize(payload).map_err(from_bincode)
+    }
+
+    /// Encode a snapshot of the given running tree. 
+    pub fn encode_snapshot<S>(&self, tree: &S) ->
+        Result<Vec<u8>, CodecError> where 
+        S: Snapshot
+    {
+        self.encode(&tree.snapshot())
+    }
+
+    /// Reconstruct a running tree from its definition and the encoding of 
+    /// its snapshot. 
+    pub fn decode_snapshot<S>(&self, definition: &S::Definition,
+        bytes: &[u8]) -> Result<S, CodecError> where 
+        S: Snapshot
+    {
+        self.decode(bytes).map(|b| S::from_snapshot(definition, b))
+    }
+
+    /// Encode a snapshot of the given running tree, stamped with the version 
+    /// of the layout of its state. 
+    pub fn encode_versioned<S>(&self, tree: &S) -> 
+        Result<Vec<u8>, CodecError> where 
+        S: Snapshot
+    {
+        let state = self.encode_payload(&tree.snapshot())?;
+        self.encode(&(S::STATE_VERSION, state))
+    }
+
+    /// Reconstruct a running tree from its definition and the encoding of 
+    /// its versioned snapshot, migrating the state if it was saved by 
+    /// another version of its layout. 
+    pub fn decode_versioned<S>(&self, definition: &S::Definition, 
+        bytes: &[u8], migrations: &Migrations<S::Blob>) -> 
+        Result<S, CodecError> where 
+        S: Snapshot
+    {
+        let (version, state) = self.decode::<(u32, Vec<u8>)>(bytes)?;
+        self.restore_versioned(definition, version, &state, migrations)
+    }
+
+    pub(crate) fn restore_versioned<S>(&self, definition: &S::Definition, 
+        version: u32, state: &[u8], migrations: &Migrations<S::Blob>) -> 
+        Result<S, CodecError> where 
+        S: Snapshot
+    {
+        let blob = if version == S::STATE_VERSION {
+            Option::Some(self.decode_payload(state)?)
+        } else {
+            migrations.migrate(self, version, state)?
+        };
+        Result::Ok(match blob {
+            Option::Some(b) => S::from_snapshot(definition, b),
+            Option::None => S::fresh(definition)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use binary_codec::{BinaryCodec, CodecError};
+
+    #[test]
+    fn binary_codec_test() {
+        let codec = BinaryCodec::new();
+        let state = (3u64, vec![true, false], "idle".to_owned());
+        let bytes = codec.encode(&state).unwrap();
+        assert_eq!(&bytes[..6], b"SBTS\x02\x00");
+        assert_eq!(bytes.len(), 6 + 1 + 1 + 2 + 1 + 4 + 4);
+        assert_eq!(codec.decode::<(u64, Vec<bool>, String)>(&bytes).unwrap(),
+            state);
+        let mut other = bytes.clone();
+        other[4] = 9;
+        match codec.decode::<(u64, Vec<bool>, String)>(&other) {
+            Result::Err(CodecError::Version(9)) => (),
+            _ => unreachable!("Expected version mismatch")
+        };
+        match codec.decode::<u64>(b"nope") {
+            Result::Err(CodecError::Magic) => (),
+            _ => unreachable!("Expected missing magic bytes")
+        };
+        let mut corrupt = bytes.clone();
+        corrupt[7] ^= 1;
+        match codec.decode::<(u64, Vec<bool>, String)>(&corrupt) {
+            Result::Err(CodecError::Checksum(_)) => (),
+            _ => unreachable!("Expected checksum mismatch")
+        };
+        match codec.decode::<(u64, Vec<bool>, String)>(&bytes[..8]) {
+            Result::Err(CodecError::Checksum(_)) => (),
+            _ => unreachable!("Expected checksum mismatch")
+        };
+    }
+
+    #[test]
+    fn binary_codec_limit_test() {
+        let codec = BinaryCodec::with_limit(16);
+        match codec.encode(&vec![0u8; 64]) {
+            Result::Err(CodecError::TooLarge) => (),
+            _ => unreachable!("Expected oversized state")
+        };
+        let bytes = BinaryCodec::new().encode(&vec![0u8; 64]).unwrap();
+        match codec.decode::<Vec<u8>>(&bytes) {
+            Result::Err(CodecError::TooLarge) => (),
+            _ => unreachable!("Expected oversized state")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,1074 @@
+use clock::{Clock, MonotonicClock};
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Typed key of a blackboard entry, naming the entry and fixing the type of 
+/// its value. 
+pub struct Key<T> {
+    name: Cow<'static, str>,
+    _junk: PhantomData<fn() -> T>
+}
+
+impl<T> Key<T> {
+    /// Create a new key with the given name. 
+    pub fn new<S>(name: S) -> Key<T> where 
+        S: Into<Cow<'static, str>>
+    {
+        Key {
+            name: name.into(),
+            _junk: PhantomData
+        }
+    }
+
+    /// Get the name of the key. 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        Key {
+            name: self.name.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<T> PartialEq for Key<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<T> fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Key").field(&self.name).finish()
+    }
+}
+
+/// How long a blackboard entry stays fresh after it is written. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Ttl {
+    /// The entry is stale once the blackboard has been ticked this many 
+    /// times. 
+    Ticks(u64),
+    /// The entry is stale once this much time has passed on the clock of 
+    /// the blackboard. 
+    Time(Duration)
+}
+
+/// Error of a read requiring fresh data. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReadError {
+    /// There is no value of the type of the key under it. 
+    Missing,
+    /// There is a value, but it has outlived its time to live. 
+    Stale
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Deadline {
+    Tick(u64),
+    Time(Duration)
+}
+
+struct Entry {
+    value: Box<Any>,
+    version: u64,
+    expires: Option<Deadline>
+}
+
+fn entry_version(entry: &Entry) -> u64 {
+    entry.version
+}
+
+struct Derivation {
+    sources: Vec<String>,
+    compute: Box<Fn(&Blackboard) -> Box<Any>>,
+    cached: RefCell<Option<(Vec<Option<u64>>, Rc<Entry>)>>
+}
+
+/// Kind of a recorded blackboard mutation. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MutationKind {
+    /// A value was written. 
+    Set,
+    /// A value was removed. 
+    Remove
+}
+
+/// Blackboard mutation recorded in the journal. 
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Mutation {
+    /// The name of the entry, on the blackboard it was stored on. 
+    pub name: String,
+    /// What was done to the entry. 
+    pub kind: MutationKind,
+    /// The version written, or the latest version at the time of a removal. 
+    pub version: u64,
+    /// The tick count at the time of the mutation. 
+    pub tick: u64
+}
+
+/// Net effect of the mutations between two journal positions. 
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JournalDiff {
+    /// The entries whose last mutation was a write, with the version of 
+    /// that write, sorted by name. 
+    pub written: Vec<(String, u64)>,
+    /// The entries whose last mutation was a removal, sorted by name. 
+    pub removed: Vec<String>
+}
+
+struct Shared {
+    revision: Cell<u64>,
+    ticks: Cell<u64>,
+    clock: Box<Clock>,
+    journal: RefCell<Option<Vec<Mutation>>>
+}
+
+impl Shared {
+    fn record(&self, name: &str, kind: MutationKind) {
+        if let Option::Some(ref mut j) = *self.journal.borrow_mut() {
+            j.push(Mutation {
+                name: name.to_owned(),
+                kind: kind,
+                version: self.revision.get(),
+                tick: self.ticks.get()
+            });
+        }
+    }
+}
+
+/// Shared working memory for the nodes of a tree, holding values of 
+/// arbitrary types under typed keys. 
+///
+/// The blackboard is written through a shared reference, so that it can be 
+/// part of the input of the nodes. Each write stamps the entry with a new 
+/// version, which nodes can compare to notice changes without comparing the 
+/// values themselves. 
+///
+/// A blackboard can be a scope of a parent blackboard, for a subtree to 
+/// keep its entries apart from those of other subtrees. Reads of names 
+/// missing from the scope fall back to the parent, while writes stay in the 
+/// scope, except for names remapped at the boundary, which are read and 
+/// written under their mapped names on the parent. A scope shares the 
+/// revision counter of its parent, so versions stay comparable across them. 
+///
+/// Entries can be written with a time to live, counted either in ticks of 
+/// the blackboard or in time on its clock, after which reads requiring 
+/// fresh data treat them as stale. Stale entries are kept, and plain reads 
+/// still see them. A scope shares the ticks and the clock of its parent. 
+pub struct Blackboard {
+    entries: RefCell<HashMap<String, Rc<Entry>>>,
+    shared: Rc<Shared>,
+    derived: RefCell<HashMap<String, Rc<Derivation>>>,
+    parent: Option<Rc<Blackboard>>,
+    remaps: HashMap<String, String>,
+    prefix_remaps: Vec<(String, String)>
+}
+
+impl Default for Blackboard {
+    fn default() -> Blackboard {
+        Blackboard::with_clock(MonotonicClock::new())
+    }
+}
+
+impl Blackboard {
+    /// Create a new, empty blackboard. 
+    pub fn new() -> Blackboard {
+        Blackboard::default()
+    }
+
+    /// Create a new, empty blackboard timing the time to live of its 
+    /// entries on the given clock. 
+    pub fn with_clock<C>(clock: C) -> Blackboard where 
+        C: Clock + 'static
+    {
+        Blackboard {
+            entries: RefCell::new(HashMap::new()),
+            shared: Rc::new(Shared {
+                revision: Cell::new(0),
+                ticks: Cell::new(0),
+                journal: RefCell::new(Option::None),
+                clock: Box::new(clock)
+            }),
+            parent: Option::None,
+            derived: RefCell::new(HashMap::new()),
+            remaps: HashMap::new(),
+            prefix_remaps: Vec::new()
+        }
+    }
+
+    /// Create a new, empty scope of the given blackboard. 
+    pub fn scoped(parent: Rc<Blackboard>) -> Blackboard {
+        Blackboard {
+            entries: RefCell::new(HashMap::new()),
+            shared: parent.shared.clone(),
+            parent: Option::Some(parent),
+            derived: RefCell::new(HashMap::new()),
+            remaps: HashMap::new(),
+            prefix_remaps: Vec::new()
+        }
+    }
+
+    /// Register a derived entry under the given key, whose value is computed 
+    /// from the blackboard by the given function when the entry is read. The 
+    /// value is kept until one of the entries with the given names has been 
+    /// written since it was computed, and each computation stamps the entry 
+    /// with a new version. 
+    ///
+    /// Entries written under the same name take precedence over the derived 
+    /// entry. The function must not write to the blackboard, nor read the 
+    /// derived entry itself. 
+    pub fn derive<T, F>(&self, key: &Key<T>, sources: &[&str], 
+        compute: F) where 
+        T: 'static,
+        F: Fn(&Blackboard) -> T + 'static
+    {
+        self.derived.borrow_mut().insert(key.name().to_owned(), 
+            Rc::new(Derivation {
+                sources: sources.iter().map(|s| (*s).to_owned()).collect(),
+                compute: Box::new(move |b| Box::new(compute(b)) as Box<Any>),
+                cached: RefCell::new(Option::None)
+            }));
+    }
+
+    /// Map the given name in the scope to the given name on the parent, so 
+    /// that reads and writes of the one go to the other. Has no effect on a 
+    /// blackboard without a parent. 
+    pub fn remap<S, T>(self, local: S, parent: T) -> Blackboard where 
+        S: Into<String>,
+        T: Into<String>
+    {
+        let mut mut_self = self;
+        mut_self.remaps.insert(local.into(), parent.into());
+        mut_self
+    }
+
+    /// Map the names in the scope starting with the given prefix to names on 
+    /// the parent, with the prefix replaced by the given parent prefix. Exact 
+    /// remappings take precedence, then the longest matching prefix. Has no 
+    /// effect on a blackboard without a parent. 
+    pub fn remap_prefix<S, T>(self, local: S, parent: T) -> Blackboard where 
+        S: Into<String>,
+        T: Into<String>
+    {
+        let mut mut_self = self;
+        mut_self.prefix_remaps.push((local.into(), parent.into()));
+        mut_self.prefix_remaps.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        mut_self
+    }
+
+    /// Get the parent of the blackboard, if it is a scope. 
+    pub fn parent(&self) -> Option<&Rc<Blackboard>> {
+        self.parent.as_ref()
+    }
+
+    fn forward(&self, name: &str) -> Option<(&Blackboard, Cow<str>)> {
+        let parent = match self.parent {
+            Option::Some(ref p) => &**p,
+            Option::None => return Option::None
+        };
+        if let Option::Some(n) = self.remaps.get(name) {
+            return Option::Some((parent, Cow::Borrowed(n.as_str())));
+        }
+        self.prefix_remaps.iter()
+            .find(|&&(ref l, _)| name.starts_with(l.as_str()))
+            .map(|&(ref l, ref p)| {
+                (parent, Cow::Owned(format!("{}{}", p, &name[l.len()..])))
+            })
+    }
+
+    fn with_entry<R, F>(&self, name: &str, func: F) -> Option<R> where 
+        F: FnOnce(&Entry) -> R
+    {
+        if let Option::Some((p, n)) = self.forward(name) {
+            return p.with_entry(&n, func);
+        }
+        let entry = self.entries.borrow().get(name).cloned();
+        if let Option::Some(e) = entry {
+            return Option::Some(func(&e));
+        }
+        let derivation = self.derived.borrow().get(name).cloned();
+        if let Option::Some(d) = derivation {
+            return Option::Some(self.with_derived(&d, func));
+        }
+        match self.parent {
+            Option::Some(ref p) => p.with_entry(name, func),
+            Option::None => Option::None
+        }
+    }
+
+    fn version_of(&self, name: &str) -> Option<u64> {
+        self.with_entry(name, entry_version)
+    }
+
+    fn with_derived<R, F>(&self, derivation: &Derivation, func: F) -> R where 
+        F: FnOnce(&Entry) -> R
+    {
+        let versions = derivation.sources.iter()
+            .map(|n| self.version_of(n))
+            .collect::<Vec<_>>();
+        let current = match *derivation.cached.borrow() {
+            Option::Some((ref v, _)) => *v == versions,
+            Option::None => false
+        };
+        if !current {
+            let value = (derivation.compute)(self);
+            let version = self.shared.revision.get() + 1;
+            self.shared.revision.set(version);
+            *derivation.cached.borrow_mut() = Option::Some((versions, 
+                Rc::new(Entry {
+                    value: value,
+                    version: version,
+                    expires: Option::None
+                })));
+        }
+        let entry = match *derivation.cached.borrow() {
+            Option::Some((_, ref e)) => e.clone(),
+            Option::None => unreachable!("Derived entry was just computed")
+        };
+        func(&entry)
+    }
+
+    fn with_name<T, R, F>(&self, name: &str, func: F) -> Option<R> where 
+        T: 'static,
+        F: FnOnce(&T) -> R
+    {
+        self.with_entry(name, |e| e.value.downcast_ref::<T>().map(func))
+            .and_then(|r| r)
+    }
+
+    fn set_name<T>(&self, name: &str, value: T, ttl: Option<Ttl>) -> u64 where 
+        T: 'static
+    {
+        if let Option::Some((p, n)) = self.forward(name) {
+            return p.set_name(&n, value, ttl);
+        }
+        self.insert_local(name, Box::new(value), ttl)
+    }
+
+    fn is_expired(&self, deadline: Deadline) -> bool {
+        match deadline {
+            Deadline::Tick(t) => self.shared.ticks.get() >= t,
+            Deadline::Time(t) => self.shared.clock.now() >= t
+        }
+    }
+
+    fn remove_name<T>(&self, name: &str) -> Option<T> where 
+        T: 'static
+    {
+        if let Option::Some((p, n)) = self.forward(name) {
+            return p.remove_name(&n);
+        }
+        let removed = self.entries.borrow_mut().remove(name);
+        if removed.is_some() {
+            self.shared.record(name, MutationKind::Remove);
+        }
+        removed.and_then(|e| Rc::try_unwrap(e).ok())
+            .and_then(|e| e.value.downcast::<T>().ok())
+            .map(|v| *v)
+    }
+
+    pub(crate) fn with_local<R, F>(&self, name: &str, func: F) -> 
+        Option<R> where 
+        F: FnOnce(&Any) -> R
+    {
+        let entry = self.entries.borrow().get(name).cloned();
+        entry.map(|e| func(&*e.value))
+    }
+
+    pub(crate) fn insert_local(&self, name: &str, value: Box<Any>, 
+        ttl: Option<Ttl>) -> u64 
+    {
+        let version = self.shared.revision.get() + 1;
+        self.shared.revision.set(version);
+        let expires = ttl.map(|t| match t {
+            Ttl::Ticks(n) => Deadline::Tick(self.shared.ticks.get() + n),
+            Ttl::Time(d) => Deadline::Time(self.shared.clock.now() + d)
+        });
+        self.entries.borrow_mut().insert(name.to_owned(), Rc::new(Entry {
+            value: value,
+            version: version,
+            expires: expires
+        }));
+        self.shared.record(name, MutationKind::Set);
+        version
+    }
+
+    /// Get a copy of the value under the given key, if there is one of the 
+    /// type of the key. 
+    pub fn get<T>(&self, key: &Key<T>) -> Option<T> where 
+        T: Clone + 'static
+    {
+        self.with(key, T::clone)
+    }
+
+    /// Apply the given function to a reference to the value under the given 
+    /// key, if there is one of the type of the key. The blackboard is not 
+    /// borrowed while the function runs, so it may write to the blackboard, 
+    /// but removing the entry it reads drops the value instead of returning 
+    /// it. 
+    pub fn with<T, R, F>(&self, key: &Key<T>, func: F) -> Option<R> where 
+        T: 'static,
+        F: FnOnce(&T) -> R
+    {
+        self.with_name(key.name(), func)
+    }
+
+    /// Set the value under the given key, returning the version it was 
+    /// stamped with. 
+    pub fn set<T>(&self, key: &Key<T>, value: T) -> u64 where 
+        T: 'static
+    {
+        self.set_name(key.name(), value, Option::None)
+    }
+
+    /// Set the value under the given key, to stay fresh for the given time 
+    /// to live, returning the version it was stamped with. 
+    pub fn set_ttl<T>(&self, key: &Key<T>, value: T, ttl: Ttl) -> u64 where 
+        T: 'static
+    {
+        self.set_name(key.name(), value, Option::Some(ttl))
+    }
+
+    /// Get a copy of the value under the given key, if there is one of the 
+    /// type of the key which has not outlived its time to live. 
+    pub fn get_fresh<T>(&self, key: &Key<T>) -> Result<T, ReadError> where 
+        T: Clone + 'static
+    {
+        let read = self.with_entry(key.name(), |e| {
+            e.value.downcast_ref::<T>()
+                .map(|v| (v.clone(), e.expires))
+        }).and_then(|r| r);
+        match read {
+            Option::Some((_, Option::Some(d))) if self.is_expired(d) => {
+                Result::Err(ReadError::Stale)
+            },
+            Option::Some((v, _)) => Result::Ok(v),
+            Option::None => Result::Err(ReadError::Missing)
+        }
+    }
+
+    /// Check whether the entry under the given key has outlived its time to 
+    /// live. Entries written without one never become stale. 
+    pub fn is_stale<T>(&self, key: &Key<T>) -> bool {
+        self.with_entry(key.name(), |e| e.expires)
+            .and_then(|d| d)
+            .map_or(false, |d| self.is_expired(d))
+    }
+
+    /// Advance the tick count against which times to live in ticks are 
+    /// counted, returning the new count. 
+    pub fn tick(&self) -> u64 {
+        let ticks = self.shared.ticks.get() + 1;
+        self.shared.ticks.set(ticks);
+        ticks
+    }
+
+    /// Get the number of times the blackboard has been ticked. 
+    pub fn ticks(&self) -> u64 {
+        self.shared.ticks.get()
+    }
+
+    /// Start recording the mutations of the blackboard, and of all 
+    /// blackboards sharing its scope hierarchy, into a new, empty journal. 
+    pub fn start_journal(&self) {
+        *self.shared.journal.borrow_mut() = Option::Some(Vec::new());
+    }
+
+    /// Stop recording mutations, returning the journal if there was one. 
+    pub fn stop_journal(&self) -> Option<Vec<Mutation>> {
+        self.shared.journal.borrow_mut().take()
+    }
+
+    /// Check whether mutations are being recorded. 
+    pub fn is_journaling(&self) -> bool {
+        self.shared.journal.borrow().is_some()
+    }
+
+    /// Get the current position in the journal, which is the number of 
+    /// mutations recorded so far, or zero when not recording. 
+    pub fn journal_position(&self) -> usize {
+        self.shared.journal.borrow().as_ref().map_or(0, |j| j.len())
+    }
+
+    /// Get the mutations recorded between the given journal positions. 
+    pub fn journal(&self, from: usize, to: usize) -> Vec<Mutation> {
+        match *self.shared.journal.borrow() {
+            Option::Some(ref j) => {
+                let to = to.min(j.len());
+                j[from.min(to)..to].to_vec()
+            },
+            Option::None => Vec::new()
+        }
+    }
+
+    /// Get the net effect of the mutations recorded between the given 
+    /// journal positions. 
+    pub fn journal_diff(&self, from: usize, to: usize) -> JournalDiff {
+        let mut last = HashMap::new();
+        for m in self.journal(from, to) {
+            last.insert(m.name, (m.kind, m.version));
+        }
+        let mut diff = JournalDiff::default();
+        for (name, (kind, version)) in last {
+            match kind {
+                MutationKind::Set => diff.written.push((name, version)),
+                MutationKind::Remove => diff.removed.push(name)
+            }
+        }
+        diff.written.sort();
+        diff.removed.sort();
+        diff
+    }
+
+    /// Remove the value under the given key, returning it if it was of the 
+    /// type of the key. Entries of the parent of a scope are only removed 
+    /// through remapped names. 
+    pub fn remove<T>(&self, key: &Key<T>) -> Option<T> where 
+        T: 'static
+    {
+        self.remove_name(key.name())
+    }
+
+    /// Check whether there is a value of the type of the key under it. 
+    pub fn contains<T>(&self, key: &Key<T>) -> bool where 
+        T: 'static
+    {
+        self.with(key, |_| ()).is_some()
+    }
+
+    /// Get the version the entry under the given key was last written with, 
+    /// if there is one. 
+    pub fn version<T>(&self, key: &Key<T>) -> Option<u64> {
+        self.version_of(key.name())
+    }
+
+    /// Get the version of the latest write to the blackboard, or to any 
+    /// blackboard sharing its scope hierarchy. 
+    pub fn revision(&self) -> u64 {
+        self.shared.revision.get()
+    }
+
+    /// Get the number of entries on the blackboard, not counting those of 
+    /// its parent. 
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Check whether the blackboard has no entries of its own. 
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+}
+
+impl fmt::Debug for Blackboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entries = self.entries.borrow();
+        let mut names = entries.keys().collect::<Vec<_>>();
+        names.sort();
+        let derived = self.derived.borrow();
+        let mut derived_names = derived.keys().collect::<Vec<_>>();
+        derived_names.sort();
+        f.debug_struct("Blackboard")
+            .field("entries", &names)
+            .field("derived", &derived_names)
+            .field("revision", &self.shared.revision.get())
+            .field("parent", &self.parent)
+            .finish()
+    }
+}
+
+struct SyncEntry {
+    value: Box<Any + Send + Sync>,
+    version: u64
+}
+
+/// Thread-safe variant of the blackboard, with the same typed-key access, 
+/// for sharing working memory between threads stepping separate nodes. 
+///
+/// The entries are split over several separately locked shards by the 
+/// hashes of their names, so that accesses to different entries rarely 
+/// contend. Versions are drawn from a single atomic counter, so they are 
+/// ordered across the whole blackboard. 
+pub struct SyncBlackboard {
+    shards: Vec<RwLock<HashMap<String, SyncEntry>>>,
+    revision: AtomicU64
+}
+
+impl SyncBlackboard {
+    /// Create a new, empty blackboard with a default number of shards. 
+    pub fn new() -> SyncBlackboard {
+        SyncBlackboard::with_shards(16)
+    }
+
+    /// Create a new, empty blackboard with the given number of shards, or 
+    /// one shard if zero is given. 
+    pub fn with_shards(shards: usize) -> SyncBlackboard {
+        SyncBlackboard {
+            shards: (0..shards.max(1)).map(|_| RwLock::new(HashMap::new()))
+                .collect(),
+            revision: AtomicU64::new(0)
+        }
+    }
+
+    fn shard(&self, name: &str) -> &RwLock<HashMap<String, SyncEntry>> {
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        let index = (hasher.finish() % self.shards.len() as u64) as usize;
+        &self.shards[index]
+    }
+
+    /// Get a copy of the value under the given key, if there is one of the 
+    /// type of the key. 
+    pub fn get<T>(&self, key: &Key<T>) -> Option<T> where 
+        T: Clone + 'static
+    {
+        self.with(key, T::clone)
+    }
+
+    /// Apply the given function to a reference to the value under the given 
+    /// key, if there is one of the type of the key. The shard of the entry 
+    /// stays locked while the function runs. 
+    pub fn with<T, R, F>(&self, key: &Key<T>, func: F) -> Option<R> where 
+        T: 'static,
+        F: FnOnce(&T) -> R
+    {
+        let shard = self.shard(key.name()).read().unwrap();
+        shard.get(key.name())
+            .and_then(|e| (&*e.value as &Any).downcast_ref::<T>())
+            .map(func)
+    }
+
+    /// Set the value under the given key, returning the version it was 
+    /// stamped with. 
+    pub fn set<T>(&self, key: &Key<T>, value: T) -> u64 where 
+        T: Send + Sync + 'static
+    {
+        let mut shard = self.shard(key.name()).write().unwrap();
+        let version = self.revision.fetch_add(1, Ordering::SeqCst) + 1;
+        shard.insert(key.name().to_owned(), SyncEntry {
+            value: Box::new(value),
+            version: version
+        });
+        version
+    }
+
+    /// Remove the value under the given key, returning it if it was of the 
+    /// type of the key. 
+    pub fn remove<T>(&self, key: &Key<T>) -> Option<T> where 
+        T: 'static
+    {
+        let mut shard = self.shard(key.name()).write().unwrap();
+        shard.remove(key.name())
+            .and_then(|e| (e.value as Box<Any>).downcast::<T>().ok())
+            .map(|v| *v)
+    }
+
+    /// Check whether there is a value of the type of the key under it. 
+    pub fn contains<T>(&self, key: &Key<T>) -> bool where 
+        T: 'static
+    {
+        self.with(key, |_| ()).is_some()
+    }
+
+    /// Get the version the entry under the given key was last written with, 
+    /// if there is one. 
+    pub fn version<T>(&self, key: &Key<T>) -> Option<u64> {
+        let shard = self.shard(key.name()).read().unwrap();
+        shard.get(key.name()).map(|e| e.version)
+    }
+
+    /// Get the version of the latest write to the blackboard. 
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::SeqCst)
+    }
+
+    /// Get the number of entries on the blackboard. 
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|s| s.read().unwrap().len()).sum()
+    }
+
+    /// Check whether the blackboard has no entries. 
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|s| s.read().unwrap().is_empty())
+    }
+}
+
+impl Default for SyncBlackboard {
+    fn default() -> SyncBlackboard {
+        SyncBlackboard::new()
+    }
+}
+
+impl fmt::Debug for SyncBlackboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = self.shards.iter()
+            .flat_map(|s| s.read().unwrap().keys().cloned().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        names.sort();
+        f.debug_struct("SyncBlackboard")
+            .field("entries", &names)
+            .field("revision", &self.revision())
+            .finish()
+    }
+}
+
+/// Blackboards of many instances of the same tree, each instance getting a 
+/// separate partition, all over one shared global section. 
+///
+/// The blackboard of an instance is a scope of the global blackboard, so 
+/// names missing from the instance fall back to the global section, and 
+/// names starting with the global prefix, "global." by default, are read 
+/// and written on the global section with the prefix stripped. This lets 
+/// the same tree definition refer to both kinds of entries by name, without 
+/// knowing which instance it runs for. 
+#[derive(Debug)]
+pub struct InstancedBlackboard<K> where 
+    K: Hash + Eq
+{
+    global: Rc<Blackboard>,
+    prefix: String,
+    instances: HashMap<K, Rc<Blackboard>>
+}
+
+impl<K> InstancedBlackboard<K> where 
+    K: Hash + Eq
+{
+    /// Create new instanced blackboards over the given global blackboard. 
+    pub fn new(global: Rc<Blackboard>) -> InstancedBlackboard<K> {
+        InstancedBlackboard::with_prefix("global.", global)
+    }
+
+    /// Create new instanced blackboards over the given global blackboard, 
+    /// with the given prefix for names on the global section. 
+    pub fn with_prefix<S>(prefix: S, global: Rc<Blackboard>) -> 
+        InstancedBlackboard<K> where 
+        S: Into<String>
+    {
+        InstancedBlackboard {
+            global: global,
+            prefix: prefix.into(),
+            instances: HashMap::new()
+        }
+    }
+
+    /// Get the global blackboard. 
+    pub fn global(&self) -> &Rc<Blackboard> {
+        &self.global
+    }
+
+    /// Get the blackboard of the given instance, creating it if there is 
+    /// none yet. 
+    pub fn instance(&mut self, id: K) -> Rc<Blackboard> {
+        let global = &self.global;
+        let prefix = &self.prefix;
+        self.instances.entry(id).or_insert_with(|| {
+            Rc::new(Blackboard::scoped(global.clone())
+                .remap_prefix(prefix.clone(), ""))
+        }).clone()
+    }
+
+    /// Get the blackboard of the given instance, if it has been created. 
+    pub fn get(&self, id: &K) -> Option<&Rc<Blackboard>> {
+        self.instances.get(id)
+    }
+
+    /// Remove the blackboard of the given instance, returning it if there 
+    /// was one. 
+    pub fn remove(&mut self, id: &K) -> Option<Rc<Blackboard>> {
+        self.instances.remove(id)
+    }
+
+    /// Get the number of instances. 
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Check whether there are no instances. 
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+/// Trait for inputs which give access to a blackboard, used by the nodes 
+/// reading and writing blackboard entries. 
+pub trait AsBlackboard {
+    /// Get a reference to the blackboard. 
+    fn blackboard(&self) -> &Blackboard;
+}
+
+impl AsBlackboard for Blackboard {
+    fn blackboard(&self) -> &Blackboard {
+        self
+    }
+}
+
+impl<'a, B> AsBlackboard for &'a B where 
+    B: AsBlackboard + ?Sized
+{
+    fn blackboard(&self) -> &Blackboard {
+        (**self).blackboard()
+    }
+}
+
+/// Standard input bundling a reference to the world an agent acts in with 
+/// a reference to its blackboard. 
+#[derive(Debug)]
+pub struct Context<'a, W> where 
+    W: 'a
+{
+    /// The world the agent acts in. 
+    pub world: &'a W,
+    /// The blackboard of the agent. 
+    pub blackboard: &'a Blackboard
+}
+
+impl<'a, W> Context<'a, W> {
+    /// Bundle the given world and blackboard. 
+    pub fn new(world: &'a W, blackboard: &'a Blackboard) -> Context<'a, W> {
+        Context {
+            world: world,
+            blackboard: blackboard
+        }
+    }
+}
+
+impl<'a, W> Clone for Context<'a, W> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, W> Copy for Context<'a, W> {}
+
+impl<'a, W> AsBlackboard for Context<'a, W> {
+    fn blackboard(&self) -> &Blackboard {
+        self.blackboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackboard::{Blackboard, Key};
+
+    #[test]
+    fn blackboard_test() {
+        let board = Blackboard::new();
+        let health = Key::<i64>::new("health");
+        let name = Key::<String>::new("name");
+        assert_eq!(board.get(&health), Option::None);
+        assert_eq!(board.set(&health, 20), 1);
+        assert_eq!(board.set(&name, "Ferris".to_owned()), 2);
+        assert_eq!(board.get(&health), Option::Some(20));
+        assert_eq!(board.with(&name, |n| n.len()), Option::Some(6));
+        assert_eq!(board.version(&health), Option::Some(1));
+        board.set(&health, 15);
+        assert_eq!(board.version(&health), Option::Some(3));
+        assert_eq!(board.revision(), 3);
+        assert_eq!(board.remove(&health), Option::Some(15));
+        assert!(!board.contains(&health));
+        assert_eq!(board.len(), 1);
+    }
+
+    #[test]
+    fn blackboard_type_test() {
+        let board = Blackboard::new();
+        board.set(&Key::<i64>::new("value"), 3);
+        // A key of another type does not see the entry
+        assert_eq!(board.get(&Key::<u8>::new("value")), Option::None);
+        assert!(board.contains(&Key::<i64>::new("value")));
+    }
+
+    #[test]
+    fn blackboard_nested_write_test() {
+        let board = Blackboard::new();
+        let health = Key::<i64>::new("health");
+        let doubled = Key::<i64>::new("doubled");
+        board.set(&health, 4);
+        // Writing from within a read does not conflict with the read
+        board.with(&health, |h| {
+            board.set(&doubled, h * 2);
+            board.set(&health, h + 1);
+        });
+        assert_eq!(board.get(&doubled), Option::Some(8));
+        assert_eq!(board.get(&health), Option::Some(5));
+        assert_eq!(board.with(&health, |_| board.remove(&health)), 
+            Option::Some(Option::None));
+        assert!(!board.contains(&health));
+    }
+
+    #[test]
+    fn blackboard_scope_test() {
+        use std::rc::Rc;
+        let root = Rc::new(Blackboard::new());
+        let target = Key::<i64>::new("target");
+        let goal = Key::<i64>::new("goal");
+        root.set(&target, 1);
+        root.set(&goal, 5);
+        let left = Blackboard::scoped(root.clone());
+        let right = Blackboard::scoped(root.clone()).remap("result", "right");
+        // Missing names fall back to the parent
+        assert_eq!(left.get(&target), Option::Some(1));
+        // Writes stay in the scope, shadowing the parent
+        left.set(&target, 2);
+        right.set(&target, 3);
+        assert_eq!(left.get(&target), Option::Some(2));
+        assert_eq!(right.get(&target), Option::Some(3));
+        assert_eq!(root.get(&target), Option::Some(1));
+        // Remapped names go through to the parent
+        let result = Key::<i64>::new("result");
+        assert_eq!(right.set(&result, 9), 5);
+        assert_eq!(root.get(&Key::<i64>::new("right")), Option::Some(9));
+        assert_eq!(right.version(&result), Option::Some(5));
+        assert_eq!(right.remove(&goal), Option::None);
+        assert_eq!(right.get(&goal), Option::Some(5));
+        assert_eq!(root.revision(), 5);
+        assert_eq!(right.len(), 1);
+    }
+
+    #[test]
+    fn blackboard_ttl_test() {
+        use blackboard::{ReadError, Ttl};
+        use clock::ManualClock;
+        use std::time::Duration;
+        let clock = ManualClock::new();
+        let board = Blackboard::with_clock(clock.clone());
+        let seen = Key::<i64>::new("seen");
+        let heard = Key::<i64>::new("heard");
+        board.set_ttl(&seen, 1, Ttl::Ticks(2));
+        board.set_ttl(&heard, 2, Ttl::Time(Duration::from_millis(100)));
+        board.tick();
+        assert_eq!(board.get_fresh(&seen), Result::Ok(1));
+        board.tick();
+        assert_eq!(board.get_fresh(&seen), Result::Err(ReadError::Stale));
+        assert!(board.is_stale(&seen));
+        // Plain reads still see stale entries
+        assert_eq!(board.get(&seen), Option::Some(1));
+        assert_eq!(board.get_fresh(&heard), Result::Ok(2));
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(board.get_fresh(&heard), Result::Err(ReadError::Stale));
+        assert_eq!(board.get_fresh(&Key::<i64>::new("smelled")), 
+            Result::Err(ReadError::Missing));
+        // Rewriting the entry makes it fresh again
+        board.set_ttl(&seen, 3, Ttl::Ticks(1));
+        assert_eq!(board.get_fresh(&seen), Result::Ok(3));
+    }
+
+    #[test]
+    fn blackboard_derive_test() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        let board = Blackboard::new();
+        let hp = Key::<i64>::new("hp");
+        let armor = Key::<i64>::new("armor");
+        let toughness = Key::<i64>::new("toughness");
+        let computed = Rc::new(Cell::new(0));
+        let counter = computed.clone();
+        board.set(&hp, 10);
+        board.set(&armor, 5);
+        board.derive(&toughness, &["hp", "armor"], move |b| {
+            counter.set(counter.get() + 1);
+            b.get(&Key::<i64>::new("hp")).unwrap_or(0) * 
+                b.get(&Key::<i64>::new("armor")).unwrap_or(0)
+        });
+        assert_eq!(board.get(&toughness), Option::Some(50));
+        assert_eq!(board.get(&toughness), Option::Some(50));
+        assert_eq!(computed.get(), 1);
+        let version = board.version(&toughness);
+        board.set(&armor, 2);
+        assert_eq!(board.get(&toughness), Option::Some(20));
+        assert_eq!(computed.get(), 2);
+        assert!(board.version(&toughness) > version);
+    }
+
+    #[test]
+    fn instanced_blackboard_test() {
+        use blackboard::InstancedBlackboard;
+        use std::rc::Rc;
+        let mut boards = InstancedBlackboard::new(Rc::new(Blackboard::new()));
+        let target = Key::<i64>::new("target");
+        let alarm = Key::<bool>::new("global.alarm");
+        boards.global().set(&Key::<i64>::new("target"), 0);
+        let first = boards.instance(1u32);
+        let second = boards.instance(2u32);
+        first.set(&target, 4);
+        assert_eq!(first.get(&target), Option::Some(4));
+        assert_eq!(second.get(&target), Option::Some(0));
+        second.set(&alarm, true);
+        assert_eq!(first.get(&alarm), Option::Some(true));
+        assert_eq!(boards.global().get(&Key::<bool>::new("alarm")), 
+            Option::Some(true));
+        assert!(Rc::ptr_eq(&boards.instance(1), &first));
+        assert_eq!(boards.len(), 2);
+    }
+
+    #[test]
+    fn blackboard_journal_test() {
+        use blackboard::{MutationKind, JournalDiff};
+        let board = Blackboard::new();
+        let hp = Key::<i64>::new("hp");
+        let ammo = Key::<i64>::new("ammo");
+        board.set(&hp, 1);
+        assert!(!board.is_journaling());
+        board.start_journal();
+        board.set(&hp, 10);
+        board.tick();
+        let middle = board.journal_position();
+        board.set(&ammo, 3);
+        board.set(&hp, 8);
+        board.remove(&ammo);
+        let end = board.journal_position();
+        let recorded = board.journal(middle, end);
+        assert_eq!(recorded.len(), 3);
+        assert_eq!(recorded[0].name, "ammo");
+        assert_eq!(recorded[0].tick, 1);
+        assert_eq!(recorded[2].kind, MutationKind::Remove);
+        assert_eq!(board.journal_diff(middle, end), JournalDiff {
+            written: vec![("hp".to_owned(), 4)],
+            removed: vec!["ammo".to_owned()]
+        });
+        assert_eq!(board.stop_journal().map(|j| j.len()), Option::Some(4));
+    }
+
+    #[test]
+    fn sync_blackboard_test() {
+        use blackboard::SyncBlackboard;
+        use std::sync::Arc;
+        use std::thread;
+        let board = Arc::new(SyncBlackboard::with_shards(4));
+        let handles = (0..4i64).map(|i| {
+            let board = board.clone();
+            thread::spawn(move || {
+                board.set(&Key::<i64>::new(format!("worker{}", i)), i * 10);
+            })
+        }).collect::<Vec<_>>();
+        for h in handles {
+            h.join().unwrap();
+        }
+        assert_eq!(board.len(), 4);
+        assert_eq!(board.revision(), 4);
+        assert_eq!(board.get(&Key::<i64>::new("worker2")), Option::Some(20));
+        assert_eq!(board.get(&Key::<u8>::new("worker2")), Option::None);
+        assert_eq!(board.remove(&Key::<i64>::new("worker3")), Option::Some(30));
+        assert!(!board.contains(&Key::<i64>::new("worker3")));
+    }
+}
\ No newline at end of file
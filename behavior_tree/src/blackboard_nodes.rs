@@ -0,0 +1,563 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use blackboard::{AsBlackboard, Blackboard, Context, Key};
+use map_wrappers::InputProjectedNode;
+use serial_node::{SerialDecider, NontermDecision, TermDecision};
+use std::marker::PhantomData;
+
+/// Wrapper for a node, which writes the nonterminals and the terminal of 
+/// the node to the configured blackboard keys as they are emitted, passing 
+/// them through unchanged. 
+///
+/// Either key can be left out, in which case the corresponding values are 
+/// not written. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct BlackboardWrite<N> where 
+    N: BehaviorTreeNode,
+    N::Input: AsBlackboard,
+    N::Nonterminal: Clone + 'static,
+    N::Terminal: Clone + 'static
+{
+    node: N,
+    nonterminal_key: Option<Key<N::Nonterminal>>,
+    terminal_key: Option<Key<N::Terminal>>
+}
+
+impl<N> BlackboardWrite<N> where 
+    N: BehaviorTreeNode,
+    N::Input: AsBlackboard,
+    N::Nonterminal: Clone + 'static,
+    N::Terminal: Clone + 'static
+{
+    /// Create a new blackboard writing wrapper. 
+    pub fn new(nonterminal_key: Option<Key<N::Nonterminal>>,
+        terminal_key: Option<Key<N::Terminal>>, node: N) -> BlackboardWrite<N>
+    {
+        BlackboardWrite {
+            node: node,
+            nonterminal_key: nonterminal_key,
+            terminal_key: terminal_key
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for BlackboardWrite<N> where 
+    N: BehaviorTreeNode,
+    N::Input: AsBlackboard,
+    N::Nonterminal: Clone + 'static,
+    N::Terminal: Clone + 'static
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        N::Terminal, Self>
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                if let Option::Some(ref k) = self.nonterminal_key {
+                    input.blackboard().set(k, n.clone());
+                }
+                NodeResult::Nonterminal(n, BlackboardWrite {
+                    node: m,
+                    nonterminal_key: self.nonterminal_key,
+                    terminal_key: self.terminal_key
+                })
+            },
+            NodeResult::Terminal(t) => {
+                if let Option::Some(ref k) = self.terminal_key {
+                    input.blackboard().set(k, t.clone());
+                }
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Serial decider wrapper, which writes the terminals of the configured 
+/// children to blackboard keys as they terminate, before passing them on to 
+/// the wrapped decider, so that the children run after them can read them. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Dataflow<D> where 
+    D: SerialDecider,
+    D::Enum: Copy + PartialEq,
+    D::Input: AsBlackboard,
+    D::Term: Clone + 'static
+{
+    decider: D,
+    wires: Vec<(D::Enum, Key<D::Term>)>
+}
+
+impl<D> Dataflow<D> where 
+    D: SerialDecider,
+    D::Enum: Copy + PartialEq,
+    D::Input: AsBlackboard,
+    D::Term: Clone + 'static
+{
+    /// Create a new dataflow decider with no wired children. 
+    pub fn new(decider: D) -> Dataflow<D> {
+        Dataflow {
+            decider: decider,
+            wires: Vec::new()
+        }
+    }
+
+    /// Write the terminals of the given child to the given key. A child can 
+    /// be wired to several keys. 
+    pub fn wire(self, child: D::Enum, key: Key<D::Term>) -> Dataflow<D> {
+        let mut mut_self = self;
+        mut_self.wires.push((child, key));
+        mut_self
+    }
+}
+
+impl<D> SerialDecider for Dataflow<D> where 
+    D: SerialDecider,
+    D::Enum: Copy + PartialEq,
+    D::Input: AsBlackboard,
+    D::Term: Clone + 'static
+{
+    type Enum = D::Enum;
+    type Input = D::Input;
+    type Nonterm = D::Nonterm;
+    type Term = D::Term;
+    type Exit = D::Exit;
+
+    fn on_nonterminal(&self, input: &D::Input, ord: D::Enum, 
+        statept: D::Nonterm) -> NontermDecision<D::Enum, D::Nonterm, D::Exit>
+    {
+        self.decider.on_nonterminal(input, ord, statept)
+    }
+
+    fn on_terminal(&self, input: &D::Input, ord: D::Enum, statept: D::Term) -> 
+        TermDecision<D::Enum, D::Term, D::Exit>
+    {
+        for &(child, ref key) in self.wires.iter() {
+            if child == ord {
+                input.blackboard().set(key, statept.clone());
+            }
+        }
+        self.decider.on_terminal(input, ord, statept)
+    }
+}
+
+fn always<T>(_value: &T) -> bool {
+    true
+}
+
+/// Leaf node watching a blackboard key, which waits until the entry under 
+/// the key is written with a value satisfying a predicate, then terminates 
+/// with a copy of that value. 
+///
+/// The version of the entry is remembered between steps, so the predicate 
+/// is only evaluated again once the entry has been written since, rather 
+/// than comparing the values on every step. 
+#[derive(PartialEq, Debug)]
+pub struct WatchKey<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool
+{
+    key: Key<T>,
+    predicate: P,
+    seen: Option<u64>,
+    skip_first: bool,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I, T, P> Clone for WatchKey<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool + Clone
+{
+    fn clone(&self) -> Self {
+        WatchKey {
+            key: self.key.clone(),
+            predicate: self.predicate.clone(),
+            seen: self.seen,
+            skip_first: self.skip_first,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T, P> WatchKey<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool
+{
+    /// Create a new watch, which terminates as soon as the entry holds a 
+    /// value satisfying the predicate, including on the first step. 
+    pub fn new(key: Key<T>, predicate: P) -> WatchKey<I, T, P> {
+        WatchKey {
+            key: key,
+            predicate: predicate,
+            seen: Option::None,
+            skip_first: false,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T> WatchKey<I, T, fn(&T) -> bool> where 
+    I: AsBlackboard,
+    T: Clone + 'static
+{
+    /// Create a new watch, which terminates on the first write to the entry 
+    /// after the first step. 
+    pub fn changed(key: Key<T>) -> WatchKey<I, T, fn(&T) -> bool> {
+        WatchKey {
+            key: key,
+            predicate: always::<T>,
+            seen: Option::None,
+            skip_first: true,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T, P> BehaviorTreeNode for WatchKey<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), T, Self> {
+        let board = input.blackboard();
+        let version = board.version(&self.key);
+        if self.skip_first {
+            return NodeResult::Nonterminal((), WatchKey {
+                seen: version,
+                skip_first: false,
+                .. self
+            });
+        }
+        if version.is_some() && version != self.seen {
+            if let Option::Some(v) = board.get(&self.key) {
+                if (self.predicate)(&v) {
+                    return NodeResult::Terminal(v);
+                }
+            }
+        }
+        NodeResult::Nonterminal((), WatchKey {
+            seen: version,
+            .. self
+        })
+    }
+}
+
+/// Leaf node testing a predicate on the value under a blackboard key, which 
+/// succeeds if the value is fresh and satisfies the predicate, and fails if 
+/// the value is missing, stale, or does not satisfy it. 
+#[derive(PartialEq, Debug)]
+pub struct FreshCondition<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool
+{
+    key: Key<T>,
+    predicate: P,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I, T, P> Clone for FreshCondition<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool + Clone
+{
+    fn clone(&self) -> Self {
+        FreshCondition::new(self.key.clone(), self.predicate.clone())
+    }
+}
+
+impl<I, T, P> FreshCondition<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool
+{
+    /// Create a new fresh condition. 
+    pub fn new(key: Key<T>, predicate: P) -> FreshCondition<I, T, P> {
+        FreshCondition {
+            key: key,
+            predicate: predicate,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T, P> BehaviorTreeNode for FreshCondition<I, T, P> where 
+    I: AsBlackboard,
+    T: Clone + 'static,
+    P: Fn(&T) -> bool
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<(), ()>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), Result<(), ()>, Self> {
+        match input.blackboard().get_fresh(&self.key) {
+            Result::Ok(ref v) if (self.predicate)(v) => {
+                NodeResult::Terminal(Result::Ok(()))
+            },
+            _ => NodeResult::Terminal(Result::Err(()))
+        }
+    }
+}
+
+fn world_of<'a, 'b, W>(context: &'b Context<'a, W>) -> &'b W {
+    context.world
+}
+
+fn blackboard_of<'a, 'b, W>(context: &'b Context<'a, W>) -> &'b Blackboard {
+    context.blackboard
+}
+
+/// Node taking only the world, adapted to take a whole context. 
+pub type OnWorld<'a, W, N> = InputProjectedNode<N, 
+    for<'b> fn(&'b Context<'a, W>) -> &'b W, Context<'a, W>>;
+
+/// Node taking only the blackboard, adapted to take a whole context. 
+pub type OnBlackboard<'a, W, N> = InputProjectedNode<N, 
+    for<'b> fn(&'b Context<'a, W>) -> &'b Blackboard, Context<'a, W>>;
+
+/// Adapt a node taking only the world to take a whole context. 
+pub fn on_world<'a, W, N>(node: N) -> OnWorld<'a, W, N> where 
+    N: BehaviorTreeNode<Input=W>
+{
+    InputProjectedNode::new(world_of, node)
+}
+
+/// Adapt a node taking only the blackboard to take a whole context. 
+pub fn on_blackboard<'a, W, N>(node: N) -> OnBlackboard<'a, W, N> where 
+    N: BehaviorTreeNode<Input=Blackboard>
+{
+    InputProjectedNode::new(blackboard_of, node)
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use blackboard::{Blackboard, Key};
+    use blackboard_nodes::BlackboardWrite;
+    use base_nodes::Evaluation;
+
+    #[test]
+    fn blackboard_write_test() {
+        let board = Blackboard::new();
+        let progress = Key::<i64>::new("progress");
+        let result = Key::<i64>::new("result");
+        let base_node = PredicateWait::new(|input: &Blackboard| {
+            let count = input.get(&Key::<i64>::new("count")).unwrap_or(0);
+            if count < 2 {
+                Statepoint::Nonterminal(count)
+            } else {
+                Statepoint::Terminal(count * 10)
+            }
+        });
+        let test_node = BlackboardWrite::new(Option::Some(progress.clone()),
+            Option::Some(result.clone()), base_node);
+        let test_node_1 = match test_node.step(&board) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(board.get(&progress), Option::Some(0));
+        assert_eq!(board.get(&result), Option::None);
+        board.set(&Key::new("count"), 2i64);
+        match test_node_1.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, 20),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(board.get(&result), Option::Some(20));
+    }
+
+    #[test]
+    fn context_projection_test() {
+        use blackboard::Context;
+        use blackboard_nodes::{on_world, on_blackboard};
+        let board = Blackboard::new();
+        let target = Key::<i64>::new("target");
+        board.set(&target, 7);
+        let world = 3i64;
+        let context = Context::new(&world, &board);
+        match on_world(Evaluation::new(|w: &i64| *w * 2)).step(&context) {
+            NodeResult::Terminal(t) => assert_eq!(t, 6),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let read = Evaluation::new(move |b: &Blackboard| b.get(&target));
+        match on_blackboard::<i64, _>(read).step(&context) {
+            NodeResult::Terminal(t) => assert_eq!(t, Option::Some(7)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn watch_key_test() {
+        use blackboard_nodes::WatchKey;
+        let board = Blackboard::new();
+        let alarm = Key::<i64>::new("alarm");
+        board.set(&alarm, 1);
+        let test_node = WatchKey::<Blackboard, _, _>::new(alarm.clone(),
+            |v: &i64| *v > 2);
+        let test_node_1 = match test_node.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        board.set(&alarm, 3);
+        match test_node_1.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, 3),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn watch_key_changed_test() {
+        use blackboard_nodes::WatchKey;
+        let board = Blackboard::new();
+        let alarm = Key::<i64>::new("alarm");
+        board.set(&alarm, 1);
+        let test_node = WatchKey::<Blackboard, _, _>::changed(alarm.clone());
+        let test_node_1 = match test_node.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // Writing the same value again is still a change
+        board.set(&alarm, 1);
+        match test_node_2.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, 1),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn dataflow_test() {
+        use blackboard_nodes::Dataflow;
+        use sequence_node::SerialSequence;
+        use serial_node::{EnumNode, SerialBranchNode};
+        use num_derive::{FromPrimitive, ToPrimitive};
+
+        type Leaf = Evaluation<Blackboard, Result<i64, ()>, 
+            fn(&Blackboard) -> Result<i64, ()>>;
+
+        #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+        enum StepEnum {
+            Produce,
+            Consume
+        }
+
+        enum Steps {
+            Produce(Leaf),
+            Consume(Leaf)
+        }
+
+        fn produce(_board: &Blackboard) -> Result<i64, ()> {
+            Result::Ok(3)
+        }
+
+        fn consume(board: &Blackboard) -> Result<i64, ()> {
+            board.get(&Key::<Result<i64, ()>>::new("produced"))
+                .unwrap_or(Result::Err(()))
+                .map(|v| v * 2)
+        }
+
+        impl BehaviorTreeNode for Steps {
+            type Input = Blackboard;
+            type Nonterminal = ();
+            type Terminal = Result<i64, ()>;
+
+            fn step(self, input: &Blackboard) -> NodeResult<(), 
+                Result<i64, ()>, Self> 
+            {
+                match self {
+                    Steps::Produce(n) => match n.step(input) {
+                        NodeResult::Nonterminal(r, m) => {
+                            NodeResult::Nonterminal(r, Steps::Produce(m))
+                        },
+                        NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                    },
+                    Steps::Consume(n) => match n.step(input) {
+                        NodeResult::Nonterminal(r, m) => {
+                            NodeResult::Nonterminal(r, Steps::Consume(m))
+                        },
+                        NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                    }
+                }
+            }
+        }
+
+        impl EnumNode for Steps {
+            type Discriminant = StepEnum;
+
+            fn new(thing: StepEnum) -> Steps {
+                match thing {
+                    StepEnum::Produce => {
+                        Steps::Produce(Evaluation::new(produce))
+                    },
+                    StepEnum::Consume => {
+                        Steps::Consume(Evaluation::new(consume))
+                    }
+                }
+            }
+
+            fn discriminant_of(&self) -> StepEnum {
+                match *self {
+                    Steps::Produce(_) => StepEnum::Produce,
+                    Steps::Consume(_) => StepEnum::Consume
+                }
+            }
+        }
+
+        let board = Blackboard::new();
+        let decider = Dataflow::new(SerialSequence::new())
+            .wire(StepEnum::Produce, Key::new("produced"));
+        let test_node = SerialBranchNode::<Steps, _>::new(decider, 
+            StepEnum::Produce);
+        let test_node_1 = match test_node.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(board.get(&Key::<Result<i64, ()>>::new("produced")), 
+            Option::Some(Result::Ok(3)));
+        match test_node_1.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(6)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn fresh_condition_test() {
+        use blackboard::Ttl;
+        use blackboard_nodes::FreshCondition;
+        let board = Blackboard::new();
+        let distance = Key::<i64>::new("distance");
+        let close = FreshCondition::<Blackboard, _, _>::new(distance.clone(), 
+            |d: &i64| *d < 10);
+        board.set_ttl(&distance, 4, Ttl::Ticks(1));
+        match close.clone().step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+        board.tick();
+        match close.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
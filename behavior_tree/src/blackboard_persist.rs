@@ -0,0 +1,209 @@
+use blackboard::{Blackboard, Key};
+use checksum::{self, ChecksumError};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{self, Value};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Saved entries of a blackboard, in a form which can itself be serialized 
+/// to any format supported by serde, such as alongside a snapshot of the 
+/// state of a tree. 
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct BlackboardState {
+    /// The saved values, under the names of their entries. 
+    pub entries: BTreeMap<String, Value>
+}
+
+impl BlackboardState {
+    /// Write the saved entries as JSON, followed by a checksum. 
+    pub fn to_bytes(&self) -> Vec<u8> {
+        checksum::seal(serde_json::to_vec(self)
+            .expect("Blackboard states always serialize"))
+    }
+
+    /// Read saved entries written by `to_bytes`, verifying their checksum 
+    /// first. 
+    pub fn from_bytes(bytes: &[u8]) -> Result<BlackboardState, PersistError> {
+        let contents = checksum::unseal(bytes).map_err(PersistError::Checksum)?;
+        serde_json::from_slice(contents).map_err(PersistError::Encoding)
+    }
+}
+
+/// Error of saving or restoring a blackboard. 
+#[derive(Debug)]
+pub enum PersistError {
+    /// The value with the given name failed to be converted. 
+    Value(String, serde_json::Error),
+    /// The saved state has a value with the given name, which is not 
+    /// registered. 
+    Unregistered(String),
+    /// The checksum of the written state does not match, as the bytes were 
+    /// truncated or corrupted. 
+    Checksum(ChecksumError),
+    /// The written state failed to be parsed. 
+    Encoding(serde_json::Error)
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PersistError::Value(ref n, ref e) => write!(f, "entry {}: {}", 
+                n, e),
+            PersistError::Unregistered(ref n) => write!(f,
+                "entry {} is not registered", n),
+            PersistError::Checksum(ref e) => write!(f, "corrupt state: {}", e),
+            PersistError::Encoding(ref e) => write!(f, "invalid state: {}", e)
+        }
+    }
+}
+
+type SaveFn = fn(&Any) -> Option<Result<Value, serde_json::Error>>;
+type LoadFn = fn(Value) -> Result<Box<Any>, serde_json::Error>;
+
+fn save_as<T>(value: &Any) -> Option<Result<Value, serde_json::Error>> where 
+    T: Serialize + 'static
+{
+    value.downcast_ref::<T>().map(serde_json::to_value)
+}
+
+fn load_as<T>(value: Value) -> Result<Box<Any>, serde_json::Error> where 
+    T: DeserializeOwned + 'static
+{
+    serde_json::from_value::<T>(value).map(|v| Box::new(v) as Box<Any>)
+}
+
+/// Registry of the blackboard keys whose entries are saved and restored, 
+/// along with the conversions of their values. 
+///
+/// Entries under names which are not registered, or which hold a value of 
+/// another type than the registered key, are left out of the saved state. 
+/// Only the entries of the blackboard itself are saved, not those of the 
+/// parent of a scope. 
+#[derive(Clone, Default)]
+pub struct Persistence {
+    codecs: HashMap<String, (SaveFn, LoadFn)>
+}
+
+impl Persistence {
+    /// Create a new registry with no keys. 
+    pub fn new() -> Persistence {
+        Persistence::default()
+    }
+
+    /// Register the given key. 
+    pub fn register<T>(self, key: &Key<T>) -> Persistence where 
+        T: Serialize + DeserializeOwned + 'static
+    {
+        let mut mut_self = self;
+        mut_self.codecs.insert(key.name().to_owned(),
+            (save_as::<T>, load_as::<T>));
+        mut_self
+    }
+
+    /// Save the entries of the blackboard under the registered keys. 
+    pub fn save(&self, board: &Blackboard) -> Result<BlackboardState,
+        PersistError>
+    {
+        let mut entries = BTreeMap::new();
+        for (name, codec) in self.codecs.iter() {
+            match board.with_local(name, codec.0).and_then(|r| r) {
+                Option::Some(Result::Ok(v)) => {
+                    entries.insert(name.clone(), v);
+                },
+                Option::Some(Result::Err(e)) => {
+                    return Result::Err(PersistError::Value(name.clone(), e));
+                },
+                Option::None => ()
+            }
+        }
+        Result::Ok(BlackboardState {
+            entries: entries
+        })
+    }
+
+    /// Restore the saved entries onto the blackboard, overwriting the 
+    /// entries under the same names. The restored entries are stamped with 
+    /// new versions. Nothing is written if any of the entries fails to be 
+    /// restored. 
+    pub fn restore(&self, board: &Blackboard, state: BlackboardState) ->
+        Result<(), PersistError>
+    {
+        let mut values = Vec::with_capacity(state.entries.len());
+        for (name, value) in state.entries.into_iter() {
+            let load = match self.codecs.get(&name) {
+                Option::Some(c) => c.1,
+                Option::None => {
+                    return Result::Err(PersistError::Unregistered(name));
+                }
+            };
+            match load(value) {
+                Result::Ok(v) => values.push((name, v)),
+                Result::Err(e) => {
+                    return Result::Err(PersistError::Value(name, e));
+                }
+            }
+        }
+        for (name, value) in values.into_iter() {
+            board.insert_local(&name, value, Option::None);
+        }
+        Result::Ok(())
+    }
+}
+
+impl fmt::Debug for Persistence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names = self.codecs.keys().collect::<Vec<_>>();
+        names.sort();
+        f.debug_struct("Persistence")
+            .field("keys", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackboard::{Blackboard, Key};
+    use blackboard_persist::{BlackboardState, Persistence, PersistError};
+    use checksum::ChecksumError;
+
+    #[test]
+    fn persistence_test() {
+        let health = Key::<i64>::new("health");
+        let name = Key::<String>::new("name");
+        let board = Blackboard::new();
+        board.set(&health, 20);
+        board.set(&name, "Ferris".to_owned());
+        board.set(&Key::<u8>::new("scratch"), 1);
+        let persistence = Persistence::new().register(&health).register(&name);
+        let state = persistence.save(&board).unwrap();
+        assert_eq!(state.entries.len(), 2);
+        let restored = Blackboard::new();
+        persistence.restore(&restored, state.clone()).unwrap();
+        assert_eq!(restored.get(&health), Option::Some(20));
+        assert_eq!(restored.get(&name), Option::Some("Ferris".to_owned()));
+        match Persistence::new().register(&health).restore(&restored, state) {
+            Result::Err(PersistError::Unregistered(n)) => assert_eq!(n, "name"),
+            _ => unreachable!("Expected unregistered entry")
+        };
+    }
+
+    #[test]
+    fn persistence_checksum_test() {
+        let health = Key::<i64>::new("health");
+        let board = Blackboard::new();
+        board.set(&health, 20);
+        let state = Persistence::new().register(&health).save(&board)
+            .unwrap();
+        let bytes = state.to_bytes();
+        assert_eq!(BlackboardState::from_bytes(&bytes).unwrap(), state);
+        let mut corrupt = bytes.clone();
+        corrupt[3] = b'X';
+        match BlackboardState::from_bytes(&corrupt) {
+            Result::Err(PersistError::Checksum(ChecksumError::Mismatch(..))) =>
+                (),
+            _ => unreachable!("Expected checksum mismatch")
+        };
+    }
+}
\ No newline at end of file
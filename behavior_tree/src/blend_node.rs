@@ -0,0 +1,255 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use parallel_join::{JoinPolicy, ParallelJoinNode};
+use std::marker::PhantomData;
+
+/// Trait for nonterminal outputs which can be blended together by weight, 
+/// such as steering forces. 
+pub trait Blend: Sized {
+    /// The neutral output, which blending starts from. 
+    fn zero() -> Self;
+    /// Add the given output, scaled by the given weight, to this one. 
+    fn add_weighted(self, other: Self, weight: f64) -> Self;
+}
+
+impl Blend for f64 {
+    fn zero() -> f64 {
+        0.0
+    }
+
+    fn add_weighted(self, other: f64, weight: f64) -> f64 {
+        self + other * weight
+    }
+}
+
+impl Blend for f32 {
+    fn zero() -> f32 {
+        0.0
+    }
+
+    fn add_weighted(self, other: f32, weight: f64) -> f32 {
+        self + other * weight as f32
+    }
+}
+
+impl<A, B> Blend for (A, B) where 
+    A: Blend,
+    B: Blend
+{
+    fn zero() -> (A, B) {
+        (A::zero(), B::zero())
+    }
+
+    fn add_weighted(self, other: (A, B), weight: f64) -> (A, B) {
+        (self.0.add_weighted(other.0, weight), self.1.add_weighted(other.1, weight))
+    }
+}
+
+impl<A, B, C> Blend for (A, B, C) where 
+    A: Blend,
+    B: Blend,
+    C: Blend
+{
+    fn zero() -> (A, B, C) {
+        (A::zero(), B::zero(), C::zero())
+    }
+
+    fn add_weighted(self, other: (A, B, C), weight: f64) -> (A, B, C) {
+        (
+            self.0.add_weighted(other.0, weight),
+            self.1.add_weighted(other.1, weight),
+            self.2.add_weighted(other.2, weight)
+        )
+    }
+}
+
+/// Trait for the weights with which the outputs of the children of a blend 
+/// node are combined. 
+pub trait BlendWeights {
+    /// Type of the inputs of the children. 
+    type Input;
+    /// Given a reference to the input and the index of a child, return the 
+    /// weight of its output, which should be nonnegative. 
+    fn weight(&self, input: &Self::Input, index: usize) -> f64;
+}
+
+/// Blend weights which are fixed, given in the order of the children. 
+/// Children without a given weight have a weight of zero. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct FixedWeights<I> {
+    weights: Box<[f64]>,
+    _junk: PhantomData<I>
+}
+
+impl<I> FixedWeights<I> {
+    /// Create fixed blend weights from the given weights. 
+    pub fn new<K>(weights: K) -> FixedWeights<I> where 
+        K: IntoIterator<Item=f64>
+    {
+        FixedWeights {
+            weights: weights.into_iter().collect::<Vec<_>>().into_boxed_slice(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I> BlendWeights for FixedWeights<I> {
+    type Input = I;
+
+    fn weight(&self, _input: &I, index: usize) -> f64 {
+        self.weights.get(index).map_or(0.0, |w| *w)
+    }
+}
+
+/// A parallel node which steps all of its running children each step, and 
+/// blends their nonterminal outputs together by weight, until its 
+/// JoinPolicy decides to exit. 
+///
+/// The weights of the children which are still running are normalized by 
+/// their total, so that the blended output is a weighted average. If no 
+/// running child has positive weight, Option::None is returned instead. 
+pub struct BlendNode<C, W, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    C::Nonterminal: Blend,
+    W: BlendWeights<Input=P::Input>,
+    P: JoinPolicy
+{
+    node: ParallelJoinNode<C, P>,
+    weights: W
+}
+
+impl<C, W, P> Clone for BlendNode<C, W, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    C::Nonterminal: Blend,
+    W: BlendWeights<Input=P::Input> + Clone,
+    P: JoinPolicy,
+    ParallelJoinNode<C, P>: Clone
+{
+    fn clone(&self) -> Self {
+        BlendNode {
+            node: self.node.clone(),
+            weights: self.weights.clone()
+        }
+    }
+}
+
+impl<C, W, P> BlendNode<C, W, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    C::Nonterminal: Blend,
+    W: BlendWeights<Input=P::Input>,
+    P: JoinPolicy
+{
+    /// Create a new blend node over the given children. 
+    pub fn new<K>(weights: W, policy: P, children: K) -> BlendNode<C, W, P> where 
+        K: IntoIterator<Item=C>
+    {
+        BlendNode {
+            node: ParallelJoinNode::new(policy, children),
+            weights: weights
+        }
+    }
+}
+
+impl<C, W, P> BehaviorTreeNode for BlendNode<C, W, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    C::Nonterminal: Blend,
+    W: BlendWeights<Input=P::Input>,
+    P: JoinPolicy
+{
+    type Input = P::Input;
+    type Nonterminal = Option<C::Nonterminal>;
+    type Terminal = P::Exit;
+
+    #[inline]
+    fn step(self, input: &P::Input) -> NodeResult<Self::Nonterminal, P::Exit,
+        Self>
+    {
+        let weights = self.weights;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(outputs, m) => {
+                let weighted = outputs.into_vec()
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(i, o)| o.map(|o| {
+                        (weights.weight(input, i).max(0.0), o)
+                    }))
+                    .collect::<Vec<_>>();
+                let total = weighted.iter().map(|&(w, _)| w).sum::<f64>();
+                let blended = if total > 0.0 {
+                    Option::Some(weighted.into_iter().fold(
+                        C::Nonterminal::zero(),
+                        |acc, (w, o)| acc.add_weighted(o, w / total)
+                    ))
+                } else {
+                    Option::None
+                };
+                NodeResult::Nonterminal(blended, BlendNode {
+                    node: m,
+                    weights: weights
+                })
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use blend_node::{BlendNode, FixedWeights};
+    use parallel_join::JoinAll;
+
+    struct Steer {
+        force: (f64, f64),
+        steps: usize
+    }
+
+    impl BehaviorTreeNode for Steer {
+        type Input = ();
+        type Nonterminal = (f64, f64);
+        type Terminal = ();
+
+        fn step(self, _input: &()) -> NodeResult<(f64, f64), (), Self> {
+            if self.steps > 0 {
+                NodeResult::Nonterminal(self.force, Steer {
+                    force: self.force,
+                    steps: self.steps - 1
+                })
+            } else {
+                NodeResult::Terminal(())
+            }
+        }
+    }
+
+    #[test]
+    fn blend_test() {
+        let test_node = BlendNode::new(
+            FixedWeights::new(vec![3.0, 1.0]),
+            JoinAll::new(),
+            vec![Steer { force: (1.0, 0.0), steps: 1 },
+                Steer { force: (0.0, 4.0), steps: 2 }]
+        );
+        let test_node_1 = match test_node.step(&()) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, Option::Some((0.75, 1.0)));
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // Only the second child is still running, so it gets all the weight
+        let test_node_2 = match test_node_1.step(&()) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, Option::Some((0.0, 4.0)));
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_2.step(&()) {
+            NodeResult::Terminal(t) => assert_eq!(t.len(), 2),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,548 @@
+//! The `.bt` format writes a tree as its root node, where each node is its 
+//! kind, followed by its parameters in parentheses, if it has any, and by 
+//! its children in braces, if it has any: 
+//!
+//! ```text
+//! // Engage the nearest enemy, or keep patrolling
+//! Selector {
+//!     Sequence {
+//!         InRange(target: "enemy", distance: 12.5)
+//!         Attack(burst: 3, aimed: true)
+//!     }
+//!     Patrol
+//! }
+//! ```
+//!
+//! Parameters are separated by commas, and children may be. Parameter 
+//! values are integers, floating point numbers, including `inf`, `-inf` and 
+//! `nan`, `true` or `false`, or double-quoted strings with the escapes `\"`, 
+//! `\\`, `\n` and `\t`. Line comments start with `//`. 
+
+use expression::Value;
+use node_registry::{DynTree, NodeRegistry};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+use tree_def::{LoadError, NodeDef};
+
+/// Error of parsing or compiling a tree in the `.bt` format, pointing at the 
+/// part of the source it is about. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct BtError {
+    /// The byte range in the source the error is about, which is empty at 
+    /// the end of the source if it ended early. 
+    pub span: Range<usize>,
+    /// What was wrong. 
+    pub message: String
+}
+
+impl BtError {
+    fn new<S>(span: Range<usize>, message: S) -> BtError where 
+        S: Into<String>
+    {
+        BtError {
+            span: span,
+            message: message.into()
+        }
+    }
+
+    /// Get the line and the column, both counted from 1, at which the error 
+    /// starts in the given source. Columns are counted in characters. 
+    pub fn line_column(&self, source: &str) -> (usize, usize) {
+        let before = &source[..self.span.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1)
+    }
+
+    /// Render the error as a diagnostic quoting the line of the given source 
+    /// it starts on, with the span underlined. 
+    pub fn render(&self, source: &str) -> String {
+        let (line, column) = self.line_column(source);
+        let line_start = source[..self.span.start].rfind('\n')
+            .map_or(0, |i| i + 1);
+        let line_end = source[line_start..].find('\n')
+            .map_or(source.len(), |i| line_start + i);
+        let text = &source[line_start..line_end];
+        let marked = source[self.span.start..self.span.end.min(line_end)]
+            .chars().count().max(1);
+        let gutter = " ".repeat(line.to_string().len());
+        format!("error: {}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.message, gutter, line, column, gutter, line, text, gutter,
+            " ".repeat(column - 1), "^".repeat(marked))
+    }
+}
+
+impl fmt::Display for BtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at bytes {}..{}", self.message, self.span.start,
+            self.span.end)
+    }
+}
+
+impl From<BtError> for LoadError {
+    fn from(error: BtError) -> LoadError {
+        LoadError::Parse(error.to_string())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Ident(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Punct(char)
+}
+
+fn skip_while<F>(chars: &mut Peekable<CharIndices>, accept: F) where 
+    F: Fn(char) -> bool
+{
+    while chars.peek().is_some_and(|&(_, c)| accept(c)) {
+        chars.next();
+    }
+}
+
+fn string(chars: &mut Peekable<CharIndices>, start: usize, end: usize) ->
+    Result<String, BtError>
+{
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Option::Some((_, '"')) => return Result::Ok(text),
+            Option::Some((i, '\\')) => match chars.next() {
+                Option::Some((_, '"')) => text.push('"'),
+                Option::Some((_, '\\')) => text.push('\\'),
+                Option::Some((_, 'n')) => text.push('\n'),
+                Option::Some((_, 't')) => text.push('\t'),
+                Option::Some((j, c)) => return Result::Err(BtError::new(
+                    i..j + c.len_utf8(), format!("unknown escape \\{}", c))),
+                Option::None => break
+            },
+            Option::Some((_, c)) => text.push(c),
+            Option::None => break
+        }
+    }
+    Result::Err(BtError::new(start..end, "unterminated string"))
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Range<usize>, Token)>, BtError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Option::Some((start, c)) = chars.next() {
+        let token = if c.is_whitespace() {
+            continue;
+        } else if c == '/' && source[start + 1..].starts_with('/') {
+            skip_while(&mut chars, |c| c != '\n');
+            continue;
+        } else if "(){}:,".contains(c) {
+            Token::Punct(c)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            skip_while(&mut chars, |c| {
+                c.is_ascii_alphanumeric() || c == '_' || c == '.'
+            });
+            let end = chars.peek().map_or(source.len(), |p| p.0);
+            Token::Ident(source[start..end].to_owned())
+        } else if c.is_ascii_digit() || c == '-' {
+            skip_while(&mut chars, |c| c.is_ascii_digit() || c == '.');
+            if chars.peek().is_some_and(|&(_, c)| c == 'e' || c == 'E') {
+                chars.next();
+                skip_while(&mut chars, |c| c == '+' || c == '-');
+                skip_while(&mut chars, |c| c.is_ascii_digit());
+            }
+            if c == '-' && source[start + 1..].starts_with("inf") {
+                skip_while(&mut chars, |c| {
+                    c.is_ascii_alphanumeric() || c == '_'
+                });
+            }
+            let end = chars.peek().map_or(source.len(), |p| p.0);
+            let text = &source[start..end];
+            let token = if text == "-inf" {
+                Option::Some(Token::Float(f64::NEG_INFINITY))
+            } else if text.contains(&['.', 'e', 'E'][..]) {
+                text.parse().map(Token::Float).ok()
+            } else {
+                text.parse().map(Token::Int).ok()
+            };
+            match token {
+                Option::Some(t) => t,
+                Option::None => return Result::Err(BtError::new(start..end,
+                    format!("invalid number {}", text)))
+            }
+        } else if c == '"' {
+            Token::Str(string(&mut chars, start, source.len())?)
+        } else {
+            return Result::Err(BtError::new(start..start + c.len_utf8(),
+                format!("unexpected character {}", c)));
+        };
+        let end = chars.peek().map_or(source.len(), |p| p.0);
+        tokens.push((start..end, token));
+    }
+    Result::Ok(tokens)
+}
+
+/// Node as parsed, along with the spans of its kind and of the values of its 
+/// parameters, which compiling errors point at. 
+struct Parsed {
+    kind: String,
+    span: Range<usize>,
+    params: BTreeMap<String, Value>,
+    values: BTreeMap<String, Range<usize>>,
+    children: Vec<Parsed>
+}
+
+impl Parsed {
+    fn def(&self) -> NodeDef {
+        NodeDef {
+            kind: self.kind.clone(),
+            params: self.params.clone(),
+            children: self.children.iter().map(Parsed::def).collect()
+        }
+    }
+
+    fn locate(&self, error: LoadError) -> BtError {
+        let span = match error {
+            LoadError::Param(ref k, ref p) if *k == self.kind => {
+                self.values.get(p).cloned()
+            },
+            _ => Option::None
+        };
+        BtError::new(span.unwrap_or_else(|| self.span.clone()),
+            error.to_string())
+    }
+
+    fn instantiate<I>(&self, registry: &NodeRegistry<I>) ->
+        Result<DynTree<I>, BtError> where 
+        I: 'static
+    {
+        if !registry.contains(&self.kind) {
+            return Result::Err(BtError::new(self.span.clone(),
+                format!("unknown node kind {}", self.kind)));
+        }
+        let children = self.children.iter()
+            .map(|c| c.instantiate(registry))
+            .collect::<Result<Vec<_>, _>>()?;
+        registry.instantiate(self.kind.clone(), self.params.clone(), children)
+            .map_err(|e| self.locate(e))
+    }
+}
+
+struct BtParser {
+    tokens: Vec<(Range<usize>, Token)>,
+    index: usize,
+    end: usize
+}
+
+impl BtParser {
+    fn span(&self) -> Range<usize> {
+        let end = self.end;
+        self.tokens.get(self.index).map_or(end..end, |t| t.0.clone())
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, BtError> {
+        Result::Err(BtError::new(self.span(), message))
+    }
+
+    fn at_end(&self) -> bool {
+        self.index == self.tokens.len()
+    }
+
+    fn eat(&mut self, punct: char) -> Option<Range<usize>> {
+        match self.tokens.get(self.index) {
+            Option::Some(&(ref s, Token::Punct(p))) if p == punct => {
+                self.index += 1;
+                Option::Some(s.clone())
+            },
+            _ => Option::None
+        }
+    }
+
+    fn ident(&mut self) -> Option<(Range<usize>, String)> {
+        match self.tokens.get(self.index) {
+            Option::Some(&(ref s, Token::Ident(ref i))) => {
+                self.index += 1;
+                Option::Some((s.clone(), i.clone()))
+            },
+            _ => Option::None
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, BtError> {
+        let value = match self.tokens.get(self.index) {
+            Option::Some(&(_, Token::Int(i))) => Value::Int(i),
+            Option::Some(&(_, Token::Float(f))) => Value::Float(f),
+            Option::Some(&(_, Token::Str(ref s))) => Value::Str(s.clone()),
+            Option::Some(&(_, Token::Ident(ref i))) if i == "true" => {
+                Value::Bool(true)
+            },
+            Option::Some(&(_, Token::Ident(ref i))) if i == "false" => {
+                Value::Bool(false)
+            },
+            Option::Some(&(_, Token::Ident(ref i))) if i == "inf" => {
+                Value::Float(f64::INFINITY)
+            },
+            Option::Some(&(_, Token::Ident(ref i))) if i == "nan" => {
+                Value::Float(f64::NAN)
+            },
+            Option::Some(&(_, Token::Ident(_))) => {
+                return self.error("expected a value, strings are quoted");
+            },
+            _ => return self.error("expected a value")
+        };
+        self.index += 1;
+        Result::Ok(value)
+    }
+
+    fn params(&mut self, node: &mut Parsed, open: Range<usize>) ->
+        Result<(), BtError>
+    {
+        while self.eat(')').is_none() {
+            if self.at_end() {
+                return Result::Err(BtError::new(open, "unclosed ("));
+            }
+            let (name_span, name) = match self.ident() {
+                Option::Some(n) => n,
+                Option::None => return self.error("expected a parameter name")
+            };
+            if self.eat(':').is_none() {
+                return self.error("expected : after the parameter name");
+            }
+            let value_span = self.span();
+            let value = self.value()?;
+            if node.params.contains_key(&name) {
+                return Result::Err(BtError::new(name_span,
+                    format!("duplicate parameter {}", name)));
+            }
+            node.params.insert(name.clone(), value);
+            node.values.insert(name, value_span);
+            if self.eat(',').is_none() {
+                match self.tokens.get(self.index) {
+                    Option::Some(&(_, Token::Punct(')'))) => (),
+                    Option::None => {
+                        return Result::Err(BtError::new(open, "unclosed ("));
+                    },
+                    _ => return self.error("expected , or )")
+                }
+            }
+        }
+        Result::Ok(())
+    }
+
+    fn node(&mut self) -> Result<Parsed, BtError> {
+        let (span, kind) = match self.ident() {
+            Option::Some(k) => k,
+            Option::None => return self.error("expected a node kind")
+        };
+        let mut node = Parsed {
+            kind: kind,
+            span: span,
+            params: BTreeMap::new(),
+            values: BTreeMap::new(),
+            children: Vec::new()
+        };
+        if let Option::Some(open) = self.eat('(') {
+            self.params(&mut node, open)?;
+        }
+        if let Option::Some(open) = self.eat('{') {
+            while self.eat('}').is_none() {
+                if self.at_end() {
+                    return Result::Err(BtError::new(open, "unclosed {"));
+                }
+                node.children.push(self.node()?);
+                self.eat(',');
+            }
+        }
+        Result::Ok(node)
+    }
+}
+
+fn parse_tree(source: &str) -> Result<Parsed, BtError> {
+    let mut parser = BtParser {
+        tokens: tokenize(source)?,
+        index: 0,
+        end: source.len()
+    };
+    let root = parser.node()?;
+    if !parser.at_end() {
+        return parser.error("expected the end of the tree after its root");
+    }
+    Result::Ok(root)
+}
+
+/// Parse the definition of a tree from the `.bt` format. 
+pub fn parse(source: &str) -> Result<NodeDef, BtError> {
+    parse_tree(source).map(|p| p.def())
+}
+
+/// Instantiate a tree written in the `.bt` format through the registry. 
+///
+/// Unlike instantiating the parsed definition, the errors of instantiating 
+/// each node point at its kind, or at the value of the parameter the node 
+/// rejected, in the source. 
+pub fn compile<I>(registry: &NodeRegistry<I>, source: &str) ->
+    Result<DynTree<I>, BtError> where 
+    I: 'static
+{
+    parse_tree(source)?.instantiate(registry)
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match *value {
+        Value::Int(i) => out.push_str(&i.to_string()),
+        Value::Float(f) if f.is_nan() => out.push_str("nan"),
+        Value::Float(f) => out.push_str(&format!("{:?}", f)),
+        Value::Bool(b) => out.push_str(&b.to_string()),
+        Value::Str(ref s) => {
+            out.push('"');
+            for c in s.chars() {
+                match c {
+                    '"' => out.push_str("\\\""),
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    '\t' => out.push_str("\\t"),
+                    c => out.push(c)
+                }
+            }
+            out.push('"');
+        }
+    }
+}
+
+fn write_node(def: &NodeDef, depth: usize, out: &mut String) {
+    out.push_str(&"    ".repeat(depth));
+    out.push_str(&def.kind);
+    if !def.params.is_empty() {
+        out.push('(');
+        for (i, (name, value)) in def.params.iter().enumerate() {
+            if i > 0 {
+                out.push_str(", ");
+            }
+            out.push_str(name);
+            out.push_str(": ");
+            write_value(value, out);
+        }
+        out.push(')');
+    }
+    if !def.children.is_empty() {
+        out.push_str(" {\n");
+        for child in def.children.iter() {
+            write_node(child, depth + 1, out);
+        }
+        out.push_str(&"    ".repeat(depth));
+        out.push('}');
+    }
+    out.push('\n');
+}
+
+/// Write the definition of a tree in the `.bt` format. 
+pub fn write(def: &NodeDef) -> String {
+    let mut out = String::new();
+    write_node(def, 0, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+    use bt_dsl::{compile, parse, write, BtError};
+    use node_registry::{Arity, NodeRegistry, NodeSpec, ParamSpec, ParamType};
+    use tree_def::{LoadError, NodeDef};
+
+    const TREE: &str = "// Guard the gate
+Selector {
+    AtLeast(bound: 10)
+    Sequence {
+        Tagged(tag: \"gate \\\"north\\\"\", weight: -0.5, loud: false),
+        Inverter { AtLeast(bound: 5,) }
+    }
+}
+";
+
+    fn registry() -> NodeRegistry<i64> {
+        let at_least = NodeSpec::new("AtLeast", Arity::Leaf)
+            .param(ParamSpec::new("bound", ParamType::Int));
+        NodeRegistry::with_builtins()
+            .leaf_spec(at_least, |d: &NodeDef| {
+                let bound = d.int("bound")?;
+                Result::Ok(PredicateWait::new(move |i: &i64| {
+                    Statepoint::Terminal(BtStatus::from(*i >= bound))
+                }))
+            })
+    }
+
+    #[test]
+    fn bt_parse_test() {
+        let def = NodeDef::new("Selector")
+            .child(NodeDef::new("AtLeast").param("bound", 10))
+            .child(NodeDef::new("Sequence")
+                .child(NodeDef::new("Tagged")
+                    .param("tag", "gate \"north\"")
+                    .param("weight", -0.5)
+                    .param("loud", false))
+                .child(NodeDef::new("Inverter")
+                    .child(NodeDef::new("AtLeast").param("bound", 5))));
+        assert_eq!(parse(TREE), Result::Ok(def.clone()));
+        assert_eq!(parse(&write(&def)), Result::Ok(def));
+        assert_eq!(parse("Wait(ticks: 2e3)"),
+            Result::Ok(NodeDef::new("Wait").param("ticks", 2000.0)));
+        let def = NodeDef::new("Clamp").param("low", f64::NEG_INFINITY)
+            .param("high", f64::INFINITY);
+        assert_eq!(write(&def), "Clamp(high: inf, low: -inf)\n");
+        assert_eq!(parse(&write(&def)), Result::Ok(def));
+        let written = write(&NodeDef::new("Wait").param("ticks", f64::NAN));
+        assert_eq!(written, "Wait(ticks: nan)\n");
+        assert!(parse(&written).unwrap().float("ticks").unwrap().is_nan());
+        assert!(parse("Wait(ticks: -info)").is_err());
+    }
+
+    #[test]
+    fn bt_error_test() {
+        let error = |s: &str| parse(s).unwrap_err();
+        assert_eq!(error("Sequence { Wait ? }"),
+            BtError::new(16..17, "unexpected character ?"));
+        assert_eq!(error("Wait(name: \"x)").span, 11..14);
+        assert_eq!(error("Sequence {\n    Wait\n").span, 9..10);
+        assert_eq!(error("Wait(ticks 2)").span, 11..12);
+        assert_eq!(error("Wait(ticks: 1, ticks: 2)").span, 15..20);
+        assert_eq!(error("Wait(ticks: two)").message,
+            "expected a value, strings are quoted");
+        assert_eq!(error("Wait Wait").span, 5..9);
+        assert_eq!(error("").span, 0..0);
+        let source = "Sequence {\n    Wait(ticks: 1 2)\n}";
+        assert_eq!(error(source).render(source), "error: expected , or )
+ --> 2:19
+  |
+2 |     Wait(ticks: 1 2)
+  |                   ^
+");
+        match registry().load_bt("Inverter {") {
+            Result::Err(LoadError::Parse(m)) => {
+                assert_eq!(m, "unclosed { at bytes 9..10")
+            },
+            _ => unreachable!("Expected parse error")
+        };
+    }
+
+    #[test]
+    fn bt_compile_test() {
+        let registry = registry();
+        let tree = compile(&registry, "Inverter { AtLeast(bound: 5) }")
+            .unwrap();
+        match tree.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let error = |s: &str| compile(&registry, s).err().unwrap();
+        assert_eq!(error("Sequence { AtLeast(bound: 1) Atleast }"),
+            BtError::new(29..36, "unknown node kind Atleast"));
+        assert_eq!(error("Selector { AtLeast(bound: \"1\") }").span, 26..29);
+        assert_eq!(error("Sequence { AtLeast }").span, 11..18);
+        assert_eq!(error("Inverter { AtLeast(bound: 1) AtLeast(bound: 2) }"),
+            BtError::new(0..8,
+                "node Inverter has the wrong number of children"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,488 @@
+use expression::Value;
+use std::collections::BTreeMap;
+use tree_def::{LoadError, NodeDef};
+
+/// Element of an XML document, with its attributes in document order. Text 
+/// content is not kept, as the trees of BehaviorTree.CPP have none. 
+#[derive(Clone, PartialEq, Debug)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Element>
+}
+
+struct XmlParser<'a> {
+    source: &'a str,
+    position: usize
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == ':'
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl<'a> XmlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn error(&self, message: &str) -> LoadError {
+        LoadError::Parse(format!("{} at byte {}", message, self.position))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn skip_past(&mut self, end: &str) -> Result<(), LoadError> {
+        match self.rest().find(end) {
+            Option::Some(i) => {
+                self.position += i + end.len();
+                Result::Ok(())
+            },
+            Option::None => Result::Err(self.error("Unterminated markup"))
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), LoadError> {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            Result::Ok(())
+        } else {
+            Result::Err(self.error(&format!("Expected {}", token)))
+        }
+    }
+
+    fn skip_misc(&mut self) -> Result<(), LoadError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Result::Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, LoadError> {
+        let rest = self.rest();
+        let len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Result::Err(self.error("Expected a name"));
+        }
+        self.position += len;
+        Result::Ok(rest[..len].to_owned())
+    }
+
+    fn attribute_value(&mut self) -> Result<String, LoadError> {
+        let quote = match self.rest().chars().next() {
+            Option::Some(q) if q == '"' || q == '\'' => q,
+            _ => return Result::Err(self.error("Expected a quoted value"))
+        };
+        self.position += 1;
+        match self.rest().find(quote) {
+            Option::Some(i) => {
+                let value = unescape(&self.rest()[..i]);
+                self.position += i + 1;
+                Result::Ok(value)
+            },
+            Option::None => Result::Err(self.error("Unterminated value"))
+        }
+    }
+
+    fn element(&mut self) -> Result<Element, LoadError> {
+        self.expect("<")?;
+        let name = self.name()?;
+        let mut attrs = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Result::Ok(Element {
+                    name: name,
+                    attrs: attrs,
+                    children: Vec::new()
+                });
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            let attr = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let value = self.attribute_value()?;
+            attrs.push((attr, value));
+        }
+        let mut children = Vec::new();
+        loop {
+            match self.rest().find('<') {
+                Option::Some(i) => self.position += i,
+                Option::None => {
+                    return Result::Err(self.error("Unclosed element"));
+                }
+            }
+            if self.rest().starts_with("</") {
+                self.position += 2;
+                if self.name()? != name {
+                    return Result::Err(self.error("Mismatched closing tag"));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Result::Ok(Element {
+                    name: name,
+                    attrs: attrs,
+                    children: children
+                });
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<![CDATA[") {
+                self.skip_past("]]>")?;
+            } else {
+                children.push(self.element()?);
+            }
+        }
+    }
+
+    fn document(source: &str) -> Result<Element, LoadError> {
+        let mut parser = XmlParser {
+            source: source,
+            position: 0
+        };
+        parser.skip_misc()?;
+        let root = parser.element()?;
+        parser.skip_misc()?;
+        if parser.position == parser.source.len() {
+            Result::Ok(root)
+        } else {
+            Result::Err(parser.error("Unexpected content after the root"))
+        }
+    }
+}
+
+/// Read an attribute as a parameter, taking it as an integer, a number, or a 
+/// boolean where it reads as one, and as a string otherwise. 
+fn param_value(text: &str) -> Value {
+    if let Result::Ok(i) = text.parse::<i64>() {
+        Value::Int(i)
+    } else if let Result::Ok(f) = text.parse::<f64>() {
+        Value::Float(f)
+    } else if text == "true" {
+        Value::Bool(true)
+    } else if text == "false" {
+        Value::Bool(false)
+    } else {
+        Value::Str(text.to_owned())
+    }
+}
+
+fn param_text(value: &Value) -> String {
+    match *value {
+        Value::Int(i) => i.to_string(),
+        Value::Float(f) => format!("{:?}", f),
+        Value::Bool(b) => b.to_string(),
+        Value::Str(ref s) => s.clone()
+    }
+}
+
+/// Get the name of the blackboard entry a parameter refers to, for values 
+/// written as the name within braces, such as `{target}`. 
+pub fn blackboard_ref(value: &Value) -> Option<&str> {
+    match *value {
+        Value::Str(ref s) if s.len() > 2 && s.starts_with('{') &&
+            s.ends_with('}') => Option::Some(&s[1..s.len() - 1]),
+        _ => Option::None
+    }
+}
+
+fn import_node(element: &Element) -> NodeDef {
+    let mut attrs = element.attrs.iter().collect::<Vec<_>>();
+    let kind = match element.name.as_str() {
+        "Action" | "Condition" | "Decorator" | "Control" => {
+            match attrs.iter().position(|a| a.0 == "ID") {
+                Option::Some(i) => attrs.remove(i).1.clone(),
+                Option::None => element.name.clone()
+            }
+        },
+        "Fallback" => "Selector".to_owned(),
+        _ => element.name.clone()
+    };
+    let mut def = attrs.into_iter().fold(NodeDef::new(kind),
+        |d, &(ref n, ref v)| d.param(n.clone(), param_value(v)));
+    def.children = element.children.iter().map(import_node).collect();
+    def
+}
+
+fn export_node(def: &NodeDef, depth: usize, out: &mut String) {
+    let name = match def.kind.as_str() {
+        "Selector" => "Fallback",
+        k => k
+    };
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+    out.push('<');
+    out.push_str(name);
+    for (n, v) in def.params.iter() {
+        out.push_str(&format!(" {}=\"{}\"", n, escape(&param_text(v))));
+    }
+    if def.children.is_empty() {
+        out.push_str("/>\n");
+        return;
+    }
+    out.push_str(">\n");
+    for c in def.children.iter() {
+        export_node(c, depth + 1, out);
+    }
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+    out.push_str(&format!("</{}>\n", name));
+}
+
+/// Reserved attributes of subtree calls, which are not remaps of ports. 
+const SUBTREE_ATTRS: [&str; 3] = ["ID", "name", "_autoremap"];
+
+/// Set of named tree definitions in the XML format of BehaviorTree.CPP, 
+/// where trees may include each other as subtrees. 
+///
+/// Fallback nodes are read as the Selector kind and written back as 
+/// Fallback, and the actions and conditions of the older form of the format, 
+/// which name their kind by an ID attribute, are read as nodes of that 
+/// kind. Attributes are read as parameters, as integers, numbers and 
+/// booleans where they read as such, and as strings otherwise. 
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TreeDocument {
+    /// The ID of the tree to run, if the document names one. 
+    pub main: Option<String>,
+    /// The trees of the document, by ID. Subtree calls are kept as nodes of 
+    /// the SubTree kind, with the ID of the tree they call as a parameter. 
+    pub trees: BTreeMap<String, NodeDef>
+}
+
+impl TreeDocument {
+    /// Create a new document with no trees. 
+    pub fn new() -> TreeDocument {
+        TreeDocument::default()
+    }
+
+    /// Add a tree to the document under the given ID. 
+    pub fn tree<S>(self, id: S, def: NodeDef) -> TreeDocument where 
+        S: Into<String>
+    {
+        let mut mut_self = self;
+        mut_self.trees.insert(id.into(), def);
+        mut_self
+    }
+
+    /// Name the tree of the document to run. 
+    pub fn main<S>(self, id: S) -> TreeDocument where 
+        S: Into<String>
+    {
+        TreeDocument {
+            main: Option::Some(id.into()),
+            .. self
+        }
+    }
+
+    /// Parse a document in the XML format. 
+    pub fn from_xml(source: &str) -> Result<TreeDocument, LoadError> {
+        let root = XmlParser::document(source)?;
+        if root.name != "root" {
+            return Result::Err(LoadError::Parse(
+                "Expected a root element".to_owned()));
+        }
+        let main = root.attrs.iter()
+            .find(|a| a.0 == "main_tree_to_execute")
+            .map(|a| a.1.clone());
+        let mut trees = BTreeMap::new();
+        for tree in root.children.iter().filter(|c| c.name == "BehaviorTree") {
+            let id = match tree.attrs.iter().find(|a| a.0 == "ID") {
+                Option::Some(a) => a.1.clone(),
+                Option::None => return Result::Err(LoadError::Parse(
+                    "Tree without an ID".to_owned()))
+            };
+            if tree.children.len() != 1 {
+                return Result::Err(LoadError::Children(id));
+            }
+            trees.insert(id, import_node(&tree.children[0]));
+        }
+        Result::Ok(TreeDocument {
+            main: main,
+            trees: trees
+        })
+    }
+
+    /// Write the document in the XML format. 
+    pub fn to_xml(&self) -> String {
+        let mut out = String::from("<root BTCPP_format=\"4\"");
+        if let Option::Some(ref m) = self.main {
+            out.push_str(&format!(" main_tree_to_execute=\"{}\"", escape(m)));
+        }
+        out.push_str(">\n");
+        for (id, def) in self.trees.iter() {
+            out.push_str(&format!("  <BehaviorTree ID=\"{}\">\n", escape(id)));
+            export_node(def, 2, &mut out);
+            out.push_str("  </BehaviorTree>\n");
+        }
+        out.push_str("</root>\n");
+        out
+    }
+
+    /// Get the definition of the tree to run, which is the one the document 
+    /// names, or its only tree if it names none, with its subtree calls 
+    /// replaced by the trees they call. 
+    ///
+    /// Within a called tree, parameters referring to a port the call remaps 
+    /// are replaced by the value the call gives, so that they refer to the 
+    /// blackboard entries of the caller. Parameters referring to other 
+    /// ports are left as they are. 
+    pub fn main_tree(&self) -> Result<NodeDef, LoadError> {
+        let id = match self.main {
+            Option::Some(ref m) => m.clone(),
+            Option::None if self.trees.len() == 1 => {
+                self.trees.keys().next().unwrap().clone()
+            },
+            Option::None => return Result::Err(LoadError::Parse(
+                "No tree to run is named".to_owned()))
+        };
+        self.inline(&id, &BTreeMap::new(), &mut Vec::new())
+    }
+
+    fn inline(&self, id: &str, remaps: &BTreeMap<String, Value>,
+        stack: &mut Vec<String>) -> Result<NodeDef, LoadError>
+    {
+        if stack.iter().any(|s| s == id) {
+            return Result::Err(LoadError::Subtree(id.to_owned()));
+        }
+        let def = match self.trees.get(id) {
+            Option::Some(d) => d,
+            Option::None => return Result::Err(
+                LoadError::Subtree(id.to_owned()))
+        };
+        stack.push(id.to_owned());
+        let inlined = self.inline_node(def, remaps, stack);
+        stack.pop();
+        inlined
+    }
+
+    fn inline_node(&self, def: &NodeDef, remaps: &BTreeMap<String, Value>,
+        stack: &mut Vec<String>) -> Result<NodeDef, LoadError>
+    {
+        let params = def.params.iter()
+            .map(|(n, v)| {
+                let remapped = blackboard_ref(v).and_then(|r| remaps.get(r));
+                (n.clone(), remapped.unwrap_or(v).clone())
+            })
+            .collect::<BTreeMap<_, _>>();
+        if def.kind == "SubTree" {
+            let id = match params.get("ID") {
+                Option::Some(&Value::Str(ref i)) => i.clone(),
+                _ => return Result::Err(LoadError::Param(def.kind.clone(),
+                    "ID".to_owned()))
+            };
+            let call = params.into_iter()
+                .filter(|p| !SUBTREE_ATTRS.contains(&p.0.as_str()))
+                .collect();
+            return self.inline(&id, &call, stack);
+        }
+        let children = def.children.iter()
+            .map(|c| self.inline_node(c, remaps, stack))
+            .collect::<Result<Vec<_>, _>>()?;
+        Result::Ok(NodeDef {
+            kind: def.kind.clone(),
+            params: params,
+            children: children
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use btcpp_xml::{blackboard_ref, TreeDocument};
+    use expression::Value;
+    use tree_def::{LoadError, NodeDef};
+
+    const DOCUMENT: &str = r#"<?xml version="1.0"?>
+<root BTCPP_format="4" main_tree_to_execute="Main">
+  <!-- Open the door, or go around it -->
+  <BehaviorTree ID="Main">
+    <Fallback name="entry">
+      <SubTree ID="Approach" goal="{door}" speed="2"/>
+      <Action ID="GoAround" margin="1.5" loud="false"/>
+    </Fallback>
+  </BehaviorTree>
+  <BehaviorTree ID="Approach">
+    <Sequence>
+      <MoveTo target="{goal}" speed="{speed}"/>
+      <Condition ID="IsOpen" door="{goal}" note="a &amp; b"/>
+    </Sequence>
+  </BehaviorTree>
+</root>
+"#;
+
+    #[test]
+    fn xml_import_test() {
+        let document = TreeDocument::from_xml(DOCUMENT).unwrap();
+        assert_eq!(document.main, Option::Some("Main".to_owned()));
+        assert_eq!(document.trees.len(), 2);
+        assert_eq!(document.main_tree(), Result::Ok(NodeDef::new("Selector")
+            .param("name", "entry")
+            .child(NodeDef::new("Sequence")
+                .child(NodeDef::new("MoveTo").param("target", "{door}")
+                    .param("speed", 2))
+                .child(NodeDef::new("IsOpen").param("door", "{door}")
+                    .param("note", "a & b")))
+            .child(NodeDef::new("GoAround").param("margin", 1.5)
+                .param("loud", false))));
+        assert_eq!(blackboard_ref(&Value::Str("{door}".to_owned())),
+            Option::Some("door"));
+    }
+
+    #[test]
+    fn xml_round_trip_test() {
+        let document = TreeDocument::from_xml(DOCUMENT).unwrap();
+        let written = document.to_xml();
+        assert!(written.contains("<Fallback name=\"entry\">"));
+        assert_eq!(TreeDocument::from_xml(&written), Result::Ok(document));
+    }
+
+    #[test]
+    fn xml_float_round_trip_test() {
+        let document = TreeDocument::new().main("A")
+            .tree("A", NodeDef::new("Wait").param("seconds", 1.0));
+        let written = document.to_xml();
+        assert!(written.contains("seconds=\"1.0\""));
+        assert_eq!(TreeDocument::from_xml(&written), Result::Ok(document));
+    }
+
+    #[test]
+    fn xml_error_test() {
+        assert!(TreeDocument::from_xml("<root><BehaviorTree ID=\"A\">\
+            <Sequence></Fallback></BehaviorTree></root>").is_err());
+        let looping = TreeDocument::new().main("A")
+            .tree("A", NodeDef::new("SubTree").param("ID", "A"));
+        assert_eq!(looping.main_tree(),
+            Result::Err(LoadError::Subtree("A".to_owned())));
+    }
+}
\ No newline at end of file
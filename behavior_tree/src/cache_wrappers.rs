@@ -0,0 +1,471 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Storage for the terminal latched by a family of latch wrappers. 
+///
+/// Clones of the storage share the same latched terminal, so that fresh 
+/// instances of a latch wrapper made by a repeater or a selector can see the 
+/// terminal of the earlier ones. 
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LatchCache<T> {
+    terminal: Rc<RefCell<Option<T>>>
+}
+
+impl<T> LatchCache<T> {
+    /// Create a new, empty latch storage. 
+    pub fn new() -> LatchCache<T> {
+        LatchCache {
+            terminal: Rc::new(RefCell::new(Option::None))
+        }
+    }
+
+    /// Check whether a terminal has been latched. 
+    pub fn is_latched(&self) -> bool {
+        self.terminal.borrow().is_some()
+    }
+
+    /// Release the latched terminal, if there is one, so that the next latch 
+    /// wrapper to be entered runs its node again. 
+    pub fn release(&self) -> Option<T> {
+        self.terminal.borrow_mut().take()
+    }
+}
+
+impl<T> LatchCache<T> where 
+    T: Clone
+{
+    /// Get a copy of the latched terminal, if there is one. 
+    pub fn get(&self) -> Option<T> {
+        self.terminal.borrow().clone()
+    }
+}
+
+/// Wrapper for a node, which runs the node only until some instance of the 
+/// wrapper sharing its storage has seen it terminate, and from then on 
+/// exits immediately with that terminal on each entry, for "do once" 
+/// behaviors. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Latch<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    node: N,
+    cache: LatchCache<N::Terminal>
+}
+
+impl<N> Latch<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    /// Create a new latch wrapper using the given storage. 
+    pub fn new(cache: LatchCache<N::Terminal>, node: N) -> Latch<N> {
+        Latch {
+            node: node,
+            cache: cache
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for Latch<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Clone
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        N::Terminal, Self>
+    {
+        if let Option::Some(t) = self.cache.get() {
+            self.node.abort();
+            return NodeResult::Terminal(t);
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                Latch::new(self.cache, m)
+            ),
+            NodeResult::Terminal(t) => {
+                *self.cache.terminal.borrow_mut() = Option::Some(t.clone());
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Bounded storage for the terminals memoized by a family of memoizing 
+/// wrappers, keyed by the hashes of the inputs they were entered with. 
+///
+/// Once full, the least recently used entry is evicted to make room for a 
+/// new one. Clones of the storage share the same entries. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct MemoCache<T> {
+    entries: Rc<RefCell<VecDeque<(u64, T)>>>,
+    capacity: usize
+}
+
+impl<T> MemoCache<T> {
+    /// Create a new, empty memoization storage holding at most the given 
+    /// number of entries, which has to be at least one. 
+    pub fn new(capacity: usize) -> MemoCache<T> {
+        assert!(capacity > 0, "MemoCache requires a capacity of at least one");
+        MemoCache {
+            entries: Rc::new(RefCell::new(VecDeque::with_capacity(capacity))),
+            capacity: capacity
+        }
+    }
+
+    /// Get the number of entries currently held. 
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Check whether no entries are held. 
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    /// Remove all held entries. 
+    pub fn clear(&self) {
+        self.entries.borrow_mut().clear()
+    }
+
+    fn insert(&self, key: u64, terminal: T) {
+        let mut entries = self.entries.borrow_mut();
+        if let Option::Some(i) = entries.iter().position(|&(k, _)| k == key) {
+            entries.remove(i);
+        } else if entries.len() >= self.capacity {
+            entries.pop_back();
+        }
+        entries.push_front((key, terminal));
+    }
+}
+
+impl<T> MemoCache<T> where 
+    T: Clone
+{
+    fn lookup(&self, key: u64) -> Option<T> {
+        let mut entries = self.entries.borrow_mut();
+        let index = entries.iter().position(|&(k, _)| k == key)?;
+        let entry = entries.remove(index)?;
+        let terminal = entry.1.clone();
+        entries.push_front(entry);
+        Option::Some(terminal)
+    }
+}
+
+/// Wrapper for a node, which exits immediately with the memoized terminal 
+/// when entered with an input whose hash was seen before, and otherwise 
+/// runs the node, memoizing its terminal under the hash of the input the 
+/// wrapper was entered with. 
+///
+/// This is intended for expensive, pure computations, whose terminal only 
+/// depends on the input they are entered with. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Memoize<N> where 
+    N: BehaviorTreeNode,
+    N::Input: Hash,
+    N::Terminal: Clone
+{
+    node: N,
+    cache: MemoCache<N::Terminal>,
+    key: Option<u64>
+}
+
+impl<N> Memoize<N> where 
+    N: BehaviorTreeNode,
+    N::Input: Hash,
+    N::Terminal: Clone
+{
+    /// Create a new memoizing wrapper using the given storage. 
+    pub fn new(cache: MemoCache<N::Terminal>, node: N) -> Memoize<N> {
+        Memoize {
+            node: node,
+            cache: cache,
+            key: Option::None
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for Memoize<N> where 
+    N: BehaviorTreeNode,
+    N::Input: Hash,
+    N::Terminal: Clone
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        N::Terminal, Self>
+    {
+        let key = match self.key {
+            Option::Some(k) => k,
+            Option::None => {
+                let mut hasher = DefaultHasher::new();
+                input.hash(&mut hasher);
+                let k = hasher.finish();
+                if let Option::Some(t) = self.cache.lookup(k) {
+                    self.node.abort();
+                    return NodeResult::Terminal(t);
+                }
+                k
+            }
+        };
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                Memoize {
+                    node: m,
+                    cache: self.cache,
+                    key: Option::Some(key)
+                }
+            ),
+            NodeResult::Terminal(t) => {
+                self.cache.insert(key, t.clone());
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Wrapper for a node, which skips stepping the node when the key of the 
+/// input is unchanged since the previous step, repeating the last 
+/// nonterminal of the node instead. 
+///
+/// The node is always stepped on the first step of the wrapper. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ChangeFilter<N, K, F> where 
+    N: BehaviorTreeNode,
+    N::Nonterminal: Clone,
+    K: PartialEq,
+    F: Fn(&N::Input) -> K
+{
+    node: N,
+    key: F,
+    last: Option<(K, N::Nonterminal)>
+}
+
+impl<N> ChangeFilter<N, N::Input, fn(&N::Input) -> N::Input> where 
+    N: BehaviorTreeNode,
+    N::Input: Clone + PartialEq,
+    N::Nonterminal: Clone
+{
+    /// Create a new change filter comparing whole inputs. 
+    pub fn new(node: N) -> ChangeFilter<N, N::Input, fn(&N::Input) -> N::Input> {
+        ChangeFilter::with_key(Clone::clone, node)
+    }
+}
+
+impl<N, K, F> ChangeFilter<N, K, F> where 
+    N: BehaviorTreeNode,
+    N::Nonterminal: Clone,
+    K: PartialEq,
+    F: Fn(&N::Input) -> K
+{
+    /// Create a new change filter comparing the keys extracted from the 
+    /// inputs by the given function. 
+    pub fn with_key(key: F, node: N) -> ChangeFilter<N, K, F> {
+        ChangeFilter {
+            node: node,
+            key: key,
+            last: Option::None
+        }
+    }
+}
+
+impl<N, K, F> BehaviorTreeNode for ChangeFilter<N, K, F> where 
+    N: BehaviorTreeNode,
+    N::Nonterminal: Clone,
+    K: PartialEq,
+    F: Fn(&N::Input) -> K
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        N::Terminal, Self>
+    {
+        let key = (self.key)(input);
+        match self.last {
+            Option::Some((ref k, ref n)) if *k == key => {
+                let n = n.clone();
+                return NodeResult::Nonterminal(n, ChangeFilter {
+                    node: self.node,
+                    key: self.key,
+                    last: self.last
+                });
+            },
+            _ => ()
+        };
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n.clone(),
+                ChangeFilter {
+                    node: m,
+                    key: self.key,
+                    last: Option::Some((key, n))
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{Evaluation, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use cache_wrappers::{Latch, LatchCache, Memoize, MemoCache, ChangeFilter};
+
+    fn wait_nonzero(input: &i64) -> Statepoint<i64, i64> {
+        if *input == 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[test]
+    fn latch_test() {
+        let cache = LatchCache::new();
+        let test_node = Latch::new(cache.clone(), PredicateWait::new(wait_nonzero));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert!(!cache.is_latched());
+        match test_node_1.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, 3),
+            _ => unreachable!("Expected terminal transition")
+        };
+        // A fresh instance reports the latched terminal without running
+        let test_node_2 = Latch::new(cache.clone(), Evaluation::new(|i: &i64| *i));
+        match test_node_2.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, 3),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(cache.release(), Option::Some(3));
+        let test_node_3 = Latch::new(cache.clone(), Evaluation::new(|i: &i64| *i));
+        match test_node_3.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, 5),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn memoize_test() {
+        use std::cell::Cell;
+        let runs = Cell::new(0);
+        let square = |i: &i64| {
+            runs.set(runs.get() + 1);
+            i * i
+        };
+        let cache = MemoCache::new(2);
+        for &(input, output) in [(2, 4), (3, 9), (2, 4)].iter() {
+            match Memoize::new(cache.clone(), Evaluation::new(&square)).step(&input) {
+                NodeResult::Terminal(t) => assert_eq!(t, output),
+                _ => unreachable!("Expected terminal transition")
+            };
+        }
+        assert_eq!(runs.get(), 2);
+        // Entering with 4 evicts 3, the least recently used input
+        match Memoize::new(cache.clone(), Evaluation::new(&square)).step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(t, 16),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match Memoize::new(cache.clone(), Evaluation::new(&square)).step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, 9),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(runs.get(), 4);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn memoize_nonterminal_test() {
+        let cache = MemoCache::new(4);
+        let test_node = Memoize::new(cache.clone(), PredicateWait::new(wait_nonzero));
+        // The terminal is memoized under the input of the first step
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&7) {
+            NodeResult::Terminal(t) => assert_eq!(t, 7),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match Memoize::new(cache, PredicateWait::new(wait_nonzero)).step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, 7),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn change_filter_test() {
+        use std::cell::Cell;
+        let runs = Cell::new(0);
+        let counted = |input: &i64| {
+            runs.set(runs.get() + 1);
+            wait_nonzero(input)
+        };
+        let test_node = ChangeFilter::new(PredicateWait::new(&counted));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(runs.get(), 1);
+        match test_node_2.step(&2) {
+            NodeResult::Terminal(t) => assert_eq!(t, 2),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(runs.get(), 2);
+    }
+
+    #[test]
+    fn change_filter_key_test() {
+        let test_node = ChangeFilter::with_key(|input: &(i64, i64)| input.0, 
+            PredicateWait::new(|input: &(i64, i64)| wait_nonzero(&input.1)));
+        let test_node_1 = match test_node.step(&(1, 0)) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // The second element is not part of the key, so it goes unseen
+        let test_node_2 = match test_node_1.step(&(1, 5)) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_2.step(&(2, 5)) {
+            NodeResult::Terminal(t) => assert_eq!(t, 5),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
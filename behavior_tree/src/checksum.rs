@@ -0,0 +1,86 @@
+use std::fmt;
+
+const CRC32_POLYNOMIAL: u32 = 0xedb8_8320;
+
+/// Length of the checksum appended to sealed bytes. 
+pub const CHECKSUM_LEN: usize = 4;
+
+/// Error of verifying the checksum of sealed bytes. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ChecksumError {
+    /// The bytes are too short to hold a checksum. 
+    Truncated,
+    /// The checksum appended to the bytes, which is the first value, does 
+    /// not match the checksum of their contents, which is the second. 
+    Mismatch(u32, u32)
+}
+
+impl fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ChecksumError::Truncated => write!(f, "checksum is missing"),
+            ChecksumError::Mismatch(s, c) => write!(f,
+                "checksum {:08x} does not match contents {:08x}", s, c)
+        }
+    }
+}
+
+/// Compute the CRC-32 (IEEE) checksum of the given bytes. 
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for b in bytes {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (CRC32_POLYNOMIAL & mask);
+        }
+    }
+    !crc
+}
+
+/// Append the checksum of the given bytes to them. 
+pub fn seal(bytes: Vec<u8>) -> Vec<u8> {
+    let crc = crc32(&bytes);
+    let mut mut_bytes = bytes;
+    mut_bytes.extend_from_slice(&crc.to_le_bytes());
+    mut_bytes
+}
+
+/// Verify the checksum appended to the given bytes, and get the contents 
+/// before it. 
+pub fn unseal(bytes: &[u8]) -> Result<&[u8], ChecksumError> {
+    if bytes.len() < CHECKSUM_LEN {
+        return Result::Err(ChecksumError::Truncated);
+    }
+    let (contents, sum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    let stored = sum[0] as u32 | (sum[1] as u32) << 8 | (sum[2] as u32) << 16
+        | (sum[3] as u32) << 24;
+    let computed = crc32(contents);
+    if stored == computed {
+        Result::Ok(contents)
+    } else {
+        Result::Err(ChecksumError::Mismatch(stored, computed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use checksum::{crc32, seal, unseal, ChecksumError};
+
+    #[test]
+    fn checksum_test() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+        let sealed = seal(b"state".to_vec());
+        assert_eq!(sealed.len(), 9);
+        assert_eq!(unseal(&sealed), Result::Ok(&b"state"[..]));
+        let mut flipped = sealed.clone();
+        flipped[1] ^= 0x10;
+        match unseal(&flipped) {
+            Result::Err(ChecksumError::Mismatch(s, _)) =>
+                assert_eq!(s, crc32(b"state")),
+            _ => unreachable!("Expected checksum mismatch")
+        };
+        assert_eq!(unseal(b"abc"), Result::Err(ChecksumError::Truncated));
+    }
+}
\ No newline at end of file
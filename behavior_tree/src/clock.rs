@@ -0,0 +1,95 @@
+use std::time::{Duration, Instant};
+use std::rc::Rc;
+use std::cell::Cell;
+
+/// Source of time for the nodes which depend on real time, which can be 
+/// mocked to make them deterministic in tests. 
+pub trait Clock {
+    /// Get the time elapsed since some fixed epoch of the clock. 
+    fn now(&self) -> Duration;
+}
+
+/// Clock backed by std::time::Instant, whose epoch is the moment it was 
+/// created. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MonotonicClock {
+    epoch: Instant
+}
+
+impl MonotonicClock {
+    /// Create a new monotonic clock starting now. 
+    pub fn new() -> MonotonicClock {
+        MonotonicClock {
+            epoch: Instant::now()
+        }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> MonotonicClock {
+        MonotonicClock::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Duration {
+        self.epoch.elapsed()
+    }
+}
+
+/// Clock which only moves when it is advanced by hand. 
+///
+/// Clones of the clock share the same time, so that a test can keep one of 
+/// them to advance the time seen by the nodes holding the others. 
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ManualClock {
+    time: Rc<Cell<Duration>>
+}
+
+impl ManualClock {
+    /// Create a new manual clock at its epoch. 
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    /// Move the clock forward by the given duration. 
+    pub fn advance(&self, by: Duration) {
+        self.time.set(self.time.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.time.get()
+    }
+}
+
+impl<'a, C> Clock for &'a C where 
+    C: Clock
+{
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clock::{Clock, ManualClock, MonotonicClock};
+    use std::time::Duration;
+
+    #[test]
+    fn manual_clock_test() {
+        let clock = ManualClock::new();
+        let shared = clock.clone();
+        assert_eq!(shared.now(), Duration::from_millis(0));
+        clock.advance(Duration::from_millis(5));
+        assert_eq!(shared.now(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn monotonic_clock_test() {
+        let clock = MonotonicClock::new();
+        let earlier = clock.now();
+        assert!(clock.now() >= earlier);
+    }
+}
\ No newline at end of file
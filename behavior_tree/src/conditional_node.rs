@@ -0,0 +1,371 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+/// Nonterminal type of the conditional nodes. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConditionalNonterm<R, S, N> {
+    /// The condition node was stepped, and did not terminate. 
+    Condition(R),
+    /// The condition node terminated with the contained terminal. 
+    Decided(S),
+    /// The branch node committed to was stepped, and did not terminate. 
+    Branch(N)
+}
+
+/// Ternary node, which runs a condition node until it terminates, and then 
+/// commits to the then node if the condition terminated with a Result::Ok, 
+/// and to the else node otherwise, running it until it terminates. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum IfThenElse<C, A, B> {
+    /// The condition node is running. 
+    Condition(C, A, B),
+    /// The condition succeeded, and the then node is running. 
+    Then(A),
+    /// The condition failed, and the else node is running. 
+    Else(B)
+}
+
+impl<C, A, B> IfThenElse<C, A, B> {
+    /// Create a new conditional node. 
+    pub fn new(condition: C, then: A, otherwise: B) -> IfThenElse<C, A, B> {
+        IfThenElse::Condition(condition, then, otherwise)
+    }
+}
+
+impl<C, A, B, X, Y> BehaviorTreeNode for IfThenElse<C, A, B> where 
+    A: BehaviorTreeNode,
+    B: BehaviorTreeNode<Input=A::Input, Nonterminal=A::Nonterminal,
+        Terminal=A::Terminal>,
+    C: BehaviorTreeNode<Input=A::Input, Terminal=Result<X, Y>>
+{
+    type Input = A::Input;
+    type Nonterminal = ConditionalNonterm<C::Nonterminal, Result<X, Y>,
+        A::Nonterminal>;
+    type Terminal = A::Terminal;
+
+    #[inline]
+    fn step(self, input: &A::Input) -> NodeResult<Self::Nonterminal,
+        A::Terminal, Self>
+    {
+        match self {
+            IfThenElse::Condition(c, a, b) => match c.step(input) {
+                NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                    ConditionalNonterm::Condition(n),
+                    IfThenElse::Condition(m, a, b)
+                ),
+                NodeResult::Terminal(Result::Ok(x)) => NodeResult::Nonterminal(
+                    ConditionalNonterm::Decided(Result::Ok(x)),
+                    IfThenElse::Then(a)
+                ),
+                NodeResult::Terminal(Result::Err(y)) => NodeResult::Nonterminal(
+                    ConditionalNonterm::Decided(Result::Err(y)),
+                    IfThenElse::Else(b)
+                )
+            },
+            IfThenElse::Then(a) => match a.step(input) {
+                NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                    ConditionalNonterm::Branch(n),
+                    IfThenElse::Then(m)
+                ),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            },
+            IfThenElse::Else(b) => match b.step(input) {
+                NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                    ConditionalNonterm::Branch(n),
+                    IfThenElse::Else(m)
+                ),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        match self {
+            IfThenElse::Condition(c, _, _) => c.abort(),
+            IfThenElse::Then(a) => a.abort(),
+            IfThenElse::Else(b) => b.abort()
+        }
+    }
+}
+
+/// Nonterminal type of the while node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WhileNonterm<R, S, N, T> {
+    /// The condition node was stepped, and did not terminate. 
+    Condition(R),
+    /// The condition node terminated with the contained terminal. 
+    Decided(S),
+    /// The body node was stepped, and did not terminate. 
+    Body(N),
+    /// The body node terminated, completing the iteration with the 
+    /// contained index. 
+    Completed(usize, T)
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum WhileState<C, B> {
+    Condition(C),
+    Body(B)
+}
+
+/// Loop node, which runs a fresh condition node until it terminates, and if 
+/// it terminated with a Result::Ok, runs a fresh body node until it 
+/// terminates, repeating for as long as the condition holds. 
+/// 
+/// Once the condition fails, the loop exits with Result::Ok of the exit 
+/// value it was configured with. If a maximum number of iterations is set, 
+/// and the condition still holds after that many iterations, the loop exits 
+/// with Result::Err of the number of iterations instead. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct WhileNode<C, B, MC, MB, T> where 
+    MC: Fn() -> C,
+    MB: Fn() -> B
+{
+    state: WhileState<C, B>,
+    condition: MC,
+    body: MB,
+    exit: T,
+    iterations: usize,
+    max_iterations: Option<usize>
+}
+
+impl<C, B, MC, MB, T> WhileNode<C, B, MC, MB, T> where 
+    MC: Fn() -> C,
+    MB: Fn() -> B
+{
+    /// Create a new while node from constructors of the condition and body 
+    /// nodes, which exits with the given value once the condition fails. 
+    pub fn new(condition: MC, body: MB, exit: T) -> WhileNode<C, B, MC, MB, T> {
+        WhileNode {
+            state: WhileState::Condition(condition()),
+            condition: condition,
+            body: body,
+            exit: exit,
+            iterations: 0,
+            max_iterations: Option::None
+        }
+    }
+
+    /// Create a new while node which, additionally, exits after the given 
+    /// number of iterations. 
+    pub fn with_max_iterations(condition: MC, body: MB, exit: T, max: usize) -> 
+        WhileNode<C, B, MC, MB, T>
+    {
+        let mut node = WhileNode::new(condition, body, exit);
+        node.max_iterations = Option::Some(max);
+        node
+    }
+}
+
+impl<C, B, MC, MB, T, X, Y> BehaviorTreeNode for WhileNode<C, B, MC, MB, T> where 
+    B: BehaviorTreeNode,
+    C: BehaviorTreeNode<Input=B::Input, Terminal=Result<X, Y>>,
+    MC: Fn() -> C,
+    MB: Fn() -> B
+{
+    type Input = B::Input;
+    type Nonterminal = WhileNonterm<C::Nonterminal, Result<X, Y>, B::Nonterminal,
+        B::Terminal>;
+    type Terminal = Result<T, usize>;
+
+    #[inline]
+    fn step(self, input: &B::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut this = self;
+        match this.state {
+            WhileState::Condition(c) => match c.step(input) {
+                NodeResult::Nonterminal(n, m) => {
+                    this.state = WhileState::Condition(m);
+                    NodeResult::Nonterminal(WhileNonterm::Condition(n), this)
+                },
+                NodeResult::Terminal(Result::Ok(x)) => {
+                    let iterations = this.iterations;
+                    if this.max_iterations.map_or(false, |m| iterations >= m) {
+                        return NodeResult::Terminal(Result::Err(iterations));
+                    }
+                    this.state = WhileState::Body((this.body)());
+                    NodeResult::Nonterminal(
+                        WhileNonterm::Decided(Result::Ok(x)),
+                        this
+                    )
+                },
+                NodeResult::Terminal(Result::Err(_)) => {
+                    NodeResult::Terminal(Result::Ok(this.exit))
+                }
+            },
+            WhileState::Body(b) => match b.step(input) {
+                NodeResult::Nonterminal(n, m) => {
+                    this.state = WhileState::Body(m);
+                    NodeResult::Nonterminal(WhileNonterm::Body(n), this)
+                },
+                NodeResult::Terminal(t) => {
+                    let index = this.iterations;
+                    this.iterations += 1;
+                    this.state = WhileState::Condition((this.condition)());
+                    NodeResult::Nonterminal(WhileNonterm::Completed(index, t), this)
+                }
+            }
+        }
+    }
+
+    fn abort(self) {
+        match self.state {
+            WhileState::Condition(c) => c.abort(),
+            WhileState::Body(b) => b.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use conditional_node::{ConditionalNonterm, WhileNode};
+
+    fn sign_check(input: &i64) -> Statepoint<(), Result<i64, i64>> {
+        if *input > 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else if *input < 0 {
+            Statepoint::Terminal(Result::Err(*input))
+        } else {
+            Statepoint::Nonterminal(())
+        }
+    }
+
+    fn wait_positive(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    fn wait_negative(input: &i64) -> Statepoint<i64, i64> {
+        if *input < 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(-*input)
+        }
+    }
+
+    #[test]
+    fn if_then_test() {
+        use conditional_node::IfThenElse;
+        let test_node = IfThenElse::new(
+            PredicateWait::new(sign_check),
+            PredicateWait::new(wait_positive as fn(&i64) -> Statepoint<i64, i64>),
+            PredicateWait::new(wait_negative as fn(&i64) -> Statepoint<i64, i64>)
+        );
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(ConditionalNonterm::Condition(()), n) => n,
+            _ => unreachable!("Expected condition nonterminal")
+        };
+        let test_node_2 = match test_node_1.step(&3) {
+            NodeResult::Nonterminal(ConditionalNonterm::Decided(v), n) => {
+                assert_eq!(v, Result::Ok(3));
+                n
+            },
+            _ => unreachable!("Expected condition terminal")
+        };
+        // Committed to the then branch, even though the sign changed
+        let test_node_3 = match test_node_2.step(&4) {
+            NodeResult::Nonterminal(ConditionalNonterm::Branch(v), n) => {
+                assert_eq!(v, 4);
+                n
+            },
+            _ => unreachable!("Expected branch nonterminal")
+        };
+        match test_node_3.step(&-2) {
+            NodeResult::Terminal(t) => assert_eq!(t, -2),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn if_else_test() {
+        use conditional_node::IfThenElse;
+        let test_node = IfThenElse::new(
+            PredicateWait::new(sign_check),
+            PredicateWait::new(wait_positive as fn(&i64) -> Statepoint<i64, i64>),
+            PredicateWait::new(wait_negative as fn(&i64) -> Statepoint<i64, i64>)
+        );
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(ConditionalNonterm::Decided(v), n) => {
+                assert_eq!(v, Result::Err(-1));
+                n
+            },
+            _ => unreachable!("Expected condition terminal")
+        };
+        match test_node_1.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, -5),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    fn below_three(input: &i64) -> Statepoint<(), Result<i64, i64>> {
+        if *input < 3 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    #[test]
+    fn while_test() {
+        use conditional_node::WhileNonterm;
+        let test_node = WhileNode::new(
+            || PredicateWait::new(below_three),
+            || PredicateWait::new(wait_positive),
+            "done"
+        );
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(WhileNonterm::Decided(v), n) => {
+                assert_eq!(v, Result::Ok(1));
+                n
+            },
+            _ => unreachable!("Expected condition terminal")
+        };
+        let test_node_2 = match test_node_1.step(&1) {
+            NodeResult::Nonterminal(WhileNonterm::Body(v), n) => {
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected body nonterminal")
+        };
+        let test_node_3 = match test_node_2.step(&0) {
+            NodeResult::Nonterminal(WhileNonterm::Completed(i, v), n) => {
+                assert_eq!(i, 0);
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected body terminal")
+        };
+        match test_node_3.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok("done")),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn while_max_iterations_test() {
+        let test_node = WhileNode::with_max_iterations(
+            || PredicateWait::new(below_three),
+            || PredicateWait::new(wait_positive),
+            "done",
+            2
+        );
+        let mut test_node_1 = test_node;
+        for _ in 0..4 {
+            test_node_1 = match test_node_1.step(&0) {
+                NodeResult::Nonterminal(_, n) => n,
+                _ => unreachable!("Expected nonterminal transition")
+            };
+        }
+        match test_node_1.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(2)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
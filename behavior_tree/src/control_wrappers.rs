@@ -1,6 +1,11 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::panic::{self, AssertUnwindSafe};
+use std::any::Any;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GuardFailure<N>(pub N); 
 
 /// Guard wrapper for a node, which, if the guard condition fails, causes an 
@@ -44,6 +49,7 @@ impl<N, G> BehaviorTreeNode for GuardedNode<N, G> where
                 if (self.guard)(input, &n) {
                     NodeResult::Nonterminal(n, GuardedNode::new(self.guard, m))
                 } else {
+                    m.abort();
                     NodeResult::Terminal(Result::Err(GuardFailure(n)))
                 }
             },
@@ -52,10 +58,668 @@ impl<N, G> BehaviorTreeNode for GuardedNode<N, G> where
             )
         }
     }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Guard wrapper for a node, which checks a predicate over the input before 
+/// each step, stepping the node while it holds, and otherwise aborting the 
+/// node and exiting with a configured terminal. 
+///
+/// Unlike GuardedNode, the predicate is checked before the node sees the 
+/// input, which makes this the wrapper of choice for interruptible 
+/// behaviors. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Guard<N, P> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input) -> bool
+{
+    node: N,
+    predicate: P,
+    interrupted: N::Terminal
+}
+
+impl<N, P> Guard<N, P> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input) -> bool
+{
+    /// Create a new guard, exiting with the given terminal once the 
+    /// predicate stops holding. 
+    pub fn new(predicate: P, interrupted: N::Terminal, node: N) -> Guard<N, P> {
+        Guard {
+            node: node,
+            predicate: predicate,
+            interrupted: interrupted
+        }
+    }
+}
+
+impl<N, P> BehaviorTreeNode for Guard<N, P> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input) -> bool
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        if !(self.predicate)(input) {
+            self.node.abort();
+            return NodeResult::Terminal(self.interrupted);
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n, 
+                Guard::new(self.predicate, self.interrupted, m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Counted tokens shared by a family of semaphore wrappers, possibly across 
+/// many trees and threads, limiting how many of them may run their nodes 
+/// at once. 
+#[derive(Clone, Debug)]
+pub struct Semaphore {
+    available: Arc<AtomicUsize>
+}
+
+impl Semaphore {
+    /// Create a new semaphore with the given number of tokens. 
+    pub fn new(tokens: usize) -> Semaphore {
+        Semaphore {
+            available: Arc::new(AtomicUsize::new(tokens))
+        }
+    }
+
+    /// Get the number of tokens currently available. 
+    pub fn available(&self) -> usize {
+        self.available.load(Ordering::SeqCst)
+    }
+
+    fn acquire(&self) -> Option<Permit> {
+        let mut current = self.available.load(Ordering::SeqCst);
+        while current > 0 {
+            match self.available.compare_exchange(current, current - 1, 
+                Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Result::Ok(_) => return Option::Some(Permit {
+                    semaphore: self.clone()
+                }),
+                Result::Err(c) => current = c
+            }
+        }
+        Option::None
+    }
+}
+
+/// A token held by a semaphore wrapper, returned to the semaphore when 
+/// dropped. 
+#[derive(Debug)]
+struct Permit {
+    semaphore: Semaphore
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.semaphore.available.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Nonterminal enum for a semaphore wrapper. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SemaphoreNonterm<N> {
+    /// No token was available, so the node was not stepped. 
+    Waiting,
+    /// The node was stepped while holding a token. 
+    Running(N)
+}
+
+/// Wrapper for a node, which only steps the node while holding one of the 
+/// tokens of a semaphore, waiting for a token to become available 
+/// otherwise. 
+///
+/// The token is held from the first step the node is run until the wrapper 
+/// terminates, is aborted, or is dropped. 
+#[derive(Debug)]
+pub struct SemaphoreNode<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    semaphore: Semaphore,
+    permit: Option<Permit>
+}
+
+impl<N> SemaphoreNode<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new semaphore wrapper taking tokens from the given 
+    /// semaphore. 
+    pub fn new(semaphore: Semaphore, node: N) -> SemaphoreNode<N> {
+        SemaphoreNode {
+            node: node,
+            semaphore: semaphore,
+            permit: Option::None
+        }
+    }
+
+    /// Check whether the wrapper is holding a token. 
+    pub fn is_running(&self) -> bool {
+        self.permit.is_some()
+    }
+}
+
+impl<N> BehaviorTreeNode for SemaphoreNode<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = SemaphoreNonterm<N::Nonterminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        let permit = match self.permit {
+            Option::Some(p) => p,
+            Option::None => match self.semaphore.acquire() {
+                Option::Some(p) => p,
+                Option::None => return NodeResult::Nonterminal(
+                    SemaphoreNonterm::Waiting,
+                    SemaphoreNode::new(self.semaphore, self.node)
+                )
+            }
+        };
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                SemaphoreNonterm::Running(n),
+                SemaphoreNode {
+                    node: m,
+                    semaphore: self.semaphore,
+                    permit: Option::Some(permit)
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Failure value of the panic catching wrapper, describing the payload of 
+/// the panic. 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Panicked(pub String);
+
+impl Panicked {
+    fn from_payload(payload: Box<Any + Send>) -> Panicked {
+        if let Option::Some(s) = payload.downcast_ref::<&str>() {
+            Panicked((*s).to_owned())
+        } else if let Option::Some(s) = payload.downcast_ref::<String>() {
+            Panicked(s.clone())
+        } else {
+            Panicked("Box<Any>".to_owned())
+        }
+    }
+}
+
+/// Wrapper for a node, which catches panics while stepping the node, and 
+/// fails with a description of the panic, so that one misbehaving node 
+/// cannot bring the whole tree down. 
+///
+/// The node is lost when it panics, so it cannot be aborted. As with 
+/// std::panic::catch_unwind, panics which abort the process are not caught, 
+/// and the panic hook still runs. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CatchPanic<N> where 
+    N: BehaviorTreeNode
+{
+    node: N
+}
+
+impl<N> CatchPanic<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new panic catching wrapper. 
+    pub fn new(node: N) -> CatchPanic<N> {
+        CatchPanic {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for CatchPanic<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, Panicked>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        let node = self.node;
+        match panic::catch_unwind(AssertUnwindSafe(|| node.step(input))) {
+            Result::Ok(NodeResult::Nonterminal(n, m)) => {
+                NodeResult::Nonterminal(n, CatchPanic::new(m))
+            },
+            Result::Ok(NodeResult::Terminal(t)) => {
+                NodeResult::Terminal(Result::Ok(t))
+            },
+            Result::Err(e) => {
+                NodeResult::Terminal(Result::Err(Panicked::from_payload(e)))
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Handle for interrupting interruptible wrappers from outside of the tree, 
+/// possibly from another thread. 
+///
+/// Clones of the handle share the same interrupt. 
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle {
+    triggered: Arc<AtomicBool>
+}
+
+impl InterruptHandle {
+    /// Create a new, untriggered interrupt handle. 
+    pub fn new() -> InterruptHandle {
+        InterruptHandle::default()
+    }
+
+    /// Trigger the interrupt, which interrupts the next interruptible 
+    /// wrapper stepped with this handle. 
+    pub fn trigger(&self) {
+        self.triggered.store(true, Ordering::SeqCst)
+    }
+
+    /// Withdraw a pending interrupt which has not been handled yet. 
+    pub fn clear(&self) {
+        self.triggered.store(false, Ordering::SeqCst)
+    }
+
+    /// Check whether an interrupt is pending. 
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.load(Ordering::SeqCst)
+    }
+}
+
+/// Wrapper for a node, which, when its interrupt handle is triggered, 
+/// aborts the node on the next step and exits with a configured terminal. 
+///
+/// Handling the interrupt clears it, so each trigger interrupts a single 
+/// wrapper. Creating a wrapper clears it too, so a trigger fired while no 
+/// wrapper was running does not interrupt the next one created with the 
+/// handle. 
+#[derive(Clone, Debug)]
+pub struct Interruptible<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    handle: InterruptHandle,
+    interrupted: N::Terminal
+}
+
+impl<N> Interruptible<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new interruptible wrapper, exiting with the given terminal 
+    /// once interrupted through the given handle. Any interrupt pending on 
+    /// the handle is withdrawn. 
+    pub fn new(handle: InterruptHandle, interrupted: N::Terminal, node: N) 
+        -> Interruptible<N> 
+    {
+        handle.clear();
+        Interruptible {
+            node: node,
+            handle: handle,
+            interrupted: interrupted
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for Interruptible<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        if self.handle.triggered.swap(false, Ordering::SeqCst) {
+            self.node.abort();
+            return NodeResult::Terminal(self.interrupted);
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n, 
+                Interruptible {
+                    node: m,
+                    handle: self.handle,
+                    interrupted: self.interrupted
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Handle for pausing and resuming pausable wrappers from outside of the 
+/// tree, possibly from another thread. 
+///
+/// Clones of the handle share the same state. 
+#[derive(Clone, Debug, Default)]
+pub struct PauseHandle {
+    paused: Arc<AtomicBool>
+}
+
+impl PauseHandle {
+    /// Create a new pause handle, initially not paused. 
+    pub fn new() -> PauseHandle {
+        PauseHandle::default()
+    }
+
+    /// Pause the wrappers using this handle. 
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst)
+    }
+
+    /// Resume the wrappers using this handle. 
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst)
+    }
+
+    /// Check whether the wrappers using this handle are paused. 
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+}
+
+/// Nonterminal enum for a pausable wrapper. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PauseNonterm<N> {
+    /// The wrapper was paused, so the node was not stepped. 
+    Paused,
+    /// The node was stepped as normal. 
+    Running(N)
+}
+
+/// Wrapper for a node, which does not step the node while its pause handle 
+/// is paused, for cutscenes and debuggers. 
+#[derive(Clone, Debug)]
+pub struct Pausable<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    handle: PauseHandle
+}
+
+impl<N> Pausable<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new pausable wrapper controlled by the given handle. 
+    pub fn new(handle: PauseHandle, node: N) -> Pausable<N> {
+        Pausable {
+            node: node,
+            handle: handle
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for Pausable<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = PauseNonterm<N::Nonterminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        if self.handle.is_paused() {
+            return NodeResult::Nonterminal(PauseNonterm::Paused, self);
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                PauseNonterm::Running(n), 
+                Pausable::new(self.handle, m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// A resource held by a scoped wrapper, which is released when dropped. 
+struct HeldResource<X, R> where 
+    R: FnOnce(X)
+{
+    resource: Option<X>,
+    release: Option<R>
+}
+
+impl<X, R> Drop for HeldResource<X, R> where 
+    R: FnOnce(X)
+{
+    fn drop(&mut self) {
+        if let (Option::Some(x), Option::Some(r)) = (self.resource.take(), 
+            self.release.take()) 
+        {
+            r(x)
+        }
+    }
+}
+
+enum ScopedInner<X, A, R> where 
+    R: FnOnce(X)
+{
+    Pending(A, R),
+    Held(HeldResource<X, R>)
+}
+
+/// Wrapper for a node, which acquires a resource when first stepped, and 
+/// releases it once the node terminates, or the wrapper is aborted or 
+/// dropped, so that preempted behaviors do not leak resources. 
+///
+/// The resource is released before the terminal of the node is returned. 
+pub struct ScopedResource<N, X, A, R> where 
+    N: BehaviorTreeNode,
+    A: FnOnce(&N::Input) -> X,
+    R: FnOnce(X)
+{
+    node: N,
+    inner: ScopedInner<X, A, R>
+}
+
+impl<N, X, A, R> ScopedResource<N, X, A, R> where 
+    N: BehaviorTreeNode,
+    A: FnOnce(&N::Input) -> X,
+    R: FnOnce(X)
+{
+    /// Create a new scoped resource wrapper, acquiring the resource from the 
+    /// first input, and releasing it with the given closure. 
+    pub fn new(acquire: A, release: R, node: N) -> ScopedResource<N, X, A, R> {
+        ScopedResource {
+            node: node,
+            inner: ScopedInner::Pending(acquire, release)
+        }
+    }
+
+    /// Get a reference to the resource, if it has been acquired. 
+    pub fn resource(&self) -> Option<&X> {
+        match self.inner {
+            ScopedInner::Held(ref h) => h.resource.as_ref(),
+            ScopedInner::Pending(_, _) => Option::None
+        }
+    }
+}
+
+impl<N, X, A, R> BehaviorTreeNode for ScopedResource<N, X, A, R> where 
+    N: BehaviorTreeNode,
+    A: FnOnce(&N::Input) -> X,
+    R: FnOnce(X)
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        N::Terminal, Self> 
+    {
+        let held = match self.inner {
+            ScopedInner::Pending(a, r) => HeldResource {
+                resource: Option::Some(a(input)),
+                release: Option::Some(r)
+            },
+            ScopedInner::Held(h) => h
+        };
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n, 
+                ScopedResource {
+                    node: m,
+                    inner: ScopedInner::Held(held)
+                }
+            ),
+            NodeResult::Terminal(t) => {
+                drop(held);
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        let held = self.inner;
+        self.node.abort();
+        drop(held)
+    }
+}
+
+/// Failure value of the contract wrapper, telling which condition of the 
+/// contract was violated. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ContractViolation<T> {
+    /// The precondition did not hold for the first input. 
+    Precondition,
+    /// The postcondition did not hold for the contained terminal. 
+    Postcondition(T)
+}
+
+/// Wrapper for a node, which checks a precondition on the input the node 
+/// is entered with, and a postcondition on the terminal of the node, 
+/// failing with the violation if either does not hold. 
+///
+/// If the precondition does not hold, the node is aborted without being 
+/// stepped. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Contract<N, P, Q> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input) -> bool,
+    Q: Fn(&N::Input, &N::Terminal) -> bool
+{
+    node: N,
+    precondition: P,
+    postcondition: Q,
+    entered: bool
+}
+
+impl<N, P, Q> Contract<N, P, Q> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input) -> bool,
+    Q: Fn(&N::Input, &N::Terminal) -> bool
+{
+    /// Create a new contract wrapper. 
+    pub fn new(precondition: P, postcondition: Q, node: N) -> Contract<N, P, Q> {
+        Contract {
+            node: node,
+            precondition: precondition,
+            postcondition: postcondition,
+            entered: false
+        }
+    }
+}
+
+impl<N, P, Q> BehaviorTreeNode for Contract<N, P, Q> where 
+    N: BehaviorTreeNode,
+    P: Fn(&N::Input) -> bool,
+    Q: Fn(&N::Input, &N::Terminal) -> bool
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, ContractViolation<N::Terminal>>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        if !self.entered && !(self.precondition)(input) {
+            self.node.abort();
+            return NodeResult::Terminal(
+                Result::Err(ContractViolation::Precondition)
+            );
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n, 
+                Contract {
+                    node: m,
+                    precondition: self.precondition,
+                    postcondition: self.postcondition,
+                    entered: true
+                }
+            ),
+            NodeResult::Terminal(t) => if (self.postcondition)(input, &t) {
+                NodeResult::Terminal(Result::Ok(t))
+            } else {
+                NodeResult::Terminal(
+                    Result::Err(ContractViolation::Postcondition(t))
+                )
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
 }
 
 /// Enumeration of the possible decisions of a StepControl controller.
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum StepDecision<N> {
     /// Don't step the machine. 
     Pause, 
@@ -69,6 +733,7 @@ pub enum StepDecision<N> {
 
 /// Nonterminal enum for a step-controlled node. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum StepCtrlNonterm<I> {
     /// The node was stepped as normal, perhaps after resetting it. 
     Stepped(I),
@@ -128,12 +793,14 @@ impl<N, S> BehaviorTreeNode for StepControlledNode<N, S> where
                 }
             },
             StepDecision::Reset(new_node) => {
+                self.node.abort();
                 NodeResult::Nonterminal(StepCtrlNonterm::Paused, Self::new(
                     self.stepper,
                     new_node
                 ))
             },
-            StepDecision::ResetPlay(mut new_machine) => {
+            StepDecision::ResetPlay(new_machine) => {
+                self.node.abort();
                 match new_machine.step(input) {
                     NodeResult::Nonterminal(n, m) => {
                         NodeResult::Nonterminal(
@@ -146,9 +813,14 @@ impl<N, S> BehaviorTreeNode for StepControlledNode<N, S> where
             }
         }
     }
+
+    fn abort(self) {
+        self.node.abort()
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PostResetNonterm<N, T> {
     /// The node was not reset. 
     NoReset(N),
@@ -197,10 +869,13 @@ impl <N, P> BehaviorTreeNode for PostResetNode<N, P> where
         match self.node.step(input) {
             NodeResult::Nonterminal(v, n) => {
                 match (self.resetter)(input, Statepoint::Nonterminal(&v)) {
-                    Option::Some(k) => NodeResult::Nonterminal(
-                        PostResetNonterm::ManualReset(v),
-                        Self::new(self.resetter, k)
-                    ),
+                    Option::Some(k) => {
+                        n.abort();
+                        NodeResult::Nonterminal(
+                            PostResetNonterm::ManualReset(v),
+                            Self::new(self.resetter, k)
+                        )
+                    },
                     Option::None => NodeResult::Nonterminal(
                         PostResetNonterm::NoReset(v),
                         Self::new(self.resetter, n)
@@ -218,6 +893,10 @@ impl <N, P> BehaviorTreeNode for PostResetNode<N, P> where
             }
         }
     }
+
+    fn abort(self) {
+        self.node.abort()
+    }
 }
 
 #[cfg(test)]
@@ -260,20 +939,248 @@ mod tests {
         };
     }
 
-    #[derive(Copy, Clone)]
+    #[test]
+    fn guard_test() {
+        use control_wrappers::Guard;
+        let base_node = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(Result::Ok(*input))
+            }
+        });
+        let wrapped_node = Guard::new(|input: &i64| *input < 5, Result::Err(()), 
+            base_node);
+        let wrapped_node_1 = match wrapped_node.step(&3) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 3);
+                m
+            },
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        match wrapped_node_1.step(&7) {
+            NodeResult::Terminal(x) => assert_eq!(x, Result::Err(())),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+        match wrapped_node_1.step(&0) {
+            NodeResult::Terminal(x) => assert_eq!(x, Result::Ok(0)),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn semaphore_test() {
+        use control_wrappers::{Semaphore, SemaphoreNode, SemaphoreNonterm};
+        let wait = |input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        };
+        let semaphore = Semaphore::new(1);
+        let first = SemaphoreNode::new(semaphore.clone(), PredicateWait::new(wait));
+        let second = SemaphoreNode::new(semaphore.clone(), PredicateWait::new(wait));
+        let first_1 = match first.step(&1) {
+            NodeResult::Nonterminal(SemaphoreNonterm::Running(1), m) => m,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        assert_eq!(semaphore.available(), 0);
+        let second_1 = match second.step(&1) {
+            NodeResult::Nonterminal(SemaphoreNonterm::Waiting, m) => m,
+            _ => unreachable!("Expected waiting nonterminal")
+        };
+        match first_1.step(&0) {
+            NodeResult::Terminal(x) => assert_eq!(x, 0),
+            _ => unreachable!("Expected terminal state")
+        };
+        assert_eq!(semaphore.available(), 1);
+        let second_2 = match second_1.step(&2) {
+            NodeResult::Nonterminal(SemaphoreNonterm::Running(2), m) => m,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        second_2.abort();
+        assert_eq!(semaphore.available(), 1);
+    }
+
+    #[test]
+    fn catch_panic_test() {
+        use control_wrappers::{CatchPanic, Panicked};
+        let base_node = PredicateWait::new(|input: &i64| {
+            if *input < 0 {
+                panic!("negative input");
+            } else if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let wrapped_node = CatchPanic::new(base_node);
+        let wrapped_node_1 = match wrapped_node.step(&3) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 3);
+                m
+            },
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        match wrapped_node_1.step(&-1) {
+            NodeResult::Terminal(x) => assert_eq!(x, 
+                Result::Err(Panicked("negative input".to_owned()))),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn interruptible_test() {
+        use control_wrappers::{InterruptHandle, Interruptible};
+        let wait = |input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(Result::Ok(*input))
+            }
+        };
+        let handle = InterruptHandle::new();
+        let wrapped_node = Interruptible::new(handle.clone(), Result::Err(()), 
+            PredicateWait::new(wait));
+        let wrapped_node_1 = match wrapped_node.step(&3) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 3);
+                m
+            },
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        handle.trigger();
+        match wrapped_node_1.step(&3) {
+            NodeResult::Terminal(x) => assert_eq!(x, Result::Err(())),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+        assert!(!handle.is_triggered());
+        handle.trigger();
+        let fresh_node = Interruptible::new(handle.clone(), Result::Err(()), 
+            PredicateWait::new(wait));
+        assert!(!handle.is_triggered());
+        match fresh_node.step(&0) {
+            NodeResult::Terminal(x) => assert_eq!(x, Result::Ok(0)),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn pausable_test() {
+        use control_wrappers::{PauseHandle, Pausable, PauseNonterm};
+        let base_node = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let handle = PauseHandle::new();
+        let wrapped_node = Pausable::new(handle.clone(), base_node);
+        handle.pause();
+        let wrapped_node_1 = match wrapped_node.step(&0) {
+            NodeResult::Nonterminal(PauseNonterm::Paused, m) => m,
+            _ => unreachable!("Expected paused nonterminal")
+        };
+        handle.resume();
+        let wrapped_node_2 = match wrapped_node_1.step(&2) {
+            NodeResult::Nonterminal(PauseNonterm::Running(2), m) => m,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        match wrapped_node_2.step(&0) {
+            NodeResult::Terminal(x) => assert_eq!(x, 0),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[test]
+    fn scoped_resource_test() {
+        use control_wrappers::ScopedResource;
+        use std::cell::Cell;
+        let held = Cell::new(0);
+        let wait = |input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        };
+        let acquire = |input: &i64| {
+            held.set(held.get() + 1);
+            *input
+        };
+        let release = |_: i64| held.set(held.get() - 1);
+        let wrapped_node = ScopedResource::new(acquire, release, 
+            PredicateWait::new(wait));
+        assert_eq!(held.get(), 0);
+        let wrapped_node_1 = match wrapped_node.step(&3) {
+            NodeResult::Nonterminal(_, m) => m,
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        assert_eq!(held.get(), 1);
+        assert_eq!(wrapped_node_1.resource(), Option::Some(&3));
+        match wrapped_node_1.step(&0) {
+            NodeResult::Terminal(x) => assert_eq!(x, 0),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+        assert_eq!(held.get(), 0);
+        let aborted_node = ScopedResource::new(acquire, release, 
+            PredicateWait::new(wait));
+        match aborted_node.step(&3) {
+            NodeResult::Nonterminal(_, m) => m.abort(),
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        assert_eq!(held.get(), 0);
+    }
+
+    #[test]
+    fn contract_test() {
+        use control_wrappers::{Contract, ContractViolation};
+        let wait = |input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        };
+        let pre = |input: &i64| *input < 10;
+        let post = |_input: &i64, output: &i64| *output > -10;
+        let wrapped_node = Contract::new(pre, post, PredicateWait::new(wait));
+        match wrapped_node.step(&12) {
+            NodeResult::Terminal(x) => assert_eq!(x, 
+                Result::Err(ContractViolation::Precondition)),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+        let wrapped_node_1 = match wrapped_node.step(&3) {
+            NodeResult::Nonterminal(_, m) => m,
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        // The precondition is only checked on entry
+        let wrapped_node_2 = match wrapped_node_1.step(&12) {
+            NodeResult::Nonterminal(_, m) => m,
+            NodeResult::Terminal(_) => unreachable!("Expected nonterminal state")
+        };
+        match wrapped_node_2.step(&-20) {
+            NodeResult::Terminal(x) => assert_eq!(x, 
+                Result::Err(ContractViolation::Postcondition(-20))),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+        match wrapped_node.step(&0) {
+            NodeResult::Terminal(x) => assert_eq!(x, Result::Ok(0)),
+            NodeResult::Nonterminal(_, _) => unreachable!("Expected terminal state")
+        };
+    }
+
+    #[derive(Copy, Clone, Default)]
     enum Ratchet {
+        #[default]
         Zero,
         One,
         Two,
         Three
     }
 
-    impl Default for Ratchet {
-        fn default() -> Ratchet {
-            Ratchet::Zero
-        }
-    }
-
     impl ReferenceTransition for Ratchet {
         type Input = i64;
         type Action = Statepoint<i64, ()>;
@@ -0,0 +1,240 @@
+use binary_codec::{BinaryCodec, CodecError};
+use snapshot::Snapshot;
+use std::collections::BTreeMap;
+
+/// Snapshots of a population of running trees, keyed by agent, as recorded 
+/// on one tick. 
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Frame<K> where 
+    K: Ord
+{
+    /// The encoded snapshots of every tree, as the keyframe with the given 
+    /// number. 
+    Keyframe(u64, BTreeMap<K, Vec<u8>>),
+    /// The encoded snapshots of the trees whose state changed since the 
+    /// keyframe with the given number, along with the agents which were 
+    /// removed since. 
+    Delta(u64, BTreeMap<K, Vec<u8>>, Vec<K>)
+}
+
+/// Recorder of the snapshots of a population of running trees, such as 
+/// the agents persisted by a server on every tick. 
+///
+/// Every so many ticks, the recorder writes a keyframe with the snapshots 
+/// of all of the trees. In between, it writes deltas with the snapshots of 
+/// only the trees whose encoded state differs from the last keyframe, so 
+/// that any one delta restores the population along with that keyframe. 
+#[derive(Clone, Debug)]
+pub struct DeltaRecorder<K> where 
+    K: Ord
+{
+    codec: BinaryCodec,
+    interval: u32,
+    ticks: u32,
+    keyframe: Option<u64>,
+    states: BTreeMap<K, Vec<u8>>
+}
+
+impl<K> DeltaRecorder<K> where 
+    K: Ord + Clone
+{
+    /// Create a new recorder, which writes a keyframe every given number 
+    /// of ticks, its first one on the first tick. 
+    pub fn new(codec: BinaryCodec, interval: u32) -> DeltaRecorder<K> {
+        DeltaRecorder {
+            codec: codec,
+            interval: if interval == 0 { 1 } else { interval },
+            ticks: 0,
+            keyframe: Option::None,
+            states: BTreeMap::new()
+        }
+    }
+
+    /// Make the next recorded frame a keyframe. 
+    pub fn force_keyframe(&mut self) {
+        self.ticks = 0;
+    }
+
+    /// Record the snapshots of the given agents on this tick. 
+    pub fn record<'a, S, I>(&mut self, agents: I) ->
+        Result<Frame<K>, CodecError> where 
+        S: Snapshot + 'a,
+        I: IntoIterator<Item = (K, &'a S)>
+    {
+        let mut states = BTreeMap::new();
+        for (key, tree) in agents {
+            states.insert(key, self.codec.encode_payload(&tree.snapshot())?);
+        }
+        let base = match self.keyframe {
+            Option::Some(n) if self.ticks != 0 => n,
+            _ => {
+                let number = self.keyframe.map_or(0, |n| n + 1);
+                self.keyframe = Option::Some(number);
+                self.ticks = 1 % self.interval;
+                self.states = states.clone();
+                return Result::Ok(Frame::Keyframe(number, states));
+            }
+        };
+        self.ticks = (self.ticks + 1) % self.interval;
+        let removed = self.states.keys()
+            .filter(|k| !states.contains_key(k))
+            .cloned()
+            .collect();
+        let changed = states.into_iter()
+            .filter(|&(ref k, ref v)| self.states.get(k) != Option::Some(v))
+            .collect();
+        Result::Ok(Frame::Delta(base, changed, removed))
+    }
+}
+
+/// Restorer of a population of running trees from the frames written by a 
+/// delta recorder. 
+#[derive(Clone, Debug)]
+pub struct DeltaRestorer<K> where 
+    K: Ord
+{
+    codec: BinaryCodec,
+    keyframe: Option<u64>,
+    states: BTreeMap<K, Vec<u8>>,
+    changed: BTreeMap<K, Vec<u8>>,
+    removed: Vec<K>
+}
+
+impl<K> DeltaRestorer<K> where 
+    K: Ord
+{
+    /// Create a new restorer, with no frames applied. 
+    pub fn new(codec: BinaryCodec) -> DeltaRestorer<K> {
+        DeltaRestorer {
+            codec: codec,
+            keyframe: Option::None,
+            states: BTreeMap::new(),
+            changed: BTreeMap::new(),
+            removed: Vec::new()
+        }
+    }
+
+    /// Apply the given frame. A delta replaces the last delta applied, and 
+    /// fails to be applied if it is not against the last keyframe applied. 
+    pub fn apply(&mut self, frame: Frame<K>) -> Result<(), CodecError> {
+        match frame {
+            Frame::Keyframe(number, states) => {
+                self.keyframe = Option::Some(number);
+                self.states = states;
+                self.changed = BTreeMap::new();
+                self.removed = Vec::new();
+            },
+            Frame::Delta(base, changed, removed) => {
+                if self.keyframe != Option::Some(base) {
+                    return Result::Err(CodecError::Keyframe(base));
+                }
+                self.changed = changed;
+                self.removed = removed;
+            }
+        };
+        Result::Ok(())
+    }
+
+    /// Get the keys of the agents in the restored population, in order. 
+    pub fn keys(&self) -> Vec<&K> {
+        let mut keys = self.states.keys()
+            .filter(|k| !self.removed.contains(k))
+            .chain(self.changed.keys().filter(|k| !self.states.contains_key(k)))
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys
+    }
+
+    /// Reconstruct the running tree of the given agent from its definition, 
+    /// or get nothing if the agent is not in the restored population. 
+    pub fn restore<S>(&self, key: &K, definition: &S::Definition) ->
+        Option<Result<S, CodecError>> where 
+        S: Snapshot
+    {
+        if self.removed.contains(key) {
+            return Option::None;
+        }
+        self.changed.get(key).or_else(|| self.states.get(key)).map(|b| {
+            self.codec.decode_payload(b)
+                .map(|blob| S::from_snapshot(definition, blob))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use binary_codec::{BinaryCodec, CodecError};
+    use delta_snapshot::{DeltaRecorder, DeltaRestorer, Frame};
+    use node_runner::NodeRunner;
+    use persist_state::PersistState;
+    use stackbt_automata_impl::automaton::Automaton;
+
+    struct Countdown(u32);
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = ();
+        type Nonterminal = u32;
+        type Terminal = ();
+
+        fn step(self, _input: &()) -> NodeResult<u32, (), Self> {
+            match self.0 {
+                0 => NodeResult::Terminal(()),
+                n => NodeResult::Nonterminal(n, Countdown(n - 1))
+            }
+        }
+    }
+
+    impl PersistState for Countdown {
+        type State = u32;
+
+        fn save_state(&self) -> u32 {
+            self.0
+        }
+
+        fn restore_state(&mut self, state: u32) {
+            self.0 = state
+        }
+    }
+
+    #[test]
+    fn delta_snapshot_test() {
+        let definition = || Countdown(5);
+        let mut agents = (0..4).map(|_| NodeRunner::new(definition))
+            .collect::<Vec<_>>();
+        let mut recorder = DeltaRecorder::new(BinaryCodec::new(), 3);
+        let keyframe = recorder.record(agents.iter().enumerate()).unwrap();
+        match keyframe {
+            Frame::Keyframe(0, ref s) => assert_eq!(s.len(), 4),
+            _ => unreachable!("Expected keyframe")
+        };
+        agents[2].transition(&());
+        let delta = recorder.record(agents.iter().enumerate().take(3))
+            .unwrap();
+        match delta {
+            Frame::Delta(0, ref c, ref r) => {
+                assert_eq!(c.keys().collect::<Vec<_>>(), vec![&2]);
+                assert_eq!(r, &vec![3]);
+            },
+            _ => unreachable!("Expected delta")
+        };
+        let mut restorer = DeltaRestorer::new(BinaryCodec::new());
+        match restorer.apply(delta.clone()) {
+            Result::Err(CodecError::Keyframe(0)) => (),
+            _ => unreachable!("Expected missing keyframe")
+        };
+        restorer.apply(keyframe).unwrap();
+        restorer.apply(delta).unwrap();
+        assert_eq!(restorer.keys(), vec![&0, &1, &2]);
+        let mut restored: NodeRunner<Countdown, _> = restorer
+            .restore(&2, &definition).unwrap().unwrap();
+        assert_eq!(restored.transition(&()), Statepoint::Nonterminal(4));
+        assert!(restorer.restore::<NodeRunner<Countdown, _>>(&3, &definition)
+            .is_none());
+        recorder.record(agents.iter().enumerate()).unwrap();
+        match recorder.record(agents.iter().enumerate()).unwrap() {
+            Frame::Keyframe(1, _) => (),
+            _ => unreachable!("Expected keyframe")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,389 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::NontermReturn;
+use map_wrappers::InputMappedNode;
+use parallel_join::{JoinPolicy, ParallelJoinNode};
+use std::collections::VecDeque;
+
+/// Object-safe counterpart of BehaviorTreeNode, implemented for all behavior 
+/// tree nodes, which allows nodes of different types to be stepped through 
+/// a box. 
+pub trait DynNode<I, N, T> {
+    /// Step the boxed node. 
+    fn step_boxed(self: Box<Self>, input: &I) -> NodeResult<N, T, BoxedNode<I, N, T>>;
+    /// Abort the boxed node. 
+    fn abort_boxed(self: Box<Self>);
+}
+
+impl<I, N, T, B> DynNode<I, N, T> for B where 
+    B: BehaviorTreeNode<Input=I, Nonterminal=N, Terminal=T> + 'static
+{
+    fn step_boxed(self: Box<Self>, input: &I) -> NodeResult<N, T, BoxedNode<I, N, T>> {
+        match (*self).step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                BoxedNode::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort_boxed(self: Box<Self>) {
+        (*self).abort()
+    }
+}
+
+/// A type-erased behavior tree node, for trees whose shape is only known at 
+/// runtime, such as those loaded from data files or built by editors. 
+pub struct BoxedNode<I, N, T> {
+    node: Box<DynNode<I, N, T>>
+}
+
+impl<I, N, T> BoxedNode<I, N, T> {
+    /// Box up the given node. 
+    pub fn new<B>(node: B) -> BoxedNode<I, N, T> where 
+        B: BehaviorTreeNode<Input=I, Nonterminal=N, Terminal=T> + 'static
+    {
+        BoxedNode {
+            node: Box::new(node)
+        }
+    }
+
+    /// Box up the given node, paired with a projection from the input of 
+    /// the box to the input of the node, so that the children of the 
+    /// composites over boxed nodes need not all consume the same input. 
+    pub fn projected<B, P>(projection: P, node: B) -> BoxedNode<I, N, T> where 
+        B: BehaviorTreeNode<Nonterminal=N, Terminal=T> + 'static,
+        P: Fn(&I) -> B::Input + 'static,
+        I: 'static
+    {
+        BoxedNode::new(InputMappedNode::new(projection, node))
+    }
+}
+
+impl<I, N, T> BehaviorTreeNode for BoxedNode<I, N, T> {
+    type Input = I;
+    type Nonterminal = N;
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<N, T, Self> {
+        self.node.step_boxed(input)
+    }
+
+    fn abort(self) {
+        self.node.abort_boxed()
+    }
+}
+
+/// Sequence node over a runtime list of boxed nodes, run in order. 
+///
+/// Like SequenceNode, it moves on to the next node whenever the current one 
+/// terminates with a Result::Ok, exiting with the success value of the last 
+/// node, and exits as soon as a node terminates with a Result::Err, along 
+/// with the index of that node. 
+pub struct DynSequence<I, N, T, F> {
+    pending: VecDeque<BoxedNode<I, N, Result<T, F>>>,
+    index: usize
+}
+
+impl<I, N, T, F> DynSequence<I, N, T, F> {
+    /// Create a new sequence over the given nodes, or Option::None if no 
+    /// nodes are given. 
+    pub fn new<K>(children: K) -> Option<DynSequence<I, N, T, F>> where 
+        K: IntoIterator<Item=BoxedNode<I, N, Result<T, F>>>
+    {
+        let pending = children.into_iter().collect::<VecDeque<_>>();
+        if pending.is_empty() {
+            return Option::None;
+        }
+        Option::Some(DynSequence {
+            pending: pending,
+            index: 0
+        })
+    }
+}
+
+impl<I, N, T, F> BehaviorTreeNode for DynSequence<I, N, T, F> {
+    type Input = I;
+    type Nonterminal = NontermReturn<usize, N, Result<T, F>>;
+    type Terminal = Result<T, (usize, F)>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<Self::Nonterminal, Self::Terminal,
+        Self>
+    {
+        let mut pending = self.pending;
+        let index = self.index;
+        let child = pending.pop_front().unwrap();
+        match child.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                pending.push_front(m);
+                NodeResult::Nonterminal(
+                    NontermReturn::Nonterminal(index, n),
+                    DynSequence {
+                        pending: pending,
+                        index: index
+                    }
+                )
+            },
+            NodeResult::Terminal(Result::Ok(t)) => if pending.is_empty() {
+                NodeResult::Terminal(Result::Ok(t))
+            } else {
+                NodeResult::Nonterminal(
+                    NontermReturn::Terminal(index, Result::Ok(t)),
+                    DynSequence {
+                        pending: pending,
+                        index: index + 1
+                    }
+                )
+            },
+            NodeResult::Terminal(Result::Err(f)) => {
+                NodeResult::Terminal(Result::Err((index, f)))
+            }
+        }
+    }
+
+    fn abort(self) {
+        let mut pending = self.pending;
+        if let Option::Some(child) = pending.pop_front() {
+            child.abort()
+        }
+    }
+}
+
+/// Selector node over a runtime list of boxed nodes, tried in order. 
+///
+/// Like SelectorNode, it moves on to the next node whenever the current one 
+/// terminates with a Result::Err, exiting with the failure value of the last 
+/// node, and exits as soon as a node terminates with a Result::Ok, along 
+/// with the index of that node. 
+pub struct DynSelector<I, N, T, F> {
+    pending: VecDeque<BoxedNode<I, N, Result<T, F>>>,
+    index: usize
+}
+
+impl<I, N, T, F> DynSelector<I, N, T, F> {
+    /// Create a new selector over the given nodes, or Option::None if no 
+    /// nodes are given. 
+    pub fn new<K>(children: K) -> Option<DynSelector<I, N, T, F>> where 
+        K: IntoIterator<Item=BoxedNode<I, N, Result<T, F>>>
+    {
+        let pending = children.into_iter().collect::<VecDeque<_>>();
+        if pending.is_empty() {
+            return Option::None;
+        }
+        Option::Some(DynSelector {
+            pending: pending,
+            index: 0
+        })
+    }
+}
+
+impl<I, N, T, F> BehaviorTreeNode for DynSelector<I, N, T, F> {
+    type Input = I;
+    type Nonterminal = NontermReturn<usize, N, Result<T, F>>;
+    type Terminal = Result<(usize, T), F>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<Self::Nonterminal, Self::Terminal,
+        Self>
+    {
+        let mut pending = self.pending;
+        let index = self.index;
+        let child = pending.pop_front().unwrap();
+        match child.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                pending.push_front(m);
+                NodeResult::Nonterminal(
+                    NontermReturn::Nonterminal(index, n),
+                    DynSelector {
+                        pending: pending,
+                        index: index
+                    }
+                )
+            },
+            NodeResult::Terminal(Result::Ok(t)) => {
+                NodeResult::Terminal(Result::Ok((index, t)))
+            },
+            NodeResult::Terminal(Result::Err(f)) => if pending.is_empty() {
+                NodeResult::Terminal(Result::Err(f))
+            } else {
+                NodeResult::Nonterminal(
+                    NontermReturn::Terminal(index, Result::Err(f)),
+                    DynSelector {
+                        pending: pending,
+                        index: index + 1
+                    }
+                )
+            }
+        }
+    }
+
+    fn abort(self) {
+        let mut pending = self.pending;
+        if let Option::Some(child) = pending.pop_front() {
+            child.abort()
+        }
+    }
+}
+
+/// Parallel node over a runtime list of boxed nodes with nonterminals of 
+/// type N, joined by a policy. 
+pub type DynParallel<N, P> = ParallelJoinNode<BoxedNode<<P as JoinPolicy>::Input,
+    N, <P as JoinPolicy>::Term>, P>;
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::NontermReturn;
+    use dynamic_node::BoxedNode;
+
+    fn sign_check(input: &i64) -> Result<i64, i64> {
+        if *input >= 0 {
+            Result::Ok(*input)
+        } else {
+            Result::Err(*input)
+        }
+    }
+
+    fn check_now(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        Statepoint::Terminal(sign_check(input))
+    }
+
+    fn wait_nonzero(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input == 0 {
+            Statepoint::Nonterminal(0)
+        } else {
+            Statepoint::Terminal(sign_check(input))
+        }
+    }
+
+    fn children() -> Vec<BoxedNode<i64, i64, Result<i64, i64>>> {
+        vec![
+            BoxedNode::new(PredicateWait::new(check_now)),
+            BoxedNode::new(PredicateWait::new(wait_nonzero)),
+            BoxedNode::new(PredicateWait::new(check_now))
+        ]
+    }
+
+    #[test]
+    fn dyn_sequence_test() {
+        use dynamic_node::DynSequence;
+        let test_node = DynSequence::new(children()).unwrap();
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(i, v), n) => {
+                assert_eq!(i, 0);
+                assert_eq!(v, Result::Ok(0));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(i, v), n) => {
+                assert_eq!(i, 1);
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        let test_node_3 = match test_node_2.step(&2) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(i, _), n) => {
+                assert_eq!(i, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        match test_node_3.step(&-2) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err((2, -2))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn dyn_selector_test() {
+        use dynamic_node::DynSelector;
+        let test_node = DynSelector::new(children()).unwrap();
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(i, _), n) => {
+                assert_eq!(i, 0);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        match test_node_1.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok((1, 3))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn dyn_empty_test() {
+        use dynamic_node::{DynSelector, DynSequence};
+        let none: Vec<BoxedNode<i64, i64, Result<i64, i64>>> = Vec::new();
+        assert!(DynSequence::new(none).is_none());
+        let none: Vec<BoxedNode<i64, i64, Result<i64, i64>>> = Vec::new();
+        assert!(DynSelector::new(none).is_none());
+    }
+
+    #[test]
+    fn dyn_parallel_test() {
+        use dynamic_node::DynParallel;
+        use parallel_join::ThresholdPolicy;
+        let test_node: DynParallel<_, _> = DynParallel::new(
+            ThresholdPolicy::new(3, 0),
+            children()
+        );
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(&*v, &[Option::None, Option::Some(0), Option::None]);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&4) {
+            NodeResult::Terminal(Result::Ok(v)) => assert_eq!(v.len(), 3),
+            _ => unreachable!("Expected successful terminal transition")
+        };
+    }
+
+    #[test]
+    fn dyn_projected_test() {
+        use dynamic_node::DynSequence;
+        let test_node = DynSequence::new(vec![
+            BoxedNode::projected(|i: &(i64, bool)| i.0, PredicateWait::new(check_now)),
+            BoxedNode::projected(|i: &(i64, bool)| *i, PredicateWait::new(
+                |i: &(i64, bool)| if i.1 {
+                    Statepoint::Terminal(Result::Ok(i.0))
+                } else {
+                    Statepoint::Nonterminal(i.0)
+                }
+            )),
+            BoxedNode::projected(|i: &(i64, bool)| i.1, PredicateWait::new(
+                |i: &bool| Statepoint::Terminal(if *i {
+                    Result::Ok(1)
+                } else {
+                    Result::Err(0)
+                })
+            ))
+        ]).unwrap();
+        let test_node_1 = match test_node.step(&(1, false)) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(0, v), n) => {
+                assert_eq!(v, Result::Ok(1));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&(2, true)) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(1, v), n) => {
+                assert_eq!(v, Result::Ok(2));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        match test_node_2.step(&(3, false)) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err((2, 0))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
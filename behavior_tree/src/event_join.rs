@@ -0,0 +1,190 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+/// Boolean combination of events an event join node waits for. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Combination {
+    /// Wait for all of the events. 
+    All,
+    /// Wait for any one of the events. 
+    Any,
+    /// Wait for at least the given number of events. 
+    AtLeast(usize)
+}
+
+impl Combination {
+    /// Get the number of events out of the given count required to satisfy 
+    /// the combination. 
+    pub fn required(&self, count: usize) -> usize {
+        match *self {
+            Combination::All => count,
+            Combination::Any => 1.min(count),
+            Combination::AtLeast(k) => k.min(count)
+        }
+    }
+}
+
+/// Nonterminal type of the event join node, reporting its progress. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EventProgress {
+    /// The number of events which have occurred. 
+    pub occurred: usize,
+    /// The number of events required to satisfy the combination. 
+    pub required: usize
+}
+
+/// Synchronization node, which waits for a combination of events, each of 
+/// which is signalled by a condition node succeeding. 
+///
+/// Each step, the conditions whose events have not yet occurred are 
+/// stepped. A condition which fails is replaced with a fresh one from its 
+/// constructor, to be checked again on the next step, while a condition 
+/// which succeeds latches its event. Once the combination is satisfied, the 
+/// node exits with the latched success values, with Option::None standing 
+/// in for events which did not occur, and any conditions still running are 
+/// aborted. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct EventJoinNode<C, M, T> where 
+    M: Fn() -> C
+{
+    makers: Box<[M]>,
+    conditions: Box<[Option<C>]>,
+    occurred: Box<[Option<T>]>,
+    combination: Combination
+}
+
+impl<C, M, T> EventJoinNode<C, M, T> where 
+    M: Fn() -> C
+{
+    /// Create a new event join node waiting for the given combination of 
+    /// events, from the constructors of their condition nodes. 
+    pub fn new<K>(combination: Combination, makers: K) -> EventJoinNode<C, M, T> where 
+        K: IntoIterator<Item=M>
+    {
+        let makers = makers.into_iter().collect::<Vec<_>>();
+        let conditions = makers.iter()
+            .map(|m| Option::Some(m()))
+            .collect::<Vec<_>>();
+        let occurred = makers.iter()
+            .map(|_| Option::None)
+            .collect::<Vec<_>>();
+        EventJoinNode {
+            makers: makers.into_boxed_slice(),
+            conditions: conditions.into_boxed_slice(),
+            occurred: occurred.into_boxed_slice(),
+            combination: combination
+        }
+    }
+}
+
+impl<C, M, T, F> BehaviorTreeNode for EventJoinNode<C, M, T> where 
+    C: BehaviorTreeNode<Terminal=Result<T, F>>,
+    M: Fn() -> C
+{
+    type Input = C::Input;
+    type Nonterminal = EventProgress;
+    type Terminal = Box<[Option<T>]>;
+
+    #[inline]
+    fn step(self, input: &C::Input) -> NodeResult<EventProgress,
+        Box<[Option<T>]>, Self>
+    {
+        let mut this = self;
+        for ((slot, done), maker) in this.conditions.iter_mut()
+            .zip(this.occurred.iter_mut())
+            .zip(this.makers.iter())
+        {
+            if let Option::Some(condition) = slot.take() {
+                match condition.step(input) {
+                    NodeResult::Nonterminal(_, m) => *slot = Option::Some(m),
+                    NodeResult::Terminal(Result::Ok(t)) => *done = Option::Some(t),
+                    NodeResult::Terminal(Result::Err(_)) => *slot = Option::Some(maker())
+                }
+            }
+        }
+        let count = this.occurred.iter().filter(|o| o.is_some()).count();
+        let required = this.combination.required(this.occurred.len());
+        if count >= required {
+            for condition in this.conditions.into_vec().into_iter().flatten() {
+                condition.abort();
+            }
+            NodeResult::Terminal(this.occurred)
+        } else {
+            NodeResult::Nonterminal(EventProgress {
+                occurred: count,
+                required: required
+            }, this)
+        }
+    }
+
+    fn abort(self) {
+        for condition in self.conditions.into_vec().into_iter().flatten() {
+            condition.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use event_join::{EventJoinNode, EventProgress, Combination};
+
+    type Condition = PredicateWait<i64, (), Result<i64, ()>,
+        Box<Fn(&i64) -> Statepoint<(), Result<i64, ()>>>>;
+
+    fn above(threshold: i64) -> Box<Fn() -> Condition> {
+        Box::new(move || PredicateWait::new(Box::new(move |input: &i64| {
+            if *input > threshold {
+                Statepoint::Terminal(Result::Ok(threshold))
+            } else {
+                Statepoint::Terminal(Result::Err(()))
+            }
+        })))
+    }
+
+    #[test]
+    fn event_k_of_n_test() {
+        let test_node = EventJoinNode::new(
+            Combination::AtLeast(2),
+            vec![above(10), above(1), above(5)]
+        );
+        let test_node_1 = match test_node.step(&3) {
+            NodeResult::Nonterminal(p, n) => {
+                assert_eq!(p, EventProgress { occurred: 1, required: 2 });
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // The latched event stays counted even though its condition no
+        // longer holds
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(p, n) => {
+                assert_eq!(p, EventProgress { occurred: 1, required: 2 });
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_2.step(&6) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(&*t, &[Option::None, Option::Some(1), Option::Some(5)])
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn event_any_test() {
+        let test_node = EventJoinNode::new(
+            Combination::Any,
+            vec![above(10), above(5)]
+        );
+        match test_node.step(&11) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(&*t, &[Option::Some(10), Option::Some(5)])
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,257 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use blackboard::{AsBlackboard, Key};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Shared queue of events of the given type, for separate subtrees, or the 
+/// trees of separate agents, to coordinate through. 
+///
+/// A queue is a handle, with clones of it referring to the same events, so it 
+/// can be stored on the blackboards of several agents at once. Pushing and 
+/// taking events does not count as writing the blackboard entry holding the 
+/// queue. 
+#[derive(Debug, Default)]
+pub struct EventQueue<T> {
+    events: Rc<RefCell<VecDeque<T>>>
+}
+
+impl<T> Clone for EventQueue<T> {
+    fn clone(&self) -> Self {
+        EventQueue {
+            events: self.events.clone()
+        }
+    }
+}
+
+impl<T> EventQueue<T> {
+    /// Create a new, empty event queue. 
+    pub fn new() -> EventQueue<T> {
+        EventQueue {
+            events: Rc::new(RefCell::new(VecDeque::new()))
+        }
+    }
+
+    /// Push an event onto the back of the queue. 
+    pub fn push(&self, event: T) {
+        self.events.borrow_mut().push_back(event)
+    }
+
+    /// Take the event at the front of the queue, if there is one. 
+    pub fn pop(&self) -> Option<T> {
+        self.events.borrow_mut().pop_front()
+    }
+
+    /// Take the frontmost event satisfying the given predicate, if there is 
+    /// one, leaving the other events in order. 
+    pub fn take<P>(&self, predicate: P) -> Option<T> where 
+        P: Fn(&T) -> bool
+    {
+        let mut events = self.events.borrow_mut();
+        let index = events.iter().position(predicate);
+        index.and_then(|i| events.remove(i))
+    }
+
+    /// Get the number of events in the queue. 
+    pub fn len(&self) -> usize {
+        self.events.borrow().len()
+    }
+
+    /// Check whether the queue has no events. 
+    pub fn is_empty(&self) -> bool {
+        self.events.borrow().is_empty()
+    }
+
+    /// Discard all events in the queue. 
+    pub fn clear(&self) {
+        self.events.borrow_mut().clear()
+    }
+}
+
+/// Leaf node pushing an event made from its input onto the event queue 
+/// under the given blackboard key, then terminating immediately. A new queue 
+/// is put under the key if there is none. 
+#[derive(PartialEq, Debug)]
+pub struct SendEvent<I, T, F> where 
+    I: AsBlackboard,
+    T: 'static,
+    F: Fn(&I) -> T
+{
+    key: Key<EventQueue<T>>,
+    make: F,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I, T, F> Clone for SendEvent<I, T, F> where 
+    I: AsBlackboard,
+    T: 'static,
+    F: Fn(&I) -> T + Clone
+{
+    fn clone(&self) -> Self {
+        SendEvent::new(self.key.clone(), self.make.clone())
+    }
+}
+
+impl<I, T, F> SendEvent<I, T, F> where 
+    I: AsBlackboard,
+    T: 'static,
+    F: Fn(&I) -> T
+{
+    /// Create a new event sending node. 
+    pub fn new(key: Key<EventQueue<T>>, make: F) -> SendEvent<I, T, F> {
+        SendEvent {
+            key: key,
+            make: make,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T, F> BehaviorTreeNode for SendEvent<I, T, F> where 
+    I: AsBlackboard,
+    T: 'static,
+    F: Fn(&I) -> T
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = ();
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), (), Self> {
+        let board = input.blackboard();
+        let queue = match board.get(&self.key) {
+            Option::Some(q) => q,
+            Option::None => {
+                let q = EventQueue::new();
+                board.set(&self.key, q.clone());
+                q
+            }
+        };
+        queue.push((self.make)(input));
+        NodeResult::Terminal(())
+    }
+}
+
+fn any<T>(_event: &T) -> bool {
+    true
+}
+
+/// Leaf node waiting for an event satisfying a predicate to be in the event 
+/// queue under the given blackboard key, then taking it from the queue and 
+/// terminating with it. 
+#[derive(PartialEq, Debug)]
+pub struct AwaitEvent<I, T, P> where 
+    I: AsBlackboard,
+    T: 'static,
+    P: Fn(&T) -> bool
+{
+    key: Key<EventQueue<T>>,
+    predicate: P,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I, T, P> Clone for AwaitEvent<I, T, P> where 
+    I: AsBlackboard,
+    T: 'static,
+    P: Fn(&T) -> bool + Clone
+{
+    fn clone(&self) -> Self {
+        AwaitEvent::new(self.key.clone(), self.predicate.clone())
+    }
+}
+
+impl<I, T, P> AwaitEvent<I, T, P> where 
+    I: AsBlackboard,
+    T: 'static,
+    P: Fn(&T) -> bool
+{
+    /// Create a new node waiting for an event satisfying the predicate. 
+    pub fn new(key: Key<EventQueue<T>>, predicate: P) -> AwaitEvent<I, T, P> {
+        AwaitEvent {
+            key: key,
+            predicate: predicate,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T> AwaitEvent<I, T, fn(&T) -> bool> where 
+    I: AsBlackboard,
+    T: 'static
+{
+    /// Create a new node waiting for any event. 
+    pub fn any(key: Key<EventQueue<T>>) -> AwaitEvent<I, T, fn(&T) -> bool> {
+        AwaitEvent::new(key, any::<T>)
+    }
+}
+
+impl<I, T, P> BehaviorTreeNode for AwaitEvent<I, T, P> where 
+    I: AsBlackboard,
+    T: 'static,
+    P: Fn(&T) -> bool
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), T, Self> {
+        let taken = input.blackboard()
+            .with(&self.key, |q| q.take(&self.predicate))
+            .and_then(|e| e);
+        match taken {
+            Option::Some(e) => NodeResult::Terminal(e),
+            Option::None => NodeResult::Nonterminal((), self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use blackboard::{Blackboard, Key};
+    use event_nodes::{AwaitEvent, EventQueue, SendEvent};
+
+    #[test]
+    fn event_queue_test() {
+        let queue = EventQueue::new();
+        queue.push(1);
+        queue.push(2);
+        queue.clone().push(3);
+        assert_eq!(queue.take(|e| *e % 2 == 0), Option::Some(2));
+        assert_eq!(queue.pop(), Option::Some(1));
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn send_await_test() {
+        let board = Blackboard::new();
+        let alerts = Key::<EventQueue<&'static str>>::new("alerts");
+        let wait = AwaitEvent::<Blackboard, _, _>::new(alerts.clone(),
+            |e: &&str| e.starts_with("enemy"));
+        let wait_1 = match wait.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let send = SendEvent::new(alerts.clone(), |_: &Blackboard| "noise");
+        match send.step(&board) {
+            NodeResult::Terminal(()) => (),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let wait_2 = match wait_1.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        SendEvent::new(alerts.clone(), |_: &Blackboard| "enemy spotted")
+            .step(&board);
+        match wait_2.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, "enemy spotted"),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match AwaitEvent::<Blackboard, _, _>::any(alerts).step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, "noise"),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
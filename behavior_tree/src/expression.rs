@@ -0,0 +1,611 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use blackboard::{AsBlackboard, Blackboard, Key};
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Value an expression evaluates to, also used for the parameters of the 
+/// nodes of trees defined as data. 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum Value {
+    /// An integer, read from entries of type i64. 
+    Int(i64),
+    /// A floating point number, read from entries of type f64. 
+    Float(f64),
+    /// A boolean, read from entries of type bool. 
+    Bool(bool),
+    /// A string, read from entries of type String. 
+    Str(String)
+}
+
+impl Value {
+    pub(crate) fn lookup(board: &Blackboard, name: &str) -> Option<Value> {
+        let name = name.to_owned();
+        board.get(&Key::<i64>::new(name.clone())).map(Value::Int)
+            .or_else(|| board.get(&Key::<f64>::new(name.clone()))
+                .map(Value::Float))
+            .or_else(|| board.get(&Key::<bool>::new(name.clone()))
+                .map(Value::Bool))
+            .or_else(|| board.get(&Key::<String>::new(name)).map(Value::Str))
+    }
+
+    fn number(&self) -> Option<f64> {
+        match *self {
+            Value::Int(i) => Option::Some(i as f64),
+            Value::Float(f) => Option::Some(f),
+            _ => Option::None
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Value {
+        Value::Int(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Value {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::Bool(value)
+    }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(value: &'a str) -> Value {
+        Value::Str(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::Str(value)
+    }
+}
+
+/// Error of parsing an expression. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    /// The byte offset in the source at which the error was found. 
+    pub position: usize,
+    /// What was wrong. 
+    pub message: String
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
+
+/// Error of evaluating an expression. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum EvalError {
+    /// No entry of a supported type is under the given name. 
+    Missing(String),
+    /// An operator was applied to values of types it does not support. 
+    Type(&'static str),
+    /// An integer was divided by zero. 
+    DivisionByZero
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EvalError::Missing(ref n) => write!(f, "no entry named {}", n),
+            EvalError::Type(o) => write!(f, "mismatched types for {}", o),
+            EvalError::DivisionByZero => write!(f, "division by zero")
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div
+}
+
+impl BinOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Or => "||",
+            BinOp::And => "&&",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/"
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Expr {
+    Literal(Value),
+    Entry(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    Open,
+    Close
+}
+
+const OPERATORS: [&str; 14] = ["||", "&&", "==", "!=", "<=", ">=", "<",
+    ">", "+", "-", "*", "/", "!", "="];
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push((i, Token::Open));
+            i += 1;
+        } else if c == ')' {
+            tokens.push((i, Token::Close));
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && ((bytes[i] as char).is_ascii_digit() ||
+                bytes[i] == b'.')
+            {
+                i += 1;
+            }
+            let text = &source[start..i];
+            let token = if text.contains('.') {
+                text.parse().map(Token::Float).ok()
+            } else {
+                text.parse().map(Token::Int).ok()
+            };
+            match token {
+                Option::Some(t) => tokens.push((start, t)),
+                Option::None => return Result::Err(ParseError {
+                    position: start,
+                    message: format!("invalid number {}", text)
+                })
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || 
+                bytes[i] == b'_' || bytes[i] == b'.')
+            {
+                i += 1;
+            }
+            tokens.push((start, Token::Ident(source[start..i].to_owned())));
+        } else if c == '"' {
+            let start = i;
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += 1;
+            }
+            if i == bytes.len() {
+                return Result::Err(ParseError {
+                    position: start,
+                    message: "unterminated string".to_owned()
+                });
+            }
+            tokens.push((start, Token::Str(source[start + 1..i].to_owned())));
+            i += 1;
+        } else {
+            match OPERATORS.iter().find(|o| source[i..].starts_with(**o)) {
+                Option::Some(&"=") | Option::None => {
+                    return Result::Err(ParseError {
+                        position: i,
+                        message: format!("unexpected character {}",
+                            &source[i..].chars().next().unwrap())
+                    });
+                },
+                Option::Some(o) => {
+                    tokens.push((i, Token::Op(o)));
+                    i += o.len();
+                }
+            }
+        }
+    }
+    Result::Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    end: usize
+}
+
+impl Parser {
+    fn position(&self) -> usize {
+        self.tokens.get(self.index).map_or(self.end, |t| t.0)
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+        Result::Err(ParseError {
+            position: self.position(),
+            message: message.to_owned()
+        })
+    }
+
+    fn eat_op(&mut self, ops: &[(&'static str, BinOp)]) -> Option<BinOp> {
+        let found = match self.tokens.get(self.index) {
+            Option::Some(&(_, Token::Op(o))) => ops.iter()
+                .find(|p| p.0 == o)
+                .map(|p| p.1),
+            _ => Option::None
+        };
+        if found.is_some() {
+            self.index += 1;
+        }
+        found
+    }
+
+    fn binary<F>(&mut self, ops: &[(&'static str, BinOp)], next: F) ->
+        Result<Expr, ParseError> where 
+        F: Fn(&mut Parser) -> Result<Expr, ParseError>
+    {
+        let mut left = next(self)?;
+        while let Option::Some(op) = self.eat_op(ops) {
+            let right = next(self)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Result::Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        self.binary(&[("||", BinOp::Or)], Parser::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        self.binary(&[("&&", BinOp::And)], Parser::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let left = self.sum()?;
+        match self.eat_op(&[("==", BinOp::Eq), ("!=", BinOp::Ne),
+            ("<=", BinOp::Le), (">=", BinOp::Ge), ("<", BinOp::Lt),
+            (">", BinOp::Gt)])
+        {
+            Option::Some(op) => {
+                let right = self.sum()?;
+                Result::Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+            },
+            Option::None => Result::Ok(left)
+        }
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        self.binary(&[("+", BinOp::Add), ("-", BinOp::Sub)], Parser::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        self.binary(&[("*", BinOp::Mul), ("/", BinOp::Div)], Parser::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        match self.tokens.get(self.index) {
+            Option::Some(&(_, Token::Op("!"))) => {
+                self.index += 1;
+                Result::Ok(Expr::Not(Box::new(self.unary()?)))
+            },
+            Option::Some(&(_, Token::Op("-"))) => {
+                self.index += 1;
+                Result::Ok(Expr::Neg(Box::new(self.unary()?)))
+            },
+            _ => self.atom()
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        let token = match self.tokens.get(self.index) {
+            Option::Some(t) => t.1.clone(),
+            Option::None => return self.error("unexpected end of expression")
+        };
+        let expr = match token {
+            Token::Int(i) => Expr::Literal(Value::Int(i)),
+            Token::Float(f) => Expr::Literal(Value::Float(f)),
+            Token::Str(s) => Expr::Literal(Value::Str(s)),
+            Token::Ident(ref n) if n == "true" => {
+                Expr::Literal(Value::Bool(true))
+            },
+            Token::Ident(ref n) if n == "false" => {
+                Expr::Literal(Value::Bool(false))
+            },
+            Token::Ident(n) => Expr::Entry(n),
+            Token::Open => {
+                self.index += 1;
+                let inner = self.or()?;
+                match self.tokens.get(self.index) {
+                    Option::Some(&(_, Token::Close)) => (),
+                    _ => return self.error("expected )")
+                };
+                inner
+            },
+            _ => return self.error("expected a value")
+        };
+        self.index += 1;
+        Result::Ok(expr)
+    }
+}
+
+fn truth(value: Value, op: &'static str) -> Result<bool, EvalError> {
+    match value {
+        Value::Bool(b) => Result::Ok(b),
+        _ => Result::Err(EvalError::Type(op))
+    }
+}
+
+fn arithmetic(op: BinOp, left: Value, right: Value) -> 
+    Result<Value, EvalError> 
+{
+    if let (&Value::Int(a), &Value::Int(b)) = (&left, &right) {
+        return match op {
+            BinOp::Add => Result::Ok(Value::Int(a.wrapping_add(b))),
+            BinOp::Sub => Result::Ok(Value::Int(a.wrapping_sub(b))),
+            BinOp::Mul => Result::Ok(Value::Int(a.wrapping_mul(b))),
+            _ if b == 0 => Result::Err(EvalError::DivisionByZero),
+            _ => Result::Ok(Value::Int(a.wrapping_div(b)))
+        };
+    }
+    match (left.number(), right.number()) {
+        (Option::Some(a), Option::Some(b)) => Result::Ok(Value::Float(match op {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            _ => a / b
+        })),
+        _ => Result::Err(EvalError::Type(op.symbol()))
+    }
+}
+
+fn compare(op: BinOp, left: Value, right: Value) -> Result<Value, EvalError> {
+    let ordering = match (&left, &right) {
+        (&Value::Str(ref a), &Value::Str(ref b)) => Option::Some(a.cmp(b)),
+        (&Value::Bool(a), &Value::Bool(b)) => Option::Some(a.cmp(&b)),
+        (&Value::Int(a), &Value::Int(b)) => Option::Some(a.cmp(&b)),
+        _ => match (left.number(), right.number()) {
+            (Option::Some(a), Option::Some(b)) => a.partial_cmp(&b),
+            _ => return Result::Err(EvalError::Type(op.symbol()))
+        }
+    };
+    let result = match (op, ordering) {
+        (BinOp::Ne, o) => o != Option::Some(::std::cmp::Ordering::Equal),
+        (_, Option::None) => false,
+        (BinOp::Eq, Option::Some(o)) => o == ::std::cmp::Ordering::Equal,
+        (BinOp::Lt, Option::Some(o)) => o == ::std::cmp::Ordering::Less,
+        (BinOp::Le, Option::Some(o)) => o != ::std::cmp::Ordering::Greater,
+        (BinOp::Gt, Option::Some(o)) => o == ::std::cmp::Ordering::Greater,
+        (_, Option::Some(o)) => o != ::std::cmp::Ordering::Less
+    };
+    Result::Ok(Value::Bool(result))
+}
+
+impl Expr {
+    fn evaluate(&self, board: &Blackboard) -> Result<Value, EvalError> {
+        match *self {
+            Expr::Literal(ref v) => Result::Ok(v.clone()),
+            Expr::Entry(ref n) => Value::lookup(board, n)
+                .ok_or_else(|| EvalError::Missing(n.clone())),
+            Expr::Not(ref e) => truth(e.evaluate(board)?, "!")
+                .map(|b| Value::Bool(!b)),
+            Expr::Neg(ref e) => match e.evaluate(board)? {
+                Value::Int(i) => Result::Ok(Value::Int(i.wrapping_neg())),
+                Value::Float(f) => Result::Ok(Value::Float(-f)),
+                _ => Result::Err(EvalError::Type("-"))
+            },
+            Expr::Binary(BinOp::Or, ref l, ref r) => {
+                if truth(l.evaluate(board)?, "||")? {
+                    Result::Ok(Value::Bool(true))
+                } else {
+                    truth(r.evaluate(board)?, "||").map(Value::Bool)
+                }
+            },
+            Expr::Binary(BinOp::And, ref l, ref r) => {
+                if truth(l.evaluate(board)?, "&&")? {
+                    truth(r.evaluate(board)?, "&&").map(Value::Bool)
+                } else {
+                    Result::Ok(Value::Bool(false))
+                }
+            },
+            Expr::Binary(op, ref l, ref r) => {
+                let left = l.evaluate(board)?;
+                let right = r.evaluate(board)?;
+                match op {
+                    BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div => {
+                        arithmetic(op, left, right)
+                    },
+                    _ => compare(op, left, right)
+                }
+            }
+        }
+    }
+}
+
+/// Parsed expression over the entries of a blackboard, such as 
+/// `hp < 20 && has_ammo`, for conditions specified as strings. 
+///
+/// Names in an expression refer to the blackboard entries under them, which 
+/// must hold values of type i64, f64, bool, or String. Literals are integers, 
+/// decimal numbers, the booleans true and false, and double-quoted strings. 
+/// The operators are, from the loosest binding to the tightest, `||`, `&&`, 
+/// the comparisons, `+` and `-`, `*` and `/`, and the prefix `!` and `-`. 
+/// Integers and decimal numbers mix in arithmetic and comparisons, and `&&` 
+/// and `||` only evaluate their right side when needed. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Expression {
+    source: String,
+    root: Expr
+}
+
+impl Expression {
+    /// Parse an expression from the given source. 
+    pub fn parse(source: &str) -> Result<Expression, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            index: 0,
+            end: source.len()
+        };
+        let root = parser.or()?;
+        if parser.index < parser.tokens.len() {
+            return parser.error("unexpected trailing input");
+        }
+        Result::Ok(Expression {
+            source: source.to_owned(),
+            root: root
+        })
+    }
+
+    /// Get the source the expression was parsed from. 
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate the expression over the given blackboard. 
+    pub fn evaluate(&self, board: &Blackboard) -> Result<Value, EvalError> {
+        self.root.evaluate(board)
+    }
+
+    /// Evaluate the expression over the given blackboard as a condition, 
+    /// which must evaluate to a boolean. 
+    pub fn test(&self, board: &Blackboard) -> Result<bool, EvalError> {
+        truth(self.evaluate(board)?, "condition")
+    }
+}
+
+/// Leaf node testing an expression over the blackboard of its input, which 
+/// succeeds if the expression holds, and fails if it does not or cannot be 
+/// evaluated. 
+#[derive(PartialEq, Debug)]
+pub struct ExpressionCondition<I> where 
+    I: AsBlackboard
+{
+    expression: Expression,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I> Clone for ExpressionCondition<I> where 
+    I: AsBlackboard
+{
+    fn clone(&self) -> Self {
+        ExpressionCondition::new(self.expression.clone())
+    }
+}
+
+impl<I> ExpressionCondition<I> where 
+    I: AsBlackboard
+{
+    /// Create a new expression condition. 
+    pub fn new(expression: Expression) -> ExpressionCondition<I> {
+        ExpressionCondition {
+            expression: expression,
+            _junk: PhantomData
+        }
+    }
+
+    /// Parse an expression condition from the given source. 
+    pub fn parse(source: &str) -> Result<ExpressionCondition<I>, ParseError> {
+        Expression::parse(source).map(ExpressionCondition::new)
+    }
+}
+
+impl<I> BehaviorTreeNode for ExpressionCondition<I> where 
+    I: AsBlackboard
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<(), ()>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), Result<(), ()>, Self> {
+        match self.expression.test(input.blackboard()) {
+            Result::Ok(true) => NodeResult::Terminal(Result::Ok(())),
+            _ => NodeResult::Terminal(Result::Err(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackboard::{Blackboard, Key};
+    use expression::{EvalError, Expression, Value};
+
+    fn board() -> Blackboard {
+        let board = Blackboard::new();
+        board.set(&Key::<i64>::new("hp"), 15);
+        board.set(&Key::<bool>::new("has_ammo"), true);
+        board.set(&Key::<f64>::new("range"), 2.5);
+        board.set(&Key::<String>::new("stance"), "crouch".to_owned());
+        board
+    }
+
+    #[test]
+    fn expression_test() {
+        let board = board();
+        let check = |s: &str| Expression::parse(s).unwrap().evaluate(&board);
+        assert_eq!(check("hp < 20 && has_ammo"), Result::Ok(Value::Bool(true)));
+        assert_eq!(check("hp * 2 - 1"), Result::Ok(Value::Int(29)));
+        assert_eq!(check("range * 2 >= 5"), Result::Ok(Value::Bool(true)));
+        assert_eq!(check("!(stance == \"crouch\") || -hp > 0"),
+            Result::Ok(Value::Bool(false)));
+        assert_eq!(check("false && missing"), Result::Ok(Value::Bool(false)));
+        assert_eq!(check("missing"),
+            Result::Err(EvalError::Missing("missing".to_owned())));
+        assert_eq!(check("hp && true"), Result::Err(EvalError::Type("&&")));
+        assert_eq!(check("hp / 0"), Result::Err(EvalError::DivisionByZero));
+    }
+
+    #[test]
+    fn expression_parse_error_test() {
+        assert_eq!(Expression::parse("hp < ").unwrap_err().position, 5);
+        assert_eq!(Expression::parse("(hp < 2").unwrap_err().position, 7);
+        assert_eq!(Expression::parse("hp = 2").unwrap_err().position, 3);
+        assert_eq!(Expression::parse("hp 2").unwrap_err().position, 3);
+        assert_eq!(Expression::parse("hp < \u{e9}").unwrap_err().position, 5);
+    }
+
+    #[test]
+    fn expression_condition_test() {
+        use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+        use expression::ExpressionCondition;
+        let board = board();
+        let low = ExpressionCondition::<Blackboard>::parse("hp < 20").unwrap();
+        match low.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let bad = ExpressionCondition::<Blackboard>::parse("hp + 1").unwrap();
+        match bad.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,201 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+
+/// Nonterminal type of the fold node, where each variant carries the index 
+/// of the run of the child node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FoldNonterm<N> {
+    /// The child node was stepped, and did not terminate. 
+    Running(usize, N),
+    /// The child node terminated, and its terminal was folded into the 
+    /// accumulator, which was not done. 
+    Folded(usize)
+}
+
+/// Node which repeatedly runs fresh instances of a child node, folding the 
+/// terminal of each run into an accumulator, until the fold function exits. 
+///
+/// The fold function is called with the accumulator and the terminal of the 
+/// run, and returns either Statepoint::Nonterminal of the new accumulator, 
+/// which starts a new run, or Statepoint::Terminal of the value to exit with. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FoldNode<N, M, F, A> where 
+    M: Fn() -> N
+{
+    node: N,
+    maker: M,
+    fold: F,
+    acc: A,
+    runs: usize
+}
+
+impl<N, M, F, A> FoldNode<N, M, F, A> where 
+    M: Fn() -> N
+{
+    /// Create a new fold node from a constructor of the child node, a fold 
+    /// function, and the initial value of the accumulator. 
+    pub fn new(maker: M, fold: F, init: A) -> FoldNode<N, M, F, A> {
+        FoldNode {
+            node: maker(),
+            maker: maker,
+            fold: fold,
+            acc: init,
+            runs: 0
+        }
+    }
+
+    /// Get the current value of the accumulator. 
+    pub fn accumulator(&self) -> &A {
+        &self.acc
+    }
+}
+
+impl<N, M, F, A, X> BehaviorTreeNode for FoldNode<N, M, F, A> where 
+    N: BehaviorTreeNode,
+    M: Fn() -> N,
+    F: Fn(A, N::Terminal) -> Statepoint<A, X>
+{
+    type Input = N::Input;
+    type Nonterminal = FoldNonterm<N::Nonterminal>;
+    type Terminal = X;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal, X, Self> {
+        let runs = self.runs;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                FoldNonterm::Running(runs, n),
+                FoldNode {
+                    node: m,
+                    maker: self.maker,
+                    fold: self.fold,
+                    acc: self.acc,
+                    runs: runs
+                }
+            ),
+            NodeResult::Terminal(t) => match (self.fold)(self.acc, t) {
+                Statepoint::Nonterminal(a) => NodeResult::Nonterminal(
+                    FoldNonterm::Folded(runs),
+                    FoldNode {
+                        node: (self.maker)(),
+                        maker: self.maker,
+                        fold: self.fold,
+                        acc: a,
+                        runs: runs + 1
+                    }
+                ),
+                Statepoint::Terminal(x) => NodeResult::Terminal(x)
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+
+/// Type of the fold function of the bounded loop node, whose accumulator is 
+/// the number of runs left along with the terminals collected so far. 
+pub type LoopFold<T> = fn((usize, Vec<T>), T) -> Statepoint<(usize, Vec<T>), Vec<T>>;
+
+/// Bounded loop node, which runs fresh instances of a child node exactly 
+/// the given number of times, and exits with their terminals in order. The 
+/// index of the current run is exposed in the nonterminal. 
+pub type LoopN<N, M, T> = FoldNode<N, M, LoopFold<T>, (usize, Vec<T>)>;
+
+fn collect_run<T>(acc: (usize, Vec<T>), term: T) -> Statepoint<(usize, Vec<T>), Vec<T>> {
+    let (left, mut terms) = acc;
+    terms.push(term);
+    if left > 1 {
+        Statepoint::Nonterminal((left - 1, terms))
+    } else {
+        Statepoint::Terminal(terms)
+    }
+}
+
+impl<N, M, T> LoopN<N, M, T> where 
+    M: Fn() -> N
+{
+    /// Create a new bounded loop node from a constructor of the child node, 
+    /// running it the given number of times, which has to be at least one. 
+    pub fn times(maker: M, count: usize) -> LoopN<N, M, T> {
+        assert!(count > 0, "Bounded loop requires at least one run");
+        FoldNode::new(maker, collect_run, (count, Vec::with_capacity(count)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use fold_node::{FoldNode, FoldNonterm};
+
+    fn sample(input: &i64) -> Statepoint<(), i64> {
+        if *input >= 0 {
+            Statepoint::Terminal(*input)
+        } else {
+            Statepoint::Nonterminal(())
+        }
+    }
+
+    fn best_of_three(acc: (usize, i64), sample: i64) -> Statepoint<(usize, i64),
+        i64>
+    {
+        let (count, best) = acc;
+        let best = if sample > best { sample } else { best };
+        if count + 1 < 3 {
+            Statepoint::Nonterminal((count + 1, best))
+        } else {
+            Statepoint::Terminal(best)
+        }
+    }
+
+    #[test]
+    fn fold_test() {
+        let test_node = FoldNode::new(
+            || PredicateWait::new(sample),
+            best_of_three,
+            (0, i64::MIN)
+        );
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(FoldNonterm::Running(0, ()), n) => n,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        let test_node_2 = match test_node_1.step(&4) {
+            NodeResult::Nonterminal(FoldNonterm::Folded(0), n) => n,
+            _ => unreachable!("Expected folded nonterminal")
+        };
+        assert_eq!(*test_node_2.accumulator(), (1, 4));
+        let test_node_3 = match test_node_2.step(&7) {
+            NodeResult::Nonterminal(FoldNonterm::Folded(1), n) => n,
+            _ => unreachable!("Expected folded nonterminal")
+        };
+        match test_node_3.step(&2) {
+            NodeResult::Terminal(t) => assert_eq!(t, 7),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn loop_n_test() {
+        use fold_node::LoopN;
+        let test_node = LoopN::times(|| PredicateWait::new(sample), 2);
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(FoldNonterm::Running(0, ()), n) => n,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        let test_node_2 = match test_node_1.step(&3) {
+            NodeResult::Nonterminal(FoldNonterm::Folded(0), n) => n,
+            _ => unreachable!("Expected folded nonterminal")
+        };
+        let test_node_3 = match test_node_2.step(&-1) {
+            NodeResult::Nonterminal(FoldNonterm::Running(1, ()), n) => n,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        match test_node_3.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, vec![3, 5]),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
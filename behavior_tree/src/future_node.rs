@@ -0,0 +1,159 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use time_wrappers::TimedOut;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::ptr;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+fn noop_clone(_data: *const ()) -> RawWaker {
+    noop_raw_waker()
+}
+
+fn noop(_data: *const ()) {}
+
+static NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(noop_clone, noop,
+    noop, noop);
+
+fn noop_raw_waker() -> RawWaker {
+    RawWaker::new(ptr::null(), &NOOP_VTABLE)
+}
+
+/// Create a waker which does nothing when woken. 
+///
+/// Since future nodes poll their futures on every step anyway, this is the 
+/// waker to use when nothing needs to be notified of progress. 
+pub fn noop_waker() -> Waker {
+    unsafe { Waker::from_raw(noop_raw_waker()) }
+}
+
+/// Leaf node owning a future, which polls the future on each step, staying 
+/// nonterminal while it is pending, and terminating with Result::Ok of its 
+/// output once it is ready. 
+///
+/// If a timeout is set, and the future is still pending after that many 
+/// steps, the node fails with Result::Err instead, dropping the future. 
+/// Aborting the node also drops the future, cancelling it. 
+pub struct FutureNode<I, F> where 
+    F: Future
+{
+    future: Pin<Box<F>>,
+    waker: Waker,
+    polls: usize,
+    timeout: Option<usize>,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I, F> FutureNode<I, F> where 
+    F: Future
+{
+    /// Create a new future node, polling the future with a waker which does 
+    /// nothing. 
+    pub fn new(future: F) -> FutureNode<I, F> {
+        FutureNode::with_waker(noop_waker(), future)
+    }
+
+    /// Create a new future node, polling the future with the given waker. 
+    pub fn with_waker(waker: Waker, future: F) -> FutureNode<I, F> {
+        FutureNode {
+            future: Box::pin(future),
+            waker: waker,
+            polls: 0,
+            timeout: Option::None,
+            _junk: PhantomData
+        }
+    }
+
+    /// Fail after the given number of steps with the future still pending. 
+    pub fn timeout(self, steps: usize) -> FutureNode<I, F> {
+        FutureNode {
+            timeout: Option::Some(steps),
+            .. self
+        }
+    }
+
+    /// Get the number of steps the future has been found pending on. 
+    pub fn polls(&self) -> usize {
+        self.polls
+    }
+}
+
+impl<I, F> BehaviorTreeNode for FutureNode<I, F> where 
+    F: Future
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<F::Output, TimedOut<()>>;
+
+    #[inline]
+    fn step(self, _input: &I) -> NodeResult<(), Self::Terminal, Self> {
+        let mut mut_self = self;
+        let ready = {
+            let mut context = Context::from_waker(&mut_self.waker);
+            mut_self.future.as_mut().poll(&mut context)
+        };
+        match ready {
+            Poll::Ready(v) => NodeResult::Terminal(Result::Ok(v)),
+            Poll::Pending => {
+                mut_self.polls += 1;
+                if mut_self.timeout.map_or(false, |t| mut_self.polls >= t) {
+                    NodeResult::Terminal(Result::Err(TimedOut(())))
+                } else {
+                    NodeResult::Nonterminal((), mut_self)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use future_node::FutureNode;
+    use time_wrappers::TimedOut;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    struct Countdown(usize);
+
+    impl Future for Countdown {
+        type Output = &'static str;
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<&'static str> {
+            if self.0 == 0 {
+                Poll::Ready("done")
+            } else {
+                self.0 -= 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn future_node_test() {
+        let test_node = FutureNode::<(), _>::new(Countdown(1));
+        let test_node_1 = match test_node.step(&()) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(test_node_1.polls(), 1);
+        match test_node_1.step(&()) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok("done")),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn future_timeout_test() {
+        let test_node = FutureNode::<(), _>::new(Countdown(5)).timeout(2);
+        let test_node_1 = match test_node.step(&()) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&()) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(TimedOut(()))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,523 @@
+use btcpp_xml::TreeDocument;
+use expression::Value;
+use introspect::{Introspect, NodeStatus, TreeMonitor};
+use node_registry::{DynTree, NodeRegistry};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::rc::Rc;
+use tree_def::{LoadError, NodeDef};
+
+/// Port Groot2 connects to by default. 
+pub const DEFAULT_PORT: u16 = 1667;
+
+const PROTOCOL_ID: u8 = 2;
+const FULL_TREE: u8 = b'T';
+const STATUS: u8 = b'S';
+
+/// Status of a node as reported to Groot2, on the wire as its discriminant. 
+pub type GrootStatus = NodeStatus;
+
+#[derive(Debug)]
+struct Described {
+    xml: String,
+    tree_id: [u8; 16]
+}
+
+fn with_uids(def: &NodeDef, next: &mut i64) -> NodeDef {
+    *next += 1;
+    let mut marked = def.clone().param("_uid", Value::Int(*next));
+    marked.children = def.children.iter()
+        .map(|c| with_uids(c, next))
+        .collect();
+    marked
+}
+
+/// Monitor of a tree instantiated from a definition, which records the 
+/// status of each of its nodes through a tree monitor, and answers the 
+/// requests of the Groot2 visual tool for the structure of the tree and the 
+/// statuses of its nodes. 
+///
+/// The nodes are identified to Groot2 by their position in the preorder of 
+/// the tree, counting from one, as written in the `_uid` attributes of the 
+/// structure. The statuses of all nodes are reset when the root is stepped 
+/// again after it terminated. 
+#[derive(Clone, Debug)]
+pub struct GrootMonitor {
+    tree: TreeMonitor,
+    described: Rc<RefCell<Described>>
+}
+
+impl Default for GrootMonitor {
+    fn default() -> GrootMonitor {
+        GrootMonitor::new()
+    }
+}
+
+impl GrootMonitor {
+    /// Create a new monitor of no tree. 
+    pub fn new() -> GrootMonitor {
+        GrootMonitor {
+            tree: TreeMonitor::new(),
+            described: Rc::new(RefCell::new(Described {
+                xml: String::new(),
+                tree_id: [0; 16]
+            }))
+        }
+    }
+
+    /// Instantiate a tree from its definition through the registry, with 
+    /// each of its nodes reporting its status to the monitor. Instantiating 
+    /// another tree replaces the monitored one. 
+    pub fn build<I>(&self, registry: &NodeRegistry<I>, def: &NodeDef) ->
+        Result<DynTree<I>, LoadError> where 
+        I: 'static
+    {
+        let marked = with_uids(def, &mut 0);
+        let xml = TreeDocument::new().main("MainTree")
+            .tree("MainTree", marked)
+            .to_xml();
+        let mut tree_id = [0; 16];
+        for (seed, half) in tree_id.chunks_mut(8).enumerate() {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            xml.hash(&mut hasher);
+            half.copy_from_slice(&u64_bytes(hasher.finish()));
+        }
+        let tree = self.tree.build(registry, def)?;
+        let mut described = self.described.borrow_mut();
+        described.xml = xml;
+        described.tree_id = tree_id;
+        Result::Ok(tree)
+    }
+
+    /// Get the structure of the monitored tree, in the XML format of 
+    /// BehaviorTree.CPP. 
+    pub fn tree_xml(&self) -> String {
+        self.described.borrow().xml.clone()
+    }
+
+    /// Get the status of the node with the given identifier. 
+    pub fn status(&self, uid: u16) -> Option<GrootStatus> {
+        let index = (uid as usize).checked_sub(1)?;
+        self.tree.node_status(index)
+    }
+
+    /// Answer a request of Groot2, given as the frames of its message, 
+    /// returning the frames of the reply. Requests other than those for the 
+    /// structure of the tree and the statuses of its nodes are answered with 
+    /// an error. 
+    pub fn reply(&self, request: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        let header = match request.first() {
+            Option::Some(h) if h.len() == 6 && h[0] == PROTOCOL_ID => h,
+            _ => return error_reply("wrong header")
+        };
+        let described = self.described.borrow();
+        let mut reply_header = header.clone();
+        reply_header.extend_from_slice(&described.tree_id);
+        match header[1] {
+            FULL_TREE => vec![reply_header, described.xml.clone().into_bytes()],
+            STATUS => {
+                let statuses = self.tree.statuses();
+                let mut payload = Vec::with_capacity(3 * statuses.len());
+                for (i, s) in statuses.iter().enumerate() {
+                    let uid = i as u16 + 1;
+                    payload.push(uid as u8);
+                    payload.push((uid >> 8) as u8);
+                    payload.push(*s as u8);
+                }
+                vec![reply_header, payload]
+            },
+            _ => error_reply("unsupported request")
+        }
+    }
+}
+
+impl Introspect for GrootMonitor {
+    fn definition(&self) -> NodeDef {
+        self.tree.definition()
+    }
+
+    fn node_status(&self, index: usize) -> Option<NodeStatus> {
+        self.tree.node_status(index)
+    }
+}
+
+fn u64_bytes(value: u64) -> [u8; 8] {
+    let mut bytes = [0; 8];
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (value >> (8 * i)) as u8;
+    }
+    bytes
+}
+
+fn error_reply(message: &str) -> Vec<Vec<u8>> {
+    vec![b"error".to_vec(), message.as_bytes().to_vec()]
+}
+
+const MORE: u8 = 0x01;
+const LONG: u8 = 0x02;
+const COMMAND: u8 = 0x04;
+
+/// Longest frame accepted from a client, beyond which it is disconnected. 
+const MAX_FRAME_LEN: usize = 1 << 20;
+
+/// Most output queued for a client, beyond which it is disconnected. 
+const MAX_PENDING: usize = 1 << 24;
+
+fn greeting() -> Vec<u8> {
+    let mut greeting = vec![0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0x7F, 3, 0];
+    let mut mechanism = b"NULL".to_vec();
+    mechanism.resize(20, 0);
+    greeting.extend_from_slice(&mechanism);
+    greeting.resize(64, 0);
+    greeting
+}
+
+fn frame(flags: u8, body: &[u8], out: &mut Vec<u8>) {
+    if body.len() > 255 {
+        let len = body.len() as u64;
+        out.push(flags | LONG);
+        out.extend((0..8).rev().map(|i| (len >> (8 * i)) as u8));
+    } else {
+        out.push(flags);
+        out.push(body.len() as u8);
+    }
+    out.extend_from_slice(body);
+}
+
+fn ready(socket_type: &str) -> Vec<u8> {
+    let mut body = b"\x05READY\x0bSocket-Type".to_vec();
+    body.extend_from_slice(&[0, 0, 0, socket_type.len() as u8]);
+    body.extend_from_slice(socket_type.as_bytes());
+    let mut out = Vec::new();
+    frame(COMMAND, &body, &mut out);
+    out
+}
+
+/// Connection of a Groot2 client, speaking the ZeroMQ transport protocol. 
+#[derive(Debug)]
+struct Peer {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    output: Vec<u8>,
+    greeted: bool,
+    frames: Vec<Vec<u8>>
+}
+
+impl Peer {
+    fn new(stream: TcpStream) -> Peer {
+        Peer {
+            stream: stream,
+            buffer: Vec::new(),
+            output: Vec::new(),
+            greeted: false,
+            frames: Vec::new()
+        }
+    }
+
+    /// Queue bytes to send to the client, sending what it accepts without 
+    /// blocking. 
+    fn send(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.output.extend_from_slice(bytes);
+        if self.output.len() > MAX_PENDING {
+            return Result::Err(io::Error::new(ErrorKind::TimedOut,
+                "Client is not reading its replies"));
+        }
+        self.flush()
+    }
+
+    /// Send as much of the queued output as the client accepts without 
+    /// blocking, keeping the rest for the next poll. 
+    fn flush(&mut self) -> io::Result<()> {
+        let mut written = 0;
+        while written < self.output.len() {
+            match self.stream.write(&self.output[written..]) {
+                Result::Ok(0) => {
+                    return Result::Err(ErrorKind::WriteZero.into())
+                },
+                Result::Ok(n) => written += n,
+                Result::Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    break
+                },
+                Result::Err(e) => return Result::Err(e)
+            }
+        }
+        self.output.drain(..written);
+        Result::Ok(())
+    }
+
+    /// Read what the client has sent, returning whether it is still 
+    /// connected. 
+    fn read(&mut self) -> io::Result<bool> {
+        let mut chunk = [0; 4096];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Result::Ok(0) => return Result::Ok(false),
+                Result::Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Result::Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    return Result::Ok(true)
+                },
+                Result::Err(e) => return Result::Err(e)
+            }
+        }
+    }
+
+    /// Take the next complete frame from the buffer, along with its flags, 
+    /// or nothing if the frame is incomplete. Frames longer than the limit 
+    /// are rejected. 
+    fn next_frame(&mut self) -> io::Result<Option<(u8, Vec<u8>)>> {
+        let flags = match self.buffer.first() {
+            Option::Some(f) => *f,
+            Option::None => return Result::Ok(Option::None)
+        };
+        let (start, len) = if flags & LONG != 0 {
+            if self.buffer.len() < 9 {
+                return Result::Ok(Option::None);
+            }
+            (9usize, self.buffer[1..9].iter()
+                .fold(0u64, |l, b| (l << 8) | u64::from(*b)))
+        } else {
+            match self.buffer.get(1) {
+                Option::Some(l) => (2, u64::from(*l)),
+                Option::None => return Result::Ok(Option::None)
+            }
+        };
+        let end = match start.checked_add(len as usize) {
+            Option::Some(e) if len <= MAX_FRAME_LEN as u64 => e,
+            _ => return Result::Err(io::Error::new(ErrorKind::InvalidData,
+                "Frame is too long"))
+        };
+        if self.buffer.len() < end {
+            return Result::Ok(Option::None);
+        }
+        let body = self.buffer[start..end].to_vec();
+        self.buffer.drain(..end);
+        Result::Ok(Option::Some((flags, body)))
+    }
+
+    /// Process what the client has sent, answering each of its complete 
+    /// requests. 
+    fn process(&mut self, monitor: &GrootMonitor) -> io::Result<()> {
+        if !self.greeted {
+            if self.buffer.len() < 64 {
+                return Result::Ok(());
+            }
+            if self.buffer[0] != 0xFF || self.buffer[9] != 0x7F ||
+                self.buffer[10] < 3
+            {
+                return Result::Err(io::Error::new(ErrorKind::InvalidData,
+                    "Not a ZeroMQ peer"));
+            }
+            self.buffer.drain(..64);
+            self.greeted = true;
+        }
+        while let Option::Some((flags, body)) = self.next_frame()? {
+            if flags & COMMAND != 0 {
+                continue;
+            }
+            self.frames.push(body);
+            if flags & MORE != 0 {
+                continue;
+            }
+            let mut message = self.frames.drain(..).collect::<Vec<_>>();
+            let split = message.iter().position(|f| f.is_empty())
+                .map(|p| p + 1)
+                .unwrap_or(0);
+            let request = message.split_off(split);
+            let frames = message.into_iter()
+                .chain(monitor.reply(&request))
+                .collect::<Vec<_>>();
+            let mut out = Vec::new();
+            for (i, f) in frames.iter().enumerate() {
+                let more = if i + 1 < frames.len() { MORE } else { 0 };
+                frame(more, f, &mut out);
+            }
+            self.send(&out)?;
+        }
+        Result::Ok(())
+    }
+}
+
+/// Server answering the requests of Groot2 for a monitored tree. 
+///
+/// Groot2 talks to the reply socket of a ZeroMQ server over TCP, which is 
+/// implemented here for unencrypted connections, without depending on the 
+/// ZeroMQ library. The server never blocks waiting for requests. Instead, 
+/// it is polled, such as once after each step of the tree. 
+#[derive(Debug)]
+pub struct GrootServer {
+    listener: TcpListener,
+    monitor: GrootMonitor,
+    peers: Vec<Peer>
+}
+
+impl GrootServer {
+    /// Create a new server for the given monitor, listening on the given 
+    /// address. 
+    pub fn bind<A>(address: A, monitor: GrootMonitor) ->
+        io::Result<GrootServer> where 
+        A: ToSocketAddrs
+    {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        Result::Ok(GrootServer {
+            listener: listener,
+            monitor: monitor,
+            peers: Vec::new()
+        })
+    }
+
+    /// Get the address the server listens on. 
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Get the monitor the server answers for. 
+    pub fn monitor(&self) -> &GrootMonitor {
+        &self.monitor
+    }
+
+    /// Accept the clients which connected since the last poll, and answer 
+    /// the requests they sent. Clients which disconnected or misbehaved are 
+    /// dropped. 
+    pub fn poll(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Result::Ok((stream, _)) => {
+                    stream.set_nonblocking(true)?;
+                    let mut peer = Peer::new(stream);
+                    let mut hello = greeting();
+                    hello.extend(ready("REP"));
+                    if peer.send(&hello).is_ok() {
+                        self.peers.push(peer);
+                    }
+                },
+                Result::Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    break
+                },
+                Result::Err(e) => return Result::Err(e)
+            }
+        }
+        let monitor = &self.monitor;
+        let mut kept = Vec::with_capacity(self.peers.len());
+        for mut peer in self.peers.drain(..) {
+            let connected = peer.read().unwrap_or(false);
+            if peer.flush().is_ok() && peer.process(monitor).is_ok() &&
+                connected
+            {
+                kept.push(peer);
+            }
+        }
+        self.peers = kept;
+        Result::Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+    use groot::{frame, greeting, ready, GrootMonitor, GrootServer,
+        GrootStatus, Peer, LONG, MORE};
+    use node_registry::NodeRegistry;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+    use tree_def::NodeDef;
+
+    fn registry() -> NodeRegistry<i64> {
+        NodeRegistry::with_builtins()
+            .leaf("WaitNonzero", |_: &NodeDef| {
+                Result::Ok(PredicateWait::new(|i: &i64| if *i == 0 {
+                    Statepoint::Nonterminal(())
+                } else {
+                    Statepoint::Terminal(BtStatus::from(*i > 0))
+                }))
+            })
+    }
+
+    fn def() -> NodeDef {
+        NodeDef::new("Sequence")
+            .child(NodeDef::new("WaitNonzero"))
+            .child(NodeDef::new("WaitNonzero"))
+    }
+
+    #[test]
+    fn groot_monitor_test() {
+        let monitor = GrootMonitor::new();
+        let tree = monitor.build(&registry(), &def()).unwrap();
+        assert!(monitor.tree_xml().contains("<WaitNonzero _uid=\"3\"/>"));
+        let tree_1 = match tree.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(monitor.status(1), Option::Some(GrootStatus::Running));
+        assert_eq!(monitor.status(2), Option::Some(GrootStatus::Running));
+        assert_eq!(monitor.status(3), Option::Some(GrootStatus::Idle));
+        let tree_2 = match tree_1.step(&1) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(monitor.status(2), Option::Some(GrootStatus::Success));
+        match tree_2.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Failure),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(monitor.status(3), Option::Some(GrootStatus::Failure));
+        let status = monitor.reply(&[vec![2, b'S', 7, 0, 0, 0]]);
+        assert_eq!(&status[0][..6], &[2, b'S', 7, 0, 0, 0]);
+        assert_eq!(status[0].len(), 22);
+        assert_eq!(status[1], vec![1, 0, 3, 2, 0, 2, 3, 0, 3]);
+        assert_eq!(monitor.reply(&[vec![1]])[0], b"error".to_vec());
+        let tree_3 = monitor.build(&registry(), &def()).unwrap();
+        tree_3.step(&0);
+        assert_eq!(monitor.status(3), Option::Some(GrootStatus::Idle));
+    }
+
+    #[test]
+    fn groot_server_test() {
+        let monitor = GrootMonitor::new();
+        monitor.build(&registry(), &def()).unwrap();
+        let mut server = GrootServer::bind("127.0.0.1:0", monitor).unwrap();
+        let mut client = TcpStream::connect(server.local_addr().unwrap())
+            .unwrap();
+        let mut request = greeting();
+        request.extend(ready("REQ"));
+        frame(MORE, &[], &mut request);
+        frame(0, &[2, b'T', 1, 0, 0, 0], &mut request);
+        client.write_all(&request).unwrap();
+        let mut reply = Vec::new();
+        let mut chunk = [0; 4096];
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !String::from_utf8_lossy(&reply).contains("</root>") {
+            assert!(Instant::now() < deadline, "Timed out awaiting the tree");
+            server.poll().unwrap();
+            client.set_nonblocking(true).unwrap();
+            if let Result::Ok(n) = client.read(&mut chunk) {
+                reply.extend_from_slice(&chunk[..n]);
+            }
+        }
+        assert_eq!(&reply[..10], &greeting()[..10]);
+        assert!(String::from_utf8_lossy(&reply).contains("_uid=\"1\""));
+    }
+
+    #[test]
+    fn groot_frame_limit_test() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut peer = Peer::new(TcpStream::connect(listener.local_addr()
+            .unwrap()).unwrap());
+        peer.buffer = vec![LONG, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xF7];
+        assert!(peer.next_frame().is_err());
+        peer.buffer = vec![LONG, 0, 0, 0, 0, 0, 0x20, 0, 0];
+        assert!(peer.next_frame().is_err());
+        peer.buffer = vec![0, 2, 7];
+        assert_eq!(peer.next_frame().unwrap(), Option::None);
+        peer.buffer.push(8);
+        assert_eq!(peer.next_frame().unwrap(), Option::Some((0, vec![7, 8])));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,277 @@
+use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult, Statepoint};
+use dynamic_node::BoxedNode;
+use node_registry::{DynTree, NodeRegistry};
+use stackbt_automata_impl::automaton::Automaton;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use tree_def::{LoadError, NodeDef};
+
+type Slot<I> = Rc<RefCell<Option<DynTree<I>>>>;
+
+/// Node of a reloadable tree, which steps the running subtree held in its 
+/// slot, so that the subtree can be moved into the reloaded tree. 
+struct Retained<I> {
+    slot: Slot<I>
+}
+
+impl<I> BehaviorTreeNode for Retained<I> {
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = BtStatus;
+
+    fn step(self, input: &I) -> NodeResult<(), BtStatus, Self> {
+        let node = self.slot.borrow_mut().take()
+            .expect("Retained subtree was moved by a reload");
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                *self.slot.borrow_mut() = Option::Some(m);
+                NodeResult::Nonterminal(n, self)
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        let node = self.slot.borrow_mut().take();
+        if let Option::Some(n) = node {
+            n.abort()
+        }
+    }
+}
+
+struct Retention<I> {
+    hash: u64,
+    slot: Slot<I>
+}
+
+impl<I> Clone for Retention<I> {
+    fn clone(&self) -> Retention<I> {
+        Retention {
+            hash: self.hash,
+            slot: self.slot.clone()
+        }
+    }
+}
+
+struct Rebuild<'a, I> where 
+    I: 'a
+{
+    registry: &'a NodeRegistry<I>,
+    old: HashMap<Vec<usize>, Retention<I>>,
+    new: HashMap<Vec<usize>, Retention<I>>,
+    moved: Vec<(Slot<I>, Slot<I>)>
+}
+
+impl<'a, I> Rebuild<'a, I> where 
+    I: 'static
+{
+    fn node(&mut self, def: &NodeDef, path: &mut Vec<usize>) ->
+        Result<DynTree<I>, LoadError>
+    {
+        let hash = def.structural_hash();
+        let reused = match self.old.get(path) {
+            Option::Some(r) if r.hash == hash => r.slot.borrow_mut().take()
+                .map(|n| (r.slot.clone(), n)),
+            _ => Option::None
+        };
+        if let Option::Some((old, node)) = reused {
+            let slot = Rc::new(RefCell::new(Option::Some(node)));
+            for (p, r) in self.old.iter() {
+                if p.len() > path.len() && p.starts_with(path) {
+                    self.new.insert(p.clone(), r.clone());
+                }
+            }
+            self.moved.push((old, slot.clone()));
+            return Result::Ok(self.retain(path, hash, slot));
+        }
+        let mut children = Vec::new();
+        for (i, child) in def.children.iter().enumerate() {
+            path.push(i);
+            let built = self.node(child, path);
+            path.pop();
+            children.push(built?);
+        }
+        let node = self.registry.construct(def, children)?;
+        Result::Ok(self.retain(path, hash,
+            Rc::new(RefCell::new(Option::Some(node)))))
+    }
+
+    fn retain(&mut self, path: &[usize], hash: u64, slot: Slot<I>) ->
+        DynTree<I>
+    {
+        self.new.insert(path.to_vec(), Retention {
+            hash: hash,
+            slot: slot.clone()
+        });
+        BoxedNode::new(Retained {
+            slot: slot
+        })
+    }
+
+    fn undo(&mut self) {
+        for &(ref old, ref new) in self.moved.iter() {
+            *old.borrow_mut() = new.borrow_mut().take();
+        }
+    }
+}
+
+/// Running tree instantiated from a definition, into which changed 
+/// definitions can be reloaded while it runs, so that designers can iterate 
+/// on trees live. 
+///
+/// On a reload, each subtree of the new definition which is structurally 
+/// the same as the subtree at the same position of the running definition, 
+/// as told by their structural hashes, keeps its running state, while the 
+/// changed subtrees, including all of the ancestors of a change, are 
+/// reinitialized. Like the node runner, the tree is restarted from its 
+/// definition whenever it terminates. 
+pub struct ReloadableTree<I> {
+    registry: NodeRegistry<I>,
+    def: NodeDef,
+    root: Option<DynTree<I>>,
+    retained: HashMap<Vec<usize>, Retention<I>>
+}
+
+impl<I> fmt::Debug for ReloadableTree<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReloadableTree")
+            .field("def", &self.def)
+            .finish()
+    }
+}
+
+impl<I> ReloadableTree<I> where 
+    I: 'static
+{
+    /// Instantiate a tree from its definition through the registry. 
+    pub fn new(registry: NodeRegistry<I>, def: NodeDef) ->
+        Result<ReloadableTree<I>, LoadError>
+    {
+        let mut tree = ReloadableTree {
+            registry: registry,
+            def: NodeDef::new(""),
+            root: Option::None,
+            retained: HashMap::new()
+        };
+        tree.reload(def)?;
+        Result::Ok(tree)
+    }
+
+    /// Get the definition of the running tree. 
+    pub fn definition(&self) -> &NodeDef {
+        &self.def
+    }
+
+    /// Reload the tree from the given definition, returning the number of 
+    /// subtrees which kept their running state. 
+    ///
+    /// If the definition fails to be instantiated, the running tree is left 
+    /// as it was. 
+    pub fn reload(&mut self, def: NodeDef) -> Result<usize, LoadError> {
+        let expanded = self.registry.expand(&def)?;
+        let mut rebuild = Rebuild {
+            registry: &self.registry,
+            old: self.retained.clone(),
+            new: HashMap::new(),
+            moved: Vec::new()
+        };
+        let root = match rebuild.node(&expanded, &mut Vec::new()) {
+            Result::Ok(r) => r,
+            Result::Err(e) => {
+                rebuild.undo();
+                return Result::Err(e);
+            }
+        };
+        if let Option::Some(old) = self.root.take() {
+            old.abort();
+        }
+        self.def = def;
+        self.root = Option::Some(root);
+        self.retained = rebuild.new;
+        Result::Ok(rebuild.moved.len())
+    }
+
+    fn restart(&mut self) {
+        self.retained = HashMap::new();
+        let def = self.def.clone();
+        self.reload(def).expect("Running definitions always build");
+    }
+}
+
+impl<I> Automaton<'static> for ReloadableTree<I> where 
+    I: 'static
+{
+    type Input = I;
+    type Action = Statepoint<(), BtStatus>;
+
+    fn transition(&mut self, input: &I) -> Statepoint<(), BtStatus> {
+        match self.root.take().expect("Reloadable tree was poisoned")
+            .step(input)
+        {
+            NodeResult::Nonterminal(n, m) => {
+                self.root = Option::Some(m);
+                Statepoint::Nonterminal(n)
+            },
+            NodeResult::Terminal(t) => {
+                self.restart();
+                Statepoint::Terminal(t)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+    use hot_reload::ReloadableTree;
+    use node_registry::NodeRegistry;
+    use stackbt_automata_impl::automaton::Automaton;
+    use tree_def::{LoadError, NodeDef};
+
+    struct Ticks(i64);
+
+    impl BehaviorTreeNode for Ticks {
+        type Input = ();
+        type Nonterminal = ();
+        type Terminal = BtStatus;
+
+        fn step(self, _input: &()) -> NodeResult<(), BtStatus, Self> {
+            match self.0 {
+                0 => NodeResult::Terminal(BtStatus::Success),
+                n => NodeResult::Nonterminal((), Ticks(n - 1))
+            }
+        }
+    }
+
+    fn ticks(first: i64, second: i64) -> NodeDef {
+        NodeDef::new("Sequence")
+            .child(NodeDef::new("Ticks").param("n", first))
+            .child(NodeDef::new("Ticks").param("n", second))
+    }
+
+    #[test]
+    fn hot_reload_test() {
+        let registry = NodeRegistry::with_builtins()
+            .leaf("Ticks", |d: &NodeDef| Result::Ok(Ticks(d.int("n")?)));
+        let mut tree = ReloadableTree::new(registry, ticks(3, 2)).unwrap();
+        assert_eq!(tree.transition(&()), Statepoint::Nonterminal(()));
+        match tree.reload(ticks(3, 0).child(NodeDef::new("Unknown"))) {
+            Result::Err(LoadError::UnknownKind(_)) => (),
+            _ => unreachable!("Expected unknown kind")
+        };
+        assert_eq!(tree.reload(ticks(3, 0)).unwrap(), 1);
+        assert_eq!(tree.definition(), &ticks(3, 0));
+        for _ in 0..3 {
+            assert_eq!(tree.transition(&()), Statepoint::Nonterminal(()));
+        }
+        assert_eq!(tree.transition(&()),
+            Statepoint::Terminal(BtStatus::Success));
+        assert_eq!(tree.reload(ticks(3, 0)).unwrap(), 1);
+        for _ in 0..4 {
+            assert_eq!(tree.transition(&()), Statepoint::Nonterminal(()));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,157 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use parallel_join::JoinPolicy;
+
+/// A composite node which, unlike the parallel nodes, steps exactly one of 
+/// its children each step, rotating through the children which are still 
+/// running, so that many cheap behaviors can share a single step. 
+///
+/// Each step, the index of the child that was stepped is returned, along 
+/// with its nonterminal, or Option::None if it terminated. Terminated 
+/// children are skipped in the rotation, and their terminals are kept until 
+/// the JoinPolicy decides to exit, at which point the children still running 
+/// are aborted. 
+///
+/// Stepping the node while none of its children are running panics, which 
+/// cannot happen with a policy that exits once all children have terminated. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct InterleavedNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    children: Box<[Option<C>]>,
+    finished: Box<[Option<P::Term>]>,
+    policy: P,
+    next: usize
+}
+
+impl<C, P> InterleavedNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    /// Create a new interleaved node over the given children. 
+    pub fn new<K>(policy: P, children: K) -> InterleavedNode<C, P> where 
+        K: IntoIterator<Item=C>
+    {
+        let children = children.into_iter()
+            .map(Option::Some)
+            .collect::<Vec<_>>();
+        let finished = children.iter()
+            .map(|_| Option::None)
+            .collect::<Vec<_>>();
+        InterleavedNode {
+            children: children.into_boxed_slice(),
+            finished: finished.into_boxed_slice(),
+            policy: policy,
+            next: 0
+        }
+    }
+}
+
+impl<C, P> BehaviorTreeNode for InterleavedNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    type Input = P::Input;
+    type Nonterminal = (usize, Option<C::Nonterminal>);
+    type Terminal = P::Exit;
+
+    #[inline]
+    fn step(self, input: &P::Input) -> NodeResult<Self::Nonterminal, P::Exit,
+        Self>
+    {
+        let mut children = self.children;
+        let mut finished = self.finished;
+        let count = children.len();
+        let next = self.next;
+        let index = (0..count)
+            .map(|offset| (next + offset) % count)
+            .find(|&i| children[i].is_some())
+            .expect("Interleaved node stepped with no running children");
+        let nonterm = match children[index].take().unwrap().step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                children[index] = Option::Some(m);
+                Option::Some(n)
+            },
+            NodeResult::Terminal(t) => {
+                finished[index] = Option::Some(t);
+                Option::None
+            }
+        };
+        match self.policy.join(input, finished) {
+            Statepoint::Nonterminal(f) => NodeResult::Nonterminal(
+                (index, nonterm),
+                InterleavedNode {
+                    children: children,
+                    finished: f,
+                    policy: self.policy,
+                    next: (index + 1) % count
+                }
+            ),
+            Statepoint::Terminal(x) => {
+                for child in children.into_vec().into_iter().flatten() {
+                    child.abort();
+                }
+                NodeResult::Terminal(x)
+            }
+        }
+    }
+
+    fn abort(self) {
+        for child in self.children.into_vec().into_iter().flatten() {
+            child.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+    struct Countdown {
+        remaining: i64
+    }
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = ();
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, _input: &()) -> NodeResult<i64, i64, Self> {
+            if self.remaining > 1 {
+                NodeResult::Nonterminal(self.remaining, Countdown {
+                    remaining: self.remaining - 1
+                })
+            } else {
+                NodeResult::Terminal(self.remaining)
+            }
+        }
+    }
+
+    #[test]
+    fn interleave_test() {
+        use interleave_node::InterleavedNode;
+        use parallel_join::JoinAll;
+        let test_node = InterleavedNode::new(
+            JoinAll::new(),
+            vec![Countdown { remaining: 1 }, Countdown { remaining: 3 },
+                Countdown { remaining: 2 }]
+        );
+        let mut test_node_1 = test_node;
+        let expected = [(0, Option::None), (1, Option::Some(3)),
+            (2, Option::Some(2)), (1, Option::Some(2)), (2, Option::None)];
+        for &ran in expected.iter() {
+            test_node_1 = match test_node_1.step(&()) {
+                NodeResult::Nonterminal(v, n) => {
+                    assert_eq!(v, ran);
+                    n
+                },
+                _ => unreachable!("Expected nonterminal transition")
+            };
+        }
+        // Only the middle child is left running
+        match test_node_1.step(&()) {
+            NodeResult::Terminal(v) => assert_eq!(&*v, &[1, 1, 1]),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
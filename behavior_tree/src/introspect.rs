@@ -0,0 +1,223 @@
+use behavior_tree_node::BtStatus;
+use dynamic_node::BoxedNode;
+use expression::Value;
+use node_registry::{DynTree, NodeRegistry};
+use observe_wrappers::{NodeObserver, ObservedNode};
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::rc::Rc;
+use tree_def::{LoadError, NodeDef};
+
+/// Status of a node of a running tree. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum NodeStatus {
+    /// The node is not running. 
+    Idle = 0,
+    /// The node was stepped to a nonterminal. 
+    Running = 1,
+    /// The node terminated successfully. 
+    Success = 2,
+    /// The node terminated with a failure. 
+    Failure = 3
+}
+
+impl NodeStatus {
+    fn label(&self) -> &'static str {
+        match *self {
+            NodeStatus::Idle => "idle",
+            NodeStatus::Running => "running",
+            NodeStatus::Success => "success",
+            NodeStatus::Failure => "failure"
+        }
+    }
+}
+
+/// Introspection of a running tree, exposing the definition of the tree 
+/// along with the status of each of its nodes, numbered in preorder from 
+/// zero. 
+pub trait Introspect {
+    /// Get the definition of the tree. 
+    fn definition(&self) -> NodeDef;
+
+    /// Get the status of the node with the given preorder index. 
+    fn node_status(&self, index: usize) -> Option<NodeStatus>;
+
+    /// Render the tree as indented text, for logs and bug reports. 
+    ///
+    /// Each node is written on its own line, with its kind, parameters and 
+    /// status, indented below its parent. The nodes on the active path, 
+    /// which are still running, are marked with an asterisk. 
+    fn dump_state(&self) -> String {
+        let mut out = String::new();
+        dump_node(self, &self.definition(), 0, &mut 0, &mut out);
+        out
+    }
+}
+
+fn dump_node<T>(tree: &T, def: &NodeDef, depth: usize, next: &mut usize,
+    out: &mut String) where 
+    T: Introspect + ?Sized
+{
+    let status = tree.node_status(*next).unwrap_or(NodeStatus::Idle);
+    *next += 1;
+    let marker = if status == NodeStatus::Running { '*' } else { ' ' };
+    let _ = write!(out, "{}{} {}", marker, "  ".repeat(depth), def.kind);
+    for (name, value) in def.params.iter() {
+        let _ = match *value {
+            Value::Str(ref s) => write!(out, " {}={:?}", name, s),
+            Value::Int(i) => write!(out, " {}={}", name, i),
+            Value::Float(f) => write!(out, " {}={:?}", name, f),
+            Value::Bool(b) => write!(out, " {}={}", name, b)
+        };
+    }
+    let _ = writeln!(out, " [{}]", status.label());
+    for child in def.children.iter() {
+        dump_node(tree, child, depth + 1, next, out);
+    }
+}
+
+#[derive(Debug)]
+struct Watched {
+    def: NodeDef,
+    statuses: Vec<NodeStatus>
+}
+
+/// Observer of a node of a monitored tree, recording its status. 
+#[derive(Clone, Debug)]
+struct StatusObserver {
+    index: usize,
+    state: Rc<RefCell<Watched>>
+}
+
+impl<I> NodeObserver<I, (), BtStatus> for StatusObserver {
+    fn on_step(&mut self, _input: &I) {
+        let mut state = self.state.borrow_mut();
+        let finished = state.statuses[0] == NodeStatus::Success ||
+            state.statuses[0] == NodeStatus::Failure;
+        if self.index == 0 && finished {
+            for s in state.statuses.iter_mut() {
+                *s = NodeStatus::Idle;
+            }
+        }
+    }
+
+    fn on_nonterminal(&mut self, _input: &I, _nonterminal: &()) {
+        self.state.borrow_mut().statuses[self.index] = NodeStatus::Running;
+    }
+
+    fn on_terminal(&mut self, _input: &I, terminal: &BtStatus) {
+        self.state.borrow_mut().statuses[self.index] = match *terminal {
+            BtStatus::Success => NodeStatus::Success,
+            BtStatus::Failure => NodeStatus::Failure
+        };
+    }
+
+    fn on_abort(&mut self) {
+        self.state.borrow_mut().statuses[self.index] = NodeStatus::Idle;
+    }
+}
+
+/// Monitor of a tree instantiated from a definition, which records the 
+/// status of each of its nodes through observers. 
+///
+/// The statuses of all nodes are reset when the root is stepped again after 
+/// it terminated. 
+#[derive(Clone, Debug)]
+pub struct TreeMonitor {
+    state: Rc<RefCell<Watched>>
+}
+
+impl Default for TreeMonitor {
+    fn default() -> TreeMonitor {
+        TreeMonitor::new()
+    }
+}
+
+impl TreeMonitor {
+    /// Create a new monitor of no tree. 
+    pub fn new() -> TreeMonitor {
+        TreeMonitor {
+            state: Rc::new(RefCell::new(Watched {
+                def: NodeDef::new(""),
+                statuses: Vec::new()
+            }))
+        }
+    }
+
+    /// Instantiate a tree from its definition through the registry, with 
+    /// each of its nodes reporting its status to the monitor. Instantiating 
+    /// another tree replaces the monitored one. 
+    pub fn build<I>(&self, registry: &NodeRegistry<I>, def: &NodeDef) ->
+        Result<DynTree<I>, LoadError> where 
+        I: 'static
+    {
+        let mut statuses = Vec::new();
+        let state = self.state.clone();
+        let tree = registry.build_wrapped(def, &mut |i, _, n| {
+            statuses.push(NodeStatus::Idle);
+            BoxedNode::new(ObservedNode::new(StatusObserver {
+                index: i,
+                state: state.clone()
+            }, n))
+        })?;
+        let mut watched = self.state.borrow_mut();
+        watched.def = def.clone();
+        watched.statuses = statuses;
+        Result::Ok(tree)
+    }
+
+    /// Get the statuses of all nodes, in preorder. 
+    pub fn statuses(&self) -> Vec<NodeStatus> {
+        self.state.borrow().statuses.clone()
+    }
+}
+
+impl Introspect for TreeMonitor {
+    fn definition(&self) -> NodeDef {
+        self.state.borrow().def.clone()
+    }
+
+    fn node_status(&self, index: usize) -> Option<NodeStatus> {
+        self.state.borrow().statuses.get(index).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+    use introspect::{Introspect, NodeStatus, TreeMonitor};
+    use node_registry::NodeRegistry;
+    use tree_def::NodeDef;
+
+    #[test]
+    fn dump_state_test() {
+        let registry = NodeRegistry::with_builtins()
+            .leaf("WaitNonzero", |_: &NodeDef| {
+                Result::Ok(PredicateWait::new(|i: &i64| if *i == 0 {
+                    Statepoint::Nonterminal(())
+                } else {
+                    Statepoint::Terminal(BtStatus::from(*i > 0))
+                }))
+            });
+        let def = NodeDef::new("Sequence")
+            .child(NodeDef::new("WaitNonzero").param("label", "first"))
+            .child(NodeDef::new("Inverter")
+                .child(NodeDef::new("WaitNonzero").param("ticks", 2)));
+        let monitor = TreeMonitor::new();
+        let tree = monitor.build(&registry, &def).unwrap();
+        let tree_1 = match tree.step(&1) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        tree_1.step(&0);
+        assert_eq!(monitor.node_status(1), Option::Some(NodeStatus::Success));
+        assert_eq!(monitor.dump_state(), "\
+* Sequence [running]
+    WaitNonzero label=\"first\" [success]
+*   Inverter [running]
+*     WaitNonzero ticks=2 [running]
+");
+    }
+}
\ No newline at end of file
@@ -25,27 +25,168 @@
 //! is translated to something more like a state machine transition in code, 
 //! especially if optimizations are turned on. 
 
-#![cfg_attr(feature = "try_trait", feature(try_trait))]
-#![cfg_attr(feature = "existential_type", feature(existential_type))]
+#![cfg_attr(feature = "try_trait", feature(try_trait_v2))]
+#![cfg_attr(feature = "try_trait", feature(try_trait_v2_residual))]
+#![cfg_attr(feature = "type_alias_impl_trait", feature(type_alias_impl_trait))]
 
 extern crate stackbt_automata_impl;
 extern crate num_traits;
 extern crate num_derive;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+#[cfg(feature = "serde_yaml")]
+extern crate serde_yaml;
+#[cfg(feature = "bincode")]
+extern crate bincode;
+#[cfg(feature = "ron")]
+extern crate ron;
+#[cfg(feature = "protobuf")]
+extern crate prost;
+#[cfg(feature = "rhai")]
+extern crate rhai;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_test;
+#[cfg(test)]
+extern crate stackbt_macros;
+#[cfg(test)]
+extern crate self as stackbt_behavior_tree;
 
 /// The base leaf nodes on which behavior trees are built. 
 pub mod base_nodes;
 /// The behavior tree node trait and associated enums. 
 pub mod behavior_tree_node;
+/// Externalizing the state of nodes which hold closures. 
+#[cfg(feature = "serde")]
+pub mod persist_state;
+/// Clocks for the nodes which depend on real time. 
+pub mod clock;
+/// Typed shared working memory for the nodes of a tree. 
+pub mod blackboard;
+/// Saving and restoring the entries of blackboards. 
+#[cfg(feature = "persistence")]
+pub mod blackboard_persist;
 /// An automaton wrapper for behavior tree nodes. 
 pub mod node_runner;
+/// Capturing and reconstructing running trees. 
+#[cfg(feature = "serde")]
+pub mod snapshot;
+/// Integrity checksums of saved state. 
+pub mod checksum;
+/// Compact binary encoding of state. 
+#[cfg(feature = "binary")]
+pub mod binary_codec;
+/// Migrations of states saved by earlier versions of their layout. 
+#[cfg(feature = "binary")]
+pub mod migration;
+/// Incremental snapshots of populations of trees. 
+#[cfg(feature = "binary")]
+pub mod delta_snapshot;
+/// Recording the steps of trees to trace files. 
+#[cfg(feature = "binary")]
+pub mod trace;
+/// Exchanging snapshots and statuses as protocol buffers. 
+#[cfg(feature = "protobuf")]
+pub mod proto_codec;
 /// A serial running node controller. 
 #[macro_use]
 pub mod serial_node;
+/// A pushdown variant of the serial branch node. 
+pub mod stack_node;
 /// A parallel running node controller. 
 pub mod parallel_node;
+/// A parallel node controller joining its children by a policy. 
+pub mod parallel_join;
+/// A synchronization node waiting for a combination of events. 
+pub mod event_join;
+/// A planner node ordering its children to reach a goal. 
+pub mod planner_node;
+/// A parallel node blending the outputs of its children by weight. 
+pub mod blend_node;
+/// A node controller stepping one child at a time in rotation. 
+pub mod interleave_node;
 /// An assortment of mapping wrappers for behavior tree nodes. 
 pub mod map_wrappers;
 /// An assortment of controlling wrappers for behavior tree nodes. 
 pub mod control_wrappers;
+/// Wrappers for nodes which succeed or fail, acting on their status. 
+pub mod status_wrappers;
+/// Wrappers running fresh instances of a node repeatedly, or until success. 
+pub mod repeat_wrappers;
+/// Wrappers limiting how long or how often a node runs. 
+pub mod time_wrappers;
+/// Wrappers reusing the outputs of earlier steps or runs of a node. 
+pub mod cache_wrappers;
+/// Wrappers observing the steps of a node without altering it. 
+pub mod observe_wrappers;
+/// Nodes reading and writing blackboard entries. 
+pub mod blackboard_nodes;
+/// Ports binding the inputs and outputs of nodes to blackboard entries. 
+pub mod ports;
+/// A small expression language for conditions over blackboard entries. 
+pub mod expression;
+/// Leaves and deciders scripted in Rhai. 
+#[cfg(feature = "rhai")]
+pub mod scripting;
+/// Leaf nodes coordinating through queues of events. 
+pub mod event_nodes;
+/// A leaf node querying structured blackboard contents. 
+pub mod query_node;
+/// A leaf node bridging futures into behavior trees. 
+pub mod future_node;
 /// An assortment of serial and parallel node controllers. 
-pub mod node_compositions;
\ No newline at end of file
+pub mod node_compositions;
+/// Sequence nodes built on success and failure terminals. 
+pub mod sequence_node;
+/// Selector nodes built on success and failure terminals. 
+pub mod selector_node;
+/// Conditional and looping nodes driven by the outcome of a condition node. 
+pub mod conditional_node;
+/// A switch node running the child keyed by its input. 
+pub mod switch_node;
+/// A node retrying its main child after running a recovery child. 
+pub mod recovery_node;
+/// A selector node drawing its children at random by weight. 
+pub mod random_selector;
+/// A serial node controller rotating through its children. 
+pub mod round_robin;
+/// A selector node running whichever child scores the highest utility. 
+pub mod utility_selector;
+/// A selector node whose higher priority children preempt lower ones. 
+pub mod priority_selector;
+/// Type-erased nodes and composites over runtime lists of them. 
+pub mod dynamic_node;
+/// Definitions of trees as data. 
+pub mod tree_def;
+/// Import and export of trees in the XML format of BehaviorTree.CPP. 
+pub mod btcpp_xml;
+/// Definitions of trees in a textual format, with diagnostics pointing into 
+/// the source. 
+pub mod bt_dsl;
+/// Introspection of running trees. 
+pub mod introspect;
+/// Live monitoring of trees with the Groot2 visual tool. 
+pub mod groot;
+/// Instantiating trees from their definitions by the kinds of their nodes. 
+pub mod node_registry;
+/// Fluent building of dynamic trees in code. 
+pub mod tree_builder;
+/// A macro expressing whole trees in a nested syntax. 
+#[macro_use]
+pub mod tree_macro;
+/// Reloading changed definitions into running trees. 
+pub mod hot_reload;
+/// Composites over tuples of heterogeneous nodes. 
+pub mod tuple_node;
+/// Shared subtree definitions, instantiable anywhere in a tree. 
+pub mod shared_subtree;
+/// A pipeline node feeding the terminal of one stage into the next. 
+pub mod pipeline_node;
+/// A node folding the terminals of repeated runs of a child. 
+pub mod fold_node;
+/// Re-exports of the most commonly used items of this crate. 
+pub mod prelude;
\ No newline at end of file
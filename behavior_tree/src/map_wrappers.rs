@@ -1,8 +1,14 @@
 use behavior_tree_node::{BehaviorTreeNode, NodeResult};
 use std::marker::PhantomData;
+#[cfg(feature = "serde")]
+use persist_state::PersistState;
 
 /// Wrapper for a node which converts between the provided input type and 
 /// the input type expected by the node. 
+///
+/// Wrapping the children of a composite node brings them to a common input 
+/// type, so that nodes written for different inputs can be variants of the 
+/// same enum node. 
 #[derive(PartialEq, Debug)]
 pub struct InputMappedNode<N, M, I> where 
     N: BehaviorTreeNode,
@@ -63,6 +69,97 @@ impl<N, M, I> BehaviorTreeNode for InputMappedNode<N, M, I> where
             NodeResult::Terminal(t) => NodeResult::Terminal(t)
         }
     }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<N, M, I> PersistState for InputMappedNode<N, M, I> where 
+    N: PersistState,
+    M: Fn(&I) -> N::Input
+{
+    type State = N::State;
+
+    const STATE_VERSION: u32 = N::STATE_VERSION;
+
+    fn save_state(&self) -> N::State {
+        self.node.save_state()
+    }
+
+    fn restore_state(&mut self, state: N::State) {
+        self.node.restore_state(state)
+    }
+}
+
+/// Wrapper for a node which projects a reference to the input expected by 
+/// the node out of the provided input, such as a field of a larger context, 
+/// without copying it. 
+#[derive(PartialEq, Debug)]
+pub struct InputProjectedNode<N, M, I> where 
+    N: BehaviorTreeNode,
+    M: Fn(&I) -> &N::Input
+{
+    node: N,
+    projection: M,
+    _junk: PhantomData<I>
+}
+
+impl<N, M, I> Clone for InputProjectedNode<N, M, I> where 
+    N: BehaviorTreeNode + Clone,
+    M: Fn(&I) -> &N::Input + Clone
+{
+    fn clone(&self) -> Self {
+        InputProjectedNode {
+            node: self.node.clone(),
+            projection: self.projection.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<N, M, I> Copy for InputProjectedNode<N, M, I> where 
+    N: BehaviorTreeNode + Copy,
+    M: Fn(&I) -> &N::Input + Copy
+{}
+
+impl<N, M, I> InputProjectedNode<N, M, I> where 
+    N: BehaviorTreeNode,
+    M: Fn(&I) -> &N::Input
+{
+    /// Create a new input projected node. 
+    pub fn new(projection: M, node: N) -> InputProjectedNode<N, M, I> {
+        InputProjectedNode {
+            node,
+            projection,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<N, M, I> BehaviorTreeNode for InputProjectedNode<N, M, I> where 
+    N: BehaviorTreeNode,
+    M: Fn(&I) -> &N::Input
+{
+    type Input = I;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        match self.node.step((self.projection)(input)) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                InputProjectedNode::new(self.projection, m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
 }
 
 /// Wrapper for a node which converts between the statepoints emitted by the 
@@ -142,6 +239,94 @@ impl<N, M, O, S, T> BehaviorTreeNode for OutputMappedNode<N, M, O, S, T> where
             )
         }
     }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Wrapper for a node which converts between the statepoints emitted by the 
+/// node and the ones exposed by the wrapper, with mappers which may keep 
+/// state between steps, such as smoothing filters over the nonterminals. 
+#[derive(PartialEq, Debug)]
+pub struct StatefulOutputMappedNode<N, M, O, S, T> where 
+    N: BehaviorTreeNode,
+    M: FnMut(N::Nonterminal) -> S,
+    O: FnOnce(N::Terminal) -> T
+{
+    node: N,
+    nonterminal_mapper: M,
+    terminal_mapper: O,
+    _junk: PhantomData<(S, T)>
+}
+
+impl<N, M, O, S, T> Clone for StatefulOutputMappedNode<N, M, O, S, T> where 
+    N: BehaviorTreeNode + Clone,
+    M: FnMut(N::Nonterminal) -> S + Clone,
+    O: FnOnce(N::Terminal) -> T + Clone
+{
+    fn clone(&self) -> Self {
+        StatefulOutputMappedNode {
+            node: self.node.clone(),
+            nonterminal_mapper: self.nonterminal_mapper.clone(),
+            terminal_mapper: self.terminal_mapper.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<N, M, O, S, T> Copy for StatefulOutputMappedNode<N, M, O, S, T> where 
+    N: BehaviorTreeNode + Copy,
+    M: FnMut(N::Nonterminal) -> S + Copy,
+    O: FnOnce(N::Terminal) -> T + Copy
+{}
+
+impl<N, M, O, S, T> StatefulOutputMappedNode<N, M, O, S, T> where 
+    N: BehaviorTreeNode,
+    M: FnMut(N::Nonterminal) -> S,
+    O: FnOnce(N::Terminal) -> T
+{
+    /// Create a new stateful output mapped node. 
+    pub fn new(nonterm: M, term: O, node: N) -> Self {
+        StatefulOutputMappedNode {
+            node,
+            nonterminal_mapper: nonterm,
+            terminal_mapper: term,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<N, M, O, S, T> BehaviorTreeNode for StatefulOutputMappedNode<N, M, O, S, T> where 
+    N: BehaviorTreeNode,
+    M: FnMut(N::Nonterminal) -> S,
+    O: FnOnce(N::Terminal) -> T
+{
+    type Input = N::Input;
+    type Nonterminal = S;
+    type Terminal = T;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<S, T, Self> {
+        let mut mut_self = self;
+        match mut_self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                (mut_self.nonterminal_mapper)(n),
+                StatefulOutputMappedNode::new(
+                    mut_self.nonterminal_mapper,
+                    mut_self.terminal_mapper, 
+                    m
+                )
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(
+                (mut_self.terminal_mapper)(t)
+            )
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -207,6 +392,85 @@ impl<N, M> BehaviorTreeNode for LazyConstructedNode<N, M> where
             NodeResult::Terminal(t) => NodeResult::Terminal(t)
         }
     }
+
+    fn abort(self) {
+        if let Option::Some(LazyConstructedInner::Node(n)) = self.inside {
+            n.abort()
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum DeferredInner<N, M> where 
+    M: FnOnce() -> N
+{
+    Node(N),
+    Pending(M)
+}
+
+/// Wrapper for a node, which defers the construction of the node until the 
+/// wrapper is first stepped, so that trees can be built eagerly without 
+/// paying for branches which are never visited. 
+///
+/// Unlike LazyConstructedNode, the factory does not depend on the input. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct DeferredNode<N, M> where 
+    N: BehaviorTreeNode,
+    M: FnOnce() -> N
+{
+    inside: DeferredInner<N, M>
+}
+
+impl<N, M> DeferredNode<N, M> where 
+    N: BehaviorTreeNode,
+    M: FnOnce() -> N
+{
+    /// Create a new deferred node from a factory for the node. 
+    pub fn new(factory: M) -> DeferredNode<N, M> {
+        DeferredNode {
+            inside: DeferredInner::Pending(factory)
+        }
+    }
+
+    /// Check whether the node has been constructed yet. 
+    pub fn is_constructed(&self) -> bool {
+        match self.inside {
+            DeferredInner::Node(_) => true,
+            DeferredInner::Pending(_) => false
+        }
+    }
+}
+
+impl<N, M> BehaviorTreeNode for DeferredNode<N, M> where 
+    N: BehaviorTreeNode,
+    M: FnOnce() -> N
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, N::Terminal, Self> {
+        let node = match self.inside {
+            DeferredInner::Node(n) => n,
+            DeferredInner::Pending(f) => f()
+        };
+        match node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v,
+                DeferredNode {
+                    inside: DeferredInner::Node(n)
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        if let DeferredInner::Node(n) = self.inside {
+            n.abort()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -240,6 +504,33 @@ mod tests {
         };
     }
 
+    #[test]
+    fn input_projection_test() {
+        use map_wrappers::InputProjectedNode;
+        fn first(input: &(i64, char)) -> &i64 {
+            &input.0
+        }
+        let base_node = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let wrapped_node = InputProjectedNode::new(first, base_node);
+        let wrapped_node_1 = match wrapped_node.step(&(5, 'a')) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 5);
+                m
+            },
+            _ => unreachable!("Expected nonterminal state")
+        };
+        match wrapped_node_1.step(&(-4, 'b')) {
+            NodeResult::Terminal(x) => assert_eq!(x, -4),
+            _ => unreachable!("Expected terminal state"),
+        };
+    }
+
     #[test]
     fn output_map_test() {
         use map_wrappers::OutputMappedNode;
@@ -268,6 +559,45 @@ mod tests {
         };
     }
 
+    #[test]
+    fn stateful_output_map_test() {
+        use map_wrappers::StatefulOutputMappedNode;
+        let base_node = PredicateWait::new(|input: &i64| {
+            if *input > 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        });
+        let mut total = 0;
+        let wrapped_node = StatefulOutputMappedNode::new(
+            move |val: i64| {
+                total += val;
+                total
+            },
+            |val: i64| val-1,
+            base_node
+        );
+        let wrapped_node_1 = match wrapped_node.step(&5) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 5);
+                m
+            },
+            _ => unreachable!("Expected nonterminal state")
+        };
+        let wrapped_node_2 = match wrapped_node_1.step(&2) {
+            NodeResult::Nonterminal(v, m) => {
+                assert_eq!(v, 7);
+                m
+            },
+            _ => unreachable!("Expected nonterminal state")
+        };
+        match wrapped_node_2.step(&-4) {
+            NodeResult::Terminal(x) => assert_eq!(x, -5),
+            _ => unreachable!("Expected terminal state"),
+        };
+    }
+
     #[derive(Copy, Clone, Default)]
     struct IndefinitePlayback;
 
@@ -317,4 +647,30 @@ mod tests {
             _ => unreachable!("Expected nonterminal state")
         };
     }
+
+    #[test]
+    fn deferred_test() {
+        use map_wrappers::DeferredNode;
+        use std::cell::Cell;
+        let built = Cell::new(0);
+        let new_node = DeferredNode::new(|| {
+            built.set(built.get() + 1);
+            MachineWrapper::new(InternalStateMachine::new(IndefinitePlayback, 3))
+        });
+        assert_eq!(built.get(), 0);
+        assert!(!new_node.is_constructed());
+        let new_node_1 = match new_node.step(&2) {
+            NodeResult::Nonterminal(x, y) => {
+                assert_eq!(x, 3);
+                y
+            },
+            _ => unreachable!("Expected nonterminal state")
+        };
+        assert!(new_node_1.is_constructed());
+        match new_node_1.step(&4) {
+            NodeResult::Nonterminal(x, _) => assert_eq!(x, 3),
+            _ => unreachable!("Expected nonterminal state")
+        };
+        assert_eq!(built.get(), 1);
+    }
 }
\ No newline at end of file
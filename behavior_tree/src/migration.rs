@@ -0,0 +1,211 @@
+use binary_codec::{BinaryCodec, CodecError};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fmt;
+
+type Upgrade<B> = Fn(&BinaryCodec, &[u8]) -> Result<B, CodecError>;
+
+/// Migrations of states of the given type saved by earlier versions of 
+/// their layout, consulted when a versioned snapshot of another version than 
+/// the current one is restored. 
+///
+/// Each migration upgrades states of one earlier version straight to the 
+/// current layout, from the type the state had at that version. States of 
+/// versions with no migration fail to be restored, unless the migrations 
+/// are set to reset them, in which case the tree is reconstructed in its 
+/// initial state instead. 
+pub struct Migrations<B> {
+    upgrades: HashMap<u32, Box<Upgrade<B>>>,
+    reset: bool
+}
+
+impl<B> fmt::Debug for Migrations<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut versions = self.upgrades.keys().collect::<Vec<_>>();
+        versions.sort();
+        f.debug_struct("Migrations")
+            .field("versions", &versions)
+            .field("reset", &self.reset)
+            .finish()
+    }
+}
+
+impl<B> Default for Migrations<B> {
+    fn default() -> Migrations<B> {
+        Migrations::new()
+    }
+}
+
+impl<B> Migrations<B> {
+    /// Create a new set of migrations, with none. 
+    pub fn new() -> Migrations<B> {
+        Migrations {
+            upgrades: HashMap::new(),
+            reset: false
+        }
+    }
+
+    /// Upgrade states of the given version, which had the type taken by the 
+    /// upgrade, to the current layout. 
+    pub fn upgrade<O, F>(self, version: u32, upgrade: F) -> Migrations<B> where 
+        O: DeserializeOwned,
+        F: Fn(O) -> B + 'static
+    {
+        let mut mut_self = self;
+        mut_self.upgrades.insert(version, Box::new(move |codec, bytes| {
+            codec.decode_payload::<O>(bytes).map(&upgrade)
+        }));
+        mut_self
+    }
+
+    /// Reset states of versions with no migration to the initial state, 
+    /// rather than failing to restore them. 
+    pub fn or_reset(self) -> Migrations<B> {
+        Migrations {
+            reset: true,
+            .. self
+        }
+    }
+
+    /// Check whether states of the given version can be upgraded. 
+    pub fn supports(&self, version: u32) -> bool {
+        self.upgrades.contains_key(&version)
+    }
+
+    /// Upgrade the encoded state of the given version, returning nothing if 
+    /// it is to be reset. 
+    pub(crate) fn migrate(&self, codec: &BinaryCodec, version: u32,
+        bytes: &[u8]) -> Result<Option<B>, CodecError>
+    {
+        match self.upgrades.get(&version) {
+            Option::Some(u) => u(codec, bytes).map(Option::Some),
+            Option::None if self.reset => Result::Ok(Option::None),
+            Option::None => Result::Err(CodecError::Migration(version))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use binary_codec::{BinaryCodec, CodecError};
+    use migration::Migrations;
+    use node_runner::NodeRunner;
+    use persist_state::PersistState;
+    use snapshot::Snapshot;
+    use stackbt_automata_impl::automaton::Automaton;
+    use tuple_node::TupleSequence;
+
+    struct Countdown<S> {
+        left: u32,
+        _junk: ::std::marker::PhantomData<S>
+    }
+
+    struct V0;
+    struct V1;
+
+    fn countdown<S>(left: u32) -> Countdown<S> {
+        Countdown {
+            left: left,
+            _junk: ::std::marker::PhantomData
+        }
+    }
+
+    impl<S> BehaviorTreeNode for Countdown<S> {
+        type Input = ();
+        type Nonterminal = u32;
+        type Terminal = Result<(), ()>;
+
+        fn step(self, _input: &()) -> NodeResult<u32, Result<(), ()>, Self> {
+            match self.left {
+                0 => NodeResult::Terminal(Result::Ok(())),
+                n => NodeResult::Nonterminal(n, countdown(n - 1))
+            }
+        }
+    }
+
+    impl PersistState for Countdown<V0> {
+        type State = u32;
+
+        fn save_state(&self) -> u32 {
+            self.left
+        }
+
+        fn restore_state(&mut self, state: u32) {
+            self.left = state
+        }
+    }
+
+    impl PersistState for Countdown<V1> {
+        type State = (u64, bool);
+
+        const STATE_VERSION: u32 = 1;
+
+        fn save_state(&self) -> (u64, bool) {
+            (self.left as u64, self.left == 0)
+        }
+
+        fn restore_state(&mut self, state: (u64, bool)) {
+            self.left = state.0 as u32
+        }
+    }
+
+    #[test]
+    fn migration_test() {
+        let codec = BinaryCodec::new();
+        let old_definition = || countdown::<V0>(5);
+        let mut old = NodeRunner::new(old_definition);
+        old.transition(&());
+        let bytes = codec.encode_versioned(&old).unwrap();
+        let definition = || countdown::<V1>(5);
+        let migrations = Migrations::new()
+            .upgrade(0, |old: u32| (old as u64, old == 0));
+        let mut restored = codec.decode_versioned::<NodeRunner<_, _>>(
+            &definition, &bytes, &migrations).unwrap();
+        assert_eq!(restored.transition(&()).clone(),
+            ::behavior_tree_node::Statepoint::Nonterminal(4));
+        let current = codec.encode_versioned(&restored).unwrap();
+        assert!(codec.decode_versioned::<NodeRunner<_, _>>(&definition,
+            &current, &Migrations::new()).is_ok());
+        match codec.decode_versioned::<NodeRunner<_, _>>(&definition, &bytes,
+            &Migrations::new())
+        {
+            Result::Err(CodecError::Migration(0)) => (),
+            _ => unreachable!("Expected missing migration")
+        };
+        let reset = codec.decode_versioned::<NodeRunner<_, _>>(
+            &definition, &bytes, &Migrations::new().or_reset()).unwrap();
+        assert_eq!(reset.snapshot(), (5, false));
+    }
+
+    #[test]
+    fn nested_migration_test() {
+        let codec = BinaryCodec::new();
+        let old_definition = || TupleSequence::new(
+            (countdown::<V0>(1), countdown::<V0>(5)));
+        let mut old = NodeRunner::new(old_definition);
+        old.transition(&());
+        old.transition(&());
+        let bytes = codec.encode_versioned(&old).unwrap();
+        // Only the second child changed the layout of its state
+        let definition = || TupleSequence::new(
+            (countdown::<V0>(1), countdown::<V1>(5)));
+        match codec.decode_versioned::<NodeRunner<_, _>>(&definition, &bytes,
+            &Migrations::new())
+        {
+            Result::Err(CodecError::Migration(0)) => (),
+            _ => unreachable!("Expected missing migration")
+        };
+        let migrations = Migrations::new()
+            .upgrade(0, |old: (usize, (Option<u32>, Option<u32>))| {
+                (old.0, ((old.1).0, (old.1).1.map(|l| (l as u64, l == 0))))
+            });
+        let mut restored = codec.decode_versioned::<NodeRunner<_, _>>(
+            &definition, &bytes, &migrations).unwrap();
+        assert_eq!(restored.snapshot(),
+            (1, (Option::None, Option::Some((5, false)))));
+        restored.transition(&());
+        assert_eq!(restored.snapshot(),
+            (1, (Option::None, Option::Some((4, false)))));
+    }
+}
\ No newline at end of file
@@ -8,6 +8,7 @@ use num_traits::{FromPrimitive, ToPrimitive};
 /// Runs all nodes in sequence, one at a time, regardless of how they resolve 
 /// in the end. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SerialRunner<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
     _who_cares: PhantomData<(E, I, N, T)>
 }
@@ -54,6 +55,7 @@ impl<E, I, N, T> SerialDecider for SerialRunner<E, I, N, T> where
 /// Runs nodes in sequence until one resolves into an Option::Some, which 
 /// depending on context may be either success or failure. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SerialSelector<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
     _who_cares: PhantomData<(E, I, N, T)>
 }
@@ -108,6 +110,7 @@ impl<E, I, N, T> SerialDecider for SerialSelector<E, I, N, T> where
 /// Runs all nodes in sequence, one at a time, and from the end, repeat 
 /// back to the beginning. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SerialRepeater<E, I, N, T> where E: Copy + FromPrimitive + ToPrimitive {
     _who_cares: PhantomData<(E, I, N, T)>
 }
@@ -159,6 +162,7 @@ impl<E, I, N, T> SerialDecider for SerialRepeater<E, I, N, T> where
 /// enter a trap state indicated by returning a statepoint terminal 
 /// as the nonterminal. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParallelRunner<I, N, R, T> where 
     I: 'static,
     N: 'static,
@@ -205,7 +209,8 @@ impl<I, N, R, T> ParallelDecider for ParallelRunner<I, N, R, T> where
 
     #[inline]
     fn each_step(&self, _i: &I, states: Box<[Statepoint<Statepoint<N, R>, T>]>) -> 
-        Statepoint<Box<[Statepoint<Self::Nonterm, T>]>, Self::Exit> 
+        Statepoint<Box<[Statepoint<Statepoint<N, R>, T>]>, 
+        Box<[Statepoint<R, T>]>> 
     {
         if states.iter().any(|val| match val {
             Statepoint::Nonterminal(Statepoint::Nonterminal(_)) => true,
@@ -230,6 +235,7 @@ impl<I, N, R, T> ParallelDecider for ParallelRunner<I, N, R, T> where
 /// Runs nodes until one terminates, resolving to a tuple of the terminating
 /// index and its terminal state when it does. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParallelRacer<I, N, T>  {
     _who_cares: PhantomData<(I, N, T)>
 }
@@ -377,10 +383,14 @@ mod tests {
         fn new(thing: SomethingEnum) -> MultiMachine {
             match thing {
                 SomethingEnum::First => MultiMachine::First(
-                    MachineWrapper::default()
+                    MachineWrapper::new(InternalStateMachine::new(
+                        IndefiniteIncrement, 0
+                    ))
                 ),
                 SomethingEnum::Second => MultiMachine::Second(
-                    MachineWrapper::default()
+                    MachineWrapper::new(InternalStateMachine::new(
+                        IndefiniteIncrement, 0
+                    ))
                 )
             }
         }
@@ -588,7 +598,7 @@ mod tests {
     fn serial_repeater_test() {
         use serial_node::{SerialBranchNode, NontermReturn};
         use node_compositions::SerialRepeater;
-        let test_node = SerialBranchNode::<MultiMachine, SerialRepeater<_, _, _, _>>
+        let test_node = SerialBranchNode::<WrappedMachine, SerialRepeater<_, _, _, _>>
             ::default();
         let test_node_1 = match test_node.step(&3) {
             NodeResult::Nonterminal(ret, n) => {
@@ -621,7 +631,7 @@ mod tests {
                 match ret {
                     NontermReturn::Terminal(e, v) => {
                         assert_eq!(e, IndexEnum::First);
-                        assert_eq!(v, 2);
+                        assert_eq!(v, Option::Some(2));
                     },
                     _ => unreachable!("Expected subordinate nonterminal transition")
                 };
@@ -647,7 +657,7 @@ mod tests {
                 match ret {
                     NontermReturn::Terminal(e, v) => {
                         assert_eq!(e, IndexEnum::Second);
-                        assert_eq!(v, 1);
+                        assert_eq!(v, Option::None);
                     },
                     _ => unreachable!("Expected subordinate nonterminal transition")
                 };
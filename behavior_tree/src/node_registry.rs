@@ -0,0 +1,811 @@
+use behavior_tree_node::{BehaviorTreeNode, BtStatus, Failure};
+use bt_dsl;
+use btcpp_xml::TreeDocument;
+use dynamic_node::{BoxedNode, DynSelector, DynSequence};
+use expression::Value;
+use map_wrappers::OutputMappedNode;
+use serial_node::NontermReturn;
+use shared_subtree::SubtreeTemplate;
+use status_wrappers::Inverter;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::rc::Rc;
+use tree_def::{LoadError, NodeDef};
+
+/// Node of a tree instantiated from a definition, which all the nodes of 
+/// such trees are boxed up as, so that they can be children of each other. 
+pub type DynTree<I> = BoxedNode<I, (), BtStatus>;
+
+/// Type of a parameter of a kind of node. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ParamType {
+    /// An integer. 
+    Int,
+    /// A number, which may be written as an integer. 
+    Float,
+    /// A boolean. 
+    Bool,
+    /// A string. 
+    Str
+}
+
+impl ParamType {
+    /// Check whether the given value is of the type. 
+    pub fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (ParamType::Int, Value::Int(_)) => true,
+            (ParamType::Float, Value::Float(_)) => true,
+            (ParamType::Float, Value::Int(_)) => true,
+            (ParamType::Bool, Value::Bool(_)) => true,
+            (ParamType::Str, Value::Str(_)) => true,
+            _ => false
+        }
+    }
+}
+
+/// Descriptor of a parameter of a kind of node. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParamSpec {
+    /// The name of the parameter. 
+    pub name: String,
+    /// The type of the parameter. 
+    pub ty: ParamType,
+    /// The value the parameter takes when it is left out, or nothing if the 
+    /// parameter is required. 
+    pub default: Option<Value>
+}
+
+impl ParamSpec {
+    /// Create a new descriptor of a required parameter. 
+    pub fn new<S>(name: S, ty: ParamType) -> ParamSpec where 
+        S: Into<String>
+    {
+        ParamSpec {
+            name: name.into(),
+            ty: ty,
+            default: Option::None
+        }
+    }
+
+    /// Make the parameter optional, taking the given value when left out. 
+    pub fn or<V>(self, default: V) -> ParamSpec where 
+        V: Into<Value>
+    {
+        ParamSpec {
+            default: Option::Some(default.into()),
+            .. self
+        }
+    }
+}
+
+/// Number of children taken by a kind of node. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Arity {
+    /// No children, as for leaves. 
+    Leaf,
+    /// Exactly one child, as for decorators. 
+    One,
+    /// Any nonzero number of children, as for composites. 
+    Many
+}
+
+impl Arity {
+    /// Check whether the given number of children is accepted. 
+    pub fn accepts(&self, children: usize) -> bool {
+        match *self {
+            Arity::Leaf => children == 0,
+            Arity::One => children == 1,
+            Arity::Many => children > 0
+        }
+    }
+}
+
+/// Descriptor of a kind of node, listing its parameters and the number of 
+/// its children, against which definitions of the kind are checked before 
+/// they are instantiated, and which editors present to authors of trees. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeSpec {
+    /// The name of the kind. 
+    pub kind: String,
+    /// The parameters of the kind, in order. 
+    pub params: Vec<ParamSpec>,
+    /// The number of children taken by the kind. 
+    pub arity: Arity
+}
+
+impl NodeSpec {
+    /// Create a new descriptor of a kind with no parameters. 
+    pub fn new<S>(kind: S, arity: Arity) -> NodeSpec where 
+        S: Into<String>
+    {
+        NodeSpec {
+            kind: kind.into(),
+            params: Vec::new(),
+            arity: arity
+        }
+    }
+
+    /// Add a parameter to the end of the parameters. 
+    pub fn param(self, param: ParamSpec) -> NodeSpec {
+        let mut mut_self = self;
+        mut_self.params.push(param);
+        mut_self
+    }
+
+    /// Check the given definition against the descriptor, filling in the 
+    /// defaults of the parameters it leaves out. Parameters which are not 
+    /// described are passed through. 
+    pub fn check<'a>(&self, def: &'a NodeDef) -> Result<Cow<'a, NodeDef>,
+        LoadError>
+    {
+        self.resolve(def, def.children.len())
+    }
+
+    fn resolve<'a>(&self, def: &'a NodeDef, children: usize) ->
+        Result<Cow<'a, NodeDef>, LoadError>
+    {
+        if !self.arity.accepts(children) {
+            return Result::Err(LoadError::Children(def.kind.clone()));
+        }
+        let mut checked = Cow::Borrowed(def);
+        for p in self.params.iter() {
+            match (def.params.get(&p.name), &p.default) {
+                (Option::Some(v), _) if p.ty.accepts(v) => (),
+                (Option::None, Option::Some(d)) => {
+                    checked.to_mut().params.insert(p.name.clone(), d.clone());
+                },
+                _ => return Result::Err(LoadError::Param(def.kind.clone(),
+                    p.name.clone()))
+            }
+        }
+        Result::Ok(checked)
+    }
+}
+
+type Factory<I> = Fn(&NodeDef, Vec<DynTree<I>>) -> Result<DynTree<I>,
+    LoadError>;
+
+/// Registry of the constructors of the kinds of nodes which trees can be 
+/// instantiated from definitions with. 
+///
+/// A registry with builtins knows the kinds Sequence and Selector, taking 
+/// any nonzero number of children, and Inverter, ForceSuccess and 
+/// ForceFailure, taking one child each. The nodes of the application are 
+/// registered alongside them. Kinds may be registered along with their 
+/// descriptors, so that definitions are checked against the descriptors 
+/// before they reach the constructors, and so that editors can list the 
+/// kinds and their parameters. 
+///
+/// Kinds may also be defined as templates, which are definitions of 
+/// subtrees taking the parameters of their descriptors. Wherever a 
+/// parameter of a node of the template has a string value of `$` followed 
+/// by the name of a parameter of the template, the value the template is 
+/// given for it is substituted, and the definitions of trees have their 
+/// templates expanded before they are instantiated. 
+pub struct NodeRegistry<I> {
+    factories: HashMap<String, Rc<Factory<I>>>,
+    specs: HashMap<String, NodeSpec>,
+    templates: HashMap<String, NodeDef>
+}
+
+impl<I> Clone for NodeRegistry<I> {
+    fn clone(&self) -> Self {
+        NodeRegistry {
+            factories: self.factories.clone(),
+            specs: self.specs.clone(),
+            templates: self.templates.clone()
+        }
+    }
+}
+
+impl<I> fmt::Debug for NodeRegistry<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut kinds = self.factories.keys().chain(self.templates.keys())
+            .collect::<Vec<_>>();
+        kinds.sort();
+        f.debug_struct("NodeRegistry")
+            .field("kinds", &kinds)
+            .finish()
+    }
+}
+
+fn discard<N>(_nonterm: N) {}
+
+fn status<T, F>(term: Result<T, F>) -> BtStatus {
+    BtStatus::from(term)
+}
+
+fn only_child<I>(def: &NodeDef, children: Vec<DynTree<I>>) ->
+    Result<DynTree<I>, LoadError>
+{
+    if children.len() == 1 {
+        Result::Ok(children.into_iter().next().unwrap())
+    } else {
+        Result::Err(LoadError::Children(def.kind.clone()))
+    }
+}
+
+fn as_result<I>(children: Vec<DynTree<I>>) ->
+    Vec<BoxedNode<I, (), Result<(), Failure>>> where 
+    I: 'static
+{
+    children.into_iter()
+        .map(|c| BoxedNode::new(OutputMappedNode::new(
+            discard::<()>,
+            BtStatus::into_result,
+            c
+        )))
+        .collect()
+}
+
+pub(crate) fn sequence_of<I>(children: Vec<DynTree<I>>) -> DynTree<I> where 
+    I: 'static
+{
+    BoxedNode::new(OutputMappedNode::new(
+        discard::<NontermReturn<usize, (), Result<(), Failure>>>,
+        status::<(), (usize, Failure)>,
+        DynSequence::new(as_result(children))
+            .expect("Sequence requires at least one node")
+    ))
+}
+
+pub(crate) fn selector_of<I>(children: Vec<DynTree<I>>) -> DynTree<I> where 
+    I: 'static
+{
+    BoxedNode::new(OutputMappedNode::new(
+        discard::<NontermReturn<usize, (), Result<(), Failure>>>,
+        status::<(usize, ()), Failure>,
+        DynSelector::new(as_result(children))
+            .expect("Selector requires at least one node")
+    ))
+}
+
+pub(crate) fn force_success(_status: BtStatus) -> BtStatus {
+    BtStatus::Success
+}
+
+pub(crate) fn force_failure(_status: BtStatus) -> BtStatus {
+    BtStatus::Failure
+}
+
+pub(crate) fn forced_to<I>(child: DynTree<I>, to: fn(BtStatus) -> BtStatus) ->
+    DynTree<I> where 
+    I: 'static
+{
+    BoxedNode::new(OutputMappedNode::new(discard::<()>, to, child))
+}
+
+fn sequence<I>(def: &NodeDef, children: Vec<DynTree<I>>) ->
+    Result<DynTree<I>, LoadError> where 
+    I: 'static
+{
+    if children.is_empty() {
+        return Result::Err(LoadError::Children(def.kind.clone()));
+    }
+    Result::Ok(sequence_of(children))
+}
+
+fn selector<I>(def: &NodeDef, children: Vec<DynTree<I>>) ->
+    Result<DynTree<I>, LoadError> where 
+    I: 'static
+{
+    if children.is_empty() {
+        return Result::Err(LoadError::Children(def.kind.clone()));
+    }
+    Result::Ok(selector_of(children))
+}
+
+fn inverter<I>(def: &NodeDef, children: Vec<DynTree<I>>) ->
+    Result<DynTree<I>, LoadError> where 
+    I: 'static
+{
+    only_child(def, children).map(|c| BoxedNode::new(Inverter::new(c)))
+}
+
+fn forced<I>(def: &NodeDef, children: Vec<DynTree<I>>, to: fn(BtStatus) ->
+    BtStatus) -> Result<DynTree<I>, LoadError> where 
+    I: 'static
+{
+    only_child(def, children).map(|c| forced_to(c, to))
+}
+
+fn substitute(body: &NodeDef, args: &NodeDef) -> Result<NodeDef, LoadError> {
+    let mut params = BTreeMap::new();
+    for (name, value) in body.params.iter() {
+        let value = match *value {
+            Value::Str(ref s) if s.starts_with('$') => {
+                match args.params.get(&s[1..]) {
+                    Option::Some(v) => v.clone(),
+                    Option::None => return Result::Err(LoadError::Param(
+                        args.kind.clone(), s[1..].to_owned()))
+                }
+            },
+            ref v => v.clone()
+        };
+        params.insert(name.clone(), value);
+    }
+    let children = body.children.iter()
+        .map(|c| substitute(c, args))
+        .collect::<Result<Vec<_>, _>>()?;
+    Result::Ok(NodeDef {
+        kind: body.kind.clone(),
+        params: params,
+        children: children
+    })
+}
+
+impl<I> Default for NodeRegistry<I> {
+    fn default() -> NodeRegistry<I> {
+        NodeRegistry::new()
+    }
+}
+
+impl<I> NodeRegistry<I> {
+    /// Create a new registry with no kinds of nodes. 
+    pub fn new() -> NodeRegistry<I> {
+        NodeRegistry {
+            factories: HashMap::new(),
+            specs: HashMap::new(),
+            templates: HashMap::new()
+        }
+    }
+
+    /// Register a kind of node by a constructor, which is given the 
+    /// definition of the node, along with its instantiated children. 
+    pub fn register<S, F>(self, kind: S, factory: F) -> NodeRegistry<I> where 
+        S: Into<String>,
+        F: Fn(&NodeDef, Vec<DynTree<I>>) -> Result<DynTree<I>, LoadError>
+            + 'static
+    {
+        let mut mut_self = self;
+        mut_self.factories.insert(kind.into(), Rc::new(factory));
+        mut_self
+    }
+
+    /// Register a kind of leaf node by a constructor, which is given the 
+    /// definition of the node. Definitions of the kind with children are 
+    /// rejected. 
+    pub fn leaf<S, F, N>(self, kind: S, factory: F) -> NodeRegistry<I> where 
+        S: Into<String>,
+        F: Fn(&NodeDef) -> Result<N, LoadError> + 'static,
+        N: BehaviorTreeNode<Input=I, Nonterminal=(), Terminal=BtStatus>
+            + 'static
+    {
+        self.register(kind, move |def, children| if children.is_empty() {
+            factory(def).map(BoxedNode::new)
+        } else {
+            Result::Err(LoadError::Children(def.kind.clone()))
+        })
+    }
+
+    /// Register a kind of node by its descriptor and a constructor, which 
+    /// is given the checked definition of the node, with the defaults of 
+    /// its parameters filled in, along with its instantiated children. 
+    pub fn register_spec<F>(self, spec: NodeSpec, factory: F) ->
+        NodeRegistry<I> where 
+        F: Fn(&NodeDef, Vec<DynTree<I>>) -> Result<DynTree<I>, LoadError>
+            + 'static
+    {
+        let kind = spec.kind.clone();
+        let mut mut_self = self.register(kind.clone(), factory);
+        mut_self.specs.insert(kind, spec);
+        mut_self
+    }
+
+    /// Register a kind of leaf node by its descriptor and a constructor, 
+    /// which is given the checked definition of the node. 
+    pub fn leaf_spec<F, N>(self, spec: NodeSpec, factory: F) ->
+        NodeRegistry<I> where 
+        F: Fn(&NodeDef) -> Result<N, LoadError> + 'static,
+        N: BehaviorTreeNode<Input=I, Nonterminal=(), Terminal=BtStatus>
+            + 'static
+    {
+        let leaf = NodeSpec {
+            arity: Arity::Leaf,
+            .. spec
+        };
+        self.register_spec(leaf, move |def, _| factory(def).map(BoxedNode::new))
+    }
+
+    /// Register a kind of leaf node instantiating the given template, for 
+    /// the configuration read from the definition of the node by the given 
+    /// function. Definitions of the kind with children are rejected. 
+    pub fn template<S, F, C, N>(self, kind: S, template: SubtreeTemplate<C, N>,
+        config: F) -> NodeRegistry<I> where 
+        S: Into<String>,
+        F: Fn(&NodeDef) -> Result<C, LoadError> + 'static,
+        C: 'static,
+        N: BehaviorTreeNode<Input=I, Nonterminal=(), Terminal=BtStatus>
+            + 'static
+    {
+        self.leaf(kind, move |def: &NodeDef| {
+            config(def).map(|c| template.construct(&c))
+        })
+    }
+
+    /// Define a kind of node as a template, by its descriptor and the 
+    /// definition of the subtree it expands to. The template takes no 
+    /// children. 
+    pub fn define_template(self, spec: NodeSpec, body: NodeDef) ->
+        NodeRegistry<I>
+    {
+        let mut mut_self = self;
+        mut_self.templates.insert(spec.kind.clone(), body);
+        mut_self.specs.insert(spec.kind.clone(), NodeSpec {
+            arity: Arity::Leaf,
+            .. spec
+        });
+        mut_self
+    }
+
+    /// Check whether a kind of node is registered under the given name. 
+    pub fn contains(&self, kind: &str) -> bool {
+        self.factories.contains_key(kind) || self.templates.contains_key(kind)
+    }
+
+    /// Get the descriptor of the kind of node registered under the given 
+    /// name, if it was registered with one. 
+    pub fn spec(&self, kind: &str) -> Option<&NodeSpec> {
+        self.specs.get(kind)
+    }
+
+    /// Get the descriptors of all kinds registered with one, by name. 
+    pub fn specs(&self) -> Vec<&NodeSpec> {
+        let mut specs = self.specs.values().collect::<Vec<_>>();
+        specs.sort_by(|a, b| a.kind.cmp(&b.kind));
+        specs
+    }
+
+    /// Instantiate a node of the kind registered under the given name, with 
+    /// the given parameters and instantiated children. 
+    pub fn instantiate<S>(&self, kind: S, params: BTreeMap<String, Value>,
+        children: Vec<DynTree<I>>) -> Result<DynTree<I>, LoadError> where 
+        S: Into<String>
+    {
+        let mut def = NodeDef::new(kind);
+        def.params = params;
+        if self.templates.contains_key(&def.kind) {
+            self.specs[&def.kind].resolve(&def, children.len())?;
+            return self.build(&def);
+        }
+        self.construct(&def, children)
+    }
+
+    pub(crate) fn construct(&self, def: &NodeDef,
+        children: Vec<DynTree<I>>) -> Result<DynTree<I>, LoadError>
+    {
+        let factory = match self.factories.get(&def.kind) {
+            Option::Some(f) => f.clone(),
+            Option::None => {
+                return Result::Err(LoadError::UnknownKind(def.kind.clone()));
+            }
+        };
+        let checked = match self.specs.get(&def.kind) {
+            Option::Some(s) => s.resolve(def, children.len())?,
+            Option::None => Cow::Borrowed(def)
+        };
+        factory(&checked, children)
+    }
+
+    /// Get the definition of a tree with the templates it uses expanded, 
+    /// and their parameters substituted. 
+    pub fn expand(&self, def: &NodeDef) -> Result<NodeDef, LoadError> {
+        self.expand_from(def, &mut Vec::new())
+    }
+
+    fn expand_from(&self, def: &NodeDef, stack: &mut Vec<String>) ->
+        Result<NodeDef, LoadError>
+    {
+        let body = match self.templates.get(&def.kind) {
+            Option::Some(b) => b,
+            Option::None => {
+                let children = def.children.iter()
+                    .map(|c| self.expand_from(c, stack))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Result::Ok(NodeDef {
+                    kind: def.kind.clone(),
+                    params: def.params.clone(),
+                    children: children
+                });
+            }
+        };
+        if stack.contains(&def.kind) {
+            return Result::Err(LoadError::Subtree(def.kind.clone()));
+        }
+        let args = self.specs[&def.kind].resolve(def, def.children.len())?;
+        let instance = substitute(body, &args)?;
+        stack.push(def.kind.clone());
+        let expanded = self.expand_from(&instance, stack);
+        stack.pop();
+        expanded
+    }
+
+    /// Instantiate a tree from its definition, children first. 
+    pub fn build(&self, def: &NodeDef) -> Result<DynTree<I>, LoadError> {
+        self.build_wrapped(def, &mut |_, _, n| n)
+    }
+
+    /// Instantiate a tree from its definition, passing each node through 
+    /// the given wrapper as it is instantiated, along with its definition 
+    /// and its index in the preorder of the tree, such as for observing the 
+    /// steps of every node of the tree. 
+    pub fn build_wrapped<F>(&self, def: &NodeDef, wrap: &mut F) -> 
+        Result<DynTree<I>, LoadError> where 
+        F: FnMut(usize, &NodeDef, DynTree<I>) -> DynTree<I>
+    {
+        self.build_from(&self.expand(def)?, &mut 0, wrap)
+    }
+
+    fn build_from<F>(&self, def: &NodeDef, next: &mut usize, wrap: &mut F) ->
+        Result<DynTree<I>, LoadError> where 
+        F: FnMut(usize, &NodeDef, DynTree<I>) -> DynTree<I>
+    {
+        let index = *next;
+        *next += 1;
+        if !self.factories.contains_key(&def.kind) {
+            return Result::Err(LoadError::UnknownKind(def.kind.clone()));
+        }
+        let children = def.children.iter()
+            .map(|c| self.build_from(c, next, wrap))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.construct(def, children).map(|n| wrap(index, def, n))
+    }
+
+    /// Instantiate the tree to run from a document in the XML format of 
+    /// BehaviorTree.CPP. 
+    pub fn load_xml(&self, source: &str) -> Result<DynTree<I>, LoadError> {
+        self.build(&TreeDocument::from_xml(source)?.main_tree()?)
+    }
+
+    /// Instantiate a tree from its definition in the `.bt` format. 
+    pub fn load_bt(&self, source: &str) -> Result<DynTree<I>, LoadError> {
+        self.build(&bt_dsl::parse(source)?)
+    }
+
+    /// Instantiate a tree from its definition as JSON. 
+    #[cfg(feature = "json")]
+    pub fn load_json(&self, source: &str) -> Result<DynTree<I>, LoadError> {
+        self.build(&NodeDef::from_json(source)?)
+    }
+
+    /// Instantiate a tree from its definition as YAML. 
+    #[cfg(feature = "yaml")]
+    pub fn load_yaml(&self, source: &str) -> Result<DynTree<I>, LoadError> {
+        self.build(&NodeDef::from_yaml(source)?)
+    }
+
+    /// Instantiate a tree from its definition as RON. 
+    #[cfg(feature = "ron")]
+    pub fn load_ron(&self, source: &str) -> Result<DynTree<I>, LoadError> {
+        self.build(&NodeDef::from_ron(source)?)
+    }
+}
+
+impl<I> NodeRegistry<I> where 
+    I: 'static
+{
+    /// Create a new registry with the builtin kinds of nodes. 
+    pub fn with_builtins() -> NodeRegistry<I> {
+        NodeRegistry::new()
+            .register_spec(NodeSpec::new("Sequence", Arity::Many), sequence)
+            .register_spec(NodeSpec::new("Selector", Arity::Many), selector)
+            .register_spec(NodeSpec::new("Inverter", Arity::One), inverter)
+            .register_spec(NodeSpec::new("ForceSuccess", Arity::One),
+                |d: &NodeDef, c| forced(d, c, force_success))
+            .register_spec(NodeSpec::new("ForceFailure", Arity::One),
+                |d: &NodeDef, c| forced(d, c, force_failure))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+    use expression::Value;
+    use node_registry::{Arity, NodeRegistry, NodeSpec, ParamSpec, ParamType};
+    use shared_subtree::SubtreeTemplate;
+    use std::collections::BTreeMap;
+    use tree_def::{LoadError, NodeDef};
+
+    fn registry() -> NodeRegistry<i64> {
+        NodeRegistry::with_builtins()
+            .leaf("AtLeast", |d: &NodeDef| {
+                let bound = d.int("bound")?;
+                Result::Ok(PredicateWait::new(move |i: &i64| {
+                    Statepoint::Terminal(BtStatus::from(*i >= bound))
+                }))
+            })
+            .leaf("WaitNonzero", |_: &NodeDef| {
+                Result::Ok(PredicateWait::new(|i: &i64| if *i == 0 {
+                    Statepoint::Nonterminal(())
+                } else {
+                    Statepoint::Terminal(BtStatus::Success)
+                }))
+            })
+    }
+
+    #[test]
+    fn registry_build_test() {
+        let def = NodeDef::new("Selector")
+            .child(NodeDef::new("AtLeast").param("bound", 10))
+            .child(NodeDef::new("Sequence")
+                .child(NodeDef::new("WaitNonzero"))
+                .child(NodeDef::new("Inverter")
+                    .child(NodeDef::new("AtLeast").param("bound", 5))));
+        let registry = registry();
+        let tree = registry.build(&def).unwrap();
+        let tree_1 = match tree.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let tree_2 = match tree_1.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match tree_2.step(&3) {
+            NodeResult::Nonterminal(_, n) => match n.step(&3) {
+                NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+                _ => unreachable!("Expected terminal transition")
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match registry.build(&def).unwrap().step(&12) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn registry_error_test() {
+        let registry = registry();
+        match registry.build(&NodeDef::new("Missing")) {
+            Result::Err(e) => assert_eq!(e,
+                LoadError::UnknownKind("Missing".to_owned())),
+            _ => unreachable!("Expected unknown kind")
+        };
+        match registry.build(&NodeDef::new("AtLeast")) {
+            Result::Err(e) => assert_eq!(e, LoadError::Param(
+                "AtLeast".to_owned(), "bound".to_owned())),
+            _ => unreachable!("Expected missing parameter")
+        };
+        match registry.build(&NodeDef::new("Inverter")) {
+            Result::Err(e) => assert_eq!(e,
+                LoadError::Children("Inverter".to_owned())),
+            _ => unreachable!("Expected missing child")
+        };
+    }
+
+    #[test]
+    fn registry_spec_test() {
+        let spec = NodeSpec::new("Wait", Arity::Leaf)
+            .param(ParamSpec::new("ticks", ParamType::Int))
+            .param(ParamSpec::new("rate", ParamType::Float).or(0.5));
+        let registry = NodeRegistry::<i64>::with_builtins()
+            .leaf_spec(spec.clone(), |d: &NodeDef| {
+                let done = d.float("rate")? * d.int("ticks")? as f64 >= 1.0;
+                Result::Ok(PredicateWait::new(move |_: &i64| if done {
+                    Statepoint::Terminal(BtStatus::Success)
+                } else {
+                    Statepoint::Nonterminal(())
+                }))
+            });
+        assert_eq!(registry.spec("Wait"), Option::Some(&spec));
+        assert_eq!(registry.specs().iter().map(|s| s.kind.as_str())
+            .collect::<Vec<_>>(), vec!["ForceFailure", "ForceSuccess",
+                "Inverter", "Selector", "Sequence", "Wait"]);
+        match registry.build(&NodeDef::new("Wait").param("ticks", 2)) {
+            Result::Ok(t) => match t.step(&0) {
+                NodeResult::Terminal(s) => assert_eq!(s, BtStatus::Success),
+                _ => unreachable!("Expected terminal transition")
+            },
+            _ => unreachable!("Expected default rate")
+        };
+        match registry.build(&NodeDef::new("Wait").param("ticks", "2")) {
+            Result::Err(e) => assert_eq!(e, LoadError::Param(
+                "Wait".to_owned(), "ticks".to_owned())),
+            _ => unreachable!("Expected parameter of the wrong type")
+        };
+        let mut params = BTreeMap::new();
+        params.insert("ticks".to_owned(), Value::Int(1));
+        let wait = registry.instantiate("Wait", params, Vec::new()).unwrap();
+        match registry.instantiate("Inverter", BTreeMap::new(), vec![wait])
+            .unwrap().step(&0)
+        {
+            NodeResult::Nonterminal(_, _) => (),
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match registry.instantiate("Sequence", BTreeMap::new(), Vec::new()) {
+            Result::Err(e) => assert_eq!(e,
+                LoadError::Children("Sequence".to_owned())),
+            _ => unreachable!("Expected missing children")
+        };
+    }
+
+    #[test]
+    fn registry_template_test() {
+        let above = SubtreeTemplate::new(|bound: &i64| {
+            let bound = *bound;
+            PredicateWait::new(move |i: &i64| {
+                Statepoint::Terminal(BtStatus::from(*i > bound))
+            })
+        });
+        let band = NodeSpec::new("Band", Arity::Leaf)
+            .param(ParamSpec::new("low", ParamType::Int))
+            .param(ParamSpec::new("high", ParamType::Int).or(100));
+        let registry = registry()
+            .template("Above", above, |d: &NodeDef| d.int("bound"))
+            .define_template(band, NodeDef::new("Sequence")
+                .child(NodeDef::new("AtLeast").param("bound", "$low"))
+                .child(NodeDef::new("Inverter")
+                    .child(NodeDef::new("Above").param("bound", "$high"))))
+            .define_template(NodeSpec::new("Loop", Arity::Leaf),
+                NodeDef::new("Inverter").child(NodeDef::new("Loop")))
+            .define_template(NodeSpec::new("Unbound", Arity::Leaf),
+                NodeDef::new("AtLeast").param("bound", "$bound"));
+        assert!(registry.contains("Band"));
+        assert_eq!(registry.expand(&NodeDef::new("Band").param("low", 5)),
+            Result::Ok(NodeDef::new("Sequence")
+                .child(NodeDef::new("AtLeast").param("bound", 5))
+                .child(NodeDef::new("Inverter")
+                    .child(NodeDef::new("Above").param("bound", 100)))));
+        let def = NodeDef::new("Selector")
+            .child(NodeDef::new("Band").param("low", 0).param("high", 9))
+            .child(NodeDef::new("Band").param("low", 20).param("high", 29));
+        let mut tree = registry.build(&def).unwrap();
+        let status = loop {
+            tree = match tree.step(&25) {
+                NodeResult::Nonterminal(_, n) => n,
+                NodeResult::Terminal(t) => break t
+            };
+        };
+        assert_eq!(status, BtStatus::Success);
+        match registry.build(&NodeDef::new("Band")) {
+            Result::Err(e) => assert_eq!(e, LoadError::Param(
+                "Band".to_owned(), "low".to_owned())),
+            _ => unreachable!("Expected missing parameter")
+        };
+        match registry.build(&NodeDef::new("Unbound")) {
+            Result::Err(e) => assert_eq!(e, LoadError::Param(
+                "Unbound".to_owned(), "bound".to_owned())),
+            _ => unreachable!("Expected missing parameter")
+        };
+        match registry.build(&NodeDef::new("Loop")) {
+            Result::Err(e) => assert_eq!(e,
+                LoadError::Subtree("Loop".to_owned())),
+            _ => unreachable!("Expected recursive template")
+        };
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn registry_json_test() {
+        let tree = registry().load_json(r#"{
+            "kind": "ForceFailure",
+            "children": [{"kind": "AtLeast", "params": {"bound": 1}}]
+        }"#).unwrap();
+        match tree.step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Failure),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn registry_ron_test() {
+        let tree = registry().load_ron(r#"(
+            kind: "Inverter",
+            children: [(kind: "AtLeast", params: {"bound": 5})],
+        )"#).unwrap();
+        match tree.step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match registry().load_ron("(kind: \"Nope\")") {
+            Result::Err(e) => assert_eq!(e,
+                LoadError::UnknownKind("Nope".to_owned())),
+            _ => unreachable!("Expected unknown kind")
+        };
+    }
+}
\ No newline at end of file
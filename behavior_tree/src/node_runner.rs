@@ -25,6 +25,16 @@ impl<N, C> NodeRunner<N, C> where
             node: Option::Some(new_node)
         }
     }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn node(&self) -> &N {
+        self.node.as_ref().expect("Node runner was poisoned")
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn node_mut(&mut self) -> &mut N {
+        self.node.as_mut().expect("Node runner was poisoned")
+    }
 }
 
 impl<N, C> Automaton<'static> for NodeRunner<N, C> where 
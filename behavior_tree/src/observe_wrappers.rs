@@ -0,0 +1,134 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+/// Trait for observers of the steps of a node, as used for logging and 
+/// metrics. Each callback does nothing by default. 
+pub trait NodeObserver<I, N, T> {
+    /// Called with the input before the node is stepped. 
+    fn on_step(&mut self, _input: &I) {}
+    /// Called with the input and the nonterminal after the node is stepped 
+    /// to a nonterminal. 
+    fn on_nonterminal(&mut self, _input: &I, _nonterminal: &N) {}
+    /// Called with the input and the terminal after the node is stepped to 
+    /// a terminal. 
+    fn on_terminal(&mut self, _input: &I, _terminal: &T) {}
+    /// Called before the node is aborted. 
+    fn on_abort(&mut self) {}
+}
+
+/// Wrapper for a node, which reports each of its steps to an observer 
+/// without altering its behavior. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ObservedNode<N, O> where 
+    N: BehaviorTreeNode,
+    O: NodeObserver<N::Input, N::Nonterminal, N::Terminal>
+{
+    node: N,
+    observer: O
+}
+
+impl<N, O> ObservedNode<N, O> where 
+    N: BehaviorTreeNode,
+    O: NodeObserver<N::Input, N::Nonterminal, N::Terminal>
+{
+    /// Create a new observed node. 
+    pub fn new(observer: O, node: N) -> ObservedNode<N, O> {
+        ObservedNode {
+            node: node,
+            observer: observer
+        }
+    }
+
+    /// Get a reference to the observer. 
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+}
+
+impl<N, O> BehaviorTreeNode for ObservedNode<N, O> where 
+    N: BehaviorTreeNode,
+    O: NodeObserver<N::Input, N::Nonterminal, N::Terminal>
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        N::Terminal, Self>
+    {
+        let mut observer = self.observer;
+        observer.on_step(input);
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                observer.on_nonterminal(input, &n);
+                NodeResult::Nonterminal(n, ObservedNode::new(observer, m))
+            },
+            NodeResult::Terminal(t) => {
+                observer.on_terminal(input, &t);
+                NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    fn abort(self) {
+        let mut observer = self.observer;
+        observer.on_abort();
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use observe_wrappers::{NodeObserver, ObservedNode};
+    use std::cell::RefCell;
+
+    struct Log<'a> {
+        events: &'a RefCell<Vec<String>>
+    }
+
+    impl<'a> NodeObserver<i64, i64, i64> for Log<'a> {
+        fn on_step(&mut self, input: &i64) {
+            self.events.borrow_mut().push(format!("step {}", input));
+        }
+
+        fn on_nonterminal(&mut self, _input: &i64, nonterminal: &i64) {
+            self.events.borrow_mut().push(format!("nonterminal {}", nonterminal));
+        }
+
+        fn on_terminal(&mut self, _input: &i64, terminal: &i64) {
+            self.events.borrow_mut().push(format!("terminal {}", terminal));
+        }
+    }
+
+    fn wait_nonzero(input: &i64) -> Statepoint<i64, i64> {
+        if *input == 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[test]
+    fn observed_node_test() {
+        let events = RefCell::new(Vec::new());
+        let test_node = ObservedNode::new(
+            Log { events: &events },
+            PredicateWait::new(wait_nonzero)
+        );
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(t, 4),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(*events.borrow(), vec!["step 0", "nonterminal 0", "step 4",
+            "terminal 4"]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,539 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use std::marker::PhantomData;
+
+/// Join policy, which given the input and the terminals reached so far by 
+/// each of the children of a ParallelJoinNode, decides whether to keep 
+/// running the children or to consume the terminals and exit. 
+pub trait JoinPolicy {
+    /// Type of the input to distribute among the children. 
+    type Input;
+    /// Type of the terminals returned by each of the children. 
+    type Term;
+    /// Type of the terminal returned by the parallel node itself. 
+    type Exit;
+    /// Given the input and the boxed slice of terminals, where children 
+    /// still running have Option::None, return a statepoint of either that 
+    /// boxed slice or a terminal value. 
+    fn join(&self, input: &Self::Input, finished: Box<[Option<Self::Term>]>) ->
+        Statepoint<Box<[Option<Self::Term>]>, Self::Exit>;
+}
+
+/// A parallel node which steps all of its running children each step, and 
+/// which keeps hold of the terminals they reach until its JoinPolicy decides 
+/// to exit. 
+///
+/// Unlike ParallelBranchNode, children are not restarted when they 
+/// terminate; they simply stop being stepped. Each step, the nonterminal 
+/// of each child is returned, with Option::None standing in for children 
+/// which have terminated. When the parallel node exits, any children still 
+/// running are aborted. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParallelJoinNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    children: Box<[Option<C>]>,
+    finished: Box<[Option<P::Term>]>,
+    policy: P
+}
+
+impl<C, P> ParallelJoinNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    /// Create a new parallel join node over the given children. 
+    pub fn new<K>(policy: P, children: K) -> ParallelJoinNode<C, P> where 
+        K: IntoIterator<Item=C>
+    {
+        let children = children.into_iter()
+            .map(Option::Some)
+            .collect::<Vec<_>>();
+        let finished = children.iter()
+            .map(|_| Option::None)
+            .collect::<Vec<_>>();
+        ParallelJoinNode {
+            children: children.into_boxed_slice(),
+            finished: finished.into_boxed_slice(),
+            policy: policy
+        }
+    }
+}
+
+impl<C, P> BehaviorTreeNode for ParallelJoinNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    type Input = P::Input;
+    type Nonterminal = Box<[Option<C::Nonterminal>]>;
+    type Terminal = P::Exit;
+
+    #[inline]
+    fn step(self, input: &P::Input) -> NodeResult<Self::Nonterminal, P::Exit,
+        Self>
+    {
+        let mut children = self.children;
+        let mut finished = self.finished;
+        let nonterms = children.iter_mut()
+            .zip(finished.iter_mut())
+            .map(|(slot, done)| match slot.take() {
+                Option::Some(child) => match child.step(input) {
+                    NodeResult::Nonterminal(n, m) => {
+                        *slot = Option::Some(m);
+                        Option::Some(n)
+                    },
+                    NodeResult::Terminal(t) => {
+                        *done = Option::Some(t);
+                        Option::None
+                    }
+                },
+                Option::None => Option::None
+            })
+            .collect::<Vec<_>>();
+        match self.policy.join(input, finished) {
+            Statepoint::Nonterminal(f) => NodeResult::Nonterminal(
+                nonterms.into_boxed_slice(),
+                ParallelJoinNode {
+                    children: children,
+                    finished: f,
+                    policy: self.policy
+                }
+            ),
+            Statepoint::Terminal(x) => {
+                for child in children.into_vec().into_iter().flatten() {
+                    child.abort();
+                }
+                NodeResult::Terminal(x)
+            }
+        }
+    }
+
+    fn abort(self) {
+        for child in self.children.into_vec().into_iter().flatten() {
+            child.abort();
+        }
+    }
+}
+
+/// A parallel node which, like ParallelJoinNode, steps all of its running 
+/// children each step until its JoinPolicy decides to exit, but which 
+/// streams the progress of each child as it happens. 
+///
+/// Each step, the index of each child stepped is returned along with the 
+/// statepoint it reached, so that observers also see the terminal of a 
+/// child on the step it terminates. Children which terminated on an earlier 
+/// step are left out. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct StreamingJoinNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy,
+    P::Term: Clone
+{
+    children: Box<[Option<C>]>,
+    finished: Box<[Option<P::Term>]>,
+    policy: P
+}
+
+impl<C, P> StreamingJoinNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy,
+    P::Term: Clone
+{
+    /// Create a new streaming join node over the given children. 
+    pub fn new<K>(policy: P, children: K) -> StreamingJoinNode<C, P> where 
+        K: IntoIterator<Item=C>
+    {
+        let ParallelJoinNode {
+            children,
+            finished,
+            policy
+        } = ParallelJoinNode::new(policy, children);
+        StreamingJoinNode {
+            children: children,
+            finished: finished,
+            policy: policy
+        }
+    }
+}
+
+impl<C, P> BehaviorTreeNode for StreamingJoinNode<C, P> where 
+    C: BehaviorTreeNode<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy,
+    P::Term: Clone
+{
+    type Input = P::Input;
+    type Nonterminal = Box<[(usize, Statepoint<C::Nonterminal, P::Term>)]>;
+    type Terminal = P::Exit;
+
+    #[inline]
+    fn step(self, input: &P::Input) -> NodeResult<Self::Nonterminal, P::Exit,
+        Self>
+    {
+        let mut children = self.children;
+        let mut finished = self.finished;
+        let progress = children.iter_mut()
+            .zip(finished.iter_mut())
+            .enumerate()
+            .filter_map(|(i, (slot, done))| slot.take().map(|child| {
+                match child.step(input) {
+                    NodeResult::Nonterminal(n, m) => {
+                        *slot = Option::Some(m);
+                        (i, Statepoint::Nonterminal(n))
+                    },
+                    NodeResult::Terminal(t) => {
+                        *done = Option::Some(t.clone());
+                        (i, Statepoint::Terminal(t))
+                    }
+                }
+            }))
+            .collect::<Vec<_>>();
+        match self.policy.join(input, finished) {
+            Statepoint::Nonterminal(f) => NodeResult::Nonterminal(
+                progress.into_boxed_slice(),
+                StreamingJoinNode {
+                    children: children,
+                    finished: f,
+                    policy: self.policy
+                }
+            ),
+            Statepoint::Terminal(x) => {
+                for child in children.into_vec().into_iter().flatten() {
+                    child.abort();
+                }
+                NodeResult::Terminal(x)
+            }
+        }
+    }
+
+    fn abort(self) {
+        for child in self.children.into_vec().into_iter().flatten() {
+            child.abort();
+        }
+    }
+}
+
+/// Join policy over children which terminate with a Result, which succeeds 
+/// once enough of them have succeeded, and fails once too many of them have 
+/// failed, or once enough successes are no longer possible. 
+///
+/// On exit, the terminals reached so far are returned, wrapped in Result::Ok 
+/// on success and Result::Err on failure. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ThresholdPolicy<I, T, F> {
+    successes: usize,
+    failures: usize,
+    _who_cares: PhantomData<(I, T, F)>
+}
+
+impl<I, T, F> ThresholdPolicy<I, T, F> {
+    /// Create a new threshold policy, which requires the given number of 
+    /// successes, and allows the given number of failures. 
+    pub fn new(successes: usize, failures: usize) -> ThresholdPolicy<I, T, F> {
+        ThresholdPolicy {
+            successes: successes,
+            failures: failures,
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<I, T, F> JoinPolicy for ThresholdPolicy<I, T, F> {
+    type Input = I;
+    type Term = Result<T, F>;
+    type Exit = Result<Box<[Option<Result<T, F>>]>, Box<[Option<Result<T, F>>]>>;
+
+    fn join(&self, _i: &I, finished: Box<[Option<Result<T, F>>]>) ->
+        Statepoint<Box<[Option<Result<T, F>>]>, Self::Exit>
+    {
+        let succeeded = finished.iter()
+            .filter(|val| match val {
+                Option::Some(Result::Ok(_)) => true,
+                _ => false
+            })
+            .count();
+        let failed = finished.iter()
+            .filter(|val| match val {
+                Option::Some(Result::Err(_)) => true,
+                _ => false
+            })
+            .count();
+        if succeeded >= self.successes {
+            Statepoint::Terminal(Result::Ok(finished))
+        } else if failed > self.failures ||
+            finished.len() - failed < self.successes
+        {
+            Statepoint::Terminal(Result::Err(finished))
+        } else {
+            Statepoint::Nonterminal(finished)
+        }
+    }
+}
+
+/// Join policy which waits for all children to terminate, and then exits 
+/// with all of their terminals, in the order of the children. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct JoinAll<I, T> {
+    _who_cares: PhantomData<(I, T)>
+}
+
+impl<I, T> JoinAll<I, T> {
+    pub fn new() -> JoinAll<I, T> {
+        JoinAll {
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<I, T> Default for JoinAll<I, T> {
+    fn default() -> JoinAll<I, T> {
+        JoinAll::new()
+    }
+}
+
+impl<I, T> JoinPolicy for JoinAll<I, T> {
+    type Input = I;
+    type Term = T;
+    type Exit = Box<[T]>;
+
+    fn join(&self, _i: &I, finished: Box<[Option<T>]>) -> 
+        Statepoint<Box<[Option<T>]>, Box<[T]>>
+    {
+        if finished.iter().all(Option::is_some) {
+            Statepoint::Terminal(finished.into_vec()
+                .into_iter()
+                .map(Option::unwrap)
+                .collect::<Vec<_>>()
+                .into_boxed_slice())
+        } else {
+            Statepoint::Nonterminal(finished)
+        }
+    }
+}
+
+/// Parallel node which runs all of its children to completion, and then 
+/// exits with all of their terminals. 
+pub type JoinAllNode<C> = ParallelJoinNode<C, JoinAll<
+    <C as BehaviorTreeNode>::Input, <C as BehaviorTreeNode>::Terminal>>;
+
+/// Join policy which exits as soon as any child terminates, with the index 
+/// and terminal of that child. 
+/// 
+/// As all children are stepped within the same step, several of them may 
+/// terminate at once, in which case the child with the lowest index wins, 
+/// and the terminals of the others are dropped. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FirstTerminal<I, T> {
+    _who_cares: PhantomData<(I, T)>
+}
+
+impl<I, T> FirstTerminal<I, T> {
+    pub fn new() -> FirstTerminal<I, T> {
+        FirstTerminal {
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<I, T> Default for FirstTerminal<I, T> {
+    fn default() -> FirstTerminal<I, T> {
+        FirstTerminal::new()
+    }
+}
+
+impl<I, T> JoinPolicy for FirstTerminal<I, T> {
+    type Input = I;
+    type Term = T;
+    type Exit = (usize, T);
+
+    fn join(&self, _i: &I, finished: Box<[Option<T>]>) -> 
+        Statepoint<Box<[Option<T>]>, (usize, T)>
+    {
+        match finished.iter().position(Option::is_some) {
+            Option::Some(index) => {
+                let winner = finished.into_vec()
+                    .into_iter()
+                    .nth(index)
+                    .unwrap()
+                    .unwrap();
+                Statepoint::Terminal((index, winner))
+            },
+            Option::None => Statepoint::Nonterminal(finished)
+        }
+    }
+}
+
+/// Parallel node which races its children against each other, exiting with 
+/// the first terminal reached, and aborting the children still running. 
+pub type RaceNode<C> = ParallelJoinNode<C, FirstTerminal<
+    <C as BehaviorTreeNode>::Input, <C as BehaviorTreeNode>::Terminal>>;
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use std::rc::Rc;
+    use std::cell::Cell;
+
+    struct Countdown {
+        remaining: i64,
+        aborts: Rc<Cell<i64>>
+    }
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = bool;
+        type Nonterminal = i64;
+        type Terminal = Result<i64, i64>;
+
+        fn step(self, input: &bool) -> NodeResult<i64, Result<i64, i64>, Self> {
+            if self.remaining > 1 {
+                NodeResult::Nonterminal(self.remaining, Countdown {
+                    remaining: self.remaining - 1,
+                    aborts: self.aborts
+                })
+            } else if *input {
+                NodeResult::Terminal(Result::Ok(self.remaining))
+            } else {
+                NodeResult::Terminal(Result::Err(self.remaining))
+            }
+        }
+
+        fn abort(self) {
+            self.aborts.set(self.aborts.get() + 1);
+        }
+    }
+
+    fn countdowns(aborts: &Rc<Cell<i64>>) -> Vec<Countdown> {
+        (1..4).map(|i| Countdown {
+            remaining: i,
+            aborts: aborts.clone()
+        }).collect()
+    }
+
+    #[test]
+    fn threshold_success_test() {
+        use parallel_join::{ParallelJoinNode, ThresholdPolicy};
+        let aborts = Rc::new(Cell::new(0));
+        let test_node = ParallelJoinNode::new(
+            ThresholdPolicy::new(2, 0),
+            countdowns(&aborts)
+        );
+        let test_node_1 = match test_node.step(&true) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(&*v, &[Option::None, Option::Some(2),
+                    Option::Some(3)]);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&true) {
+            NodeResult::Terminal(Result::Ok(v)) => {
+                assert_eq!(&*v, &[Option::Some(Result::Ok(1)),
+                    Option::Some(Result::Ok(1)), Option::None]);
+            },
+            _ => unreachable!("Expected successful terminal transition")
+        };
+        assert_eq!(aborts.get(), 1);
+    }
+
+    #[test]
+    fn threshold_failure_test() {
+        use parallel_join::{ParallelJoinNode, ThresholdPolicy};
+        let aborts = Rc::new(Cell::new(0));
+        let test_node = ParallelJoinNode::new(
+            ThresholdPolicy::new(3, 1),
+            countdowns(&aborts)
+        );
+        // A single failure makes three successes impossible
+        match test_node.step(&false) {
+            NodeResult::Terminal(Result::Err(v)) => {
+                assert_eq!(v[0], Option::Some(Result::Err(1)));
+            },
+            _ => unreachable!("Expected failing terminal transition")
+        };
+        assert_eq!(aborts.get(), 2);
+    }
+
+    #[test]
+    fn join_all_test() {
+        use parallel_join::{ParallelJoinNode, JoinAll, JoinAllNode};
+        let aborts = Rc::new(Cell::new(0));
+        let test_node: JoinAllNode<_> = ParallelJoinNode::new(
+            JoinAll::new(),
+            countdowns(&aborts)
+        );
+        let mut test_node_1 = test_node;
+        for _ in 0..2 {
+            test_node_1 = match test_node_1.step(&false) {
+                NodeResult::Nonterminal(_, n) => n,
+                _ => unreachable!("Expected nonterminal transition")
+            };
+        }
+        match test_node_1.step(&true) {
+            NodeResult::Terminal(v) => {
+                assert_eq!(&*v, &[Result::Err(1), Result::Err(1),
+                    Result::Ok(1)]);
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(aborts.get(), 0);
+    }
+
+    #[test]
+    fn race_test() {
+        use parallel_join::{ParallelJoinNode, FirstTerminal, RaceNode};
+        let aborts = Rc::new(Cell::new(0));
+        let mut children = countdowns(&aborts);
+        children.reverse();
+        let test_node: RaceNode<_> = ParallelJoinNode::new(
+            FirstTerminal::new(),
+            children
+        );
+        match test_node.step(&true) {
+            NodeResult::Terminal(t) => assert_eq!(t, (2, Result::Ok(1))),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(aborts.get(), 2);
+    }
+
+    #[test]
+    fn race_tie_test() {
+        use parallel_join::{ParallelJoinNode, FirstTerminal, RaceNode};
+        let aborts = Rc::new(Cell::new(0));
+        let children = vec![2, 1, 1].into_iter().map(|i| Countdown {
+            remaining: i,
+            aborts: aborts.clone()
+        });
+        let test_node: RaceNode<_> = ParallelJoinNode::new(
+            FirstTerminal::new(),
+            children
+        );
+        // Two children terminate at once, and the earlier one wins
+        match test_node.step(&false) {
+            NodeResult::Terminal(t) => assert_eq!(t, (1, Result::Err(1))),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(aborts.get(), 1);
+    }
+
+    #[test]
+    fn streaming_test() {
+        use behavior_tree_node::Statepoint;
+        use parallel_join::{StreamingJoinNode, JoinAll};
+        let aborts = Rc::new(Cell::new(0));
+        let test_node = StreamingJoinNode::new(JoinAll::new(), countdowns(&aborts));
+        let test_node_1 = match test_node.step(&true) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(&*v, &[(0, Statepoint::Terminal(Result::Ok(1))),
+                    (1, Statepoint::Nonterminal(2)), (2, Statepoint::Nonterminal(3))]);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // The first child terminated, so it is no longer reported
+        match test_node_1.step(&false) {
+            NodeResult::Nonterminal(v, _) => {
+                assert_eq!(&*v, &[(1, Statepoint::Terminal(Result::Err(1))),
+                    (2, Statepoint::Nonterminal(2))]);
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -120,21 +120,27 @@ mod tests {
         }
     }
 
-    fn indefinite_increment_construct() -> InternalStateMachine<'static, 
-        IndefiniteIncrement> 
-    {
-        InternalStateMachine::new(IndefiniteIncrement, 0)
+    type IndefiniteWrapper = MachineWrapper<InternalStateMachine<'static, 
+        IndefiniteIncrement>, i64, i64>;
+
+    fn indefinite_increment_construct() -> IndefiniteWrapper {
+        MachineWrapper::new(InternalStateMachine::new(IndefiniteIncrement, 0))
     }
 
-    existential type IndefiniteConstructor: Fn() -> InternalStateMachine<'static, 
-        IndefiniteIncrement>;
+    type IndefiniteConstructor = fn() -> IndefiniteWrapper;
 
-    #[derive(Default)]
     struct MultiMachine {
-        first: NodeRunner<MachineWrapper<InternalStateMachine<'static, 
-            IndefiniteIncrement>, i64, i64>, IndefiniteConstructor>,
-        second: NodeRunner<MachineWrapper<InternalStateMachine<'static, 
-            IndefiniteIncrement>, i64, i64>, IndefiniteConstructor>,
+        first: NodeRunner<IndefiniteWrapper, IndefiniteConstructor>,
+        second: NodeRunner<IndefiniteWrapper, IndefiniteConstructor>,
+    }
+
+    impl Default for MultiMachine {
+        fn default() -> MultiMachine {
+            MultiMachine {
+                first: NodeRunner::new(indefinite_increment_construct),
+                second: NodeRunner::new(indefinite_increment_construct)
+            }
+        }
     }
 
     #[derive(Copy, Clone, Default)]
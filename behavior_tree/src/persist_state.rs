@@ -0,0 +1,60 @@
+use behavior_tree_node::BehaviorTreeNode;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Trait for nodes which hold closures, and therefore can not be serialized 
+/// themselves, but which can externalize the rest of their state. 
+///
+/// The tree is rebuilt from code with the same closures, and then the saved 
+/// state is restored into it. Nodes whose state is plain data derive 
+/// `Serialize` and `Deserialize` instead. 
+pub trait PersistState: BehaviorTreeNode {
+    /// Type of the serializable state of the node. 
+    type State: Serialize + DeserializeOwned;
+
+    /// Version of the layout of the state, to be increased whenever the 
+    /// layout changes, so that states saved by earlier versions of the node 
+    /// can be told apart and migrated. 
+    ///
+    /// Composites derive theirs from the versions of their children with 
+    /// combined_version, so that a change to the layout of the state of any 
+    /// node in the tree changes the version of the tree. 
+    const STATE_VERSION: u32 = 0;
+
+    /// Save the serializable state of the node. 
+    fn save_state(&self) -> Self::State;
+
+    /// Restore previously saved state into the node. 
+    fn restore_state(&mut self, state: Self::State);
+}
+
+/// Combine the versions of the states of the children of a composite into 
+/// the version of its own state. 
+///
+/// The combination is 0 while all of the versions are, and changes whenever 
+/// any one of them does. 
+pub const fn combined_version(versions: &[u32]) -> u32 {
+    let mut combined = 0u32;
+    let mut i = 0;
+    while i < versions.len() {
+        combined = combined.wrapping_mul(31).wrapping_add(versions[i]);
+        i += 1;
+    }
+    combined
+}
+
+impl<N> PersistState for Box<N> where 
+    N: PersistState
+{
+    type State = N::State;
+
+    const STATE_VERSION: u32 = N::STATE_VERSION;
+
+    fn save_state(&self) -> N::State {
+        (**self).save_state()
+    }
+
+    fn restore_state(&mut self, state: N::State) {
+        (**self).restore_state(state)
+    }
+}
\ No newline at end of file
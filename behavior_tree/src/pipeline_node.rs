@@ -0,0 +1,200 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+/// Nonterminal type of the pipeline node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PipelineNonterm<N, M> {
+    /// The upstream stage was stepped, and did not terminate. 
+    Upstream(N),
+    /// The downstream stage was stepped, and did not terminate. 
+    Downstream(M)
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum PipelineState<A, B, T> {
+    Upstream(A, B),
+    Downstream(B, T)
+}
+
+/// Two stage pipeline node, which runs an upstream stage until it 
+/// terminates, and then runs a downstream stage, with the input of the 
+/// downstream stage computed by an adapter from the input of the pipeline 
+/// and the terminal of the upstream stage. 
+///
+/// The downstream stage is first stepped on the same step the upstream 
+/// stage terminates. The terminal of the upstream stage is kept, so that the 
+/// adapter can combine it with fresh input on every step. Longer pipelines, 
+/// such as sense, plan and act, are built by nesting pipeline nodes as the 
+/// upstream stage of another pipeline node. 
+#[derive(PartialEq, Debug)]
+pub struct PipelineNode<A, B, M> where 
+    A: BehaviorTreeNode,
+    B: BehaviorTreeNode,
+    M: Fn(&A::Input, &A::Terminal) -> B::Input
+{
+    state: PipelineState<A, B, A::Terminal>,
+    adapter: M
+}
+
+impl<A, B, M> Clone for PipelineNode<A, B, M> where 
+    A: BehaviorTreeNode + Clone,
+    A::Terminal: Clone,
+    B: BehaviorTreeNode + Clone,
+    M: Fn(&A::Input, &A::Terminal) -> B::Input + Clone
+{
+    fn clone(&self) -> Self {
+        PipelineNode {
+            state: self.state.clone(),
+            adapter: self.adapter.clone()
+        }
+    }
+}
+
+impl<A, B, M> Copy for PipelineNode<A, B, M> where 
+    A: BehaviorTreeNode + Copy,
+    A::Terminal: Copy,
+    B: BehaviorTreeNode + Copy,
+    M: Fn(&A::Input, &A::Terminal) -> B::Input + Copy
+{}
+
+impl<A, B, M> PipelineNode<A, B, M> where 
+    A: BehaviorTreeNode,
+    B: BehaviorTreeNode,
+    M: Fn(&A::Input, &A::Terminal) -> B::Input
+{
+    /// Create a new pipeline node from its two stages, and the adapter 
+    /// computing the input of the downstream stage. 
+    pub fn new(upstream: A, adapter: M, downstream: B) -> PipelineNode<A, B, M> {
+        PipelineNode {
+            state: PipelineState::Upstream(upstream, downstream),
+            adapter: adapter
+        }
+    }
+}
+
+impl<A, B, M> BehaviorTreeNode for PipelineNode<A, B, M> where 
+    A: BehaviorTreeNode,
+    B: BehaviorTreeNode,
+    M: Fn(&A::Input, &A::Terminal) -> B::Input
+{
+    type Input = A::Input;
+    type Nonterminal = PipelineNonterm<A::Nonterminal, B::Nonterminal>;
+    type Terminal = B::Terminal;
+
+    #[inline]
+    fn step(self, input: &A::Input) -> NodeResult<Self::Nonterminal,
+        B::Terminal, Self>
+    {
+        let (downstream, upstream_term) = match self.state {
+            PipelineState::Upstream(a, b) => match a.step(input) {
+                NodeResult::Nonterminal(n, m) => return NodeResult::Nonterminal(
+                    PipelineNonterm::Upstream(n),
+                    PipelineNode {
+                        state: PipelineState::Upstream(m, b),
+                        adapter: self.adapter
+                    }
+                ),
+                NodeResult::Terminal(t) => (b, t)
+            },
+            PipelineState::Downstream(b, t) => (b, t)
+        };
+        match downstream.step(&(self.adapter)(input, &upstream_term)) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                PipelineNonterm::Downstream(n),
+                PipelineNode {
+                    state: PipelineState::Downstream(m, upstream_term),
+                    adapter: self.adapter
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        match self.state {
+            PipelineState::Upstream(a, _) => a.abort(),
+            PipelineState::Downstream(b, _) => b.abort()
+        }
+    }
+}
+
+/// A pipeline stage adapter which ignores the input of the pipeline, and 
+/// feeds the terminal of the upstream stage as is to the downstream stage. 
+pub fn forward<I, T>(_input: &I, term: &T) -> T where 
+    T: Clone
+{
+    term.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{Evaluation, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use pipeline_node::{PipelineNode, PipelineNonterm, forward};
+
+    fn sense(input: &i64) -> Statepoint<(), i64> {
+        if *input > 0 {
+            Statepoint::Terminal(*input * 10)
+        } else {
+            Statepoint::Nonterminal(())
+        }
+    }
+
+    fn act(input: &(i64, i64)) -> Statepoint<i64, i64> {
+        let (target, current) = *input;
+        if current >= target {
+            Statepoint::Terminal(current)
+        } else {
+            Statepoint::Nonterminal(target - current)
+        }
+    }
+
+    #[test]
+    fn pipeline_test() {
+        let test_node = PipelineNode::new(
+            PredicateWait::new(sense),
+            |input: &i64, target: &i64| (*target, *input),
+            PredicateWait::new(act)
+        );
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(PipelineNonterm::Upstream(()), n) => n,
+            _ => unreachable!("Expected upstream nonterminal")
+        };
+        // The downstream stage runs on the step the upstream stage finishes
+        let test_node_2 = match test_node_1.step(&3) {
+            NodeResult::Nonterminal(PipelineNonterm::Downstream(v), n) => {
+                assert_eq!(v, 27);
+                n
+            },
+            _ => unreachable!("Expected downstream nonterminal")
+        };
+        let test_node_3 = match test_node_2.step(&20) {
+            NodeResult::Nonterminal(PipelineNonterm::Downstream(v), n) => {
+                assert_eq!(v, 10);
+                n
+            },
+            _ => unreachable!("Expected downstream nonterminal")
+        };
+        match test_node_3.step(&30) {
+            NodeResult::Terminal(t) => assert_eq!(t, 30),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn nested_pipeline_test() {
+        let test_node = PipelineNode::new(
+            PipelineNode::new(
+                Evaluation::new(|i: &i64| *i + 1),
+                forward,
+                Evaluation::new(|i: &i64| *i * 2)
+            ),
+            forward,
+            Evaluation::new(|i: &i64| *i - 3)
+        );
+        match test_node.step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(t, 7),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,411 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::EnumNode;
+use num_traits::FromPrimitive;
+use std::collections::VecDeque;
+
+/// Trait describing the planning domain of a planner node, in the style of 
+/// goal oriented action planning. 
+///
+/// The actions are the variants of an enumerable node, each of which 
+/// declares, over an abstract world state, a precondition which has to hold 
+/// for it to be run and the effect running it successfully has. 
+pub trait PlanningDomain {
+    /// Type of the enumerating discriminant of the actions. 
+    type Enum;
+    /// Type of the inputs of the actions. 
+    type Input;
+    /// Type of the world state planned over. 
+    type State: Clone + PartialEq;
+    /// Observe the current world state from the input. 
+    fn observe(&self, input: &Self::Input) -> Self::State;
+    /// Whether the goal holds in the given state. 
+    fn goal(&self, state: &Self::State) -> bool;
+    /// Whether the given action can be run in the given state. 
+    fn precondition(&self, state: &Self::State, action: Self::Enum) -> bool;
+    /// The state after the given action is run successfully from the given 
+    /// state. 
+    fn effect(&self, state: &Self::State, action: Self::Enum) -> Self::State;
+    /// The cost of running the given action. 
+    fn cost(&self, _action: Self::Enum) -> u64 {
+        1
+    }
+}
+
+/// Nonterminal type of the planner node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlannerNonterm<E, N, T, F> {
+    /// A new plan of the contained length was made. 
+    Planned(usize),
+    /// The action was stepped, and did not terminate. 
+    Running(E, N),
+    /// The action succeeded. 
+    Succeeded(E, T),
+    /// The action failed, and the plan was discarded. 
+    Failed(E, F)
+}
+
+/// Terminal failure type of the planner node. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlanFailure {
+    /// No sequence of actions within the depth limit reaches the goal. 
+    NoPlan,
+    /// The plan was discarded more times than allowed. 
+    ReplanLimit(usize)
+}
+
+/// Planner node, which searches for the cheapest sequence of actions 
+/// reaching the goal of its PlanningDomain, and runs the actions in order, 
+/// planning again whenever an action fails or its precondition no longer 
+/// holds. 
+///
+/// The node succeeds as soon as the goal holds in the observed state at the 
+/// start of a step, and fails if no plan can be found, or if it has to plan 
+/// again more than the allowed number of times. The search is a uniform cost 
+/// search over world states, bounded by a maximum plan length, and only 
+/// needs the states to be comparable, not hashable, so it is meant for small 
+/// domains. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlannerNode<E, P> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive,
+    P: PlanningDomain<Enum=E::Discriminant, Input=E::Input>
+{
+    node: Option<E>,
+    plan: VecDeque<E::Discriminant>,
+    domain: P,
+    max_depth: usize,
+    planned: bool,
+    stale: bool,
+    replans: usize,
+    max_replans: usize
+}
+
+impl<E, P> PlannerNode<E, P> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive,
+    P: PlanningDomain<Enum=E::Discriminant, Input=E::Input>
+{
+    /// Create a new planner node over the given domain, making plans of at 
+    /// most the given length, and planning again at most the given number of 
+    /// times. 
+    pub fn new(domain: P, max_depth: usize, max_replans: usize) -> PlannerNode<E, P> {
+        PlannerNode {
+            node: Option::None,
+            plan: VecDeque::new(),
+            domain: domain,
+            max_depth: max_depth,
+            planned: false,
+            stale: false,
+            replans: 0,
+            max_replans: max_replans
+        }
+    }
+
+    /// Get the actions of the current plan which have not been started yet. 
+    pub fn remaining(&self) -> &VecDeque<E::Discriminant> {
+        &self.plan
+    }
+
+    fn search(&self, start: P::State) -> Option<VecDeque<E::Discriminant>> {
+        let mut actions = Vec::new();
+        let mut index = 0;
+        while let Option::Some(e) = E::Discriminant::from_u64(index) {
+            actions.push(e);
+            index += 1;
+        }
+        let mut frontier = vec![(0, start, VecDeque::new())];
+        let mut explored: Vec<P::State> = Vec::new();
+        while !frontier.is_empty() {
+            let cheapest = frontier.iter()
+                .enumerate()
+                .min_by_key(|&(_, &(c, _, _))| c)
+                .map(|(i, _)| i)
+                .unwrap();
+            let (cost, state, plan) = frontier.swap_remove(cheapest);
+            if self.domain.goal(&state) {
+                return Option::Some(plan);
+            }
+            if explored.contains(&state) {
+                continue;
+            }
+            if plan.len() < self.max_depth {
+                for &a in actions.iter() {
+                    if self.domain.precondition(&state, a) {
+                        let mut next = plan.clone();
+                        next.push_back(a);
+                        frontier.push((
+                            cost + self.domain.cost(a),
+                            self.domain.effect(&state, a),
+                            next
+                        ));
+                    }
+                }
+            }
+            explored.push(state);
+        }
+        Option::None
+    }
+}
+
+impl<E, P, T, F> BehaviorTreeNode for PlannerNode<E, P> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive,
+    P: PlanningDomain<Enum=E::Discriminant, Input=E::Input>
+{
+    type Input = E::Input;
+    type Nonterminal = PlannerNonterm<E::Discriminant, E::Nonterminal, T, F>;
+    type Terminal = Result<(), PlanFailure>;
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut this = self;
+        let node = match this.node.take() {
+            Option::Some(n) => n,
+            Option::None => {
+                let state = this.domain.observe(input);
+                if this.domain.goal(&state) {
+                    return NodeResult::Terminal(Result::Ok(()));
+                }
+                let ready = !this.stale && this.plan.front()
+                    .map_or(false, |&a| this.domain.precondition(&state, a));
+                if !ready {
+                    if this.planned {
+                        this.replans += 1;
+                        if this.replans > this.max_replans {
+                            return NodeResult::Terminal(Result::Err(
+                                PlanFailure::ReplanLimit(this.max_replans)
+                            ));
+                        }
+                    }
+                    match this.search(state) {
+                        Option::Some(plan) => {
+                            let length = plan.len();
+                            this.plan = plan;
+                            this.planned = true;
+                            this.stale = false;
+                            return NodeResult::Nonterminal(
+                                PlannerNonterm::Planned(length),
+                                this
+                            );
+                        },
+                        Option::None => return NodeResult::Terminal(
+                            Result::Err(PlanFailure::NoPlan)
+                        )
+                    }
+                }
+                E::new(this.plan.pop_front().unwrap())
+            }
+        };
+        let discriminant = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                this.node = Option::Some(m);
+                NodeResult::Nonterminal(PlannerNonterm::Running(discriminant, n), this)
+            },
+            NodeResult::Terminal(Result::Ok(t)) => NodeResult::Nonterminal(
+                PlannerNonterm::Succeeded(discriminant, t),
+                this
+            ),
+            NodeResult::Terminal(Result::Err(f)) => {
+                this.stale = true;
+                NodeResult::Nonterminal(PlannerNonterm::Failed(discriminant, f), this)
+            }
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::EnumNode;
+    use planner_node::{PlanningDomain, PlannerNonterm, PlanFailure};
+    use num_derive::{FromPrimitive, ToPrimitive};
+    use std::cell::Cell;
+
+    // The world is whether the agent has wood, has an axe, and has a fire,
+    // read from an input cell so that the actions can change it
+    type World = (bool, bool, bool);
+
+    fn act(input: &Cell<World>, action: ActionEnum) -> Statepoint<(), Result<(), ()>> {
+        let (wood, axe, fire) = input.get();
+        match action {
+            ActionEnum::GetAxe => input.set((wood, true, fire)),
+            ActionEnum::ChopWood => if axe {
+                input.set((true, axe, fire))
+            } else {
+                return Statepoint::Terminal(Result::Err(()));
+            },
+            ActionEnum::LightFire => input.set((false, axe, wood))
+        }
+        Statepoint::Terminal(Result::Ok(()))
+    }
+
+    fn get_axe(input: &Cell<World>) -> Statepoint<(), Result<(), ()>> {
+        act(input, ActionEnum::GetAxe)
+    }
+
+    fn chop_wood(input: &Cell<World>) -> Statepoint<(), Result<(), ()>> {
+        act(input, ActionEnum::ChopWood)
+    }
+
+    fn light_fire(input: &Cell<World>) -> Statepoint<(), Result<(), ()>> {
+        act(input, ActionEnum::LightFire)
+    }
+
+    type Action = PredicateWait<Cell<World>, (), Result<(), ()>,
+        fn(&Cell<World>) -> Statepoint<(), Result<(), ()>>>;
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum ActionEnum {
+        GetAxe,
+        ChopWood,
+        LightFire
+    }
+
+    struct Actions(ActionEnum, Action);
+
+    impl BehaviorTreeNode for Actions {
+        type Input = Cell<World>;
+        type Nonterminal = ();
+        type Terminal = Result<(), ()>;
+
+        fn step(self, input: &Cell<World>) -> NodeResult<(), Result<(), ()>, Self> {
+            match self.1.step(input) {
+                NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                    r,
+                    Actions(self.0, m)
+                ),
+                NodeResult::Terminal(t) => NodeResult::Terminal(t)
+            }
+        }
+    }
+
+    impl EnumNode for Actions {
+        type Discriminant = ActionEnum;
+
+        fn new(thing: ActionEnum) -> Actions {
+            let step = match thing {
+                ActionEnum::GetAxe => get_axe as fn(&Cell<World>) -> _,
+                ActionEnum::ChopWood => chop_wood,
+                ActionEnum::LightFire => light_fire
+            };
+            Actions(thing, PredicateWait::new(step))
+        }
+
+        fn discriminant_of(&self) -> ActionEnum {
+            self.0
+        }
+    }
+
+    struct Campfire;
+
+    impl PlanningDomain for Campfire {
+        type Enum = ActionEnum;
+        type Input = Cell<World>;
+        type State = World;
+
+        fn observe(&self, input: &Cell<World>) -> World {
+            input.get()
+        }
+
+        fn goal(&self, state: &World) -> bool {
+            state.2
+        }
+
+        fn precondition(&self, state: &World, action: ActionEnum) -> bool {
+            match action {
+                ActionEnum::GetAxe => !state.1,
+                ActionEnum::ChopWood => state.1 && !state.0,
+                ActionEnum::LightFire => state.0
+            }
+        }
+
+        fn effect(&self, state: &World, action: ActionEnum) -> World {
+            let &(wood, axe, fire) = state;
+            match action {
+                ActionEnum::GetAxe => (wood, true, fire),
+                ActionEnum::ChopWood => (true, axe, fire),
+                ActionEnum::LightFire => (false, axe, true)
+            }
+        }
+    }
+
+    #[test]
+    fn planner_test() {
+        use planner_node::PlannerNode;
+        let world = Cell::new((false, false, false));
+        let test_node = PlannerNode::<Actions, _>::new(Campfire, 5, 0);
+        let test_node_1 = match test_node.step(&world) {
+            NodeResult::Nonterminal(PlannerNonterm::Planned(3), n) => n,
+            _ => unreachable!("Expected a plan of three actions")
+        };
+        let mut test_node_2 = test_node_1;
+        for &action in [ActionEnum::GetAxe, ActionEnum::ChopWood,
+            ActionEnum::LightFire].iter()
+        {
+            test_node_2 = match test_node_2.step(&world) {
+                NodeResult::Nonterminal(PlannerNonterm::Succeeded(e, ()), n) => {
+                    assert_eq!(e, action);
+                    n
+                },
+                _ => unreachable!("Expected action success")
+            };
+        }
+        match test_node_2.step(&world) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn planner_replan_test() {
+        use planner_node::PlannerNode;
+        let world = Cell::new((false, false, false));
+        let test_node = PlannerNode::<Actions, _>::new(Campfire, 5, 1);
+        let test_node_1 = match test_node.step(&world) {
+            NodeResult::Nonterminal(PlannerNonterm::Planned(3), n) => n,
+            _ => unreachable!("Expected a plan of three actions")
+        };
+        let test_node_2 = match test_node_1.step(&world) {
+            NodeResult::Nonterminal(PlannerNonterm::Succeeded(_, ()), n) => n,
+            _ => unreachable!("Expected action success")
+        };
+        // Wood shows up on its own, so chopping is no longer needed
+        world.set((true, true, false));
+        let test_node_3 = match test_node_2.step(&world) {
+            NodeResult::Nonterminal(PlannerNonterm::Planned(1), n) => n,
+            _ => unreachable!("Expected a plan of one action")
+        };
+        assert_eq!(test_node_3.remaining().front(), Option::Some(&ActionEnum::LightFire));
+        // Losing the axe and wood again exhausts the replanning budget
+        world.set((false, false, false));
+        match test_node_3.step(&world) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(t, Result::Err(PlanFailure::ReplanLimit(1)))
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn planner_no_plan_test() {
+        use planner_node::PlannerNode;
+        let world = Cell::new((false, false, false));
+        let test_node = PlannerNode::<Actions, _>::new(Campfire, 2, 0);
+        match test_node.step(&world) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(PlanFailure::NoPlan)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
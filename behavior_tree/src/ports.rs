@@ -0,0 +1,259 @@
+use blackboard::{Blackboard, Key};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Direction of the data flowing through a port. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PortDirection {
+    /// The node reads the entry bound to the port. 
+    Input,
+    /// The node writes the entry bound to the port. 
+    Output
+}
+
+/// Declaration of a port of a node, as listed by nodes for the loaders and 
+/// checkers of trees to know which bindings a node expects. 
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PortSpec {
+    /// The name of the port. 
+    pub name: &'static str,
+    /// The direction of the port. 
+    pub direction: PortDirection
+}
+
+impl PortSpec {
+    /// Declare an input port with the given name. 
+    pub fn input(name: &'static str) -> PortSpec {
+        PortSpec {
+            name: name,
+            direction: PortDirection::Input
+        }
+    }
+
+    /// Declare an output port with the given name. 
+    pub fn output(name: &'static str) -> PortSpec {
+        PortSpec {
+            name: name,
+            direction: PortDirection::Output
+        }
+    }
+}
+
+/// Trait for nodes declaring the ports they read and write through. 
+pub trait DeclarePorts {
+    /// List the ports of the node. 
+    fn ports() -> Vec<PortSpec>;
+}
+
+/// Typed handle of a port a node reads a value of the given type from. 
+pub struct InputPort<T> {
+    name: Cow<'static, str>,
+    _junk: PhantomData<fn() -> T>
+}
+
+/// Typed handle of a port a node writes a value of the given type to. 
+pub struct OutputPort<T> {
+    name: Cow<'static, str>,
+    _junk: PhantomData<fn(T)>
+}
+
+macro_rules! port_handle {
+    ($port:ident) => {
+        impl<T> $port<T> {
+            /// Create a new port handle with the given name. 
+            pub fn new<S>(name: S) -> $port<T> where 
+                S: Into<Cow<'static, str>>
+            {
+                $port {
+                    name: name.into(),
+                    _junk: PhantomData
+                }
+            }
+
+            /// Get the name of the port. 
+            pub fn name(&self) -> &str {
+                &self.name
+            }
+        }
+
+        impl<T> Clone for $port<T> {
+            fn clone(&self) -> Self {
+                $port {
+                    name: self.name.clone(),
+                    _junk: PhantomData
+                }
+            }
+        }
+
+        impl<T> PartialEq for $port<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.name == other.name
+            }
+        }
+
+        impl<T> fmt::Debug for $port<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_tuple(stringify!($port)).field(&self.name).finish()
+            }
+        }
+    }
+}
+
+port_handle!(InputPort);
+port_handle!(OutputPort);
+
+/// Error of reading or writing through a port, or of checking bindings. 
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PortError {
+    /// The port with the given name is not bound to an entry. 
+    Unbound(String),
+    /// The entry bound to the port with the given name holds no value of 
+    /// the type of the port. 
+    Missing(String),
+    /// A binding names a port which the node does not declare. 
+    Undeclared(String)
+}
+
+/// Bindings of the ports of a node to the names of blackboard entries, 
+/// letting the same node read and write different entries depending on 
+/// where in a tree it is placed. 
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PortMap {
+    bindings: HashMap<String, String>
+}
+
+impl PortMap {
+    /// Create a new port map with no bindings. 
+    pub fn new() -> PortMap {
+        PortMap::default()
+    }
+
+    /// Bind the port with the given name to the entry with the given name. 
+    pub fn bind<S, T>(self, port: S, entry: T) -> PortMap where 
+        S: Into<String>,
+        T: Into<String>
+    {
+        let mut mut_self = self;
+        mut_self.bindings.insert(port.into(), entry.into());
+        mut_self
+    }
+
+    /// Get the name of the entry the port with the given name is bound to. 
+    pub fn entry(&self, port: &str) -> Option<&str> {
+        self.bindings.get(port).map(|e| e.as_str())
+    }
+
+    fn key<T>(&self, port: &str) -> Result<Key<T>, PortError> {
+        match self.bindings.get(port) {
+            Option::Some(e) => Result::Ok(Key::new(e.clone())),
+            Option::None => Result::Err(PortError::Unbound(port.to_owned()))
+        }
+    }
+
+    /// Read a copy of the value of the entry bound to the given port. 
+    pub fn get<T>(&self, board: &Blackboard, port: &InputPort<T>) ->
+        Result<T, PortError> where 
+        T: Clone + 'static
+    {
+        let key = self.key(port.name())?;
+        board.get(&key)
+            .ok_or_else(|| PortError::Missing(port.name().to_owned()))
+    }
+
+    /// Write the given value to the entry bound to the given port, returning 
+    /// the version it was stamped with. 
+    pub fn set<T>(&self, board: &Blackboard, port: &OutputPort<T>, value: T) ->
+        Result<u64, PortError> where 
+        T: 'static
+    {
+        let key = self.key(port.name())?;
+        Result::Ok(board.set(&key, value))
+    }
+
+    /// Check the bindings against the ports declared by a node, failing on 
+    /// the first port which is declared but unbound, or bound but not 
+    /// declared. 
+    pub fn check(&self, ports: &[PortSpec]) -> Result<(), PortError> {
+        if let Option::Some(p) = ports.iter()
+            .find(|p| !self.bindings.contains_key(p.name))
+        {
+            return Result::Err(PortError::Unbound(p.name.to_owned()));
+        }
+        let mut undeclared = self.bindings.keys()
+            .filter(|b| !ports.iter().any(|p| p.name == b.as_str()))
+            .collect::<Vec<_>>();
+        undeclared.sort();
+        match undeclared.first() {
+            Option::Some(b) => Result::Err(PortError::Undeclared((*b).clone())),
+            Option::None => Result::Ok(())
+        }
+    }
+
+    /// Create a scope of the given blackboard for a subtree, in which the 
+    /// name of each bound port is remapped to its entry on the parent. 
+    pub fn scope(&self, parent: Rc<Blackboard>) -> Blackboard {
+        self.bindings.iter().fold(Blackboard::scoped(parent),
+            |b, (p, e)| b.remap(p.clone(), e.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackboard::{Blackboard, Key};
+    use ports::{DeclarePorts, InputPort, OutputPort, PortError, PortMap,
+        PortSpec};
+
+    struct MoveTo;
+
+    impl DeclarePorts for MoveTo {
+        fn ports() -> Vec<PortSpec> {
+            vec![PortSpec::input("goal"), PortSpec::output("reached")]
+        }
+    }
+
+    #[test]
+    fn port_map_test() {
+        let board = Blackboard::new();
+        board.set(&Key::<i64>::new("door"), 4);
+        let ports = PortMap::new().bind("goal", "door")
+            .bind("reached", "at_door");
+        assert_eq!(ports.check(&MoveTo::ports()), Result::Ok(()));
+        let goal = InputPort::<i64>::new("goal");
+        let reached = OutputPort::<bool>::new("reached");
+        assert_eq!(ports.get(&board, &goal), Result::Ok(4));
+        assert!(ports.set(&board, &reached, true).is_ok());
+        assert_eq!(board.get(&Key::<bool>::new("at_door")),
+            Option::Some(true));
+        assert_eq!(PortMap::new().get(&board, &goal),
+            Result::Err(PortError::Unbound("goal".to_owned())));
+        assert_eq!(ports.get(&board, &InputPort::<u8>::new("goal")),
+            Result::Err(PortError::Missing("goal".to_owned())));
+    }
+
+    #[test]
+    fn port_check_test() {
+        let ports = PortMap::new().bind("goal", "door");
+        assert_eq!(ports.check(&MoveTo::ports()),
+            Result::Err(PortError::Unbound("reached".to_owned())));
+        let extra = ports.bind("reached", "at_door").bind("speed", "fast");
+        assert_eq!(extra.check(&MoveTo::ports()),
+            Result::Err(PortError::Undeclared("speed".to_owned())));
+    }
+
+    #[test]
+    fn port_scope_test() {
+        use std::rc::Rc;
+        let root = Rc::new(Blackboard::new());
+        root.set(&Key::<i64>::new("door"), 4);
+        let scope = PortMap::new().bind("goal", "door").scope(root.clone());
+        assert_eq!(scope.get(&Key::<i64>::new("goal")), Option::Some(4));
+        scope.set(&Key::<i64>::new("goal"), 5);
+        assert_eq!(root.get(&Key::<i64>::new("door")), Option::Some(5));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,108 @@
+//! Convenience re-exports of the traits, statepoint types, and commonly used 
+//! nodes, wrappers, and deciders of this crate. 
+//! 
+//! ```
+//! use stackbt_behavior_tree::prelude::*;
+//! 
+//! let node = Evaluation::new(|i: &i64| *i + 1);
+//! match node.step(&2) {
+//!     NodeResult::Terminal(t) => assert_eq!(t, 3),
+//!     _ => unreachable!("Node doesn't return nonterminal")
+//! };
+//! ```
+
+pub use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint, Either, 
+    BtStatus, Failure};
+#[cfg(feature = "serde")]
+pub use persist_state::PersistState;
+pub use base_nodes::{PredicateWait, Evaluation, CallLoop, MachineWrapper, 
+    MachineLoop, HysteresisState, Hysteresis};
+pub use node_runner::NodeRunner;
+#[cfg(feature = "serde")]
+pub use snapshot::Snapshot;
+pub use checksum::ChecksumError;
+#[cfg(feature = "binary")]
+pub use binary_codec::{FORMAT_VERSION, CodecError, BinaryCodec};
+#[cfg(feature = "binary")]
+pub use migration::Migrations;
+#[cfg(feature = "binary")]
+pub use delta_snapshot::{Frame, DeltaRecorder, DeltaRestorer};
+#[cfg(feature = "binary")]
+pub use trace::{TRACE_VERSION, TraceTransition, TraceRecord, TraceWriter, 
+    TraceReader, TraceRecorder};
+#[cfg(feature = "protobuf")]
+pub use proto_codec::{SnapshotMessage, NodeStatusEntry, StatusEvent};
+pub use clock::{Clock, MonotonicClock, ManualClock};
+pub use blackboard::{Key, Ttl, ReadError, Blackboard, SyncBlackboard, 
+    InstancedBlackboard, MutationKind, Mutation, JournalDiff, AsBlackboard, 
+    Context};
+#[cfg(feature = "persistence")]
+pub use blackboard_persist::{BlackboardState, PersistError, Persistence};
+pub use serial_node::{EnumNode, SerialDecider, SerialBranchNode, NontermDecision, 
+    TermDecision, NontermReturn};
+pub use parallel_node::{ParallelDecider, ParallelBranchNode};
+pub use map_wrappers::{InputMappedNode, InputProjectedNode, OutputMappedNode, 
+    StatefulOutputMappedNode, LazyConstructedNode, DeferredNode};
+pub use control_wrappers::{GuardedNode, GuardFailure, Guard, Semaphore, 
+    SemaphoreNonterm, SemaphoreNode, Panicked, CatchPanic, InterruptHandle, 
+    Interruptible, PauseHandle, PauseNonterm, Pausable, ScopedResource, 
+    ContractViolation, Contract, StepControlledNode, StepDecision, 
+    StepCtrlNonterm, PostResetNode, PostResetNonterm};
+pub use status_wrappers::{Inverter, Succeeder, Failer, AsStatus, AsResult};
+pub use repeat_wrappers::{RepeatNonterm, Repeat, RetryNonterm, Retry};
+pub use time_wrappers::{TimedOut, TickTimeout, Timeout, RateLimit, 
+    DebounceNonterm, Debounce, BudgetExhausted, StepBudget, Budgeted, 
+    JitterNonterm, Jitter, Progress, ProgressNode};
+pub use cache_wrappers::{LatchCache, Latch, MemoCache, Memoize, ChangeFilter};
+pub use observe_wrappers::{NodeObserver, ObservedNode};
+pub use blackboard_nodes::{BlackboardWrite, Dataflow, WatchKey, FreshCondition, 
+    OnWorld, OnBlackboard, on_world, on_blackboard};
+pub use ports::{PortDirection, PortSpec, DeclarePorts, InputPort, OutputPort, 
+    PortError, PortMap};
+pub use expression::{Value, ParseError, EvalError, Expression, 
+    ExpressionCondition};
+#[cfg(feature = "rhai")]
+pub use scripting::{ScriptError, Script, ScriptLeaf, ScriptDecider};
+pub use event_nodes::{EventQueue, SendEvent, AwaitEvent};
+pub use query_node::{Query, QueryNode};
+pub use future_node::{noop_waker, FutureNode};
+pub use node_compositions::{SerialRunner, SerialSelector, SerialRepeater, 
+    ParallelRunner, ParallelRacer};
+pub use sequence_node::{SerialSequence, SequenceNode, ReactiveSequence};
+pub use selector_node::{SerialFallback, SelectorNode, ReactiveSelector,
+    SelectorMemory, MemoryFallback, MemorySelectorNode};
+pub use parallel_join::{JoinPolicy, ParallelJoinNode, ThresholdPolicy, JoinAll, 
+    JoinAllNode, FirstTerminal, RaceNode, StreamingJoinNode};
+pub use random_selector::{RandomSource, XorShiftSource, WeightDecider, 
+    WeightedRandomSelector, ProbabilisticBranch};
+#[cfg(feature = "rand")]
+pub use random_selector::RngSource;
+pub use round_robin::{RoundRobin, RoundRobinNode};
+pub use utility_selector::{UtilityScorer, UtilitySelector};
+pub use priority_selector::{PriorityGuard, PrioritySelector};
+pub use dynamic_node::{DynNode, BoxedNode, DynSequence, DynSelector, DynParallel};
+pub use tree_def::{LoadError, NodeDef};
+pub use btcpp_xml::{blackboard_ref, TreeDocument};
+pub use bt_dsl::BtError;
+pub use introspect::{NodeStatus, Introspect, TreeMonitor};
+pub use groot::{GrootStatus, GrootMonitor, GrootServer};
+pub use node_registry::{DynTree, ParamType, ParamSpec, Arity, NodeSpec, 
+    NodeRegistry};
+pub use tree_builder::{Tree, TreeBuilder};
+pub use hot_reload::ReloadableTree;
+pub use tuple_node::{NodeTuple, ProjectedChild, ProjectedTuple, TupleSequence, 
+    TupleSelector, TupleParallel};
+#[cfg(feature = "serde")]
+pub use tuple_node::PersistTuple;
+pub use interleave_node::InterleavedNode;
+pub use conditional_node::{ConditionalNonterm, IfThenElse, WhileNonterm, WhileNode};
+pub use switch_node::{KeyChange, SwitchNode};
+pub use recovery_node::{RecoveryNonterm, RecoveryFailure, RecoveryNode};
+pub use shared_subtree::{Subtree, SubtreeNode, SubtreeTemplate};
+pub use pipeline_node::{PipelineNonterm, PipelineNode};
+pub use fold_node::{FoldNonterm, FoldNode, LoopFold, LoopN};
+pub use stack_node::{StackNontermDecision, StackTermDecision, StackDecider, 
+    StackBranchNode};
+pub use event_join::{Combination, EventProgress, EventJoinNode};
+pub use planner_node::{PlanningDomain, PlannerNonterm, PlanFailure, PlannerNode};
+pub use blend_node::{Blend, BlendWeights, FixedWeights, BlendNode};
\ No newline at end of file
@@ -0,0 +1,282 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, NontermReturn};
+use num_traits::{FromPrimitive, ToPrimitive};
+
+/// Trait for the priorities and guards of the nodes of a priority selector. 
+pub trait PriorityGuard {
+    /// Type of the enumerating discriminant. 
+    type Enum;
+    /// Type of the inputs of the subnodes. 
+    type Input;
+    /// Given the discriminant of a subnode, return its priority, where 
+    /// higher numbers preempt lower ones. This should not change over the 
+    /// lifetime of the selector. 
+    fn priority(&self, ord: Self::Enum) -> i64;
+    /// Given a reference to the input and the discriminant of a subnode, 
+    /// return whether that subnode may run. 
+    fn guard(&self, input: &Self::Input, ord: Self::Enum) -> bool;
+}
+
+/// Selector node which runs the highest priority node whose guard holds, 
+/// preempting the running node whenever the guard of a node with strictly 
+/// higher priority becomes true. 
+///
+/// Each step, the guards of the nodes with higher priority than the running 
+/// node are evaluated before the running node is stepped, and if one of 
+/// them holds, the running node is aborted and replaced. The guard of the 
+/// running node itself is not checked again once it has started. Among 
+/// nodes of equal priority, the earlier discriminant wins. The selector 
+/// exits with the discriminant and terminal of the node that terminates, or 
+/// with Option::None if no guard holds when a node is to be started. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct PrioritySelector<E, G> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    G: PriorityGuard<Enum=E::Discriminant, Input=E::Input>
+{
+    node: Option<E>,
+    guards: G
+}
+
+impl<E, G> PrioritySelector<E, G> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    G: PriorityGuard<Enum=E::Discriminant, Input=E::Input>
+{
+    /// Create a new priority selector with the given priorities and guards. 
+    pub fn new(guards: G) -> PrioritySelector<E, G> {
+        PrioritySelector {
+            node: Option::None,
+            guards: guards
+        }
+    }
+
+    fn preemptor(&self, input: &E::Input, above: Option<i64>) ->
+        Option<E::Discriminant>
+    {
+        let mut index = 0;
+        let mut best: Option<(E::Discriminant, i64)> = Option::None;
+        while let Option::Some(e) = E::Discriminant::from_u64(index) {
+            let priority = self.guards.priority(e);
+            let beats_running = match above {
+                Option::Some(p) => priority > p,
+                Option::None => true
+            };
+            let beats_best = match best {
+                Option::Some((_, p)) => priority > p,
+                Option::None => true
+            };
+            if beats_running && beats_best && self.guards.guard(input, e) {
+                best = Option::Some((e, priority));
+            }
+            index += 1;
+        }
+        best.map(|(e, _)| e)
+    }
+}
+
+impl<E, G> BehaviorTreeNode for PrioritySelector<E, G> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    G: PriorityGuard<Enum=E::Discriminant, Input=E::Input>
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = Option<(E::Discriminant, E::Terminal)>;
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut this = self;
+        let node = match this.node.take() {
+            Option::Some(n) => {
+                let running = this.guards.priority(n.discriminant_of());
+                match this.preemptor(input, Option::Some(running)) {
+                    Option::Some(e) => {
+                        n.abort();
+                        E::new(e)
+                    },
+                    Option::None => n
+                }
+            },
+            Option::None => match this.preemptor(input, Option::None) {
+                Option::Some(e) => E::new(e),
+                Option::None => return NodeResult::Terminal(Option::None)
+            }
+        };
+        let discriminant = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                this.node = Option::Some(m);
+                NodeResult::Nonterminal(
+                    NontermReturn::Nonterminal(discriminant, n),
+                    this
+                )
+            },
+            NodeResult::Terminal(t) => {
+                NodeResult::Terminal(Option::Some((discriminant, t)))
+            }
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use serial_node::{EnumNode, NontermReturn};
+    use priority_selector::PriorityGuard;
+    use std::rc::Rc;
+    use std::cell::Cell;
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    struct Patrol {
+        steps: i64,
+        aborts: Rc<Cell<i64>>
+    }
+
+    impl BehaviorTreeNode for Patrol {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, _input: &i64) -> NodeResult<i64, i64, Self> {
+            NodeResult::Nonterminal(self.steps, Patrol {
+                steps: self.steps + 1,
+                aborts: self.aborts
+            })
+        }
+
+        fn abort(self) {
+            self.aborts.set(self.aborts.get() + 1);
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum ActivityEnum {
+        Patrol,
+        Flee
+    }
+
+    enum Activities {
+        Patrol(Patrol),
+        Flee
+    }
+
+    thread_local! {
+        static ABORTS: Rc<Cell<i64>> = Rc::new(Cell::new(0));
+    }
+
+    impl BehaviorTreeNode for Activities {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Self> {
+            match self {
+                Activities::Patrol(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Activities::Patrol(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Activities::Flee => NodeResult::Terminal(*input)
+            }
+        }
+
+        fn abort(self) {
+            if let Activities::Patrol(n) = self {
+                n.abort()
+            }
+        }
+    }
+
+    impl EnumNode for Activities {
+        type Discriminant = ActivityEnum;
+
+        fn new(thing: ActivityEnum) -> Activities {
+            match thing {
+                ActivityEnum::Patrol => Activities::Patrol(Patrol {
+                    steps: 0,
+                    aborts: ABORTS.with(|a| a.clone())
+                }),
+                ActivityEnum::Flee => Activities::Flee
+            }
+        }
+
+        fn discriminant_of(&self) -> ActivityEnum {
+            match self {
+                Activities::Patrol(_) => ActivityEnum::Patrol,
+                Activities::Flee => ActivityEnum::Flee
+            }
+        }
+    }
+
+    struct Danger;
+
+    impl PriorityGuard for Danger {
+        type Enum = ActivityEnum;
+        type Input = i64;
+
+        fn priority(&self, ord: ActivityEnum) -> i64 {
+            match ord {
+                ActivityEnum::Patrol => 0,
+                ActivityEnum::Flee => 10
+            }
+        }
+
+        fn guard(&self, input: &i64, ord: ActivityEnum) -> bool {
+            match ord {
+                ActivityEnum::Patrol => *input >= 0,
+                ActivityEnum::Flee => *input > 5
+            }
+        }
+    }
+
+    #[test]
+    fn priority_preemption_test() {
+        use priority_selector::PrioritySelector;
+        let test_node = PrioritySelector::<Activities, _>::new(Danger);
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, ActivityEnum::Patrol);
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&3) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, ActivityEnum::Patrol);
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        // Danger preempts the patrol
+        match test_node_2.step(&8) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(t, Option::Some((ActivityEnum::Flee, 8)))
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(ABORTS.with(|a| a.get()), 1);
+    }
+
+    #[test]
+    fn priority_no_guard_test() {
+        use priority_selector::PrioritySelector;
+        let test_node = PrioritySelector::<Activities, _>::new(Danger);
+        match test_node.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, Option::None),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,193 @@
+use binary_codec::{BinaryCodec, CodecError};
+use introspect::{Introspect, NodeStatus};
+use migration::Migrations;
+use prost::Message;
+use snapshot::Snapshot;
+
+/// Snapshot of a running tree, as the `Snapshot` message of the schema in 
+/// `proto/stackbt.proto`. 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SnapshotMessage {
+    /// The version of the layout of the state of the tree. 
+    #[prost(uint32, tag = "1")]
+    pub state_version: u32,
+    /// The state of the tree, in the compact binary encoding. 
+    #[prost(bytes = "vec", tag = "2")]
+    pub state: Vec<u8>
+}
+
+/// Status of a node, as the `NodeStatusEntry` message of the schema. 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeStatusEntry {
+    /// The position of the node in the preorder of the tree. 
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// The status of the node, as the discriminant of its status. 
+    #[prost(int32, tag = "2")]
+    pub status: i32
+}
+
+impl NodeStatusEntry {
+    /// Get the status of the node, if it is a known one. 
+    pub fn node_status(&self) -> Option<NodeStatus> {
+        match self.status {
+            0 => Option::Some(NodeStatus::Idle),
+            1 => Option::Some(NodeStatus::Running),
+            2 => Option::Some(NodeStatus::Success),
+            3 => Option::Some(NodeStatus::Failure),
+            _ => Option::None
+        }
+    }
+}
+
+/// Statuses of all nodes of a running tree as of a tick, as the 
+/// `StatusEvent` message of the schema, for streaming the progress of 
+/// agents to other services. 
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StatusEvent {
+    /// The tick the statuses are as of. 
+    #[prost(uint64, tag = "1")]
+    pub tick: u64,
+    /// The statuses of the nodes, in preorder. 
+    #[prost(message, repeated, tag = "2")]
+    pub statuses: Vec<NodeStatusEntry>
+}
+
+impl StatusEvent {
+    /// Capture the statuses of the nodes of the given tree. 
+    pub fn capture<T>(tree: &T, tick: u64) -> StatusEvent where 
+        T: Introspect + ?Sized
+    {
+        let statuses = (0..)
+            .map(|i| tree.node_status(i).map(|s| NodeStatusEntry {
+                index: i as u32,
+                status: s as i32
+            }))
+            .take_while(Option::is_some)
+            .map(Option::unwrap)
+            .collect();
+        StatusEvent {
+            tick: tick,
+            statuses: statuses
+        }
+    }
+
+    /// Write the event as a protocol buffer. 
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    /// Read an event written as a protocol buffer. 
+    pub fn from_bytes(bytes: &[u8]) -> Result<StatusEvent, CodecError> {
+        StatusEvent::decode(bytes).map_err(CodecError::Protobuf)
+    }
+}
+
+impl BinaryCodec {
+    /// Encode a snapshot of the given running tree as a protocol buffer, 
+    /// stamped with the version of the layout of its state. 
+    pub fn encode_proto<S>(&self, tree: &S) -> 
+        Result<Vec<u8>, CodecError> where 
+        S: Snapshot
+    {
+        let message = SnapshotMessage {
+            state_version: S::STATE_VERSION,
+            state: self.encode_payload(&tree.snapshot())?
+        };
+        Result::Ok(message.encode_to_vec())
+    }
+
+    /// Reconstruct a running tree from its definition and its snapshot 
+    /// encoded as a protocol buffer, migrating the state if it was saved by 
+    /// another version of its layout. 
+    pub fn decode_proto<S>(&self, definition: &S::Definition, bytes: &[u8],
+        migrations: &Migrations<S::Blob>) -> Result<S, CodecError> where 
+        S: Snapshot
+    {
+        let message = SnapshotMessage::decode(bytes)
+            .map_err(CodecError::Protobuf)?;
+        self.restore_versioned(definition, message.state_version,
+            &message.state, migrations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use binary_codec::{BinaryCodec, CodecError};
+    use introspect::{NodeStatus, TreeMonitor};
+    use migration::Migrations;
+    use node_registry::NodeRegistry;
+    use node_runner::NodeRunner;
+    use persist_state::PersistState;
+    use proto_codec::StatusEvent;
+    use stackbt_automata_impl::automaton::Automaton;
+    use tree_def::NodeDef;
+
+    struct Countdown(u32);
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = ();
+        type Nonterminal = u32;
+        type Terminal = ();
+
+        fn step(self, _input: &()) -> NodeResult<u32, (), Self> {
+            match self.0 {
+                0 => NodeResult::Terminal(()),
+                n => NodeResult::Nonterminal(n, Countdown(n - 1))
+            }
+        }
+    }
+
+    impl PersistState for Countdown {
+        type State = u32;
+
+        fn save_state(&self) -> u32 {
+            self.0
+        }
+
+        fn restore_state(&mut self, state: u32) {
+            self.0 = state
+        }
+    }
+
+    #[test]
+    fn proto_snapshot_test() {
+        let codec = BinaryCodec::new();
+        let definition = || Countdown(5);
+        let mut runner = NodeRunner::new(definition);
+        runner.transition(&());
+        let bytes = codec.encode_proto(&runner).unwrap();
+        assert_eq!(bytes, vec![0x12, 0x01, 0x04]);
+        let mut restored: NodeRunner<Countdown, _> = codec.decode_proto(
+            &definition, &bytes, &Migrations::new()).unwrap();
+        assert_eq!(restored.transition(&()), Statepoint::Nonterminal(4));
+        match codec.decode_proto::<NodeRunner<Countdown, _>>(&definition,
+            &[0x12, 0x05], &Migrations::new())
+        {
+            Result::Err(CodecError::Protobuf(_)) => (),
+            _ => unreachable!("Expected truncated message")
+        };
+    }
+
+    #[test]
+    fn proto_status_test() {
+        let registry = NodeRegistry::with_builtins()
+            .leaf("Wait", |_: &NodeDef| {
+                Result::Ok(PredicateWait::new(|_: &i64| {
+                    Statepoint::Nonterminal(())
+                }))
+            });
+        let monitor = TreeMonitor::new();
+        let tree = monitor.build(&registry, &NodeDef::new("Inverter")
+            .child(NodeDef::new("Wait"))).unwrap();
+        tree.step(&0);
+        let event = StatusEvent::capture(&monitor, 7);
+        assert_eq!(event.statuses.len(), 2);
+        assert_eq!(event.statuses[1].node_status(),
+            Option::Some(NodeStatus::Running));
+        let decoded = StatusEvent::from_bytes(&event.to_bytes()).unwrap();
+        assert_eq!(decoded, event);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,267 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use blackboard::{AsBlackboard, Key};
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Query over a collection of structured values, built from filters which 
+/// the matches must all satisfy, and an optional score to rank the matches 
+/// by, such as the nearest entity tagged as an enemy within some distance. 
+pub struct Query<T> {
+    filters: Vec<Rc<Fn(&T) -> bool>>,
+    score: Option<(Rc<Fn(&T) -> f64>, bool)>,
+    limit: Option<usize>
+}
+
+impl<T> Clone for Query<T> {
+    fn clone(&self) -> Self {
+        Query {
+            filters: self.filters.clone(),
+            score: self.score.clone(),
+            limit: self.limit
+        }
+    }
+}
+
+impl<T> fmt::Debug for Query<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("filters", &self.filters.len())
+            .field("ranked", &self.score.is_some())
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+impl<T> Default for Query<T> {
+    fn default() -> Query<T> {
+        Query::new()
+    }
+}
+
+impl<T> Query<T> {
+    /// Create a new query matching everything, in collection order. 
+    pub fn new() -> Query<T> {
+        Query {
+            filters: Vec::new(),
+            score: Option::None,
+            limit: Option::None
+        }
+    }
+
+    /// Only match the values satisfying the given predicate. 
+    pub fn filter<F>(self, predicate: F) -> Query<T> where 
+        F: Fn(&T) -> bool + 'static
+    {
+        let mut mut_self = self;
+        mut_self.filters.push(Rc::new(predicate));
+        mut_self
+    }
+
+    /// Rank the matches by the given score, lowest first. 
+    pub fn lowest<F>(self, score: F) -> Query<T> where 
+        F: Fn(&T) -> f64 + 'static
+    {
+        Query {
+            score: Option::Some((Rc::new(score), false)),
+            .. self
+        }
+    }
+
+    /// Rank the matches by the given score, highest first. 
+    pub fn highest<F>(self, score: F) -> Query<T> where 
+        F: Fn(&T) -> f64 + 'static
+    {
+        Query {
+            score: Option::Some((Rc::new(score), true)),
+            .. self
+        }
+    }
+
+    /// Keep at most the given number of matches. 
+    pub fn limit(self, limit: usize) -> Query<T> {
+        Query {
+            limit: Option::Some(limit),
+            .. self
+        }
+    }
+
+    /// Check whether the given value satisfies all filters. 
+    pub fn matches(&self, value: &T) -> bool {
+        self.filters.iter().all(|f| f(value))
+    }
+
+    /// Run the query over the given values, returning the matches in rank 
+    /// order, or in collection order if unranked. Values with equal scores 
+    /// keep their collection order. 
+    pub fn run<'a>(&self, values: &'a [T]) -> Vec<&'a T> {
+        let mut found = values.iter()
+            .filter(|v| self.matches(v))
+            .collect::<Vec<_>>();
+        if let Option::Some((ref score, descending)) = self.score {
+            found.sort_by(|a, b| {
+                let order = score(a).partial_cmp(&score(b))
+                    .unwrap_or(Ordering::Equal);
+                if descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+        if let Option::Some(l) = self.limit {
+            found.truncate(l);
+        }
+        found
+    }
+
+    /// Run the query over the given values, returning the best match. 
+    pub fn first<'a>(&self, values: &'a [T]) -> Option<&'a T> {
+        self.run(values).into_iter().next()
+    }
+}
+
+/// Leaf node running a query over the collection under a blackboard key, 
+/// which succeeds after binding the best match to another key, and fails 
+/// if nothing matches. 
+///
+/// All the matches can also be bound, in rank order, to a further key. 
+/// Nothing is bound when the node fails. 
+#[derive(Debug)]
+pub struct QueryNode<I, T> where 
+    I: AsBlackboard,
+    T: Clone + 'static
+{
+    source: Key<Vec<T>>,
+    query: Query<T>,
+    binding: Key<T>,
+    all_binding: Option<Key<Vec<T>>>,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I, T> Clone for QueryNode<I, T> where 
+    I: AsBlackboard,
+    T: Clone + 'static
+{
+    fn clone(&self) -> Self {
+        QueryNode {
+            source: self.source.clone(),
+            query: self.query.clone(),
+            binding: self.binding.clone(),
+            all_binding: self.all_binding.clone(),
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<I, T> QueryNode<I, T> where 
+    I: AsBlackboard,
+    T: Clone + 'static
+{
+    /// Create a new query node. 
+    pub fn new(source: Key<Vec<T>>, query: Query<T>, binding: Key<T>) ->
+        QueryNode<I, T>
+    {
+        QueryNode {
+            source: source,
+            query: query,
+            binding: binding,
+            all_binding: Option::None,
+            _junk: PhantomData
+        }
+    }
+
+    /// Also bind all the matches to the given key. 
+    pub fn bind_all(self, key: Key<Vec<T>>) -> QueryNode<I, T> {
+        QueryNode {
+            all_binding: Option::Some(key),
+            .. self
+        }
+    }
+}
+
+impl<I, T> BehaviorTreeNode for QueryNode<I, T> where 
+    I: AsBlackboard,
+    T: Clone + 'static
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<(), ()>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), Result<(), ()>, Self> {
+        let board = input.blackboard();
+        let query = &self.query;
+        let found = board.with(&self.source, |values| {
+            query.run(values).into_iter().cloned().collect::<Vec<_>>()
+        }).unwrap_or_default();
+        if found.is_empty() {
+            return NodeResult::Terminal(Result::Err(()));
+        }
+        board.set(&self.binding, found[0].clone());
+        if let Option::Some(ref k) = self.all_binding {
+            board.set(k, found);
+        }
+        NodeResult::Terminal(Result::Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use blackboard::{Blackboard, Key};
+    use query_node::{Query, QueryNode};
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct Entity {
+        tag: &'static str,
+        dist: f64
+    }
+
+    fn entities() -> Vec<Entity> {
+        vec![
+            Entity { tag: "enemy", dist: 8.0 },
+            Entity { tag: "ally", dist: 1.0 },
+            Entity { tag: "enemy", dist: 3.0 },
+            Entity { tag: "enemy", dist: 12.0 }
+        ]
+    }
+
+    #[test]
+    fn query_test() {
+        let values = entities();
+        let query = Query::new()
+            .filter(|e: &Entity| e.tag == "enemy")
+            .filter(|e: &Entity| e.dist < 10.0)
+            .lowest(|e: &Entity| e.dist);
+        assert_eq!(query.run(&values), vec![&values[2], &values[0]]);
+        assert_eq!(query.clone().limit(1).run(&values), vec![&values[2]]);
+        let farthest = Query::new().highest(|e: &Entity| e.dist);
+        assert_eq!(farthest.first(&values), Option::Some(&values[3]));
+    }
+
+    #[test]
+    fn query_node_test() {
+        let board = Blackboard::new();
+        let seen = Key::<Vec<Entity>>::new("seen");
+        let target = Key::<Entity>::new("target");
+        let targets = Key::<Vec<Entity>>::new("targets");
+        let query = Query::new()
+            .filter(|e: &Entity| e.tag == "enemy" && e.dist < 10.0)
+            .lowest(|e: &Entity| e.dist);
+        let test_node = QueryNode::<Blackboard, _>::new(seen.clone(), query,
+            target.clone()).bind_all(targets.clone());
+        match test_node.clone().step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+        board.set(&seen, entities());
+        match test_node.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert_eq!(board.get(&target).map(|e| e.dist), Option::Some(3.0));
+        assert_eq!(board.with(&targets, |t| t.len()), Option::Some(2));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,540 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, NontermReturn};
+use num_traits::{FromPrimitive, ToPrimitive};
+use std::cmp::Ordering;
+#[cfg(feature = "rand")]
+use rand::{Rng, RngCore, SeedableRng, FromEntropy};
+#[cfg(feature = "rand")]
+use rand::rngs::StdRng;
+
+/// Source of randomness for randomized nodes. 
+///
+/// The source is owned by the node using it, and so is carried along from 
+/// step to step, which makes seeded sources fully deterministic. 
+pub trait RandomSource {
+    /// Draw a number uniformly distributed in the half-open range [0, 1). 
+    fn next_unit(&mut self) -> f64;
+}
+
+/// Small xorshift-based random source, which requires no dependencies and 
+/// is fully determined by its seed. 
+///
+/// It is meant for reproducible simulations and tests, not for anything 
+/// requiring statistical rigor. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct XorShiftSource {
+    state: u64
+}
+
+impl XorShiftSource {
+    /// Create a new xorshift source from the given seed. 
+    pub fn new(seed: u64) -> XorShiftSource {
+        XorShiftSource {
+            // The all-zeroes state is a fixed point of xorshift
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed }
+        }
+    }
+}
+
+impl RandomSource for XorShiftSource {
+    fn next_unit(&mut self) -> f64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        let bits = self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        bits as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Random source backed by a generator from the rand crate. 
+#[cfg(feature = "rand")]
+#[derive(Clone, Debug)]
+pub struct RngSource<R> where 
+    R: RngCore
+{
+    rng: R
+}
+
+#[cfg(feature = "rand")]
+impl<R> RngSource<R> where 
+    R: RngCore
+{
+    /// Wrap an existing generator. 
+    pub fn new(rng: R) -> RngSource<R> {
+        RngSource {
+            rng: rng
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RngSource<StdRng> {
+    /// Create a source from the standard generator seeded with the given 
+    /// seed. 
+    pub fn seeded(seed: u64) -> RngSource<StdRng> {
+        RngSource::new(StdRng::seed_from_u64(seed))
+    }
+}
+
+#[cfg(feature = "rand")]
+impl Default for RngSource<StdRng> {
+    fn default() -> RngSource<StdRng> {
+        RngSource::new(StdRng::from_entropy())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl<R> RandomSource for RngSource<R> where 
+    R: RngCore
+{
+    fn next_unit(&mut self) -> f64 {
+        self.rng.gen::<f64>()
+    }
+}
+
+/// Trait for the weights with which the children of a weighted random 
+/// selector are drawn. 
+pub trait WeightDecider {
+    /// Type of the enumerating discriminant. 
+    type Enum;
+    /// Type of the inputs of the subnodes. 
+    type Input;
+    /// Given a reference to the input and the discriminant of a subnode, 
+    /// return the weight of that subnode, which should be nonnegative. 
+    fn weight(&self, input: &Self::Input, ord: Self::Enum) -> f64;
+}
+
+/// Selector node which, instead of trying its nodes in discriminant order, 
+/// draws the next one to try at random, weighted by a WeightDecider. 
+///
+/// Nodes which have already failed are not drawn again, and the selector 
+/// succeeds as soon as a node succeeds, or fails with the failure value of 
+/// the last node once all of them have failed. If all the remaining nodes 
+/// have nonpositive weight, the first of them in discriminant order is 
+/// tried. The first node is drawn on the first step, as the weights may 
+/// depend on the input. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct WeightedRandomSelector<E, W, R, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    W: WeightDecider<Enum=E::Discriminant, Input=E::Input>,
+    R: RandomSource
+{
+    node: Option<E>,
+    failed: Vec<E::Discriminant>,
+    weights: W,
+    rng: R
+}
+
+impl<E, W, R, T, F> WeightedRandomSelector<E, W, R, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    W: WeightDecider<Enum=E::Discriminant, Input=E::Input>,
+    R: RandomSource
+{
+    /// Create a new weighted random selector, drawing with the given weights 
+    /// and random source. 
+    pub fn new(weights: W, rng: R) -> WeightedRandomSelector<E, W, R, T, F> {
+        WeightedRandomSelector {
+            node: Option::None,
+            failed: Vec::new(),
+            weights: weights,
+            rng: rng
+        }
+    }
+
+    fn draw(&mut self, input: &E::Input) -> Option<E::Discriminant> {
+        let mut candidates = Vec::new();
+        let mut index = 0;
+        while let Option::Some(e) = E::Discriminant::from_u64(index) {
+            if !self.failed.iter().any(|f| f.to_u64() == e.to_u64()) {
+                candidates.push((e, self.weights.weight(input, e).max(0.0)));
+            }
+            index += 1;
+        }
+        pick(&candidates, &mut self.rng)
+    }
+}
+
+fn pick<E, R>(candidates: &[(E, f64)], rng: &mut R) -> Option<E> where 
+    E: Copy,
+    R: RandomSource
+{
+    let total = candidates.iter().map(|&(_, w)| w).sum::<f64>();
+    if total.partial_cmp(&0.0) != Option::Some(Ordering::Greater) {
+        return candidates.first().map(|&(e, _)| e);
+    }
+    let mut point = rng.next_unit() * total;
+    for &(e, w) in candidates.iter() {
+        if point < w {
+            return Option::Some(e);
+        }
+        point -= w;
+    }
+    // Rounding can leave the point just past the last positive weight
+    candidates.iter().rev().find(|&&(_, w)| w > 0.0).map(|&(e, _)| e)
+}
+
+impl<E, W, R, T, F> BehaviorTreeNode for WeightedRandomSelector<E, W, R, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    W: WeightDecider<Enum=E::Discriminant, Input=E::Input>,
+    R: RandomSource
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = Result<(E::Discriminant, T), F>;
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut this = self;
+        let node = match this.node.take() {
+            Option::Some(n) => n,
+            Option::None => E::new(this.draw(input).unwrap())
+        };
+        let discriminant = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                this.node = Option::Some(m);
+                NodeResult::Nonterminal(
+                    NontermReturn::Nonterminal(discriminant, n),
+                    this
+                )
+            },
+            NodeResult::Terminal(Result::Ok(t)) => {
+                NodeResult::Terminal(Result::Ok((discriminant, t)))
+            },
+            NodeResult::Terminal(Result::Err(f)) => {
+                this.failed.push(discriminant);
+                match this.draw(input) {
+                    Option::Some(e) => {
+                        this.node = Option::Some(E::new(e));
+                        NodeResult::Nonterminal(
+                            NontermReturn::Terminal(discriminant, Result::Err(f)),
+                            this
+                        )
+                    },
+                    Option::None => NodeResult::Terminal(Result::Err(f))
+                }
+            }
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+/// Branch node which, on its first step, draws one of its nodes at random 
+/// with fixed probabilities, and then commits to it, exiting with its 
+/// discriminant and terminal once it terminates. 
+///
+/// The probabilities are given in discriminant order, and need not sum to 
+/// one, as they are normalized by their total. Nodes without a given 
+/// probability are never drawn, and if no node has positive probability, 
+/// the first node is run. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct ProbabilisticBranch<E, R> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive,
+    R: RandomSource
+{
+    node: Option<E>,
+    probabilities: Box<[f64]>,
+    rng: R
+}
+
+impl<E, R> ProbabilisticBranch<E, R> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive,
+    R: RandomSource
+{
+    /// Create a new probabilistic branch with the given probabilities, in 
+    /// discriminant order, drawing from the given random source. 
+    pub fn new<K>(probabilities: K, rng: R) -> ProbabilisticBranch<E, R> where 
+        K: IntoIterator<Item=f64>
+    {
+        ProbabilisticBranch {
+            node: Option::None,
+            probabilities: probabilities.into_iter()
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+            rng: rng
+        }
+    }
+}
+
+impl<E, R> BehaviorTreeNode for ProbabilisticBranch<E, R> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive,
+    R: RandomSource
+{
+    type Input = E::Input;
+    type Nonterminal = (E::Discriminant, E::Nonterminal);
+    type Terminal = (E::Discriminant, E::Terminal);
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut this = self;
+        let node = match this.node.take() {
+            Option::Some(n) => n,
+            Option::None => {
+                let mut candidates = Vec::new();
+                let mut index = 0;
+                while let Option::Some(e) = E::Discriminant::from_u64(index) {
+                    let p = this.probabilities.get(index as usize)
+                        .map_or(0.0, |p| p.max(0.0));
+                    candidates.push((e, p));
+                    index += 1;
+                }
+                E::new(pick(&candidates, &mut this.rng).unwrap())
+            }
+        };
+        let discriminant = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                this.node = Option::Some(m);
+                NodeResult::Nonterminal((discriminant, n), this)
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal((discriminant, t))
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{MachineWrapper, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use random_selector::{RandomSource, WeightDecider, XorShiftSource};
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    #[derive(Copy, Clone, Default)]
+    struct CountUp;
+
+    impl InternalTransition for CountUp {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, Result<i64, i64>>;
+
+        fn step(&self, input: &i64, state: &mut i64) -> Self::Action {
+            *state += 1;
+            if *state < 3 {
+                Statepoint::Nonterminal(*state)
+            } else if *input >= 0 {
+                Statepoint::Terminal(Result::Ok(*state))
+            } else {
+                Statepoint::Terminal(Result::Err(*state))
+            }
+        }
+    }
+
+    fn sign_check(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input >= 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum OptionEnum {
+        Check,
+        Count
+    }
+
+    enum Options {
+        Check(PredicateWait<i64, i64, Result<i64, i64>,
+            fn(&i64) -> Statepoint<i64, Result<i64, i64>>>),
+        Count(MachineWrapper<InternalStateMachine<'static, CountUp>, i64,
+            Result<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Options {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = Result<i64, i64>;
+
+        fn step(self, input: &i64) -> NodeResult<i64, Result<i64, i64>, Self> {
+            match self {
+                Options::Check(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Options::Check(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Options::Count(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Options::Count(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Options {
+        type Discriminant = OptionEnum;
+
+        fn new(thing: OptionEnum) -> Options {
+            match thing {
+                OptionEnum::Check => Options::Check(PredicateWait::new(sign_check)),
+                OptionEnum::Count => Options::Count(MachineWrapper::new(
+                    InternalStateMachine::new(CountUp, 0)
+                ))
+            }
+        }
+
+        fn discriminant_of(&self) -> OptionEnum {
+            match self {
+                Options::Check(_) => OptionEnum::Check,
+                Options::Count(_) => OptionEnum::Count
+            }
+        }
+    }
+
+    struct Weights(f64, f64);
+
+    impl WeightDecider for Weights {
+        type Enum = OptionEnum;
+        type Input = i64;
+
+        fn weight(&self, _i: &i64, ord: OptionEnum) -> f64 {
+            match ord {
+                OptionEnum::Check => self.0,
+                OptionEnum::Count => self.1
+            }
+        }
+    }
+
+    #[test]
+    fn xorshift_range_test() {
+        let mut source = XorShiftSource::new(0);
+        for _ in 0..1000 {
+            let val = source.next_unit();
+            assert!((0.0..1.0).contains(&val));
+        }
+    }
+
+    #[test]
+    fn weighted_failure_test() {
+        use random_selector::WeightedRandomSelector;
+        let test_node = WeightedRandomSelector::<Options, _, _, _, _>::new(
+            Weights(1.0, 0.0),
+            XorShiftSource::new(7)
+        );
+        // Only the check has weight, so it is drawn first
+        let mut test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, v), n) => {
+                assert_eq!(e, OptionEnum::Check);
+                assert_eq!(v, Result::Err(-1));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        for i in 1..3 {
+            test_node_1 = match test_node_1.step(&-1) {
+                NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                    assert_eq!(e, OptionEnum::Count);
+                    assert_eq!(v, i);
+                    n
+                },
+                _ => unreachable!("Expected subordinate nonterminal transition")
+            };
+        }
+        match test_node_1.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(3)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn weighted_draw_test() {
+        use random_selector::WeightedRandomSelector;
+        let mut source = XorShiftSource::new(42);
+        let mut checks = 0;
+        for _ in 0..200 {
+            let test_node = WeightedRandomSelector::<Options, _, _, _, _>::new(
+                Weights(3.0, 1.0),
+                source
+            );
+            source.next_unit();
+            match test_node.step(&1) {
+                NodeResult::Terminal(Result::Ok((OptionEnum::Check, _))) => {
+                    checks += 1
+                },
+                NodeResult::Nonterminal(NontermReturn::Nonterminal(e, _), _) => {
+                    assert_eq!(e, OptionEnum::Count)
+                },
+                _ => unreachable!("Expected check success or count step")
+            };
+        }
+        assert!(checks > 120 && checks < 180);
+    }
+
+    #[test]
+    fn probabilistic_commit_test() {
+        use random_selector::ProbabilisticBranch;
+        let test_node = ProbabilisticBranch::<Options, _>::new(
+            vec![0.0, 1.0],
+            XorShiftSource::new(3)
+        );
+        let mut test_node_1 = test_node;
+        // The count is committed to even though the input would let the check 
+        // succeed
+        for i in 1..3 {
+            test_node_1 = match test_node_1.step(&1) {
+                NodeResult::Nonterminal((e, v), n) => {
+                    assert_eq!(e, OptionEnum::Count);
+                    assert_eq!(v, i);
+                    n
+                },
+                _ => unreachable!("Expected nonterminal transition")
+            };
+        }
+        match test_node_1.step(&-1) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(t, (OptionEnum::Count, Result::Err(3)))
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn probabilistic_draw_test() {
+        use random_selector::ProbabilisticBranch;
+        let mut source = XorShiftSource::new(11);
+        let mut checks = 0;
+        for _ in 0..200 {
+            let test_node = ProbabilisticBranch::<Options, _>::new(
+                vec![0.25, 0.75],
+                source
+            );
+            source.next_unit();
+            match test_node.step(&1) {
+                NodeResult::Terminal((OptionEnum::Check, _)) => checks += 1,
+                NodeResult::Nonterminal((e, _), _) => {
+                    assert_eq!(e, OptionEnum::Count)
+                },
+                _ => unreachable!("Expected check terminal or count step")
+            };
+        }
+        assert!(checks > 20 && checks < 80);
+    }
+}
\ No newline at end of file
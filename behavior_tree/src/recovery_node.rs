@@ -0,0 +1,219 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+/// Nonterminal type of the recovery node, where each variant carries the 
+/// index of the attempt at running the main node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecoveryNonterm<N, F, R, S> {
+    /// The main node was stepped, and did not terminate. 
+    Main(usize, N),
+    /// The main node failed, and the recovery node was started. 
+    Failed(usize, F),
+    /// The recovery node was stepped, and did not terminate. 
+    Recovery(usize, R),
+    /// The recovery node succeeded, and the main node was restarted. 
+    Recovered(usize, S)
+}
+
+/// Terminal failure type of the recovery node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecoveryFailure<F, G> {
+    /// The main node failed after running out of retries. 
+    Exhausted(F),
+    /// The recovery node failed. 
+    Unrecoverable(G)
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum RecoveryState<M, R> {
+    Main(M),
+    Recovery(R)
+}
+
+/// Node which runs a main node, and on its failure, runs a recovery node and 
+/// then retries a fresh main node, up to a given number of retries. 
+///
+/// The recovery node exits with the success value of the main node as soon 
+/// as it succeeds. It fails with the failure value of the main node when it 
+/// fails with no retries left, or with the failure value of the recovery 
+/// node as soon as that fails. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RecoveryNode<M, R, MM, MR> where 
+    MM: Fn() -> M,
+    MR: Fn() -> R
+{
+    state: RecoveryState<M, R>,
+    main: MM,
+    recovery: MR,
+    attempt: usize,
+    retries: usize
+}
+
+impl<M, R, MM, MR> RecoveryNode<M, R, MM, MR> where 
+    MM: Fn() -> M,
+    MR: Fn() -> R
+{
+    /// Create a new recovery node from constructors of the main and recovery 
+    /// nodes, retrying the main node at most the given number of times. 
+    pub fn new(main: MM, recovery: MR, retries: usize) -> RecoveryNode<M, R, MM, MR> {
+        RecoveryNode {
+            state: RecoveryState::Main(main()),
+            main: main,
+            recovery: recovery,
+            attempt: 0,
+            retries: retries
+        }
+    }
+}
+
+impl<M, R, MM, MR, T, F, S, G> BehaviorTreeNode for RecoveryNode<M, R, MM, MR> where 
+    M: BehaviorTreeNode<Terminal=Result<T, F>>,
+    R: BehaviorTreeNode<Input=M::Input, Terminal=Result<S, G>>,
+    MM: Fn() -> M,
+    MR: Fn() -> R
+{
+    type Input = M::Input;
+    type Nonterminal = RecoveryNonterm<M::Nonterminal, F, R::Nonterminal, S>;
+    type Terminal = Result<T, RecoveryFailure<F, G>>;
+
+    #[inline]
+    fn step(self, input: &M::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut this = self;
+        let attempt = this.attempt;
+        match this.state {
+            RecoveryState::Main(m) => match m.step(input) {
+                NodeResult::Nonterminal(n, k) => {
+                    this.state = RecoveryState::Main(k);
+                    NodeResult::Nonterminal(RecoveryNonterm::Main(attempt, n), this)
+                },
+                NodeResult::Terminal(Result::Ok(t)) => {
+                    NodeResult::Terminal(Result::Ok(t))
+                },
+                NodeResult::Terminal(Result::Err(f)) => if attempt < this.retries {
+                    this.state = RecoveryState::Recovery((this.recovery)());
+                    NodeResult::Nonterminal(RecoveryNonterm::Failed(attempt, f), this)
+                } else {
+                    NodeResult::Terminal(Result::Err(RecoveryFailure::Exhausted(f)))
+                }
+            },
+            RecoveryState::Recovery(r) => match r.step(input) {
+                NodeResult::Nonterminal(n, k) => {
+                    this.state = RecoveryState::Recovery(k);
+                    NodeResult::Nonterminal(RecoveryNonterm::Recovery(attempt, n), this)
+                },
+                NodeResult::Terminal(Result::Ok(s)) => {
+                    this.attempt += 1;
+                    this.state = RecoveryState::Main((this.main)());
+                    NodeResult::Nonterminal(RecoveryNonterm::Recovered(attempt, s), this)
+                },
+                NodeResult::Terminal(Result::Err(g)) => NodeResult::Terminal(
+                    Result::Err(RecoveryFailure::Unrecoverable(g))
+                )
+            }
+        }
+    }
+
+    fn abort(self) {
+        match self.state {
+            RecoveryState::Main(m) => m.abort(),
+            RecoveryState::Recovery(r) => r.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use recovery_node::{RecoveryNode, RecoveryNonterm, RecoveryFailure};
+
+    fn attempt(input: &i64) -> Statepoint<(), Result<i64, i64>> {
+        if *input > 10 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    fn recover(input: &i64) -> Statepoint<i64, Result<(), ()>> {
+        if *input == 0 {
+            Statepoint::Nonterminal(0)
+        } else if *input > 0 {
+            Statepoint::Terminal(Result::Ok(()))
+        } else {
+            Statepoint::Terminal(Result::Err(()))
+        }
+    }
+
+    #[test]
+    fn recovery_success_test() {
+        let test_node = RecoveryNode::new(
+            || PredicateWait::new(attempt),
+            || PredicateWait::new(recover),
+            2
+        );
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(RecoveryNonterm::Failed(0, f), n) => {
+                assert_eq!(f, 1);
+                n
+            },
+            _ => unreachable!("Expected main failure")
+        };
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(RecoveryNonterm::Recovery(0, _), n) => n,
+            _ => unreachable!("Expected recovery nonterminal")
+        };
+        let test_node_3 = match test_node_2.step(&1) {
+            NodeResult::Nonterminal(RecoveryNonterm::Recovered(0, ()), n) => n,
+            _ => unreachable!("Expected recovery success")
+        };
+        match test_node_3.step(&11) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(11)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn recovery_exhausted_test() {
+        let test_node = RecoveryNode::new(
+            || PredicateWait::new(attempt),
+            || PredicateWait::new(recover),
+            1
+        );
+        let mut test_node_1 = test_node;
+        for _ in 0..2 {
+            test_node_1 = match test_node_1.step(&1) {
+                NodeResult::Nonterminal(_, n) => n,
+                _ => unreachable!("Expected nonterminal transition")
+            };
+        }
+        match test_node_1.step(&2) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(t, Result::Err(RecoveryFailure::Exhausted(2)))
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn recovery_unrecoverable_test() {
+        let test_node = RecoveryNode::new(
+            || PredicateWait::new(attempt),
+            || PredicateWait::new(recover),
+            1
+        );
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&-1) {
+            NodeResult::Terminal(t) => {
+                assert_eq!(t, Result::Err(RecoveryFailure::Unrecoverable(())))
+            },
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,286 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+
+/// Nonterminal type of the repeat wrapper, where each variant carries the 
+/// index of the repetition. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RepeatNonterm<N, T> {
+    /// The node was stepped, and did not terminate. 
+    Running(usize, N),
+    /// The node terminated with the contained terminal, and was started 
+    /// over. 
+    Repeated(usize, T)
+}
+
+/// Wrapper which runs fresh instances of a node one after another, either a 
+/// given number of times or forever, for background behaviors. 
+///
+/// The terminal of each repetition but the last is surfaced as a 
+/// nonterminal, and the wrapper exits with the terminal of the last 
+/// repetition. A wrapper repeating forever never exits. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Repeat<N, M> where 
+    M: Fn() -> N
+{
+    node: N,
+    maker: M,
+    repetition: usize,
+    repetitions: Option<usize>
+}
+
+impl<N, M> Repeat<N, M> where 
+    M: Fn() -> N
+{
+    /// Create a new repeat wrapper from a constructor of the node, running 
+    /// it the given number of times, which has to be at least one. 
+    pub fn times(maker: M, repetitions: usize) -> Repeat<N, M> {
+        assert!(repetitions > 0, "Repeat requires at least one repetition");
+        Repeat {
+            node: maker(),
+            maker: maker,
+            repetition: 0,
+            repetitions: Option::Some(repetitions)
+        }
+    }
+
+    /// Create a new repeat wrapper from a constructor of the node, running 
+    /// it forever. 
+    pub fn forever(maker: M) -> Repeat<N, M> {
+        Repeat {
+            node: maker(),
+            maker: maker,
+            repetition: 0,
+            repetitions: Option::None
+        }
+    }
+}
+
+impl<N, M> BehaviorTreeNode for Repeat<N, M> where 
+    N: BehaviorTreeNode,
+    M: Fn() -> N
+{
+    type Input = N::Input;
+    type Nonterminal = RepeatNonterm<N::Nonterminal, N::Terminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal,
+        N::Terminal, Self>
+    {
+        let repetition = self.repetition;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                RepeatNonterm::Running(repetition, n),
+                Repeat {
+                    node: m,
+                    maker: self.maker,
+                    repetition: repetition,
+                    repetitions: self.repetitions
+                }
+            ),
+            NodeResult::Terminal(t) => {
+                if self.repetitions.map_or(false, |r| repetition + 1 >= r) {
+                    NodeResult::Terminal(t)
+                } else {
+                    NodeResult::Nonterminal(
+                        RepeatNonterm::Repeated(repetition, t),
+                        Repeat {
+                            node: (self.maker)(),
+                            maker: self.maker,
+                            repetition: repetition + 1,
+                            repetitions: self.repetitions
+                        }
+                    )
+                }
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+
+/// Nonterminal type of the retry wrapper, where each variant carries the 
+/// index of the attempt. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RetryNonterm<N, F> {
+    /// The node was stepped, and did not terminate. 
+    Running(usize, N),
+    /// The node failed with the contained failure value, and was started 
+    /// over. 
+    Failed(usize, F)
+}
+
+/// Wrapper which runs fresh instances of a node until one succeeds, up to 
+/// a given number of attempts. 
+///
+/// The wrapper succeeds with the success value of the first attempt to 
+/// succeed, and fails with the failure value of the last attempt once it 
+/// runs out of attempts. Failures of earlier attempts are surfaced as 
+/// nonterminals. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Retry<N, M> where 
+    M: Fn() -> N
+{
+    node: N,
+    maker: M,
+    attempt: usize,
+    attempts: usize
+}
+
+impl<N, M> Retry<N, M> where 
+    M: Fn() -> N
+{
+    /// Create a new retry wrapper from a constructor of the node, making at 
+    /// most the given number of attempts, which has to be at least one. 
+    pub fn new(maker: M, attempts: usize) -> Retry<N, M> {
+        assert!(attempts > 0, "Retry requires at least one attempt");
+        Retry {
+            node: maker(),
+            maker: maker,
+            attempt: 0,
+            attempts: attempts
+        }
+    }
+}
+
+impl<N, M, T, F> BehaviorTreeNode for Retry<N, M> where 
+    N: BehaviorTreeNode<Terminal=Result<T, F>>,
+    M: Fn() -> N
+{
+    type Input = N::Input;
+    type Nonterminal = RetryNonterm<N::Nonterminal, F>;
+    type Terminal = Result<T, F>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal,
+        Result<T, F>, Self>
+    {
+        let attempt = self.attempt;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                RetryNonterm::Running(attempt, n),
+                Retry {
+                    node: m,
+                    maker: self.maker,
+                    attempt: attempt,
+                    attempts: self.attempts
+                }
+            ),
+            NodeResult::Terminal(Result::Ok(t)) => NodeResult::Terminal(Result::Ok(t)),
+            NodeResult::Terminal(Result::Err(f)) => if attempt + 1 < self.attempts {
+                NodeResult::Nonterminal(
+                    RetryNonterm::Failed(attempt, f),
+                    Retry {
+                        node: (self.maker)(),
+                        maker: self.maker,
+                        attempt: attempt + 1,
+                        attempts: self.attempts
+                    }
+                )
+            } else {
+                NodeResult::Terminal(Result::Err(f))
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{Evaluation, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use repeat_wrappers::{Repeat, RepeatNonterm, Retry, RetryNonterm};
+
+    fn wait_nonzero(input: &i64) -> Statepoint<(), i64> {
+        if *input == 0 {
+            Statepoint::Nonterminal(())
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[test]
+    fn repeat_times_test() {
+        let test_node = Repeat::times(|| PredicateWait::new(wait_nonzero), 2);
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(RepeatNonterm::Running(0, ()), n) => n,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        let test_node_2 = match test_node_1.step(&4) {
+            NodeResult::Nonterminal(RepeatNonterm::Repeated(0, v), n) => {
+                assert_eq!(v, 4);
+                n
+            },
+            _ => unreachable!("Expected repeated nonterminal")
+        };
+        match test_node_2.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, 5),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn repeat_forever_test() {
+        let mut test_node = Repeat::forever(|| Evaluation::new(|i: &i64| *i));
+        for i in 0..100 {
+            test_node = match test_node.step(&i) {
+                NodeResult::Nonterminal(RepeatNonterm::Repeated(r, v), n) => {
+                    assert_eq!(r as i64, i);
+                    assert_eq!(v, i);
+                    n
+                },
+                _ => unreachable!("Expected repeated nonterminal")
+            };
+        }
+    }
+
+    fn check(input: &i64) -> Statepoint<(), Result<i64, i64>> {
+        if *input == 0 {
+            Statepoint::Nonterminal(())
+        } else if *input > 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    #[test]
+    fn retry_success_test() {
+        let test_node = Retry::new(|| PredicateWait::new(check), 3);
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(RetryNonterm::Failed(0, f), n) => {
+                assert_eq!(f, -1);
+                n
+            },
+            _ => unreachable!("Expected failed nonterminal")
+        };
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(RetryNonterm::Running(1, ()), n) => n,
+            _ => unreachable!("Expected running nonterminal")
+        };
+        match test_node_2.step(&2) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(2)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn retry_exhausted_test() {
+        let test_node = Retry::new(|| PredicateWait::new(check), 2);
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(RetryNonterm::Failed(0, _), n) => n,
+            _ => unreachable!("Expected failed nonterminal")
+        };
+        match test_node_1.step(&-2) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(-2)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
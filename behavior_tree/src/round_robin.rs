@@ -0,0 +1,184 @@
+use behavior_tree_node::BehaviorTreeNode;
+use serial_node::{EnumNode, SerialBranchNode, SerialDecider, NontermDecision,
+    TermDecision};
+use std::marker::PhantomData;
+use std::cell::Cell;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+/// Rotates through all nodes in discriminant order, moving on to the next 
+/// one whenever the current one terminates, and wrapping around from the 
+/// last node back to the first. 
+///
+/// Each wraparound completes a cycle, and once the configured number of 
+/// cycles has been completed, the rotation exits with the terminal of the 
+/// last node instead of wrapping around again. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct RoundRobin<E, I, N, T> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    cycles: usize,
+    completed: Cell<usize>,
+    _who_cares: PhantomData<(E, I, N, T)>
+}
+
+impl<E, I, N, T> RoundRobin<E, I, N, T> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    /// Create a new round robin decider which exits after the given number 
+    /// of cycles. 
+    pub fn new(cycles: usize) -> RoundRobin<E, I, N, T> {
+        RoundRobin {
+            cycles: cycles,
+            completed: Cell::new(0),
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Get the number of cycles completed so far. 
+    pub fn completed(&self) -> usize {
+        self.completed.get()
+    }
+}
+
+impl<E, I, N, T> SerialDecider for RoundRobin<E, I, N, T> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = T;
+
+    fn on_nonterminal(&self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N, T> {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&self, _i: &I, ordinal: E, statept: T) -> TermDecision<E, T, T> {
+        match E::from_u64(ordinal.to_u64().unwrap()+1) {
+            Option::Some(e) => TermDecision::Trans(e, statept),
+            Option::None => {
+                self.completed.set(self.completed.get() + 1);
+                if self.completed.get() >= self.cycles {
+                    TermDecision::Exit(statept)
+                } else {
+                    TermDecision::Trans(E::from_u64(0).unwrap(), statept)
+                }
+            }
+        }
+    }
+}
+
+/// Round robin node over the variants of an enumerable node. 
+pub type RoundRobinNode<E> = SerialBranchNode<E, RoundRobin<
+    <E as EnumNode>::Discriminant, <E as BehaviorTreeNode>::Input,
+    <E as BehaviorTreeNode>::Nonterminal, <E as BehaviorTreeNode>::Terminal>>;
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    fn arrive(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum WaypointEnum {
+        East,
+        West
+    }
+
+    enum Waypoints {
+        East(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>),
+        West(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Waypoints {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Self> {
+            match self {
+                Waypoints::East(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Waypoints::East(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Waypoints::West(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Waypoints::West(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Waypoints {
+        type Discriminant = WaypointEnum;
+
+        fn new(thing: WaypointEnum) -> Waypoints {
+            match thing {
+                WaypointEnum::East => Waypoints::East(PredicateWait::new(arrive)),
+                WaypointEnum::West => Waypoints::West(PredicateWait::new(arrive))
+            }
+        }
+
+        fn discriminant_of(&self) -> WaypointEnum {
+            match self {
+                Waypoints::East(_) => WaypointEnum::East,
+                Waypoints::West(_) => WaypointEnum::West
+            }
+        }
+    }
+
+    #[test]
+    fn round_robin_test() {
+        use round_robin::{RoundRobin, RoundRobinNode};
+        let test_node = RoundRobinNode::<Waypoints>::new(
+            RoundRobin::new(2),
+            WaypointEnum::East
+        );
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, _), n) => {
+                assert_eq!(e, WaypointEnum::East);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        // Wraps around after the first cycle
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, _), n) => {
+                assert_eq!(e, WaypointEnum::West);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_3 = match test_node_2.step(&5) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, WaypointEnum::East);
+                assert_eq!(v, 5);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        let test_node_4 = match test_node_3.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_4.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, -1),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,433 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use blackboard::{AsBlackboard, Blackboard, Key};
+use expression::Value;
+use num_traits::{FromPrimitive, ToPrimitive};
+use rhai::{Dynamic, Engine, Scope, AST};
+use serial_node::{NontermDecision, SerialDecider, TermDecision};
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// Number of operations a script compiled with the default engine may run 
+/// before it fails, so that a script which never finishes cannot hang the 
+/// tree. 
+pub const MAX_OPERATIONS: u64 = 100_000;
+
+/// Depth of nested function calls a script compiled with the default engine 
+/// may reach before it fails. 
+pub const MAX_CALL_LEVELS: usize = 32;
+
+/// Error of compiling or running a script. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum ScriptError {
+    /// The source failed to compile, with the message of the engine. 
+    Parse(String),
+    /// The script failed while running, with the message of the engine. 
+    Eval(String),
+    /// The script produced a value of the named type, which has no 
+    /// counterpart among the values of entries. 
+    Type(String),
+    /// The script decided to transition to an integer which is no 
+    /// discriminant. 
+    State(i64)
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScriptError::Parse(ref m) => write!(f, "parse error: {}", m),
+            ScriptError::Eval(ref m) => write!(f, "evaluation error: {}", m),
+            ScriptError::Type(ref t) => write!(f, "unsupported value of {}", t),
+            ScriptError::State(s) => write!(f, "no state numbered {}", s)
+        }
+    }
+}
+
+fn to_dynamic(value: Value) -> Dynamic {
+    match value {
+        Value::Int(i) => Dynamic::from(i),
+        Value::Float(f) => Dynamic::from(f),
+        Value::Bool(b) => Dynamic::from(b),
+        Value::Str(s) => Dynamic::from(s)
+    }
+}
+
+fn from_dynamic(value: Dynamic) -> Result<Option<Value>, ScriptError> {
+    if value.is_unit() {
+        Result::Ok(Option::None)
+    } else if let Result::Ok(i) = value.as_int() {
+        Result::Ok(Option::Some(Value::Int(i)))
+    } else if let Result::Ok(f) = value.as_float() {
+        Result::Ok(Option::Some(Value::Float(f)))
+    } else if let Result::Ok(b) = value.as_bool() {
+        Result::Ok(Option::Some(Value::Bool(b)))
+    } else if value.is_string() {
+        let name = value.type_name();
+        value.into_string().map(|s| Option::Some(Value::Str(s)))
+            .map_err(|_| ScriptError::Type(name.to_owned()))
+    } else {
+        Result::Err(ScriptError::Type(value.type_name().to_owned()))
+    }
+}
+
+fn store(board: &Blackboard, name: &str, value: Value) {
+    let name = name.to_owned();
+    match value {
+        Value::Int(i) => board.set(&Key::<i64>::new(name), i),
+        Value::Float(f) => board.set(&Key::<f64>::new(name), f),
+        Value::Bool(b) => board.set(&Key::<bool>::new(name), b),
+        Value::Str(s) => board.set(&Key::<String>::new(name), s)
+    };
+}
+
+/// Compiled Rhai script run against the blackboard. 
+///
+/// Each declared entry of the blackboard is bound to a variable of the same 
+/// name before the script runs, or to `()` if the entry is missing, and the 
+/// variables the script changed are written back to the blackboard after it 
+/// finishes, as entries of type i64, f64, bool or String. 
+#[derive(Clone)]
+pub struct Script {
+    engine: Rc<Engine>,
+    ast: Rc<AST>,
+    source: String,
+    entries: Vec<String>
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Script")
+            .field("source", &self.source)
+            .field("entries", &self.entries)
+            .finish()
+    }
+}
+
+impl PartialEq for Script {
+    fn eq(&self, other: &Script) -> bool {
+        self.source == other.source && self.entries == other.entries
+    }
+}
+
+impl Script {
+    /// Compile a script with a default engine, limited to MAX_OPERATIONS 
+    /// operations and MAX_CALL_LEVELS nested calls per run. 
+    pub fn compile(source: &str) -> Result<Script, ScriptError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(MAX_CALL_LEVELS);
+        Script::compile_with(Rc::new(engine), source)
+    }
+
+    /// Compile a script with the given engine, such as one with functions 
+    /// registered for the scripts to call. 
+    ///
+    /// The script runs under the limits of the given engine only, so an 
+    /// engine without limits on its operations lets a script which never 
+    /// finishes hang the tree. 
+    pub fn compile_with(engine: Rc<Engine>, source: &str) ->
+        Result<Script, ScriptError>
+    {
+        let ast = engine.compile(source)
+            .map_err(|e| ScriptError::Parse(e.to_string()))?;
+        Result::Ok(Script {
+            engine: engine,
+            ast: Rc::new(ast),
+            source: source.to_owned(),
+            entries: Vec::new()
+        })
+    }
+
+    /// Declare an entry of the blackboard the script reads and writes. 
+    pub fn entry<S>(self, name: S) -> Script where 
+        S: Into<String>
+    {
+        let mut mut_self = self;
+        mut_self.entries.push(name.into());
+        mut_self
+    }
+
+    /// Get the source the script was compiled from. 
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Run the script against the blackboard, returning the value of its 
+    /// last statement, or none if it is `()`. 
+    pub fn evaluate(&self, board: &Blackboard) ->
+        Result<Option<Value>, ScriptError>
+    {
+        self.run(board, Vec::new())
+    }
+
+    fn run(&self, board: &Blackboard, variables: Vec<(&'static str, Value)>) ->
+        Result<Option<Value>, ScriptError>
+    {
+        let mut scope = Scope::new();
+        let before: Vec<_> = self.entries.iter()
+            .map(|e| Value::lookup(board, e))
+            .collect();
+        for (name, value) in self.entries.iter().zip(before.iter()) {
+            let value = value.clone().map_or(Dynamic::UNIT, to_dynamic);
+            scope.push_dynamic(name.clone(), value);
+        }
+        for (name, value) in variables {
+            scope.push_dynamic(name, to_dynamic(value));
+        }
+        let result = self.engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast)
+            .map_err(|e| ScriptError::Eval(e.to_string()))?;
+        for (name, value) in self.entries.iter().zip(before) {
+            let after = match scope.get_value::<Dynamic>(name) {
+                Option::Some(d) => from_dynamic(d)?,
+                Option::None => Option::None
+            };
+            if let Option::Some(a) = after {
+                if value.as_ref() != Option::Some(&a) {
+                    store(board, name, a);
+                }
+            }
+        }
+        from_dynamic(result)
+    }
+}
+
+/// Leaf node running a script against the blackboard of its input on each 
+/// step. 
+///
+/// The node succeeds if the script evaluates to true, fails if it evaluates 
+/// to false, cannot run or evaluates to anything else, and keeps running if 
+/// it evaluates to `()`. 
+#[derive(PartialEq, Debug)]
+pub struct ScriptLeaf<I> where 
+    I: AsBlackboard
+{
+    script: Script,
+    _junk: PhantomData<fn(&I)>
+}
+
+impl<I> Clone for ScriptLeaf<I> where 
+    I: AsBlackboard
+{
+    fn clone(&self) -> Self {
+        ScriptLeaf::new(self.script.clone())
+    }
+}
+
+impl<I> ScriptLeaf<I> where 
+    I: AsBlackboard
+{
+    /// Create a new scripted leaf. 
+    pub fn new(script: Script) -> ScriptLeaf<I> {
+        ScriptLeaf {
+            script: script,
+            _junk: PhantomData
+        }
+    }
+
+    /// Compile a scripted leaf from the given source, reading and writing 
+    /// the given entries. 
+    pub fn compile(source: &str, entries: &[&str]) ->
+        Result<ScriptLeaf<I>, ScriptError>
+    {
+        let script = entries.iter()
+            .fold(Script::compile(source)?, |s, e| s.entry(*e));
+        Result::Ok(ScriptLeaf::new(script))
+    }
+}
+
+impl<I> BehaviorTreeNode for ScriptLeaf<I> where 
+    I: AsBlackboard
+{
+    type Input = I;
+    type Nonterminal = ();
+    type Terminal = Result<(), ()>;
+
+    #[inline]
+    fn step(self, input: &I) -> NodeResult<(), Result<(), ()>, Self> {
+        match self.script.evaluate(input.blackboard()) {
+            Result::Ok(Option::None) => NodeResult::Nonterminal((), self),
+            Result::Ok(Option::Some(Value::Bool(true))) => {
+                NodeResult::Terminal(Result::Ok(()))
+            },
+            _ => NodeResult::Terminal(Result::Err(()))
+        }
+    }
+}
+
+/// Serial decider running a script against the blackboard of the input 
+/// whenever the current subnode steps. 
+///
+/// Besides the declared entries, the script sees the variable `state`, the 
+/// number of the discriminant of the current subnode, and `terminal`, which 
+/// is whether the subnode just terminated. If the script evaluates to an 
+/// integer, the decider transitions to the subnode with that discriminant. 
+/// If it evaluates to `()`, the decider keeps stepping a running subnode, 
+/// and exits with no value after a terminated one. Otherwise, the decider 
+/// exits with the value, or with the error of the script. 
+pub struct ScriptDecider<E, I, N, T> where 
+    E: FromPrimitive + ToPrimitive,
+    I: AsBlackboard
+{
+    script: Script,
+    _junk: PhantomData<fn(&I, E, N, T)>
+}
+
+impl<E, I, N, T> fmt::Debug for ScriptDecider<E, I, N, T> where 
+    E: FromPrimitive + ToPrimitive,
+    I: AsBlackboard
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ScriptDecider")
+            .field("script", &self.script)
+            .finish()
+    }
+}
+
+impl<E, I, N, T> Clone for ScriptDecider<E, I, N, T> where 
+    E: FromPrimitive + ToPrimitive,
+    I: AsBlackboard
+{
+    fn clone(&self) -> Self {
+        ScriptDecider::new(self.script.clone())
+    }
+}
+
+impl<E, I, N, T> ScriptDecider<E, I, N, T> where 
+    E: FromPrimitive + ToPrimitive,
+    I: AsBlackboard
+{
+    /// Create a new scripted decider. 
+    pub fn new(script: Script) -> ScriptDecider<E, I, N, T> {
+        ScriptDecider {
+            script: script,
+            _junk: PhantomData
+        }
+    }
+
+    fn decide(&self, input: &I, state: E, terminal: bool) ->
+        Result<Option<Value>, ScriptError>
+    {
+        let state = state.to_i64().expect("Discriminant is not an integer");
+        self.script.run(input.blackboard(), vec![
+            ("state", Value::Int(state)),
+            ("terminal", Value::Bool(terminal))
+        ])
+    }
+}
+
+fn state_of<E>(state: i64) -> Result<E, ScriptError> where 
+    E: FromPrimitive
+{
+    E::from_i64(state).ok_or(ScriptError::State(state))
+}
+
+impl<E, I, N, T> SerialDecider for ScriptDecider<E, I, N, T> where 
+    E: FromPrimitive + ToPrimitive,
+    I: AsBlackboard
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = T;
+    type Exit = Result<Option<Value>, ScriptError>;
+
+    fn on_nonterminal(&self, input: &I, state: E, value: N) ->
+        NontermDecision<E, N, Self::Exit>
+    {
+        match self.decide(input, state, false) {
+            Result::Ok(Option::None) => NontermDecision::Step(value),
+            Result::Ok(Option::Some(Value::Int(i))) => match state_of(i) {
+                Result::Ok(e) => NontermDecision::Trans(e, value),
+                Result::Err(e) => NontermDecision::Exit(Result::Err(e))
+            },
+            other => NontermDecision::Exit(other)
+        }
+    }
+
+    fn on_terminal(&self, input: &I, state: E, value: T) ->
+        TermDecision<E, T, Self::Exit>
+    {
+        match self.decide(input, state, true) {
+            Result::Ok(Option::Some(Value::Int(i))) => match state_of(i) {
+                Result::Ok(e) => TermDecision::Trans(e, value),
+                Result::Err(e) => TermDecision::Exit(Result::Err(e))
+            },
+            other => TermDecision::Exit(other)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use blackboard::{Blackboard, Key};
+    use expression::Value;
+    use scripting::{Script, ScriptDecider, ScriptError, ScriptLeaf};
+    use serial_node::{NontermDecision, SerialDecider, TermDecision};
+
+    #[test]
+    fn script_leaf_test() {
+        let board = Blackboard::new();
+        board.set(&Key::<i64>::new("ammo"), 2);
+        let leaf = ScriptLeaf::<Blackboard>::compile(
+            "if ammo == 0 { false } else { ammo -= 1; if ammo == 0 { true } }",
+            &["ammo"]
+        ).unwrap();
+        let leaf_1 = match leaf.step(&board) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(board.get(&Key::<i64>::new("ammo")), Option::Some(1));
+        match leaf_1.clone().step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match leaf_1.step(&board) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(())),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match ScriptLeaf::<Blackboard>::compile("ammo +", &[]) {
+            Result::Err(ScriptError::Parse(_)) => (),
+            _ => unreachable!("Expected parse error")
+        };
+    }
+
+    #[test]
+    fn script_decider_test() {
+        let board = Blackboard::new();
+        board.set(&Key::<i64>::new("hp"), 5);
+        let decider = ScriptDecider::<u8, Blackboard, (), bool>::new(
+            Script::compile(
+                "if terminal { if state == 1 { \"done\" } else { 3 } } \
+                else if hp < 10 { 1 }"
+            ).unwrap().entry("hp")
+        );
+        assert_eq!(decider.on_nonterminal(&board, 0, ()),
+            NontermDecision::Trans(1, ()));
+        board.set(&Key::<i64>::new("hp"), 20);
+        assert_eq!(decider.on_nonterminal(&board, 0, ()),
+            NontermDecision::Step(()));
+        assert_eq!(decider.on_terminal(&board, 1, true),
+            TermDecision::Exit(Result::Ok(Option::Some(Value::from("done")))));
+        assert_eq!(decider.on_terminal(&board, 0, true),
+            TermDecision::Trans(3, true));
+        assert_eq!(Script::compile("hp").unwrap().entry("hp").evaluate(&board),
+            Result::Ok(Option::Some(Value::Int(20))));
+    }
+
+    #[test]
+    fn script_limits_test() {
+        let board = Blackboard::new();
+        match Script::compile("loop { }").unwrap().evaluate(&board) {
+            Result::Err(ScriptError::Eval(_)) => (),
+            _ => unreachable!("Expected evaluation error")
+        };
+        match Script::compile("fn f(x) { f(x) } f(1)").unwrap()
+            .evaluate(&board)
+        {
+            Result::Err(ScriptError::Eval(_)) => (),
+            _ => unreachable!("Expected evaluation error")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,509 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, SerialBranchNode, SerialDecider, NontermDecision,
+    TermDecision, NontermReturn};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::cell::Cell;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+/// Tries nodes in sequence, moving on to the next node whenever the current 
+/// one terminates with a Result::Err, and succeeding as soon as any of them 
+/// terminates with a Result::Ok. 
+///
+/// On success, the fallback exits with the succeeding node's discriminant 
+/// and its success value, and if every node fails, it exits with the 
+/// failure value of the last node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SerialFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    _who_cares: PhantomData<(E, I, N, T, F)>
+}
+
+impl<E, I, N, T, F> SerialFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    /// Create a new fallback decider. 
+    pub fn new() -> SerialFallback<E, I, N, T, F> {
+        SerialFallback {
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N, T, F> Default for SerialFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    fn default() -> SerialFallback<E, I, N, T, F> {
+        SerialFallback::new()
+    }
+}
+
+impl<E, I, N, T, F> SerialDecider for SerialFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = Result<T, F>;
+    type Exit = Result<(E, T), F>;
+
+    fn on_nonterminal(&self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N,
+        Result<(E, T), F>>
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&self, _i: &I, ord: E, statept: Result<T, F>) -> TermDecision<E,
+        Result<T, F>, Result<(E, T), F>>
+    {
+        match statept {
+            Result::Ok(t) => TermDecision::Exit(Result::Ok((ord, t))),
+            Result::Err(f) => match E::from_u64(ord.to_u64().unwrap()+1) {
+                Option::Some(e) => TermDecision::Trans(e, Result::Err(f)),
+                Option::None => TermDecision::Exit(Result::Err(f))
+            }
+        }
+    }
+}
+
+/// Selector node over the variants of an enumerable node, run in 
+/// discriminant order. 
+///
+/// This selector remembers its running node: once a node is entered, it is 
+/// stepped until it terminates, without the nodes before it being looked at 
+/// again. For a selector which keeps checking the earlier nodes, use 
+/// ReactiveSelector. 
+pub type SelectorNode<E, T, F> = SerialBranchNode<E, SerialFallback<
+    <E as EnumNode>::Discriminant, <E as BehaviorTreeNode>::Input,
+    <E as BehaviorTreeNode>::Nonterminal, T, F>>;
+
+/// Shared handle to the memory of a memory selector, recording the node it 
+/// last left off at. 
+/// 
+/// Clones of the handle share the same memory, so the handle can be held on 
+/// to outside of the selector, and used to construct it anew, resuming from 
+/// where the previous one left off. 
+#[derive(Clone, Debug, Default)]
+pub struct SelectorMemory<E> where 
+    E: Copy
+{
+    last: Rc<Cell<Option<E>>>
+}
+
+impl<E> SelectorMemory<E> where 
+    E: Copy + FromPrimitive
+{
+    /// Create a new, empty selector memory. 
+    pub fn new() -> SelectorMemory<E> {
+        SelectorMemory {
+            last: Rc::new(Cell::new(Option::None))
+        }
+    }
+
+    /// Get the discriminant to resume from, which is the first one if 
+    /// nothing has been remembered yet. 
+    pub fn resume(&self) -> E {
+        match self.last.get() {
+            Option::Some(e) => e,
+            Option::None => E::from_u64(0).unwrap()
+        }
+    }
+
+    /// Record the given discriminant as the one left off at. 
+    pub fn remember(&self, ord: E) {
+        self.last.set(Option::Some(ord))
+    }
+
+    /// Forget the remembered discriminant, so that the next selector built 
+    /// from this memory starts over from the first node. 
+    pub fn reset(&self) {
+        self.last.set(Option::None)
+    }
+
+    /// Create a memory selector which resumes from the remembered node. 
+    pub fn selector<N, T, F>(&self) -> MemorySelectorNode<N, T, F> where 
+        N: EnumNode<Discriminant=E, Terminal=Result<T, F>>,
+        E: ToPrimitive
+    {
+        SerialBranchNode::new(MemoryFallback::new(self.clone()), self.resume())
+    }
+}
+
+/// Fallback decider which behaves like SerialFallback, except that it 
+/// records the node it is at in a SelectorMemory. 
+/// 
+/// Nodes which have already failed are thus not attempted again by 
+/// selectors created from the same memory, until it is reset. After a 
+/// success, the succeeding node is resumed from, and after every node has 
+/// failed, only the last node is. 
+#[derive(Clone, Debug)]
+pub struct MemoryFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    memory: SelectorMemory<E>,
+    _who_cares: PhantomData<(I, N, T, F)>
+}
+
+impl<E, I, N, T, F> MemoryFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    /// Create a new memory fallback decider recording into the given 
+    /// memory. 
+    pub fn new(memory: SelectorMemory<E>) -> MemoryFallback<E, I, N, T, F> {
+        MemoryFallback {
+            memory: memory,
+            _who_cares: PhantomData
+        }
+    }
+
+    /// Get the memory this decider records into. 
+    pub fn memory(&self) -> &SelectorMemory<E> {
+        &self.memory
+    }
+}
+
+impl<E, I, N, T, F> SerialDecider for MemoryFallback<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = Result<T, F>;
+    type Exit = Result<(E, T), F>;
+
+    fn on_nonterminal(&self, _i: &I, ord: E, statept: N) -> NontermDecision<E, N,
+        Result<(E, T), F>>
+    {
+        self.memory.remember(ord);
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&self, _i: &I, ord: E, statept: Result<T, F>) -> TermDecision<E,
+        Result<T, F>, Result<(E, T), F>>
+    {
+        match statept {
+            Result::Ok(t) => {
+                self.memory.remember(ord);
+                TermDecision::Exit(Result::Ok((ord, t)))
+            },
+            Result::Err(f) => match E::from_u64(ord.to_u64().unwrap()+1) {
+                Option::Some(e) => {
+                    self.memory.remember(e);
+                    TermDecision::Trans(e, Result::Err(f))
+                },
+                Option::None => {
+                    self.memory.remember(ord);
+                    TermDecision::Exit(Result::Err(f))
+                }
+            }
+        }
+    }
+}
+
+/// Selector node which remembers, across instances built from the same 
+/// SelectorMemory, which node it last left off at, rather than starting 
+/// over from the first node. 
+pub type MemorySelectorNode<E, T, F> = SerialBranchNode<E, MemoryFallback<
+    <E as EnumNode>::Discriminant, <E as BehaviorTreeNode>::Input,
+    <E as BehaviorTreeNode>::Nonterminal, T, F>>;
+
+/// Selector node which, each step, re-evaluates the nodes before its running 
+/// node, in discriminant order, from a freshly initialized state. 
+///
+/// If one of those earlier nodes succeeds, the running node is abandoned and 
+/// the selector succeeds with it, and if one of them reaches a nonterminal 
+/// state instead, it replaces the running node. Only if all of them fail is 
+/// the running node itself stepped. This makes higher priority options 
+/// preempt lower priority ones as soon as they become viable. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReactiveSelector<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    node: E,
+    _junk: PhantomData<(T, F)>
+}
+
+impl<E, T, F> ReactiveSelector<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    /// Create a new reactive selector starting at the given discriminant. 
+    pub fn new(variant: E::Discriminant) -> ReactiveSelector<E, T, F> {
+        ReactiveSelector::from_existing(E::new(variant))
+    }
+
+    /// Wrap an existing enumerated node in a reactive selector. 
+    pub fn from_existing(existing: E) -> ReactiveSelector<E, T, F> {
+        ReactiveSelector {
+            node: existing,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<E, T, F> Default for ReactiveSelector<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    fn default() -> ReactiveSelector<E, T, F> {
+        ReactiveSelector::new(E::Discriminant::from_u64(0).unwrap())
+    }
+}
+
+impl<E, T, F> BehaviorTreeNode for ReactiveSelector<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = Result<(E::Discriminant, T), F>;
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let current = self.node.discriminant_of().to_u64().unwrap();
+        for index in 0..current {
+            let earlier = E::Discriminant::from_u64(index).unwrap();
+            match E::new(earlier).step(input) {
+                NodeResult::Nonterminal(n, m) => {
+                    self.node.abort();
+                    return NodeResult::Nonterminal(
+                        NontermReturn::Nonterminal(earlier, n),
+                        Self::from_existing(m)
+                    );
+                },
+                NodeResult::Terminal(Result::Ok(t)) => {
+                    self.node.abort();
+                    return NodeResult::Terminal(Result::Ok((earlier, t)));
+                },
+                NodeResult::Terminal(Result::Err(_)) => ()
+            }
+        }
+        let discriminant = self.node.discriminant_of();
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                NontermReturn::Nonterminal(discriminant, n),
+                Self::from_existing(m)
+            ),
+            NodeResult::Terminal(Result::Ok(t)) => {
+                NodeResult::Terminal(Result::Ok((discriminant, t)))
+            },
+            NodeResult::Terminal(Result::Err(f)) => {
+                match E::Discriminant::from_u64(current+1) {
+                    Option::Some(e) => NodeResult::Nonterminal(
+                        NontermReturn::Terminal(discriminant, Result::Err(f)),
+                        Self::new(e)
+                    ),
+                    Option::None => NodeResult::Terminal(Result::Err(f))
+                }
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{MachineWrapper, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    #[derive(Copy, Clone, Default)]
+    struct CountUp;
+
+    impl InternalTransition for CountUp {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, Result<i64, i64>>;
+
+        fn step(&self, input: &i64, state: &mut i64) -> Self::Action {
+            *state += 1;
+            if *state < 3 {
+                Statepoint::Nonterminal(*state)
+            } else if *input >= 0 {
+                Statepoint::Terminal(Result::Ok(*state))
+            } else {
+                Statepoint::Terminal(Result::Err(*state))
+            }
+        }
+    }
+
+    fn sign_check(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input >= 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum OptionEnum {
+        Check,
+        Count
+    }
+
+    enum Options {
+        Check(PredicateWait<i64, i64, Result<i64, i64>,
+            fn(&i64) -> Statepoint<i64, Result<i64, i64>>>),
+        Count(MachineWrapper<InternalStateMachine<'static, CountUp>, i64,
+            Result<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Options {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = Result<i64, i64>;
+
+        fn step(self, input: &i64) -> NodeResult<i64, Result<i64, i64>, Self> {
+            match self {
+                Options::Check(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Options::Check(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Options::Count(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Options::Count(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Options {
+        type Discriminant = OptionEnum;
+
+        fn new(thing: OptionEnum) -> Options {
+            match thing {
+                OptionEnum::Check => Options::Check(PredicateWait::new(sign_check)),
+                OptionEnum::Count => Options::Count(MachineWrapper::new(
+                    InternalStateMachine::new(CountUp, 0)
+                ))
+            }
+        }
+
+        fn discriminant_of(&self) -> OptionEnum {
+            match self {
+                Options::Check(_) => OptionEnum::Check,
+                Options::Count(_) => OptionEnum::Count
+            }
+        }
+    }
+
+    #[test]
+    fn selector_test() {
+        use selector_node::SelectorNode;
+        let test_node = SelectorNode::<Options, _, _>::default();
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, v), n) => {
+                assert_eq!(e, OptionEnum::Check);
+                assert_eq!(v, Result::Err(-1));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        // The check would now succeed, but it is not looked at again
+        let test_node_2 = match test_node_1.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, OptionEnum::Count);
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        let test_node_3 = match test_node_2.step(&1) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_3.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(3)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn reactive_selector_test() {
+        use selector_node::ReactiveSelector;
+        let test_node = ReactiveSelector::<Options, _, _>::default();
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, _), n) => {
+                assert_eq!(e, OptionEnum::Check);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, OptionEnum::Count);
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        // The check succeeding preempts the running count
+        match test_node_2.step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(
+                t,
+                Result::Ok((OptionEnum::Check, 4))
+            ),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn memory_selector_test() {
+        use selector_node::SelectorMemory;
+        let memory = SelectorMemory::<OptionEnum>::new();
+        let test_node = memory.selector::<Options, _, _>();
+        let mut test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, _), n) => {
+                assert_eq!(e, OptionEnum::Check);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        for _ in 0..2 {
+            test_node_1 = match test_node_1.step(&1) {
+                NodeResult::Nonterminal(_, n) => n,
+                _ => unreachable!("Expected nonterminal transition")
+            };
+        }
+        match test_node_1.step(&1) {
+            NodeResult::Terminal(t) => assert_eq!(
+                t,
+                Result::Ok((OptionEnum::Count, 3))
+            ),
+            _ => unreachable!("Expected terminal transition")
+        };
+        // The failed check is not attempted again
+        match memory.selector::<Options, _, _>().step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, _), _) => {
+                assert_eq!(e, OptionEnum::Count);
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        memory.reset();
+        match memory.selector::<Options, _, _>().step(&1) {
+            NodeResult::Terminal(t) => assert_eq!(
+                t,
+                Result::Ok((OptionEnum::Check, 1))
+            ),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,403 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, SerialBranchNode, SerialDecider, NontermDecision,
+    TermDecision, NontermReturn};
+use std::marker::PhantomData;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+/// Runs nodes in sequence, advancing to the next node whenever the current 
+/// one terminates successfully with a Result::Ok, and failing fast as soon as 
+/// any of them terminates with a Result::Err. 
+///
+/// On success, the sequence exits with the success value of the last node, 
+/// and on failure, it exits with the failing node's discriminant and its 
+/// failure value. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SerialSequence<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    _who_cares: PhantomData<(E, I, N, T, F)>
+}
+
+impl<E, I, N, T, F> SerialSequence<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    pub fn new() -> SerialSequence<E, I, N, T, F> {
+        SerialSequence {
+            _who_cares: PhantomData
+        }
+    }
+}
+
+impl<E, I, N, T, F> Default for SerialSequence<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    fn default() -> SerialSequence<E, I, N, T, F> {
+        SerialSequence::new()
+    }
+}
+
+impl<E, I, N, T, F> SerialDecider for SerialSequence<E, I, N, T, F> where 
+    E: Copy + FromPrimitive + ToPrimitive
+{
+    type Enum = E;
+    type Input = I;
+    type Nonterm = N;
+    type Term = Result<T, F>;
+    type Exit = Result<T, (E, F)>;
+
+    fn on_nonterminal(&self, _i: &I, _o: E, statept: N) -> NontermDecision<E, N,
+        Result<T, (E, F)>>
+    {
+        NontermDecision::Step(statept)
+    }
+
+    fn on_terminal(&self, _i: &I, ord: E, statept: Result<T, F>) -> TermDecision<E,
+        Result<T, F>, Result<T, (E, F)>>
+    {
+        match statept {
+            Result::Ok(t) => match E::from_u64(ord.to_u64().unwrap()+1) {
+                Option::Some(e) => TermDecision::Trans(e, Result::Ok(t)),
+                Option::None => TermDecision::Exit(Result::Ok(t))
+            },
+            Result::Err(f) => TermDecision::Exit(Result::Err((ord, f)))
+        }
+    }
+}
+
+/// Sequence node over the variants of an enumerable node, which may each 
+/// wrap a node of a different concrete type, run in discriminant order. 
+///
+/// Construct it with SerialBranchNode::new(SerialSequence::new(), first) or 
+/// with SerialBranchNode::default(). 
+pub type SequenceNode<E, T, F> = SerialBranchNode<E, SerialSequence<
+    <E as EnumNode>::Discriminant, <E as BehaviorTreeNode>::Input,
+    <E as BehaviorTreeNode>::Nonterminal, T, F>>;
+
+/// Sequence node which, each step, re-evaluates the nodes before its running 
+/// node, in discriminant order, from a freshly initialized state. 
+/// 
+/// This is intended for sequences which start with condition nodes: while a 
+/// later node is running, the conditions are checked again every step, and 
+/// if one of them fails, the running node is aborted and the sequence fails 
+/// with it. If one of them reaches a nonterminal state instead, it replaces 
+/// the running node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReactiveSequence<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    node: E,
+    _junk: PhantomData<(T, F)>
+}
+
+impl<E, T, F> ReactiveSequence<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    /// Create a new reactive sequence starting at the given discriminant. 
+    pub fn new(variant: E::Discriminant) -> ReactiveSequence<E, T, F> {
+        ReactiveSequence::from_existing(E::new(variant))
+    }
+
+    /// Wrap an existing enumerated node in a reactive sequence. 
+    pub fn from_existing(existing: E) -> ReactiveSequence<E, T, F> {
+        ReactiveSequence {
+            node: existing,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<E, T, F> Default for ReactiveSequence<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    fn default() -> ReactiveSequence<E, T, F> {
+        ReactiveSequence::new(E::Discriminant::from_u64(0).unwrap())
+    }
+}
+
+impl<E, T, F> BehaviorTreeNode for ReactiveSequence<E, T, F> where 
+    E: EnumNode<Terminal=Result<T, F>>,
+    E::Discriminant: FromPrimitive + ToPrimitive
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = Result<T, (E::Discriminant, F)>;
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal, 
+        Self::Terminal, Self> 
+    {
+        let current = self.node.discriminant_of().to_u64().unwrap();
+        for index in 0..current {
+            let earlier = E::Discriminant::from_u64(index).unwrap();
+            match E::new(earlier).step(input) {
+                NodeResult::Nonterminal(n, m) => {
+                    self.node.abort();
+                    return NodeResult::Nonterminal(
+                        NontermReturn::Nonterminal(earlier, n),
+                        Self::from_existing(m)
+                    );
+                },
+                NodeResult::Terminal(Result::Err(f)) => {
+                    self.node.abort();
+                    return NodeResult::Terminal(Result::Err((earlier, f)));
+                },
+                NodeResult::Terminal(Result::Ok(_)) => ()
+            }
+        }
+        let discriminant = self.node.discriminant_of();
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                NontermReturn::Nonterminal(discriminant, n),
+                Self::from_existing(m)
+            ),
+            NodeResult::Terminal(Result::Ok(t)) => {
+                match E::Discriminant::from_u64(current+1) {
+                    Option::Some(e) => NodeResult::Nonterminal(
+                        NontermReturn::Terminal(discriminant, Result::Ok(t)),
+                        Self::new(e)
+                    ),
+                    Option::None => NodeResult::Terminal(Result::Ok(t))
+                }
+            },
+            NodeResult::Terminal(Result::Err(f)) => {
+                NodeResult::Terminal(Result::Err((discriminant, f)))
+            }
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{MachineWrapper, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    #[derive(Copy, Clone, Default)]
+    struct CountUp;
+
+    impl InternalTransition for CountUp {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, Result<i64, i64>>;
+
+        fn step(&self, input: &i64, state: &mut i64) -> Self::Action {
+            *state += 1;
+            if *state < 2 {
+                Statepoint::Nonterminal(*state)
+            } else if *input >= 0 {
+                Statepoint::Terminal(Result::Ok(*state))
+            } else {
+                Statepoint::Terminal(Result::Err(*state))
+            }
+        }
+    }
+
+    fn sign_check(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input >= 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum StepEnum {
+        Count,
+        Check
+    }
+
+    enum Steps {
+        Count(MachineWrapper<InternalStateMachine<'static, CountUp>, i64,
+            Result<i64, i64>>),
+        Check(PredicateWait<i64, i64, Result<i64, i64>,
+            fn(&i64) -> Statepoint<i64, Result<i64, i64>>>)
+    }
+
+    impl BehaviorTreeNode for Steps {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = Result<i64, i64>;
+
+        fn step(self, input: &i64) -> NodeResult<i64, Result<i64, i64>, Self> {
+            match self {
+                Steps::Count(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Steps::Count(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Steps::Check(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Steps::Check(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Steps {
+        type Discriminant = StepEnum;
+
+        fn new(thing: StepEnum) -> Steps {
+            match thing {
+                StepEnum::Count => Steps::Count(MachineWrapper::new(
+                    InternalStateMachine::new(CountUp, 0)
+                )),
+                StepEnum::Check => Steps::Check(PredicateWait::new(sign_check))
+            }
+        }
+
+        fn discriminant_of(&self) -> StepEnum {
+            match self {
+                Steps::Count(_) => StepEnum::Count,
+                Steps::Check(_) => StepEnum::Check
+            }
+        }
+    }
+
+    #[test]
+    fn sequence_success_test() {
+        use sequence_node::SequenceNode;
+        let test_node = SequenceNode::<Steps, _, _>::default();
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, StepEnum::Count);
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, v), n) => {
+                assert_eq!(e, StepEnum::Count);
+                assert_eq!(v, Result::Ok(2));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        match test_node_2.step(&7) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(7)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn sequence_failure_test() {
+        use sequence_node::SequenceNode;
+        let test_node = SequenceNode::<Steps, _, _>::default();
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(
+                t,
+                Result::Err((StepEnum::Count, 2))
+            ),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum GuardEnum {
+        Check,
+        Count
+    }
+
+    enum Guarded {
+        Check(PredicateWait<i64, i64, Result<i64, i64>,
+            fn(&i64) -> Statepoint<i64, Result<i64, i64>>>),
+        Count(MachineWrapper<InternalStateMachine<'static, CountUp>, i64,
+            Result<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Guarded {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = Result<i64, i64>;
+
+        fn step(self, input: &i64) -> NodeResult<i64, Result<i64, i64>, Self> {
+            match self {
+                Guarded::Check(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Guarded::Check(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Guarded::Count(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Guarded::Count(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Guarded {
+        type Discriminant = GuardEnum;
+
+        fn new(thing: GuardEnum) -> Guarded {
+            match thing {
+                GuardEnum::Check => Guarded::Check(PredicateWait::new(sign_check)),
+                GuardEnum::Count => Guarded::Count(MachineWrapper::new(
+                    InternalStateMachine::new(CountUp, 0)
+                ))
+            }
+        }
+
+        fn discriminant_of(&self) -> GuardEnum {
+            match self {
+                Guarded::Check(_) => GuardEnum::Check,
+                Guarded::Count(_) => GuardEnum::Count
+            }
+        }
+    }
+
+    #[test]
+    fn reactive_sequence_test() {
+        use sequence_node::ReactiveSequence;
+        let test_node = ReactiveSequence::<Guarded, _, _>::default();
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, v), n) => {
+                assert_eq!(e, GuardEnum::Check);
+                assert_eq!(v, Result::Ok(1));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, GuardEnum::Count);
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        // The check failing aborts the running count
+        match test_node_2.step(&-3) {
+            NodeResult::Terminal(t) => assert_eq!(
+                t,
+                Result::Err((GuardEnum::Check, -3))
+            ),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -18,8 +18,120 @@ pub trait EnumNode: BehaviorTreeNode {
     fn discriminant_of(&self) -> Self::Discriminant;
 }
 
-/// Declarative macro for quickly and easily declaring an serial node enum.
-#[cfg(feature = "existential_type")]
+/// Declare the types of the variants of a serial node enum as opaque types. 
+#[cfg(feature = "type_alias_impl_trait")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_node_variants {
+    (
+        ( $inputtype:ty, $nontermtype:ty, $termtype:ty ) 
+        $( $variant:ident )*
+    ) => {
+        $(
+            type $variant = impl BehaviorTreeNode<Input = $inputtype,
+                Nonterminal = $nontermtype, Terminal = $termtype > ;
+        )*
+    };
+}
+
+/// Declare the types of the variants of a serial node enum as boxed nodes. 
+#[cfg(not(feature = "type_alias_impl_trait"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_node_variants {
+    (
+        ( $inputtype:ty, $nontermtype:ty, $termtype:ty ) 
+        $( $variant:ident )*
+    ) => {
+        $(
+            type $variant = $crate::dynamic_node::BoxedNode< $inputtype, 
+                $nontermtype, $termtype > ;
+        )*
+    };
+}
+
+/// Define the constructor of a serial node enum, which defines the opaque 
+/// types of its variants. 
+#[cfg(feature = "type_alias_impl_trait")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_node_new {
+    (
+        $name:ident : $itername:ident {
+            $( $variant:ident $( [ $projection:expr ] )? 
+                ( $( $statements:stmt )* ) ),*
+        }
+    ) => {
+        #[define_opaque( $( $variant ),* )]
+        fn new(discriminant: $itername) -> Self {
+            match discriminant {
+                $(
+                    $itername :: $variant => $name :: $variant ( 
+                        (| | -> $variant { $crate::enum_node_child!(
+                            $( [ $projection ] )? { $( $statements )* }
+                        ) })()
+                    )
+                ),*
+            }
+        }
+    };
+}
+
+/// Define the constructor of a serial node enum, which boxes the nodes of 
+/// its variants. 
+#[cfg(not(feature = "type_alias_impl_trait"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_node_new {
+    (
+        $name:ident : $itername:ident {
+            $( $variant:ident $( [ $projection:expr ] )? 
+                ( $( $statements:stmt )* ) ),*
+        }
+    ) => {
+        fn new(discriminant: $itername) -> Self {
+            match discriminant {
+                $(
+                    $itername :: $variant => $name :: $variant ( 
+                        $crate::dynamic_node::BoxedNode::new(
+                            $crate::enum_node_child!(
+                                $( [ $projection ] )? { $( $statements )* }
+                            )
+                        )
+                    )
+                ),*
+            }
+        }
+    };
+}
+
+/// Build the node of a serial node enum variant, wrapping it in an 
+/// InputMappedNode when the variant gives a projection of the input. 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! enum_node_child {
+    ( [ $projection:expr ] $node:block ) => {
+        $crate::map_wrappers::InputMappedNode::new($projection, $node)
+    };
+    ( $node:block ) => {
+        $node
+    };
+}
+
+/// Declarative macro for quickly and easily declaring an serial node enum. 
+///
+/// With the `type_alias_impl_trait` feature, the variants hold their nodes 
+/// as opaque types, so that the enum is as compact as one written by hand. 
+/// Without it, the variants hold boxed nodes instead, which works on stable 
+/// toolchains at the cost of an allocation on every step, as the boxed node 
+/// is boxed anew each time it steps. 
+/// The `enum_node` attribute of the `stackbt_macros` crate declares the same 
+/// items on stable without boxing, from a normal enum definition naming the 
+/// types of the nodes. 
+///
+/// A variant may give a projection of the input between brackets ahead of 
+/// its statements, as `Variant [|input: &Input| ...] (statements)`, in which 
+/// case its node steps on the projected value instead of the input itself. 
 #[macro_export]
 macro_rules! enum_node {
     (
@@ -30,14 +142,14 @@ macro_rules! enum_node {
         enum $name:ident : $itername:ident {
             $( 
                 $( #[ $emval:meta ] )*
-                $variant:ident ( $( $statements:stmt )* )
+                $variant:ident $( [ $projection:expr ] )? 
+                    ( $( $statements:stmt )* )
             ),*
         }
     ) => {
-        $(
-            existential type $variant : BehaviorTreeNode<Input = $inputtype,
-                Nonterminal = $nontermtype, Terminal = $termtype > ;
-        )*
+        $crate::enum_node_variants! {
+            ( $inputtype, $nontermtype, $termtype ) $( $variant )*
+        }
 
         $( #[ $mval ] )*
         enum $name {
@@ -73,18 +185,21 @@ macro_rules! enum_node {
                     ),*
                 }
             }
+
+            fn abort(self) {
+                match self {
+                    $( $name :: $variant (val) => val.abort() ),*
+                }
+            }
         }
 
         impl EnumNode for $name {
             type Discriminant = $itername;
 
-            fn new(discriminant: $itername) -> Self {
-                match discriminant {
-                    $(
-                        $itername :: $variant => $name :: $variant ( 
-                            (| | -> $variant { $( $statements )* })()
-                        )
-                    ),*
+            $crate::enum_node_new! {
+                $name : $itername {
+                    $( $variant $( [ $projection ] )? 
+                        ( $( $statements )* ) ),*
                 }
             }
 
@@ -97,9 +212,134 @@ macro_rules! enum_node {
     };
 }
 
+/// Declarative macro for writing a SerialDecider as a table of transitions. 
+///
+/// The macro declares a unit struct, and implements SerialDecider for it 
+/// from two tables, one for the nonterminals and one for the terminals of 
+/// the subnodes. Each table names the input and the statepoint value, and 
+/// maps a variant of the discriminant enum, or `_` for any variant, along 
+/// with an optional guard, to one of the decisions `step`, `trans(Variant)` 
+/// or `exit(expression)`. Stepping and transitioning pass the value on. 
+///
+/// # Example 
+/// ```ignore
+/// decider! {
+///     struct Switcharound;
+///     type Enum = PosNegEnum;
+///     type Input = i64;
+///     type Nonterm = i64;
+///     type Term = i64;
+///     type Exit = ();
+///
+///     nonterminal(input, value) {
+///         _ => step
+///     }
+///     terminal(input, value) {
+///         _ if *input == 0 => exit(()),
+///         Positive => trans(Negative),
+///         Negative => trans(Positive)
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! decider {
+    (@state $enum:ident, _) => { _ };
+
+    (@state $enum:ident, $state:ident) => { $enum :: $state };
+
+    (@nonterm $enum:ident, $value:ident, step) => {
+        $crate::serial_node::NontermDecision::Step($value)
+    };
+
+    (@nonterm $enum:ident, $value:ident, trans ( $next:ident )) => {
+        $crate::serial_node::NontermDecision::Trans($enum :: $next, $value)
+    };
+
+    (@nonterm $enum:ident, $value:ident, exit ( $exit:expr )) => {
+        $crate::serial_node::NontermDecision::Exit($exit)
+    };
+
+    (@term $enum:ident, $value:ident, trans ( $next:ident )) => {
+        $crate::serial_node::TermDecision::Trans($enum :: $next, $value)
+    };
+
+    (@term $enum:ident, $value:ident, exit ( $exit:expr )) => {
+        $crate::serial_node::TermDecision::Exit($exit)
+    };
+
+    (
+        $( #[ $mval:meta ] )*
+        $visibility:vis struct $name:ident ;
+        type Enum = $enum:ident ;
+        type Input = $inputtype:ty ;
+        type Nonterm = $nontermtype:ty ;
+        type Term = $termtype:ty ;
+        type Exit = $exittype:ty ;
+
+        nonterminal ( $ninput:ident , $nvalue:ident ) {
+            $(
+                $nstate:tt $( if $nguard:expr )* =>
+                    $ndecision:ident $( ( $( $nargs:tt )* ) )*
+            ),* $(,)*
+        }
+        terminal ( $tinput:ident , $tvalue:ident ) {
+            $(
+                $tstate:tt $( if $tguard:expr )* =>
+                    $tdecision:ident $( ( $( $targs:tt )* ) )*
+            ),* $(,)*
+        }
+    ) => {
+        $( #[ $mval ] )*
+        $visibility struct $name;
+
+        impl $crate::serial_node::SerialDecider for $name {
+            type Enum = $enum;
+            type Input = $inputtype;
+            type Nonterm = $nontermtype;
+            type Term = $termtype;
+            type Exit = $exittype;
+
+            #[allow(unused_variables)]
+            fn on_nonterminal(&self, $ninput: & $inputtype, state: $enum,
+                $nvalue: $nontermtype) -> $crate::serial_node::NontermDecision<
+                $enum, $nontermtype, $exittype>
+            {
+                match state {
+                    $(
+                        $crate::decider!(@state $enum, $nstate)
+                            $( if $nguard )* =>
+                        {
+                            $crate::decider!(@nonterm $enum, $nvalue,
+                                $ndecision $( ( $( $nargs )* ) )*)
+                        }
+                    ),*
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn on_terminal(&self, $tinput: & $inputtype, state: $enum,
+                $tvalue: $termtype) -> $crate::serial_node::TermDecision<
+                $enum, $termtype, $exittype>
+            {
+                match state {
+                    $(
+                        $crate::decider!(@state $enum, $tstate)
+                            $( if $tguard )* =>
+                        {
+                            $crate::decider!(@term $enum, $tvalue,
+                                $tdecision $( ( $( $targs )* ) )*)
+                        }
+                    ),*
+                }
+            }
+        }
+    };
+}
+
 /// Enumeration of the possible decisions when the child node reaches a 
 /// nonterminal state. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NontermDecision<E, T, X> {
     /// Step the current subnode. 
     Step(T),
@@ -112,6 +352,7 @@ pub enum NontermDecision<E, T, X> {
 /// Enumeration of the possible decisions when the child node reaches a 
 /// terminal state. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TermDecision<E, T, X> {
     /// Transition from the current subnode to a new one. 
     Trans(E, T),
@@ -121,6 +362,7 @@ pub enum TermDecision<E, T, X> {
 
 /// Return type of the SerialBranchNode. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NontermReturn<E, N, T> {
     /// Nonterminal of a subnode. 
     Nonterminal(E, N),
@@ -160,6 +402,7 @@ pub trait SerialDecider {
 /// time, a new node may be switched to or the whole parent node transitioned 
 /// from. 
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SerialBranchNode<E, D> where
     E: EnumNode,
     D: SerialDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal, 
@@ -222,11 +465,17 @@ impl<E, D> BehaviorTreeNode for SerialBranchNode<E, D> where
                         NontermReturn::Nonterminal(discriminant, j),
                         Self::from_existing(self.decider, n)
                     ),
-                    NontermDecision::Trans(e, j) => NodeResult::Nonterminal(
-                        NontermReturn::Nonterminal(discriminant, j),
-                        Self::new(self.decider, e)
-                    ),
-                    NontermDecision::Exit(x) => NodeResult::Terminal(x)
+                    NontermDecision::Trans(e, j) => {
+                        n.abort();
+                        NodeResult::Nonterminal(
+                            NontermReturn::Nonterminal(discriminant, j),
+                            Self::new(self.decider, e)
+                        )
+                    },
+                    NontermDecision::Exit(x) => {
+                        n.abort();
+                        NodeResult::Terminal(x)
+                    }
                 }
             },
             NodeResult::Terminal(i) => {
@@ -240,6 +489,10 @@ impl<E, D> BehaviorTreeNode for SerialBranchNode<E, D> where
             }
         }
     }
+
+    fn abort(self) {
+        self.node.abort()
+    }
 }
 
 #[cfg(all(test, feature = "existential_type"))]
@@ -389,4 +642,170 @@ mod tests {
         };
     }
 
+}
+
+#[cfg(test)]
+mod macro_tests {
+    use base_nodes::{PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, SerialDecider, NontermDecision, TermDecision};
+    use stackbt_macros::enum_node;
+
+    type SignWait = PredicateWait<i64, i64, i64, 
+        fn(&i64) -> Statepoint<i64, i64>>;
+
+    #[enum_node(PosNegEnum)]
+    enum MultiMachine {
+        #[init(PredicateWait::new(|input: &i64| {
+            if *input >= 0 {
+                Statepoint::Nonterminal(*input)
+            } else {
+                Statepoint::Terminal(*input)
+            }
+        }))]
+        Positive(SignWait),
+        #[init(PredicateWait::new(|input: &i64| {
+            if *input >= 0 {
+                Statepoint::Nonterminal(-*input)
+            } else {
+                Statepoint::Terminal(-*input)
+            }
+        }))]
+        Negative(SignWait)
+    }
+
+    decider! {
+        struct Switcharound;
+        type Enum = PosNegEnum;
+        type Input = i64;
+        type Nonterm = i64;
+        type Term = i64;
+        type Exit = ();
+
+        nonterminal(input, value) {
+            _ => step
+        }
+        terminal(input, value) {
+            Positive => trans(Negative),
+            Negative => trans(Positive)
+        }
+    }
+
+    #[test]
+    fn enum_node_attribute_test() {
+        use serial_node::{SerialBranchNode, NontermReturn};
+        let test_node = SerialBranchNode::<
+            MultiMachine, _>::new(Switcharound, PosNegEnum::Positive);
+        let test_node_1 = match test_node.step(&-5) {
+            NodeResult::Nonterminal(r, n) => {
+                assert_eq!(r,
+                    NontermReturn::Terminal(PosNegEnum::Positive, -5));
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&5) {
+            NodeResult::Nonterminal(r, _) => assert_eq!(r,
+                NontermReturn::Nonterminal(PosNegEnum::Negative, -5)),
+            _ => unreachable!("Expected nonterminal transition")
+        };
+    }
+
+    enum_node! {
+        type Input = i64;
+        type Nonterminal = ();
+        type Terminal = bool;
+
+        enum SignMachine: SignEnum {
+            Sign (PredicateWait::new(|input: &i64| {
+                Statepoint::Terminal(*input >= 0)
+            })),
+            Wait (PredicateWait::new(|_: &i64| Statepoint::Nonterminal(())))
+        }
+    }
+
+    #[test]
+    fn enum_node_macro_test() {
+        let sign = SignMachine::new(SignEnum::Sign);
+        assert_eq!(sign.discriminant_of(), SignEnum::Sign);
+        match sign.step(&-2) {
+            NodeResult::Terminal(t) => assert!(!t),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match SignMachine::new(SignEnum::Wait).step(&3) {
+            NodeResult::Nonterminal(_, n) => {
+                assert_eq!(n.discriminant_of(), SignEnum::Wait)
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+    }
+
+    enum_node! {
+        type Input = (i64, bool);
+        type Nonterminal = ();
+        type Terminal = bool;
+
+        enum PairMachine: PairEnum {
+            Number [|input: &(i64, bool)| input.0] (
+                PredicateWait::new(|input: &i64| {
+                    Statepoint::Terminal(*input >= 0)
+                })
+            ),
+            Flag [|input: &(i64, bool)| input.1] (
+                PredicateWait::new(|input: &bool| Statepoint::Terminal(*input))
+            )
+        }
+    }
+
+    #[test]
+    fn enum_node_projection_test() {
+        match PairMachine::new(PairEnum::Number).step(&(-2, true)) {
+            NodeResult::Terminal(t) => assert!(!t),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match PairMachine::new(PairEnum::Flag).step(&(-2, true)) {
+            NodeResult::Terminal(t) => assert!(t),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    decider! {
+        #[derive(Copy, Clone, Debug)]
+        struct Bounded;
+        type Enum = SignEnum;
+        type Input = i64;
+        type Nonterm = ();
+        type Term = bool;
+        type Exit = i64;
+
+        nonterminal(input, value) {
+            _ if *input > 10 => exit(*input),
+            Wait => trans(Sign),
+            Sign => step
+        }
+        terminal(input, value) {
+            Sign if value => trans(Wait),
+            _ => exit(-*input)
+        }
+    }
+
+    #[test]
+    fn decider_macro_test() {
+        match Bounded.on_nonterminal(&11, SignEnum::Wait, ()) {
+            NontermDecision::Exit(x) => assert_eq!(x, 11),
+            _ => unreachable!("Expected exit")
+        };
+        match Bounded.on_nonterminal(&3, SignEnum::Wait, ()) {
+            NontermDecision::Trans(SignEnum::Sign, ()) => (),
+            _ => unreachable!("Expected transition")
+        };
+        match Bounded.on_terminal(&3, SignEnum::Sign, true) {
+            TermDecision::Trans(SignEnum::Wait, true) => (),
+            _ => unreachable!("Expected transition")
+        };
+        match Bounded.on_terminal(&3, SignEnum::Sign, false) {
+            TermDecision::Exit(x) => assert_eq!(x, -3),
+            _ => unreachable!("Expected exit")
+        };
+    }
 }
\ No newline at end of file
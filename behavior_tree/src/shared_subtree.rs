@@ -0,0 +1,236 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use std::sync::Arc;
+use std::fmt;
+
+/// A shareable definition of a subtree, which can be instantiated in as many 
+/// places in a tree as needed. 
+///
+/// The definition is a constructor behind an Arc, so cloning the definition 
+/// is cheap, and all clones construct the same subtree. 
+pub struct Subtree<N> {
+    constructor: Arc<Fn() -> N + Send + Sync>
+}
+
+impl<N> Clone for Subtree<N> {
+    fn clone(&self) -> Subtree<N> {
+        Subtree {
+            constructor: self.constructor.clone()
+        }
+    }
+}
+
+impl<N> fmt::Debug for Subtree<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Subtree")
+    }
+}
+
+impl<N> Subtree<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new subtree definition from a constructor of its root node. 
+    pub fn new<C>(constructor: C) -> Subtree<N> where 
+        C: Fn() -> N + Send + Sync + 'static
+    {
+        Subtree {
+            constructor: Arc::new(constructor)
+        }
+    }
+
+    /// Create a new subtree definition from a prototype of its root node, 
+    /// which is cloned for each instance. 
+    pub fn from_prototype(prototype: N) -> Subtree<N> where 
+        N: Clone + Send + Sync + 'static
+    {
+        Subtree::new(move || prototype.clone())
+    }
+
+    /// Construct a fresh root node of the subtree. 
+    pub fn construct(&self) -> N {
+        (self.constructor)()
+    }
+
+    /// Create a reference node, which instantiates the subtree when it is 
+    /// first stepped. 
+    pub fn instantiate(&self) -> SubtreeNode<N> {
+        SubtreeNode {
+            definition: self.clone(),
+            node: Option::None
+        }
+    }
+}
+
+/// A node referring to a shared subtree definition, which it instantiates 
+/// when it is first stepped, and which it steps from then on. 
+#[derive(Clone, Debug)]
+pub struct SubtreeNode<N> where 
+    N: BehaviorTreeNode
+{
+    definition: Subtree<N>,
+    node: Option<N>
+}
+
+impl<N> SubtreeNode<N> where 
+    N: BehaviorTreeNode
+{
+    /// Get the definition this node refers to. 
+    pub fn definition(&self) -> &Subtree<N> {
+        &self.definition
+    }
+}
+
+impl<N> BehaviorTreeNode for SubtreeNode<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, N::Terminal,
+        Self>
+    {
+        let node = match self.node {
+            Option::Some(n) => n,
+            Option::None => self.definition.construct()
+        };
+        match node.step(input) {
+            NodeResult::Nonterminal(v, n) => NodeResult::Nonterminal(
+                v,
+                SubtreeNode {
+                    definition: self.definition,
+                    node: Option::Some(n)
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+/// A shareable definition of a subtree generic over a configuration, such as 
+/// the tag of a target along with distances and timeouts, which constructs 
+/// the subtree anew for each configuration it is given. 
+///
+/// Templates are instantiated by fluent builders, and registered in node 
+/// registries as kinds of leaves whose parameters are read into the 
+/// configuration, so that the same subtree is reused by typed and 
+/// data-driven trees alike. 
+pub struct SubtreeTemplate<C, N> {
+    constructor: Arc<Fn(&C) -> N + Send + Sync>
+}
+
+impl<C, N> Clone for SubtreeTemplate<C, N> {
+    fn clone(&self) -> SubtreeTemplate<C, N> {
+        SubtreeTemplate {
+            constructor: self.constructor.clone()
+        }
+    }
+}
+
+impl<C, N> fmt::Debug for SubtreeTemplate<C, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SubtreeTemplate")
+    }
+}
+
+impl<C, N> SubtreeTemplate<C, N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new template from a constructor of the root node of the 
+    /// subtree for a configuration. 
+    pub fn new<F>(constructor: F) -> SubtreeTemplate<C, N> where 
+        F: Fn(&C) -> N + Send + Sync + 'static
+    {
+        SubtreeTemplate {
+            constructor: Arc::new(constructor)
+        }
+    }
+
+    /// Construct a fresh root node of the subtree for the given 
+    /// configuration. 
+    pub fn construct(&self, config: &C) -> N {
+        (self.constructor)(config)
+    }
+
+    /// Bind the template to the given configuration, giving the shared 
+    /// definition of the subtree for it. 
+    pub fn bind(&self, config: C) -> Subtree<N> where 
+        C: Send + Sync + 'static,
+        N: 'static
+    {
+        let template = self.clone();
+        Subtree::new(move || template.construct(&config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+    use shared_subtree::{Subtree, SubtreeTemplate};
+
+    #[derive(Copy, Clone, Debug)]
+    struct Countdown {
+        remaining: i64
+    }
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = ();
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, _input: &()) -> NodeResult<i64, i64, Self> {
+            if self.remaining > 1 {
+                NodeResult::Nonterminal(self.remaining, Countdown {
+                    remaining: self.remaining - 1
+                })
+            } else {
+                NodeResult::Terminal(self.remaining)
+            }
+        }
+    }
+
+    #[test]
+    fn shared_subtree_test() {
+        let definition = Subtree::from_prototype(Countdown { remaining: 2 });
+        let first = definition.instantiate();
+        let second = definition.clone().instantiate();
+        let first_1 = match first.step(&()) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 2);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // Instances do not share state
+        match second.step(&()) {
+            NodeResult::Nonterminal(v, _) => assert_eq!(v, 2),
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match first_1.step(&()) {
+            NodeResult::Terminal(t) => assert_eq!(t, 1),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn subtree_template_test() {
+        let template = SubtreeTemplate::new(|c: &i64| Countdown {
+            remaining: *c
+        });
+        match template.construct(&1).step(&()) {
+            NodeResult::Terminal(t) => assert_eq!(t, 1),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let definition = template.bind(3);
+        match definition.instantiate().step(&()) {
+            NodeResult::Nonterminal(v, _) => assert_eq!(v, 3),
+            _ => unreachable!("Expected nonterminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,104 @@
+use behavior_tree_node::BehaviorTreeNode;
+use node_runner::NodeRunner;
+use persist_state::PersistState;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Trait for running trees which can be captured as an owned, serializable 
+/// blob of state, and reconstructed from that blob together with the 
+/// definition of the tree, such as for saving games or for migrating agents 
+/// between servers. 
+///
+/// The definition holds what can not be serialized, such as the closures 
+/// nodes are built from, and is expected to be the same on both ends. 
+pub trait Snapshot: Sized {
+    /// Type of the definition the tree is reconstructed from. 
+    type Definition;
+    /// Type of the captured state of the tree. 
+    type Blob: Serialize + DeserializeOwned;
+
+    /// Version of the layout of the captured state. 
+    const STATE_VERSION: u32 = 0;
+
+    /// Capture the state of the running tree. 
+    fn snapshot(&self) -> Self::Blob;
+
+    /// Reconstruct a tree equivalent to the captured one. 
+    fn from_snapshot(definition: &Self::Definition, blob: Self::Blob) -> Self;
+
+    /// Construct a tree from its definition alone, in its initial state. 
+    fn fresh(definition: &Self::Definition) -> Self;
+}
+
+impl<N, C> Snapshot for NodeRunner<N, C> where 
+    N: BehaviorTreeNode + PersistState + 'static,
+    C: Fn() -> N + Clone
+{
+    type Definition = C;
+    type Blob = N::State;
+
+    const STATE_VERSION: u32 = N::STATE_VERSION;
+
+    fn snapshot(&self) -> N::State {
+        self.node().save_state()
+    }
+
+    fn from_snapshot(definition: &C, blob: N::State) -> Self {
+        let mut runner = NodeRunner::new(definition.clone());
+        runner.node_mut().restore_state(blob);
+        runner
+    }
+
+    fn fresh(definition: &C) -> Self {
+        NodeRunner::new(definition.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use node_runner::NodeRunner;
+    use persist_state::PersistState;
+    use snapshot::Snapshot;
+    use stackbt_automata_impl::automaton::Automaton;
+
+    struct Countdown(u32);
+
+    impl BehaviorTreeNode for Countdown {
+        type Input = ();
+        type Nonterminal = u32;
+        type Terminal = ();
+
+        fn step(self, _input: &()) -> NodeResult<u32, (), Self> {
+            match self.0 {
+                0 => NodeResult::Terminal(()),
+                n => NodeResult::Nonterminal(n, Countdown(n - 1))
+            }
+        }
+    }
+
+    impl PersistState for Countdown {
+        type State = u32;
+
+        fn save_state(&self) -> u32 {
+            self.0
+        }
+
+        fn restore_state(&mut self, state: u32) {
+            self.0 = state
+        }
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let definition = || Countdown(5);
+        let mut runner = NodeRunner::new(definition);
+        runner.transition(&());
+        runner.transition(&());
+        let blob = runner.snapshot();
+        assert_eq!(blob, 3);
+        let mut restored = NodeRunner::from_snapshot(&definition, blob);
+        assert_eq!(restored.transition(&()), Statepoint::Nonterminal(3));
+        assert_eq!(runner.transition(&()), Statepoint::Nonterminal(3));
+    }
+}
\ No newline at end of file
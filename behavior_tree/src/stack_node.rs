@@ -0,0 +1,372 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, NontermReturn};
+
+/// Enumeration of the possible decisions of a StackDecider when the child 
+/// node reaches a nonterminal state. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StackNontermDecision<E, T, X> {
+    /// Step the current subnode. 
+    Step(T),
+    /// Transition from the current subnode to a new one, aborting the 
+    /// current one. 
+    Trans(E, T),
+    /// Suspend the current subnode by pushing it onto the stack, and start 
+    /// a new one. 
+    Push(E, T),
+    /// Abort the current subnode, and resume the one on top of the stack. 
+    Pop(T),
+    /// Exit the current supernode entirely. 
+    Exit(X)
+}
+
+/// Enumeration of the possible decisions of a StackDecider when the child 
+/// node reaches a terminal state. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StackTermDecision<E, T, X> {
+    /// Transition from the current subnode to a new one. 
+    Trans(E, T),
+    /// Push the discriminant of the current subnode onto the stack, so that 
+    /// a fresh instance of it is resumed once popped, and start a new one. 
+    Push(E, T),
+    /// Resume the subnode on top of the stack. 
+    Pop(T),
+    /// Exit the current supernode entirely. 
+    Exit(X)
+}
+
+/// Trait for the transition behavior of a StackBranchNode. 
+///
+/// This is the SerialDecider with the additional push and pop decisions, 
+/// where the deciding functions are also given the depth of the stack. 
+pub trait StackDecider {
+    /// Type of the enumerating discriminant 
+    type Enum;
+    /// Type of the inputs of the subnodes. 
+    type Input;
+    /// Type of the nonterminals of the subnodes. 
+    type Nonterm;
+    /// Type of the terminals of the subnodes. 
+    type Term;
+    /// Supernode terminal type. 
+    type Exit;
+    /// Given a reference to the input, the current nonterminal state and 
+    /// the depth of the stack, decide what to do from the nonterminal 
+    /// statepoint. 
+    fn on_nonterminal(&self, &Self::Input, Self::Enum, Self::Nonterm, usize) ->
+        StackNontermDecision<Self::Enum, Self::Nonterm, Self::Exit>;
+    /// Given a reference to the input, the current terminal state and the 
+    /// depth of the stack, decide what to do from the terminal statepoint. 
+    fn on_terminal(&self, &Self::Input, Self::Enum, Self::Term, usize) ->
+        StackTermDecision<Self::Enum, Self::Term, Self::Exit>;
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Suspended<E, D> {
+    Saved(E),
+    Fresh(D)
+}
+
+/// A pushdown branch node, which is a serial branch node whose decider can 
+/// additionally push subnodes onto a stack, and later pop back to them. 
+///
+/// A subnode pushed while running is saved, and resumed from its saved state 
+/// when popped, while a subnode pushed as it terminates is resumed from a 
+/// fresh state. This allows subroutine like behaviors such as interrupt 
+/// handlers and nested dialogs to return to whatever they interrupted. 
+///
+/// Popping an empty stack panics, so deciders should check the depth they 
+/// are given. Exiting aborts the saved subnodes along with the current one. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct StackBranchNode<E, D> where 
+    E: EnumNode,
+    D: StackDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal,
+        Term=E::Terminal>
+{
+    node: E,
+    stack: Vec<Suspended<E, E::Discriminant>>,
+    decider: D
+}
+
+impl<E, D> StackBranchNode<E, D> where 
+    E: EnumNode,
+    D: StackDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal,
+        Term=E::Terminal>
+{
+    /// Create a new stack branch node for the given discriminant, with an 
+    /// empty stack. 
+    pub fn new(decider: D, variant: E::Discriminant) -> StackBranchNode<E, D> {
+        StackBranchNode::from_existing(decider, E::new(variant))
+    }
+
+    /// Wrap an existing enumerated node in a stack branch node, with an 
+    /// empty stack. 
+    pub fn from_existing(decider: D, existing: E) -> StackBranchNode<E, D> {
+        StackBranchNode {
+            node: existing,
+            stack: Vec::new(),
+            decider: decider
+        }
+    }
+
+    /// Get the number of subnodes on the stack. 
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn pop(stack: &mut Vec<Suspended<E, E::Discriminant>>) -> E {
+        match stack.pop().expect("Stack branch node popped an empty stack") {
+            Suspended::Saved(e) => e,
+            Suspended::Fresh(d) => E::new(d)
+        }
+    }
+
+    fn abort_stack(stack: Vec<Suspended<E, E::Discriminant>>) {
+        for suspended in stack.into_iter().rev() {
+            if let Suspended::Saved(e) = suspended {
+                e.abort();
+            }
+        }
+    }
+}
+
+impl<E, D> BehaviorTreeNode for StackBranchNode<E, D> where 
+    E: EnumNode,
+    D: StackDecider<Enum=E::Discriminant, Input=E::Input, Nonterm=E::Nonterminal,
+        Term=E::Terminal>
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = D::Exit;
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal, D::Exit, Self> {
+        let discriminant = self.node.discriminant_of();
+        let mut stack = self.stack;
+        let depth = stack.len();
+        let (ret, node) = match self.node.step(input) {
+            NodeResult::Nonterminal(i, n) => {
+                match self.decider.on_nonterminal(input, discriminant, i, depth) {
+                    StackNontermDecision::Step(j) => {
+                        (NontermReturn::Nonterminal(discriminant, j), n)
+                    },
+                    StackNontermDecision::Trans(e, j) => {
+                        n.abort();
+                        (NontermReturn::Nonterminal(discriminant, j), E::new(e))
+                    },
+                    StackNontermDecision::Push(e, j) => {
+                        stack.push(Suspended::Saved(n));
+                        (NontermReturn::Nonterminal(discriminant, j), E::new(e))
+                    },
+                    StackNontermDecision::Pop(j) => {
+                        n.abort();
+                        let resumed = Self::pop(&mut stack);
+                        (NontermReturn::Nonterminal(discriminant, j), resumed)
+                    },
+                    StackNontermDecision::Exit(x) => {
+                        n.abort();
+                        Self::abort_stack(stack);
+                        return NodeResult::Terminal(x);
+                    }
+                }
+            },
+            NodeResult::Terminal(i) => {
+                match self.decider.on_terminal(input, discriminant, i, depth) {
+                    StackTermDecision::Trans(e, j) => {
+                        (NontermReturn::Terminal(discriminant, j), E::new(e))
+                    },
+                    StackTermDecision::Push(e, j) => {
+                        stack.push(Suspended::Fresh(discriminant));
+                        (NontermReturn::Terminal(discriminant, j), E::new(e))
+                    },
+                    StackTermDecision::Pop(j) => {
+                        let resumed = Self::pop(&mut stack);
+                        (NontermReturn::Terminal(discriminant, j), resumed)
+                    },
+                    StackTermDecision::Exit(x) => {
+                        Self::abort_stack(stack);
+                        return NodeResult::Terminal(x);
+                    }
+                }
+            }
+        };
+        NodeResult::Nonterminal(ret, StackBranchNode {
+            node: node,
+            stack: stack,
+            decider: self.decider
+        })
+    }
+
+    fn abort(self) {
+        self.node.abort();
+        Self::abort_stack(self.stack);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::{MachineWrapper, PredicateWait};
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use stack_node::{StackDecider, StackNontermDecision, StackTermDecision};
+    use stackbt_automata_impl::internal_state_machine::{InternalTransition,
+        InternalStateMachine};
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    #[derive(Copy, Clone, Default)]
+    struct CountUp;
+
+    impl InternalTransition for CountUp {
+        type Input = i64;
+        type Internal = i64;
+        type Action = Statepoint<i64, i64>;
+
+        fn step(&self, _input: &i64, state: &mut i64) -> Self::Action {
+            *state += 1;
+            Statepoint::Nonterminal(*state)
+        }
+    }
+
+    fn handle(input: &i64) -> Statepoint<i64, i64> {
+        if *input > 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum TaskEnum {
+        Work,
+        Interrupt
+    }
+
+    enum Tasks {
+        Work(MachineWrapper<InternalStateMachine<'static, CountUp>, i64, i64>),
+        Interrupt(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Tasks {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Self> {
+            match self {
+                Tasks::Work(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Tasks::Work(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Tasks::Interrupt(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Tasks::Interrupt(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Tasks {
+        type Discriminant = TaskEnum;
+
+        fn new(thing: TaskEnum) -> Tasks {
+            match thing {
+                TaskEnum::Work => Tasks::Work(MachineWrapper::new(
+                    InternalStateMachine::new(CountUp, 0)
+                )),
+                TaskEnum::Interrupt => Tasks::Interrupt(PredicateWait::new(handle))
+            }
+        }
+
+        fn discriminant_of(&self) -> TaskEnum {
+            match self {
+                Tasks::Work(_) => TaskEnum::Work,
+                Tasks::Interrupt(_) => TaskEnum::Interrupt
+            }
+        }
+    }
+
+    // Interrupts the work on negative input, returning to it once the
+    // interrupt is handled, and exits on an input of 100.
+    struct Interrupter;
+
+    impl StackDecider for Interrupter {
+        type Enum = TaskEnum;
+        type Input = i64;
+        type Nonterm = i64;
+        type Term = i64;
+        type Exit = i64;
+
+        fn on_nonterminal(&self, i: &i64, s: TaskEnum, o: i64, _d: usize) ->
+            StackNontermDecision<TaskEnum, i64, i64>
+        {
+            if *i == 100 {
+                StackNontermDecision::Exit(o)
+            } else if s == TaskEnum::Work && *i < 0 {
+                StackNontermDecision::Push(TaskEnum::Interrupt, o)
+            } else {
+                StackNontermDecision::Step(o)
+            }
+        }
+
+        fn on_terminal(&self, _i: &i64, _s: TaskEnum, o: i64, d: usize) ->
+            StackTermDecision<TaskEnum, i64, i64>
+        {
+            if d > 0 {
+                StackTermDecision::Pop(o)
+            } else {
+                StackTermDecision::Exit(o)
+            }
+        }
+    }
+
+    #[test]
+    fn stack_resume_test() {
+        use stack_node::StackBranchNode;
+        let test_node = StackBranchNode::<Tasks, _>::new(Interrupter, TaskEnum::Work);
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(TaskEnum::Work, 1), n) => n,
+            _ => unreachable!("Expected work nonterminal")
+        };
+        let test_node_2 = match test_node_1.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(TaskEnum::Work, 2), n) => n,
+            _ => unreachable!("Expected work nonterminal")
+        };
+        assert_eq!(test_node_2.depth(), 1);
+        let test_node_3 = match test_node_2.step(&5) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, TaskEnum::Interrupt);
+                assert_eq!(v, 5);
+                n
+            },
+            _ => unreachable!("Expected interrupt nonterminal")
+        };
+        let test_node_4 = match test_node_3.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(e, _), n) => {
+                assert_eq!(e, TaskEnum::Interrupt);
+                n
+            },
+            _ => unreachable!("Expected interrupt terminal")
+        };
+        assert_eq!(test_node_4.depth(), 0);
+        // The work resumes from its saved count
+        let test_node_5 = match test_node_4.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, TaskEnum::Work);
+                assert_eq!(v, 3);
+                n
+            },
+            _ => unreachable!("Expected work nonterminal")
+        };
+        match test_node_5.step(&100) {
+            NodeResult::Terminal(t) => assert_eq!(t, 4),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,326 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, BtStatus, Failure};
+use std::marker::PhantomData;
+
+/// Wrapper for a node whose terminal converts into a BtStatus, which swaps 
+/// the success and failure of that terminal, passing nonterminals through. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Inverter<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Into<BtStatus>
+{
+    node: N
+}
+
+impl<N> Inverter<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Into<BtStatus>
+{
+    /// Create a new inverter around the given node. 
+    pub fn new(node: N) -> Inverter<N> {
+        Inverter {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for Inverter<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Into<BtStatus>
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = BtStatus;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, BtStatus, Self> {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                Inverter::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.into().invert())
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+
+/// Wrapper for a node, which runs it to its terminal and then always 
+/// succeeds, with the terminal of the node as the success value, passing 
+/// nonterminals through. 
+///
+/// The failure type is left open, so that optional nodes can be run within 
+/// sequences without aborting them. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Succeeder<N, F> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    _junk: PhantomData<F>
+}
+
+impl<N, F> Succeeder<N, F> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new succeeder around the given node. 
+    pub fn new(node: N) -> Succeeder<N, F> {
+        Succeeder {
+            node: node,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<N, F> BehaviorTreeNode for Succeeder<N, F> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, F>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, Self::Terminal,
+        Self>
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                Succeeder::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(Result::Ok(t))
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Wrapper for a node, which runs it to its terminal and then always fails, 
+/// with the terminal of the node as the failure value, passing nonterminals 
+/// through. 
+///
+/// The success type is left open, so that nodes can be run within selectors 
+/// without ending them. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Failer<N, T> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    _junk: PhantomData<T>
+}
+
+impl<N, T> Failer<N, T> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new failer around the given node. 
+    pub fn new(node: N) -> Failer<N, T> {
+        Failer {
+            node: node,
+            _junk: PhantomData
+        }
+    }
+}
+
+impl<N, T> BehaviorTreeNode for Failer<N, T> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<T, N::Terminal>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, Self::Terminal,
+        Self>
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                Failer::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(Result::Err(t))
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Wrapper for a node whose terminal converts into a BtStatus, which 
+/// converts the terminal, so that the node can be used where the classic 
+/// success and failure statuses are expected. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AsStatus<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Into<BtStatus>
+{
+    node: N
+}
+
+impl<N> AsStatus<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Into<BtStatus>
+{
+    /// Create a new status adapter around the given node. 
+    pub fn new(node: N) -> AsStatus<N> {
+        AsStatus {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for AsStatus<N> where 
+    N: BehaviorTreeNode,
+    N::Terminal: Into<BtStatus>
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = BtStatus;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, BtStatus, Self> {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                AsStatus::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.into())
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Wrapper for a node with a BtStatus terminal, which converts the status 
+/// into a Result terminal with no value and a Failure error, so that the 
+/// node can be used in the composites built on success and failure 
+/// terminals. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AsResult<N> where 
+    N: BehaviorTreeNode<Terminal=BtStatus>
+{
+    node: N
+}
+
+impl<N> AsResult<N> where 
+    N: BehaviorTreeNode<Terminal=BtStatus>
+{
+    /// Create a new result adapter around the given node. 
+    pub fn new(node: N) -> AsResult<N> {
+        AsResult {
+            node: node
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for AsResult<N> where 
+    N: BehaviorTreeNode<Terminal=BtStatus>
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<(), Failure>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal, 
+        Result<(), Failure>, Self> 
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n,
+                AsResult::new(m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t.into_result())
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint, BtStatus,
+        Failure};
+    use status_wrappers::{Inverter, Succeeder, Failer, AsStatus, AsResult};
+
+    fn check(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input == 0 {
+            Statepoint::Nonterminal(*input)
+        } else if *input > 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    #[test]
+    fn inverter_test() {
+        let test_node = Inverter::new(PredicateWait::new(check));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Failure),
+            _ => unreachable!("Expected terminal transition")
+        };
+        match Inverter::new(PredicateWait::new(check)).step(&-3) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn succeeder_test() {
+        let test_node = Succeeder::<_, ()>::new(PredicateWait::new(check));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&-3) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(Result::Err(-3))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn failer_test() {
+        match Failer::<_, ()>::new(PredicateWait::new(check)).step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(Result::Ok(3))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn status_adapter_test() {
+        match AsStatus::new(PredicateWait::new(check)).step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let round_trip = AsResult::new(AsStatus::new(PredicateWait::new(check)));
+        let round_trip_1 = match round_trip.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match round_trip_1.step(&-3) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(Failure)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
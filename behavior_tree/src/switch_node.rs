@@ -0,0 +1,210 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, NontermReturn};
+use num_traits::ToPrimitive;
+
+/// Enumeration of the possible behaviors of a switch node when the key 
+/// extracted from its input changes while a node is running. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum KeyChange {
+    /// Abort the running node, and switch to the node for the new key. 
+    AbortAndSwitch,
+    /// Keep running the current node until it terminates. 
+    FinishCurrent
+}
+
+/// Switch node, which extracts a key from its input, and runs the variant of 
+/// an enumerable node matching that key, exiting with the discriminant and 
+/// terminal of that variant once it terminates. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SwitchNode<E, K> where 
+    E: EnumNode,
+    E::Discriminant: ToPrimitive,
+    K: Fn(&E::Input) -> E::Discriminant
+{
+    node: Option<E>,
+    key: K,
+    on_change: KeyChange
+}
+
+impl<E, K> SwitchNode<E, K> where 
+    E: EnumNode,
+    E::Discriminant: ToPrimitive,
+    K: Fn(&E::Input) -> E::Discriminant
+{
+    /// Create a new switch node with the given key extractor and behavior on 
+    /// key change. 
+    pub fn new(key: K, on_change: KeyChange) -> SwitchNode<E, K> {
+        SwitchNode {
+            node: Option::None,
+            key: key,
+            on_change: on_change
+        }
+    }
+}
+
+impl<E, K> BehaviorTreeNode for SwitchNode<E, K> where 
+    E: EnumNode,
+    E::Discriminant: ToPrimitive,
+    K: Fn(&E::Input) -> E::Discriminant
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = (E::Discriminant, E::Terminal);
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let key = (self.key)(input);
+        let node = match self.node {
+            Option::Some(n) => if self.on_change == KeyChange::AbortAndSwitch &&
+                n.discriminant_of().to_u64() != key.to_u64()
+            {
+                n.abort();
+                E::new(key)
+            } else {
+                n
+            },
+            Option::None => E::new(key)
+        };
+        let discriminant = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                NontermReturn::Nonterminal(discriminant, n),
+                SwitchNode {
+                    node: Option::Some(m),
+                    key: self.key,
+                    on_change: self.on_change
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal((discriminant, t))
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    fn hold(input: &i64) -> Statepoint<i64, i64> {
+        if *input != 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    fn mode_of(input: &i64) -> ModeEnum {
+        if *input >= 0 {
+            ModeEnum::Calm
+        } else {
+            ModeEnum::Alert
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum ModeEnum {
+        Calm,
+        Alert
+    }
+
+    enum Modes {
+        Calm(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>),
+        Alert(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Modes {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Self> {
+            match self {
+                Modes::Calm(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Modes::Calm(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Modes::Alert(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Modes::Alert(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Modes {
+        type Discriminant = ModeEnum;
+
+        fn new(thing: ModeEnum) -> Modes {
+            match thing {
+                ModeEnum::Calm => Modes::Calm(PredicateWait::new(hold)),
+                ModeEnum::Alert => Modes::Alert(PredicateWait::new(hold))
+            }
+        }
+
+        fn discriminant_of(&self) -> ModeEnum {
+            match self {
+                Modes::Calm(_) => ModeEnum::Calm,
+                Modes::Alert(_) => ModeEnum::Alert
+            }
+        }
+    }
+
+    #[test]
+    fn switch_abort_test() {
+        use switch_node::{SwitchNode, KeyChange};
+        let test_node = SwitchNode::<Modes, _>::new(mode_of, KeyChange::AbortAndSwitch);
+        let test_node_1 = match test_node.step(&2) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, ModeEnum::Calm);
+                assert_eq!(v, 2);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        match test_node_1.step(&-2) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), _) => {
+                assert_eq!(e, ModeEnum::Alert);
+                assert_eq!(v, -2);
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+    }
+
+    #[test]
+    fn switch_finish_test() {
+        use switch_node::{SwitchNode, KeyChange};
+        let test_node = SwitchNode::<Modes, _>::new(mode_of, KeyChange::FinishCurrent);
+        let test_node_1 = match test_node.step(&2) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&-2) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, _), n) => {
+                assert_eq!(e, ModeEnum::Calm);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        match test_node_2.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, (ModeEnum::Calm, 0)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,864 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use clock::{Clock, MonotonicClock};
+use random_selector::RandomSource;
+use std::time::Duration;
+use std::rc::Rc;
+use std::cell::Cell;
+
+/// Failure value of the timeout wrappers, containing the last nonterminal of 
+/// the node which timed out. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimedOut<N>(pub N);
+
+/// Wrapper for a node, which fails if the node has not terminated within a 
+/// given number of steps, aborting it. 
+///
+/// The node is given exactly the configured number of steps, and if the 
+/// last of them is nonterminal, the wrapper exits with Result::Err of that 
+/// nonterminal instead. Otherwise, it exits with Result::Ok of the terminal 
+/// of the node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TickTimeout<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    elapsed: usize,
+    limit: usize
+}
+
+impl<N> TickTimeout<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new tick timeout wrapper allowing the given number of steps. 
+    pub fn new(limit: usize, node: N) -> TickTimeout<N> {
+        TickTimeout {
+            node: node,
+            elapsed: 0,
+            limit: limit
+        }
+    }
+
+    /// Get the number of steps taken so far. 
+    pub fn elapsed(&self) -> usize {
+        self.elapsed
+    }
+
+    /// Restart the count of steps, as when the wrapper is entered anew. 
+    pub fn reset(self) -> TickTimeout<N> {
+        TickTimeout::new(self.limit, self.node)
+    }
+}
+
+impl<N> BehaviorTreeNode for TickTimeout<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, TimedOut<N::Nonterminal>>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let elapsed = self.elapsed + 1;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => if elapsed >= self.limit {
+                m.abort();
+                NodeResult::Terminal(Result::Err(TimedOut(n)))
+            } else {
+                NodeResult::Nonterminal(n, TickTimeout {
+                    node: m,
+                    elapsed: elapsed,
+                    limit: self.limit
+                })
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(Result::Ok(t))
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+
+/// Wrapper for a node, which fails if the node has not terminated within a 
+/// given duration of real time, as measured by a Clock, aborting it. 
+///
+/// The duration is measured from the first step of the wrapper. Once a step 
+/// of the node ends with the duration elapsed, and that step is 
+/// nonterminal, the wrapper exits with Result::Err of that nonterminal. 
+/// Otherwise, it exits with Result::Ok of the terminal of the node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Timeout<N, C=MonotonicClock> where 
+    N: BehaviorTreeNode,
+    C: Clock
+{
+    node: N,
+    clock: C,
+    started: Option<Duration>,
+    limit: Duration
+}
+
+impl<N> Timeout<N, MonotonicClock> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new timeout wrapper allowing the given duration, measured 
+    /// by a monotonic clock. 
+    pub fn new(limit: Duration, node: N) -> Timeout<N, MonotonicClock> {
+        Timeout::with_clock(limit, MonotonicClock::new(), node)
+    }
+}
+
+impl<N, C> Timeout<N, C> where 
+    N: BehaviorTreeNode,
+    C: Clock
+{
+    /// Create a new timeout wrapper allowing the given duration, measured 
+    /// by the given clock. 
+    pub fn with_clock(limit: Duration, clock: C, node: N) -> Timeout<N, C> {
+        Timeout {
+            node: node,
+            clock: clock,
+            started: Option::None,
+            limit: limit
+        }
+    }
+
+    /// Get the time elapsed since the first step, if there was one. 
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.started.map(|s| self.clock.now() - s)
+    }
+
+    /// Restart the measurement of time from the next step, as when the 
+    /// wrapper is entered anew. 
+    pub fn reset(self) -> Timeout<N, C> {
+        Timeout::with_clock(self.limit, self.clock, self.node)
+    }
+}
+
+impl<N, C> BehaviorTreeNode for Timeout<N, C> where 
+    N: BehaviorTreeNode,
+    C: Clock
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, TimedOut<N::Nonterminal>>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let started = self.started.unwrap_or_else(|| self.clock.now());
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                if self.clock.now() - started >= self.limit {
+                    m.abort();
+                    NodeResult::Terminal(Result::Err(TimedOut(n)))
+                } else {
+                    NodeResult::Nonterminal(n, Timeout {
+                        node: m,
+                        clock: self.clock,
+                        started: Option::Some(started),
+                        limit: self.limit
+                    })
+                }
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(Result::Ok(t))
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Wrapper for a node, which only steps the node every given number of 
+/// steps, repeating its last nonterminal in between, so that expensive 
+/// nodes do not run every tick. 
+///
+/// The node is stepped on the first step of the wrapper, and then once per 
+/// period. Inputs given on the steps in between are not seen by the node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RateLimit<N> where 
+    N: BehaviorTreeNode,
+    N::Nonterminal: Clone
+{
+    node: N,
+    last: Option<N::Nonterminal>,
+    skipped: usize,
+    period: usize
+}
+
+impl<N> RateLimit<N> where 
+    N: BehaviorTreeNode,
+    N::Nonterminal: Clone
+{
+    /// Create a new rate limiting wrapper, stepping the node once per the 
+    /// given number of steps, which has to be at least one. 
+    pub fn new(period: usize, node: N) -> RateLimit<N> {
+        assert!(period > 0, "RateLimit requires a period of at least one step");
+        RateLimit {
+            node: node,
+            last: Option::None,
+            skipped: 0,
+            period: period
+        }
+    }
+
+    /// Step the node on the next step, regardless of the period. 
+    pub fn reset(self) -> RateLimit<N> {
+        RateLimit::new(self.period, self.node)
+    }
+}
+
+impl<N> BehaviorTreeNode for RateLimit<N> where 
+    N: BehaviorTreeNode,
+    N::Nonterminal: Clone
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        N::Terminal, Self>
+    {
+        if let Option::Some(last) = self.last {
+            if self.skipped + 1 < self.period {
+                return NodeResult::Nonterminal(last.clone(), RateLimit {
+                    node: self.node,
+                    last: Option::Some(last),
+                    skipped: self.skipped + 1,
+                    period: self.period
+                });
+            }
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                n.clone(),
+                RateLimit {
+                    node: m,
+                    last: Option::Some(n),
+                    skipped: 0,
+                    period: self.period
+                }
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Nonterminal type of the debounce wrapper. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DebounceNonterm<N> {
+    /// The condition was stepped, and did not terminate. 
+    Running(N),
+    /// The condition succeeded, and has now held for the contained number 
+    /// of consecutive steps. 
+    Held(usize)
+}
+
+/// Wrapper for a condition, which only lets its success through after the 
+/// condition has held for a given number of consecutive steps, suppressing 
+/// flicker from noisy inputs. 
+///
+/// A fresh instance of the condition is run on each step after the previous 
+/// one succeeds. The wrapper succeeds with the success value of the instance 
+/// completing the streak, and fails with the failure value of any instance 
+/// failing before then. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Debounce<N, M> where 
+    M: Fn() -> N
+{
+    node: N,
+    maker: M,
+    held: usize,
+    required: usize
+}
+
+impl<N, M> Debounce<N, M> where 
+    M: Fn() -> N
+{
+    /// Create a new debounce wrapper from a constructor of the condition, 
+    /// requiring it to hold for the given number of consecutive steps, which 
+    /// has to be at least one. 
+    pub fn new(maker: M, required: usize) -> Debounce<N, M> {
+        assert!(required > 0, "Debounce requires at least one step");
+        Debounce {
+            node: maker(),
+            maker: maker,
+            held: 0,
+            required: required
+        }
+    }
+
+    /// Get the number of consecutive steps the condition has held for. 
+    pub fn held(&self) -> usize {
+        self.held
+    }
+}
+
+impl<N, M, T, F> BehaviorTreeNode for Debounce<N, M> where 
+    N: BehaviorTreeNode<Terminal=Result<T, F>>,
+    M: Fn() -> N
+{
+    type Input = N::Input;
+    type Nonterminal = DebounceNonterm<N::Nonterminal>;
+    type Terminal = Result<T, F>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal,
+        Result<T, F>, Self>
+    {
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                DebounceNonterm::Running(n),
+                Debounce {
+                    node: m,
+                    maker: self.maker,
+                    held: self.held,
+                    required: self.required
+                }
+            ),
+            NodeResult::Terminal(Result::Ok(t)) => {
+                let held = self.held + 1;
+                if held >= self.required {
+                    NodeResult::Terminal(Result::Ok(t))
+                } else {
+                    NodeResult::Nonterminal(
+                        DebounceNonterm::Held(held),
+                        Debounce {
+                            node: (self.maker)(),
+                            maker: self.maker,
+                            held: held,
+                            required: self.required
+                        }
+                    )
+                }
+            },
+            NodeResult::Terminal(Result::Err(f)) => NodeResult::Terminal(Result::Err(f))
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Failure value of the budgeted wrapper, given when the step budget is 
+/// exhausted. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BudgetExhausted;
+
+/// Budget of steps shared by a family of budgeted wrappers, counting the 
+/// steps of a node across its whole lifetime, including re-entries. 
+///
+/// Clones of the budget share the same count of remaining steps. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct StepBudget {
+    remaining: Rc<Cell<usize>>
+}
+
+impl StepBudget {
+    /// Create a new budget of the given number of steps. 
+    pub fn new(steps: usize) -> StepBudget {
+        StepBudget {
+            remaining: Rc::new(Cell::new(steps))
+        }
+    }
+
+    /// Get the number of steps remaining. 
+    pub fn remaining(&self) -> usize {
+        self.remaining.get()
+    }
+
+    /// Check whether no steps remain. 
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining.get() == 0
+    }
+
+    /// Add the given number of steps to the budget. 
+    pub fn replenish(&self, steps: usize) {
+        self.remaining.set(self.remaining.get().saturating_add(steps));
+    }
+}
+
+/// Wrapper for a node, which charges each step of the node to a shared step 
+/// budget, and fails once the budget is exhausted, aborting the node. 
+///
+/// If the budget is already exhausted when the wrapper is stepped, the node 
+/// is not stepped at all. If the step using up the rest of the budget is 
+/// nonterminal, the wrapper also fails, so trees using the budget are 
+/// guaranteed to terminate. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct Budgeted<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    budget: StepBudget
+}
+
+impl<N> Budgeted<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new budgeted wrapper charging the given budget. 
+    pub fn new(budget: StepBudget, node: N) -> Budgeted<N> {
+        Budgeted {
+            node: node,
+            budget: budget
+        }
+    }
+}
+
+impl<N> BehaviorTreeNode for Budgeted<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = N::Nonterminal;
+    type Terminal = Result<N::Terminal, BudgetExhausted>;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<N::Nonterminal,
+        Self::Terminal, Self>
+    {
+        if self.budget.is_exhausted() {
+            self.node.abort();
+            return NodeResult::Terminal(Result::Err(BudgetExhausted));
+        }
+        self.budget.remaining.set(self.budget.remaining() - 1);
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => if self.budget.is_exhausted() {
+                m.abort();
+                NodeResult::Terminal(Result::Err(BudgetExhausted))
+            } else {
+                NodeResult::Nonterminal(n, Budgeted::new(self.budget, m))
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(Result::Ok(t))
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Nonterminal type of the jitter wrapper. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum JitterNonterm<N> {
+    /// The node has not been entered yet, and will be after the contained 
+    /// number of further steps. 
+    Delayed(usize),
+    /// The node was stepped, and did not terminate. 
+    Running(N)
+}
+
+/// Wrapper for a node, which delays the first step of the node by a random 
+/// number of steps, so that crowds of agents running identical trees do 
+/// not act in lockstep. 
+///
+/// The delay is drawn when the wrapper is created, and inputs given during 
+/// the delay are not seen by the node. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Jitter<N> where 
+    N: BehaviorTreeNode
+{
+    node: N,
+    delay: usize
+}
+
+impl<N> Jitter<N> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new jitter wrapper, delaying the node by a number of steps 
+    /// drawn uniformly from the inclusive range between the given bounds. 
+    pub fn new<R>(min: usize, max: usize, source: &mut R, node: N) -> Jitter<N> where 
+        R: RandomSource
+    {
+        assert!(min <= max, "Jitter requires a minimum delay of at most the maximum");
+        let span = (max - min) as f64 + 1.0;
+        let offset = (source.next_unit() * span) as usize;
+        Jitter::with_delay(min + offset.min(max - min), node)
+    }
+
+    /// Create a new jitter wrapper with the given delay. 
+    pub fn with_delay(delay: usize, node: N) -> Jitter<N> {
+        Jitter {
+            node: node,
+            delay: delay
+        }
+    }
+
+    /// Get the number of steps remaining before the node is entered. 
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+}
+
+impl<N> BehaviorTreeNode for Jitter<N> where 
+    N: BehaviorTreeNode
+{
+    type Input = N::Input;
+    type Nonterminal = JitterNonterm<N::Nonterminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal,
+        N::Terminal, Self>
+    {
+        if self.delay > 0 {
+            let delay = self.delay - 1;
+            return NodeResult::Nonterminal(
+                JitterNonterm::Delayed(delay), 
+                Jitter::with_delay(delay, self.node)
+            );
+        }
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => NodeResult::Nonterminal(
+                JitterNonterm::Running(n),
+                Jitter::with_delay(0, m)
+            ),
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+/// Nonterminal type of the progress wrapper, annotating the nonterminal of 
+/// the node with how long it has been running. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Progress<N> {
+    /// The nonterminal of the node. 
+    pub nonterminal: N,
+    /// The number of steps of the node so far, including this one. 
+    pub steps: usize,
+    /// The real time elapsed since the first step, if the wrapper has a 
+    /// clock. 
+    pub elapsed: Option<Duration>
+}
+
+/// Wrapper for a node, which annotates each nonterminal of the node with 
+/// the number of steps it has been running, and optionally the real time, 
+/// so that supervising nodes can act on nodes running for too long. 
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ProgressNode<N, C=MonotonicClock> where 
+    N: BehaviorTreeNode,
+    C: Clock
+{
+    node: N,
+    clock: Option<C>,
+    started: Option<Duration>,
+    steps: usize
+}
+
+impl<N> ProgressNode<N, MonotonicClock> where 
+    N: BehaviorTreeNode
+{
+    /// Create a new progress wrapper counting only steps. 
+    pub fn new(node: N) -> ProgressNode<N, MonotonicClock> {
+        ProgressNode {
+            node: node,
+            clock: Option::None,
+            started: Option::None,
+            steps: 0
+        }
+    }
+}
+
+impl<N, C> ProgressNode<N, C> where 
+    N: BehaviorTreeNode,
+    C: Clock
+{
+    /// Create a new progress wrapper counting steps, and measuring real time 
+    /// by the given clock. 
+    pub fn with_clock(clock: C, node: N) -> ProgressNode<N, C> {
+        ProgressNode {
+            node: node,
+            clock: Option::Some(clock),
+            started: Option::None,
+            steps: 0
+        }
+    }
+}
+
+impl<N, C> BehaviorTreeNode for ProgressNode<N, C> where 
+    N: BehaviorTreeNode,
+    C: Clock
+{
+    type Input = N::Input;
+    type Nonterminal = Progress<N::Nonterminal>;
+    type Terminal = N::Terminal;
+
+    #[inline]
+    fn step(self, input: &N::Input) -> NodeResult<Self::Nonterminal,
+        N::Terminal, Self>
+    {
+        let started = self.started
+            .or_else(|| self.clock.as_ref().map(|c| c.now()));
+        let steps = self.steps + 1;
+        match self.node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                let elapsed = self.clock.as_ref()
+                    .and_then(|c| started.map(|s| c.now() - s));
+                NodeResult::Nonterminal(
+                    Progress {
+                        nonterminal: n,
+                        steps: steps,
+                        elapsed: elapsed
+                    },
+                    ProgressNode {
+                        node: m,
+                        clock: self.clock,
+                        started: started,
+                        steps: steps
+                    }
+                )
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal(t)
+        }
+    }
+
+    fn abort(self) {
+        self.node.abort()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use time_wrappers::TimedOut;
+
+    fn wait_nonzero(input: &i64) -> Statepoint<i64, i64> {
+        if *input == 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[test]
+    fn tick_timeout_test() {
+        use time_wrappers::TickTimeout;
+        let test_node = TickTimeout::new(2, PredicateWait::new(wait_nonzero));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        assert_eq!(test_node_1.elapsed(), 1);
+        match test_node_1.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(TimedOut(0))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn tick_timeout_reset_test() {
+        use time_wrappers::TickTimeout;
+        let test_node = TickTimeout::new(2, PredicateWait::new(wait_nonzero));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n.reset(),
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_2.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(3)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn timeout_test() {
+        use time_wrappers::Timeout;
+        use clock::ManualClock;
+        use std::time::Duration;
+        let clock = ManualClock::new();
+        let test_node = Timeout::with_clock(
+            Duration::from_millis(10),
+            clock.clone(),
+            PredicateWait::new(wait_nonzero)
+        );
+        clock.advance(Duration::from_millis(100));
+        // The time before the first step does not count
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        clock.advance(Duration::from_millis(5));
+        assert_eq!(test_node_1.elapsed(), Option::Some(Duration::from_millis(5)));
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        clock.advance(Duration::from_millis(5));
+        match test_node_2.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(TimedOut(0))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn rate_limit_test() {
+        use time_wrappers::RateLimit;
+        let test_node = RateLimit::new(3, PredicateWait::new(wait_nonzero));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        // The node does not see the next two inputs
+        let test_node_2 = match test_node_1.step(&5) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let test_node_3 = match test_node_2.step(&6) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(v, 0);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_3.step(&7) {
+            NodeResult::Terminal(t) => assert_eq!(t, 7),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    fn sensor(input: &i64) -> Result<i64, i64> {
+        if *input > 0 {
+            Result::Ok(*input)
+        } else {
+            Result::Err(*input)
+        }
+    }
+
+    #[test]
+    fn debounce_test() {
+        use base_nodes::Evaluation;
+        use time_wrappers::{Debounce, DebounceNonterm};
+        let test_node = Debounce::new(|| Evaluation::new(sensor), 2);
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(DebounceNonterm::Held(1), n) => n,
+            _ => unreachable!("Expected held nonterminal")
+        };
+        match test_node_1.step(&2) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(2)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn debounce_flicker_test() {
+        use base_nodes::Evaluation;
+        use time_wrappers::{Debounce, DebounceNonterm};
+        let test_node = Debounce::new(|| Evaluation::new(sensor), 3);
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(DebounceNonterm::Held(1), n) => n,
+            _ => unreachable!("Expected held nonterminal")
+        };
+        match test_node_1.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(-1)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn budgeted_test() {
+        use time_wrappers::{Budgeted, BudgetExhausted, StepBudget};
+        let budget = StepBudget::new(3);
+        let test_node = Budgeted::new(budget.clone(), PredicateWait::new(wait_nonzero));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&4) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok(4)),
+            _ => unreachable!("Expected terminal transition")
+        };
+        // Reentering charges the same budget
+        let test_node_2 = Budgeted::new(budget.clone(), PredicateWait::new(wait_nonzero));
+        match test_node_2.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(BudgetExhausted)),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert!(budget.is_exhausted());
+        let test_node_3 = Budgeted::new(budget.clone(), PredicateWait::new(wait_nonzero));
+        match test_node_3.step(&5) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err(BudgetExhausted)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn jitter_test() {
+        use time_wrappers::{Jitter, JitterNonterm};
+        use random_selector::XorShiftSource;
+        let mut source = XorShiftSource::new(7);
+        for _ in 0..50 {
+            let test_node = Jitter::new(2, 4, &mut source, 
+                PredicateWait::new(wait_nonzero));
+            assert!(test_node.delay() >= 2 && test_node.delay() <= 4);
+        }
+        let test_node = Jitter::with_delay(1, PredicateWait::new(wait_nonzero));
+        let test_node_1 = match test_node.step(&5) {
+            NodeResult::Nonterminal(JitterNonterm::Delayed(0), n) => n,
+            _ => unreachable!("Expected delayed nonterminal")
+        };
+        match test_node_1.step(&6) {
+            NodeResult::Terminal(t) => assert_eq!(t, 6),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn progress_test() {
+        use time_wrappers::{Progress, ProgressNode};
+        use clock::ManualClock;
+        use std::time::Duration;
+        let test_node = ProgressNode::new(PredicateWait::new(wait_nonzero));
+        match test_node.step(&0) {
+            NodeResult::Nonterminal(p, _) => assert_eq!(p, Progress {
+                nonterminal: 0,
+                steps: 1,
+                elapsed: Option::None
+            }),
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let clock = ManualClock::new();
+        let timed_node = ProgressNode::with_clock(clock.clone(), 
+            PredicateWait::new(wait_nonzero));
+        let timed_node_1 = match timed_node.step(&0) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        clock.advance(Duration::from_millis(30));
+        match timed_node_1.step(&0) {
+            NodeResult::Nonterminal(p, _) => {
+                assert_eq!(p.steps, 2);
+                assert_eq!(p.elapsed, Option::Some(Duration::from_millis(30)));
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,385 @@
+use behavior_tree_node::Statepoint;
+use binary_codec::{BinaryCodec, CodecError};
+use checksum;
+use observe_wrappers::NodeObserver;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+/// Bytes every trace starts with, followed by the version of the format. 
+const MAGIC: [u8; 4] = *b"SBTT";
+
+/// Version of the format of traces written by this module. 
+pub const TRACE_VERSION: u16 = 1;
+
+/// What became of a traced node on a step. 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum TraceTransition {
+    /// The node was stepped to a nonterminal, and goes on running. 
+    Continued,
+    /// The node was stepped to a terminal, and exited. 
+    Terminated,
+    /// The node was aborted. 
+    Aborted
+}
+
+/// Record of one step of a traced node. 
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceRecord<N, T> {
+    /// The number of the step, counting from zero. 
+    pub tick: u64,
+    /// The digest of the input of the step, or zero for aborts. 
+    pub input_digest: u64,
+    /// The statepoint the node was stepped to, or nothing for aborts. 
+    pub statepoint: Option<Statepoint<N, T>>,
+    /// What became of the node. 
+    pub transition: TraceTransition
+}
+
+/// Digest the given input, as recorded in traces. 
+pub fn input_digest<I>(input: &I) -> u64 where 
+    I: Hash + ?Sized
+{
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Writer of traces, as a header followed by records which are each written 
+/// in the compact binary encoding, prefixed with their length and followed 
+/// by their checksum. 
+#[derive(Debug)]
+pub struct TraceWriter<W> where 
+    W: Write
+{
+    codec: BinaryCodec,
+    writer: W
+}
+
+impl TraceWriter<BufWriter<File>> {
+    /// Create a trace file at the given path, replacing any existing one. 
+    pub fn create<P>(path: P) -> Result<TraceWriter<BufWriter<File>>,
+        CodecError> where 
+        P: AsRef<Path>
+    {
+        let file = File::create(path).map_err(CodecError::Io)?;
+        TraceWriter::new(BinaryCodec::new(), BufWriter::new(file))
+    }
+}
+
+impl<W> TraceWriter<W> where 
+    W: Write
+{
+    /// Create a new trace writer, writing the header to the given writer. 
+    pub fn new(codec: BinaryCodec, writer: W) -> Result<TraceWriter<W>,
+        CodecError>
+    {
+        let mut mut_writer = writer;
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&TRACE_VERSION.to_le_bytes());
+        mut_writer.write_all(&header).map_err(CodecError::Io)?;
+        Result::Ok(TraceWriter {
+            codec: codec,
+            writer: mut_writer
+        })
+    }
+
+    /// Append a record to the trace. 
+    pub fn write<N, T>(&mut self, record: &TraceRecord<N, T>) ->
+        Result<(), CodecError> where 
+        N: Serialize,
+        T: Serialize
+    {
+        let payload = self.codec.encode_payload(record)?;
+        let mut frame = Vec::with_capacity(payload.len() + 8);
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+        frame.extend_from_slice(&checksum::crc32(&payload).to_le_bytes());
+        self.writer.write_all(&frame).map_err(CodecError::Io)
+    }
+
+    /// Flush the records written so far. 
+    pub fn flush(&mut self) -> Result<(), CodecError> {
+        self.writer.flush().map_err(CodecError::Io)
+    }
+
+    /// Get the underlying writer back. 
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn read_exact_or_end<R>(reader: &mut R, buffer: &mut [u8]) ->
+    Result<bool, CodecError> where 
+    R: Read
+{
+    let mut read = 0;
+    while read < buffer.len() {
+        match reader.read(&mut buffer[read..]) {
+            Result::Ok(0) if read == 0 => return Result::Ok(false),
+            Result::Ok(0) => return Result::Err(CodecError::Io(
+                ErrorKind::UnexpectedEof.into())),
+            Result::Ok(n) => read += n,
+            Result::Err(ref e) if e.kind() == ErrorKind::Interrupted => (),
+            Result::Err(e) => return Result::Err(CodecError::Io(e))
+        }
+    }
+    Result::Ok(true)
+}
+
+/// Reader of traces written by a trace writer. 
+#[derive(Debug)]
+pub struct TraceReader<R> where 
+    R: Read
+{
+    codec: BinaryCodec,
+    reader: R
+}
+
+impl TraceReader<BufReader<File>> {
+    /// Open the trace file at the given path. 
+    pub fn open<P>(path: P) -> Result<TraceReader<BufReader<File>>,
+        CodecError> where 
+        P: AsRef<Path>
+    {
+        let file = File::open(path).map_err(CodecError::Io)?;
+        TraceReader::new(BinaryCodec::new(), BufReader::new(file))
+    }
+}
+
+impl<R> TraceReader<R> where 
+    R: Read
+{
+    /// Create a new trace reader, reading the header from the given reader. 
+    pub fn new(codec: BinaryCodec, reader: R) -> Result<TraceReader<R>,
+        CodecError>
+    {
+        let mut mut_reader = reader;
+        let mut header = [0; 6];
+        if !read_exact_or_end(&mut mut_reader, &mut header)? ||
+            header[..4] != MAGIC
+        {
+            return Result::Err(CodecError::Magic);
+        }
+        let version = header[4] as u16 | (header[5] as u16) << 8;
+        if version != TRACE_VERSION {
+            return Result::Err(CodecError::Version(version));
+        }
+        Result::Ok(TraceReader {
+            codec: codec,
+            reader: mut_reader
+        })
+    }
+
+    /// Read the next record of the trace, or nothing at the end of the 
+    /// trace. A record cut short by the end of the trace is an error. 
+    pub fn next_record<N, T>(&mut self) ->
+        Result<Option<TraceRecord<N, T>>, CodecError> where 
+        N: DeserializeOwned,
+        T: DeserializeOwned
+    {
+        let mut len = [0; 4];
+        if !read_exact_or_end(&mut self.reader, &mut len)? {
+            return Result::Ok(Option::None);
+        }
+        let len = u32::from_le_bytes(len) as u64;
+        if len > self.codec.limit() {
+            return Result::Err(CodecError::TooLarge);
+        }
+        let mut frame = vec![0; len as usize + checksum::CHECKSUM_LEN];
+        if !read_exact_or_end(&mut self.reader, &mut frame)? {
+            return Result::Err(CodecError::Io(
+                ErrorKind::UnexpectedEof.into()));
+        }
+        let payload = checksum::unseal(&frame).map_err(CodecError::Checksum)?;
+        self.codec.decode_payload(payload).map(Option::Some)
+    }
+
+    /// Read all of the remaining records of the trace. 
+    pub fn read_all<N, T>(&mut self) ->
+        Result<Vec<TraceRecord<N, T>>, CodecError> where 
+        N: DeserializeOwned,
+        T: DeserializeOwned
+    {
+        let mut records = Vec::new();
+        while let Option::Some(r) = self.next_record()? {
+            records.push(r);
+        }
+        Result::Ok(records)
+    }
+}
+
+#[derive(Debug)]
+struct Recording<W> where 
+    W: Write
+{
+    writer: TraceWriter<W>,
+    tick: u64,
+    digest: u64,
+    error: Option<CodecError>
+}
+
+impl<W> Recording<W> where 
+    W: Write
+{
+    fn record<N, T>(&mut self, statepoint: Option<Statepoint<&N, &T>>,
+        transition: TraceTransition) where 
+        N: Serialize,
+        T: Serialize
+    {
+        let record = TraceRecord {
+            tick: self.tick,
+            input_digest: self.digest,
+            statepoint: statepoint,
+            transition: transition
+        };
+        self.tick += 1;
+        self.digest = 0;
+        if self.error.is_none() {
+            self.error = self.writer.write(&record).err();
+        }
+    }
+}
+
+/// Observer recording every step of the node it observes to a trace, as 
+/// the foundation for replaying runs and stepping back through them. 
+///
+/// Observers are shared by their clones, so that the recording outlives the 
+/// observed node. Failing to write a record stops the recording, and the 
+/// error is kept for the owner of the recorder to take. 
+#[derive(Debug)]
+pub struct TraceRecorder<W> where 
+    W: Write
+{
+    recording: Rc<RefCell<Recording<W>>>
+}
+
+impl<W> Clone for TraceRecorder<W> where 
+    W: Write
+{
+    fn clone(&self) -> Self {
+        TraceRecorder {
+            recording: self.recording.clone()
+        }
+    }
+}
+
+impl<W> TraceRecorder<W> where 
+    W: Write
+{
+    /// Create a new recorder writing to the given trace. 
+    pub fn new(writer: TraceWriter<W>) -> TraceRecorder<W> {
+        TraceRecorder {
+            recording: Rc::new(RefCell::new(Recording {
+                writer: writer,
+                tick: 0,
+                digest: 0,
+                error: Option::None
+            }))
+        }
+    }
+
+    /// Take the error which stopped the recording, if any. 
+    pub fn take_error(&self) -> Option<CodecError> {
+        self.recording.borrow_mut().error.take()
+    }
+
+    /// Flush the records written so far. 
+    pub fn flush(&self) -> Result<(), CodecError> {
+        self.recording.borrow_mut().writer.flush()
+    }
+}
+
+impl<W, I, N, T> NodeObserver<I, N, T> for TraceRecorder<W> where 
+    W: Write,
+    I: Hash,
+    N: Serialize,
+    T: Serialize
+{
+    fn on_step(&mut self, input: &I) {
+        self.recording.borrow_mut().digest = input_digest(input);
+    }
+
+    fn on_nonterminal(&mut self, _input: &I, nonterminal: &N) {
+        self.recording.borrow_mut().record::<N, T>(
+            Option::Some(Statepoint::Nonterminal(nonterminal)),
+            TraceTransition::Continued);
+    }
+
+    fn on_terminal(&mut self, _input: &I, terminal: &T) {
+        self.recording.borrow_mut().record::<N, T>(
+            Option::Some(Statepoint::Terminal(terminal)),
+            TraceTransition::Terminated);
+    }
+
+    fn on_abort(&mut self) {
+        self.recording.borrow_mut().record::<N, T>(Option::None,
+            TraceTransition::Aborted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use binary_codec::{BinaryCodec, CodecError};
+    use observe_wrappers::ObservedNode;
+    use trace::{input_digest, TraceReader, TraceRecord, TraceRecorder,
+        TraceTransition, TraceWriter};
+
+    #[test]
+    fn trace_test() {
+        let writer = TraceWriter::new(BinaryCodec::new(), Vec::new())
+            .unwrap();
+        let recorder = TraceRecorder::new(writer);
+        let node = ObservedNode::new(recorder.clone(),
+            PredicateWait::new(|i: &i64| if *i < 3 {
+                Statepoint::Nonterminal(*i)
+            } else {
+                Statepoint::Terminal(*i > 4)
+            }));
+        let node_1 = match node.step(&1) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match node_1.step(&5) {
+            NodeResult::Terminal(t) => assert!(t),
+            _ => unreachable!("Expected terminal transition")
+        };
+        assert!(recorder.take_error().is_none());
+        let bytes = match ::std::rc::Rc::try_unwrap(recorder.recording) {
+            Result::Ok(r) => r.into_inner().writer.into_inner(),
+            Result::Err(_) => unreachable!("Expected sole recorder")
+        };
+        let mut reader = TraceReader::new(BinaryCodec::new(), &bytes[..])
+            .unwrap();
+        let records: Vec<TraceRecord<i64, bool>> = reader.read_all().unwrap();
+        assert_eq!(records, vec![
+            TraceRecord {
+                tick: 0,
+                input_digest: input_digest(&1i64),
+                statepoint: Option::Some(Statepoint::Nonterminal(1)),
+                transition: TraceTransition::Continued
+            },
+            TraceRecord {
+                tick: 1,
+                input_digest: input_digest(&5i64),
+                statepoint: Option::Some(Statepoint::Terminal(true)),
+                transition: TraceTransition::Terminated
+            }
+        ]);
+        let mut cut = TraceReader::new(BinaryCodec::new(),
+            &bytes[..bytes.len() - 1]).unwrap();
+        assert!(cut.next_record::<i64, bool>().unwrap().is_some());
+        match cut.next_record::<i64, bool>() {
+            Result::Err(CodecError::Io(_)) => (),
+            _ => unreachable!("Expected truncated record")
+        };
+    }
+}
\ No newline at end of file
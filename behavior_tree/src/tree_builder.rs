@@ -0,0 +1,265 @@
+use base_nodes::PredicateWait;
+use behavior_tree_node::{BehaviorTreeNode, BtStatus, Statepoint};
+use dynamic_node::BoxedNode;
+use node_registry::{force_failure, force_success, forced_to, selector_of,
+    sequence_of, DynTree};
+use shared_subtree::SubtreeTemplate;
+use status_wrappers::Inverter;
+use std::fmt;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Composite {
+    Sequence,
+    Selector,
+    Inverter,
+    ForceSuccess,
+    ForceFailure
+}
+
+/// Entry point of the fluent building of trees, whose constructors start a 
+/// builder of the root of the tree. 
+///
+/// # Example 
+/// ```
+/// use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode,
+///     BtStatus, NodeResult};
+/// use stackbt_behavior_tree::tree_builder::Tree;
+///
+/// let tree = Tree::sequence()
+///     .condition(|i: &i64| *i > 0)
+///     .selector(|s| s
+///         .condition(|i: &i64| *i > 10)
+///         .inverter(|v| v.condition(|i: &i64| *i % 2 == 1)))
+///     .build();
+/// let tree_1 = match tree.step(&4) {
+///     NodeResult::Nonterminal(_, n) => n,
+///     _ => unreachable!("Expected nonterminal transition")
+/// };
+/// let tree_2 = match tree_1.step(&4) {
+///     NodeResult::Nonterminal(_, n) => n,
+///     _ => unreachable!("Expected nonterminal transition")
+/// };
+/// match tree_2.step(&4) {
+///     NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+///     _ => unreachable!("Expected terminal transition")
+/// };
+/// ```
+#[derive(Debug)]
+pub enum Tree {}
+
+impl Tree {
+    /// Start building a sequence. 
+    pub fn sequence<I>() -> TreeBuilder<I> where 
+        I: 'static
+    {
+        TreeBuilder::new(Composite::Sequence)
+    }
+
+    /// Start building a selector. 
+    pub fn selector<I>() -> TreeBuilder<I> where 
+        I: 'static
+    {
+        TreeBuilder::new(Composite::Selector)
+    }
+
+    /// Start building an inverter of one child. 
+    pub fn inverter<I>() -> TreeBuilder<I> where 
+        I: 'static
+    {
+        TreeBuilder::new(Composite::Inverter)
+    }
+}
+
+/// Builder of a composite or decorator of a tree, producing the same boxed 
+/// dynamic nodes as trees instantiated from definitions. 
+///
+/// The leaves are added as nodes of any type taking the input of the tree, 
+/// so that mismatched leaves are rejected at compile time. The composites 
+/// and decorators nested in the built one are each built by a closure, 
+/// starting from an empty builder. 
+pub struct TreeBuilder<I> {
+    kind: Composite,
+    children: Vec<DynTree<I>>
+}
+
+impl<I> fmt::Debug for TreeBuilder<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TreeBuilder")
+            .field("kind", &self.kind)
+            .field("children", &self.children.len())
+            .finish()
+    }
+}
+
+impl<I> TreeBuilder<I> where 
+    I: 'static
+{
+    fn new(kind: Composite) -> TreeBuilder<I> {
+        TreeBuilder {
+            kind: kind,
+            children: Vec::new()
+        }
+    }
+
+    fn nested<F>(self, kind: Composite, build: F) -> TreeBuilder<I> where 
+        F: FnOnce(TreeBuilder<I>) -> TreeBuilder<I>
+    {
+        self.tree(build(TreeBuilder::new(kind)).build())
+    }
+
+    /// Add a leaf to the end of the children. 
+    pub fn child<N>(self, node: N) -> TreeBuilder<I> where 
+        N: BehaviorTreeNode<Input=I, Nonterminal=(), Terminal=BtStatus>
+            + 'static
+    {
+        self.tree(BoxedNode::new(node))
+    }
+
+    /// Add an already built tree to the end of the children, such as one 
+    /// instantiated from a definition. 
+    pub fn tree(self, tree: DynTree<I>) -> TreeBuilder<I> {
+        let mut mut_self = self;
+        mut_self.children.push(tree);
+        mut_self
+    }
+
+    /// Add an instance of the template for the given configuration to the 
+    /// end of the children. 
+    pub fn instance<C, N>(self, template: &SubtreeTemplate<C, N>,
+        config: &C) -> TreeBuilder<I> where 
+        N: BehaviorTreeNode<Input=I, Nonterminal=(), Terminal=BtStatus>
+            + 'static
+    {
+        self.child(template.construct(config))
+    }
+
+    /// Add a leaf which succeeds when the given predicate holds for the 
+    /// input, and fails otherwise. 
+    pub fn condition<F>(self, predicate: F) -> TreeBuilder<I> where 
+        F: Fn(&I) -> bool + 'static
+    {
+        self.child(PredicateWait::new(move |i: &I| {
+            Statepoint::Terminal(BtStatus::from(predicate(i)))
+        }))
+    }
+
+    /// Add a sequence, built by the given closure. 
+    pub fn sequence<F>(self, build: F) -> TreeBuilder<I> where 
+        F: FnOnce(TreeBuilder<I>) -> TreeBuilder<I>
+    {
+        self.nested(Composite::Sequence, build)
+    }
+
+    /// Add a selector, built by the given closure. 
+    pub fn selector<F>(self, build: F) -> TreeBuilder<I> where 
+        F: FnOnce(TreeBuilder<I>) -> TreeBuilder<I>
+    {
+        self.nested(Composite::Selector, build)
+    }
+
+    /// Add an inverter of the one child added by the given closure. 
+    pub fn inverter<F>(self, build: F) -> TreeBuilder<I> where 
+        F: FnOnce(TreeBuilder<I>) -> TreeBuilder<I>
+    {
+        self.nested(Composite::Inverter, build)
+    }
+
+    /// Add a node which runs the one child added by the given closure, and 
+    /// succeeds whenever it exits. 
+    pub fn force_success<F>(self, build: F) -> TreeBuilder<I> where 
+        F: FnOnce(TreeBuilder<I>) -> TreeBuilder<I>
+    {
+        self.nested(Composite::ForceSuccess, build)
+    }
+
+    /// Add a node which runs the one child added by the given closure, and 
+    /// fails whenever it exits. 
+    pub fn force_failure<F>(self, build: F) -> TreeBuilder<I> where 
+        F: FnOnce(TreeBuilder<I>) -> TreeBuilder<I>
+    {
+        self.nested(Composite::ForceFailure, build)
+    }
+
+    /// Build the tree. 
+    ///
+    /// Panics if a composite has no children, or a decorator does not have 
+    /// exactly one child. 
+    pub fn build(self) -> DynTree<I> {
+        let kind = self.kind;
+        let mut children = self.children;
+        match kind {
+            Composite::Sequence => sequence_of(children),
+            Composite::Selector => selector_of(children),
+            _ => {
+                assert!(children.len() == 1, "{:?} requires exactly one node",
+                    kind);
+                let child = children.pop().unwrap();
+                match kind {
+                    Composite::Inverter => {
+                        BoxedNode::new(Inverter::new(child))
+                    },
+                    Composite::ForceSuccess => {
+                        forced_to(child, force_success)
+                    },
+                    _ => forced_to(child, force_failure)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+    use shared_subtree::SubtreeTemplate;
+    use tree_builder::Tree;
+
+    #[test]
+    fn tree_builder_test() {
+        let tree = Tree::selector()
+            .sequence(|s| s
+                .condition(|i: &i64| *i < 0)
+                .child(PredicateWait::new(|i: &i64| if *i == -1 {
+                    Statepoint::Nonterminal(())
+                } else {
+                    Statepoint::Terminal(BtStatus::Success)
+                })))
+            .force_failure(|f| f.condition(|_: &i64| true))
+            .build();
+        match tree.step(&3) {
+            NodeResult::Nonterminal(_, n) => match n.step(&3) {
+                NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Failure),
+                _ => unreachable!("Expected terminal transition")
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+    }
+
+    #[test]
+    fn tree_builder_instance_test() {
+        let at_least = SubtreeTemplate::new(|bound: &i64| {
+            let bound = *bound;
+            PredicateWait::new(move |i: &i64| {
+                Statepoint::Terminal(BtStatus::from(*i >= bound))
+            })
+        });
+        let tree = Tree::inverter()
+            .instance(&at_least, &5)
+            .build();
+        match tree.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    #[should_panic]
+    fn tree_builder_children_test() {
+        Tree::inverter::<i64>()
+            .condition(|_| true)
+            .condition(|_| false)
+            .build();
+    }
+}
\ No newline at end of file
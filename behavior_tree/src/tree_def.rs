@@ -0,0 +1,322 @@
+use expression::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a hash over a canonical encoding of definitions, in which lengths 
+/// and numbers are written as little endian 64 bit integers. 
+struct StructuralHasher(u64);
+
+impl StructuralHasher {
+    fn bytes(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ *b as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn int(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    fn str(&mut self, value: &str) {
+        self.int(value.len() as u64);
+        self.bytes(value.as_bytes())
+    }
+
+    fn value(&mut self, value: &Value) {
+        match *value {
+            Value::Int(i) => {
+                self.bytes(&[0]);
+                self.int(i as u64)
+            },
+            Value::Float(f) => {
+                self.bytes(&[1]);
+                self.int(if f == 0.0 { 0 } else { f.to_bits() })
+            },
+            Value::Bool(b) => self.bytes(&[2, b as u8]),
+            Value::Str(ref s) => {
+                self.bytes(&[3]);
+                self.str(s)
+            }
+        }
+    }
+
+    fn def(&mut self, def: &NodeDef) {
+        self.str(&def.kind);
+        self.int(def.params.len() as u64);
+        for (name, value) in &def.params {
+            self.str(name);
+            self.value(value);
+        }
+        self.int(def.children.len() as u64);
+        for child in &def.children {
+            self.def(child);
+        }
+    }
+}
+
+/// Error of loading a tree from a definition. 
+#[derive(Clone, PartialEq, Debug)]
+pub enum LoadError {
+    /// The source of the definition failed to be parsed, for the given 
+    /// reason. 
+    Parse(String),
+    /// No node of the given kind is registered. 
+    UnknownKind(String),
+    /// The node of the given kind is missing the parameter with the given 
+    /// name, or it has a value of the wrong type. 
+    Param(String, String),
+    /// The node of the given kind has the wrong number of children. 
+    Children(String),
+    /// The subtree with the given ID is not defined, or includes itself. 
+    Subtree(String)
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Parse(ref m) => write!(f, "parse error: {}", m),
+            LoadError::UnknownKind(ref k) => write!(f, "unknown node kind {}",
+                k),
+            LoadError::Param(ref k, ref p) => write!(f,
+                "node {} has a missing or invalid parameter {}", k, p),
+            LoadError::Children(ref k) => write!(f,
+                "node {} has the wrong number of children", k),
+            LoadError::Subtree(ref i) => write!(f,
+                "subtree {} is undefined or recursive", i)
+        }
+    }
+}
+
+/// Definition of a node of a tree as data, naming the kind of the node, 
+/// along with its parameters and the definitions of its children, from which 
+/// a node registry instantiates the tree. 
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeDef {
+    /// The kind of the node, under which its constructor is registered. 
+    pub kind: String,
+    /// The parameters of the node, by name. 
+    #[cfg_attr(feature = "serde", serde(default,
+        skip_serializing_if = "BTreeMap::is_empty"))]
+    pub params: BTreeMap<String, Value>,
+    /// The definitions of the children of the node, in order. 
+    #[cfg_attr(feature = "serde", serde(default,
+        skip_serializing_if = "Vec::is_empty"))]
+    pub children: Vec<NodeDef>
+}
+
+impl NodeDef {
+    /// Create a new definition of a node of the given kind, with no 
+    /// parameters or children. 
+    pub fn new<S>(kind: S) -> NodeDef where 
+        S: Into<String>
+    {
+        NodeDef {
+            kind: kind.into(),
+            params: BTreeMap::new(),
+            children: Vec::new()
+        }
+    }
+
+    /// Set the parameter with the given name. 
+    pub fn param<S, V>(self, name: S, value: V) -> NodeDef where 
+        S: Into<String>,
+        V: Into<Value>
+    {
+        let mut mut_self = self;
+        mut_self.params.insert(name.into(), value.into());
+        mut_self
+    }
+
+    /// Add a child to the end of the children. 
+    pub fn child(self, child: NodeDef) -> NodeDef {
+        let mut mut_self = self;
+        mut_self.children.push(child);
+        mut_self
+    }
+
+    fn invalid(&self, name: &str) -> LoadError {
+        LoadError::Param(self.kind.clone(), name.to_owned())
+    }
+
+    /// Get the integer parameter with the given name. 
+    pub fn int(&self, name: &str) -> Result<i64, LoadError> {
+        match self.params.get(name) {
+            Option::Some(&Value::Int(i)) => Result::Ok(i),
+            _ => Result::Err(self.invalid(name))
+        }
+    }
+
+    /// Get the number parameter with the given name, which may be written 
+    /// as an integer. 
+    pub fn float(&self, name: &str) -> Result<f64, LoadError> {
+        match self.params.get(name) {
+            Option::Some(&Value::Float(f)) => Result::Ok(f),
+            Option::Some(&Value::Int(i)) => Result::Ok(i as f64),
+            _ => Result::Err(self.invalid(name))
+        }
+    }
+
+    /// Get the boolean parameter with the given name. 
+    pub fn bool(&self, name: &str) -> Result<bool, LoadError> {
+        match self.params.get(name) {
+            Option::Some(&Value::Bool(b)) => Result::Ok(b),
+            _ => Result::Err(self.invalid(name))
+        }
+    }
+
+    /// Get the string parameter with the given name. 
+    pub fn str(&self, name: &str) -> Result<&str, LoadError> {
+        match self.params.get(name) {
+            Option::Some(&Value::Str(ref s)) => Result::Ok(s),
+            _ => Result::Err(self.invalid(name))
+        }
+    }
+
+    /// Get a hash of the structure and parameters of the tree defined, which 
+    /// is stable across builds, platforms and runs, so that definitions are 
+    /// cheaply compared between caches, reloads and peers. 
+    ///
+    /// Definitions that are equal have the same hash, except that a float 
+    /// parameter does not hash the same as an integer of the same value. 
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = StructuralHasher(FNV_OFFSET);
+        hasher.def(self);
+        hasher.0
+    }
+
+    /// Parse a definition from JSON. 
+    #[cfg(feature = "json")]
+    pub fn from_json(source: &str) -> Result<NodeDef, LoadError> {
+        ::serde_json::from_str(source)
+            .map_err(|e| LoadError::Parse(e.to_string()))
+    }
+
+    /// Write the definition as JSON. 
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        ::serde_json::to_string_pretty(self)
+            .expect("Definitions always serialize")
+    }
+
+    /// Parse a definition from YAML. 
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(source: &str) -> Result<NodeDef, LoadError> {
+        ::serde_yaml::from_str(source)
+            .map_err(|e| LoadError::Parse(e.to_string()))
+    }
+
+    /// Write the definition as YAML. 
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> String {
+        ::serde_yaml::to_string(self).expect("Definitions always serialize")
+    }
+
+    /// Parse a definition from RON. 
+    #[cfg(feature = "ron")]
+    pub fn from_ron(source: &str) -> Result<NodeDef, LoadError> {
+        ::ron::from_str(source).map_err(|e| LoadError::Parse(e.to_string()))
+    }
+
+    /// Write the definition as RON. 
+    #[cfg(feature = "ron")]
+    pub fn to_ron(&self) -> String {
+        ::ron::ser::to_string_pretty(self, ::ron::ser::PrettyConfig::new())
+            .expect("Definitions always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expression::Value;
+    use tree_def::{LoadError, NodeDef};
+
+    #[test]
+    fn node_def_test() {
+        let def = NodeDef::new("Wait").param("ticks", 3)
+            .param("label", "idle");
+        assert_eq!(def.int("ticks"), Result::Ok(3));
+        assert_eq!(def.float("ticks"), Result::Ok(3.0));
+        assert_eq!(def.str("label"), Result::Ok("idle"));
+        assert_eq!(def.bool("label"), Result::Err(LoadError::Param(
+            "Wait".to_owned(), "label".to_owned())));
+        assert_eq!(def.params.get("ticks"), Option::Some(&Value::Int(3)));
+    }
+
+    #[test]
+    fn structural_hash_test() {
+        let def = NodeDef::new("Sequence")
+            .child(NodeDef::new("Wait").param("ticks", 3).param("rate", 0.5))
+            .child(NodeDef::new("Say").param("text", "done"));
+        let same = NodeDef::new("Sequence")
+            .child(NodeDef::new("Wait").param("rate", 0.5).param("ticks", 3))
+            .child(NodeDef::new("Say").param("text", "done"));
+        assert_eq!(def.structural_hash(), same.structural_hash());
+        assert_eq!(NodeDef::new("Idle").structural_hash(),
+            0x0ca3_b221_78dc_3377);
+        let other = NodeDef::new("Sequence")
+            .child(NodeDef::new("Say").param("text", "done"))
+            .child(NodeDef::new("Wait").param("ticks", 3).param("rate", 0.5));
+        assert!(def.structural_hash() != other.structural_hash());
+        assert!(NodeDef::new("Wait").param("ticks", 3).structural_hash() != 
+            NodeDef::new("Wait").param("ticks", "3").structural_hash());
+        assert!(NodeDef::new("ab").child(NodeDef::new("c")).structural_hash() 
+            != NodeDef::new("a").child(NodeDef::new("bc")).structural_hash());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn node_def_json_test() {
+        let def = NodeDef::from_json(r#"{
+            "kind": "Sequence",
+            "children": [
+                {"kind": "Wait", "params": {"ticks": 2, "rate": 0.5}},
+                {"kind": "Say", "params": {"text": "done", "loud": true}}
+            ]
+        }"#).unwrap();
+        assert_eq!(def, NodeDef::new("Sequence")
+            .child(NodeDef::new("Wait").param("ticks", 2).param("rate", 0.5))
+            .child(NodeDef::new("Say").param("text", "done")
+                .param("loud", true)));
+        assert_eq!(NodeDef::from_json(&def.to_json()), Result::Ok(def));
+        assert!(NodeDef::from_json("{\"children\": []}").is_err());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn node_def_yaml_test() {
+        let def = NodeDef::from_yaml("
+kind: Selector
+children:
+  - kind: Wait
+    params:
+      ticks: 2
+  - kind: Idle
+").unwrap();
+        assert_eq!(def, NodeDef::new("Selector")
+            .child(NodeDef::new("Wait").param("ticks", 2))
+            .child(NodeDef::new("Idle")));
+        assert_eq!(NodeDef::from_yaml(&def.to_yaml()), Result::Ok(def));
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn node_def_ron_test() {
+        let def = NodeDef::from_ron(r#"(
+            kind: "Sequence",
+            children: [
+                (kind: "Wait", params: {"ticks": 2, "rate": 0.5}),
+                (kind: "Say", params: {"text": "done", "loud": true}),
+            ],
+        )"#).unwrap();
+        assert_eq!(def, NodeDef::new("Sequence")
+            .child(NodeDef::new("Wait").param("ticks", 2).param("rate", 0.5))
+            .child(NodeDef::new("Say").param("text", "done")
+                .param("loud", true)));
+        assert_eq!(NodeDef::from_ron(&def.to_ron()), Result::Ok(def));
+        assert!(NodeDef::from_ron("(children: [])").is_err());
+    }
+}
\ No newline at end of file
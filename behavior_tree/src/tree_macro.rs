@@ -0,0 +1,170 @@
+use behavior_tree_node::{BtStatus, Failure};
+use serial_node::NontermReturn;
+
+#[doc(hidden)]
+pub fn discard_nonterminal(_: NontermReturn<usize, (), Result<(), Failure>>) {}
+
+#[doc(hidden)]
+pub fn sequence_status(result: Result<(), (usize, Failure)>) -> BtStatus {
+    BtStatus::from(result)
+}
+
+#[doc(hidden)]
+pub fn selector_status(result: Result<(usize, ()), Failure>) -> BtStatus {
+    BtStatus::from(result)
+}
+
+/// Declarative macro for expressing a whole tree in a nested syntax. 
+///
+/// The tree expands to statically typed composites over tuples of nodes, 
+/// all of which have no nonterminals and a BtStatus terminal, so that small 
+/// trees need neither an enumeration of their nodes nor a decider. Each 
+/// node is one of: 
+///
+/// - `sequence { ... }` and `selector { ... }`, over up to 16 comma 
+///   separated children. 
+/// - `invert { ... }`, `force_success { ... }` and `force_failure { ... }`, 
+///   over exactly one child. 
+/// - `condition(f)`, a leaf which succeeds when the closure returns true 
+///   for the input, and fails otherwise. 
+/// - `action(f)`, a leaf stepping the closure, which returns a statepoint 
+///   with no nonterminal value and a BtStatus terminal. 
+/// - `node(n)`, any other node with no nonterminals and a BtStatus terminal. 
+///
+/// # Example 
+/// ```
+/// #[macro_use]
+/// extern crate stackbt_behavior_tree;
+/// use stackbt_behavior_tree::behavior_tree_node::{BehaviorTreeNode,
+///     BtStatus, NodeResult, Statepoint};
+///
+/// # fn main() {
+/// let tree = behavior_tree! {
+///     selector {
+///         condition(|i: &i64| *i > 10),
+///         sequence {
+///             invert { condition(|i: &i64| *i < 0) },
+///             action(|i: &i64| if *i == 0 {
+///                 Statepoint::Nonterminal(())
+///             } else {
+///                 Statepoint::Terminal(BtStatus::Success)
+///             })
+///         }
+///     }
+/// };
+/// let tree_1 = match tree.step(&3) {
+///     NodeResult::Nonterminal(_, n) => n,
+///     _ => unreachable!("Expected nonterminal transition")
+/// };
+/// let tree_2 = match tree_1.step(&3) {
+///     NodeResult::Nonterminal(_, n) => n,
+///     _ => unreachable!("Expected nonterminal transition")
+/// };
+/// match tree_2.step(&3) {
+///     NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Success),
+///     _ => unreachable!("Expected terminal transition")
+/// };
+/// # }
+/// ```
+#[macro_export]
+macro_rules! behavior_tree {
+    (sequence { $( $kind:ident $body:tt ),+ $(,)* }) => {
+        $crate::map_wrappers::OutputMappedNode::new(
+            $crate::tree_macro::discard_nonterminal,
+            $crate::tree_macro::sequence_status,
+            $crate::tuple_node::TupleSequence::new(( $(
+                $crate::status_wrappers::AsResult::new(
+                    behavior_tree!($kind $body)
+                ),
+            )+ ))
+        )
+    };
+
+    (selector { $( $kind:ident $body:tt ),+ $(,)* }) => {
+        $crate::map_wrappers::OutputMappedNode::new(
+            $crate::tree_macro::discard_nonterminal,
+            $crate::tree_macro::selector_status,
+            $crate::tuple_node::TupleSelector::new(( $(
+                $crate::status_wrappers::AsResult::new(
+                    behavior_tree!($kind $body)
+                ),
+            )+ ))
+        )
+    };
+
+    (invert { $kind:ident $body:tt $(,)* }) => {
+        $crate::status_wrappers::Inverter::new(behavior_tree!($kind $body))
+    };
+
+    (force_success { $kind:ident $body:tt $(,)* }) => {
+        $crate::status_wrappers::AsStatus::new(
+            $crate::status_wrappers::Succeeder::<_, ()>::new(
+                behavior_tree!($kind $body)
+            )
+        )
+    };
+
+    (force_failure { $kind:ident $body:tt $(,)* }) => {
+        $crate::status_wrappers::AsStatus::new(
+            $crate::status_wrappers::Failer::<_, ()>::new(
+                behavior_tree!($kind $body)
+            )
+        )
+    };
+
+    (condition ( $predicate:expr )) => {{
+        let predicate = $predicate;
+        $crate::base_nodes::PredicateWait::<_, (),
+            $crate::behavior_tree_node::BtStatus, _>::new(move |input| {
+            $crate::behavior_tree_node::Statepoint::Terminal(
+                $crate::behavior_tree_node::BtStatus::from(predicate(input))
+            )
+        })
+    }};
+
+    (action ( $closure:expr )) => {
+        $crate::base_nodes::PredicateWait::<_, (),
+            $crate::behavior_tree_node::BtStatus, _>::new($closure)
+    };
+
+    (node ( $node:expr )) => {
+        $node
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, BtStatus, NodeResult,
+        Statepoint};
+
+    #[test]
+    fn behavior_tree_macro_test() {
+        let tree = behavior_tree! {
+            sequence {
+                force_success { condition(|i: &i64| *i > 100) },
+                selector {
+                    condition(|i: &i64| *i < 0),
+                    node(PredicateWait::new(|i: &i64| if *i % 2 == 0 {
+                        Statepoint::Nonterminal(())
+                    } else {
+                        Statepoint::Terminal(BtStatus::Failure)
+                    }))
+                },
+                force_failure { action(|_: &i64| Statepoint::Nonterminal(())) }
+            }
+        };
+        let tree_1 = match tree.step(&4) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        let tree_2 = match tree_1.step(&4) {
+            NodeResult::Nonterminal(_, n) => n,
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match tree_2.step(&3) {
+            NodeResult::Terminal(t) => assert_eq!(t, BtStatus::Failure),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,661 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+use map_wrappers::InputMappedNode;
+use serial_node::NontermReturn;
+use parallel_join::JoinPolicy;
+use std::marker::PhantomData;
+#[cfg(feature = "serde")]
+use persist_state::{combined_version, PersistState};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+
+/// Trait for tuples of behavior tree nodes sharing the same input, 
+/// nonterminal and terminal types, which lets them be composed without 
+/// writing an enumerated node for them. 
+///
+/// It is implemented for tuples of up to 16 nodes. Nodes consuming some 
+/// other input can be paired with a projection of the shared input, and the 
+/// composites built from a ProjectedTuple of such pairs with their projected 
+/// constructors. 
+pub trait NodeTuple {
+    /// Type of the input of the nodes. 
+    type Input;
+    /// Type of the nonterminals of the nodes. 
+    type Nonterminal;
+    /// Type of the terminals of the nodes. 
+    type Terminal;
+    /// Tuple of optional nodes, holding the nodes which have not yet 
+    /// terminated. 
+    type Slots;
+    /// Number of nodes in the tuple. 
+    fn arity() -> usize;
+    /// Convert the tuple of nodes into a tuple of slots holding them. 
+    fn into_slots(self) -> Self::Slots;
+    /// Step the node at the given index, putting it back into its slot if 
+    /// it did not terminate. Returns Option::None if the slot is empty. 
+    fn step_slot(slots: &mut Self::Slots, index: usize, input: &Self::Input) ->
+        Option<Statepoint<Self::Nonterminal, Self::Terminal>>;
+    /// Take the node at the given index out of its slot and abort it. 
+    fn abort_slot(slots: &mut Self::Slots, index: usize);
+}
+
+/// Trait for children of tuple composites paired with a projection from 
+/// the input of the composite to the input of the child. 
+///
+/// It is implemented for pairs of a projection and a node. 
+pub trait ProjectedChild<I> {
+    /// Type of the child, consuming the input of the composite. 
+    type Node: BehaviorTreeNode<Input=I>;
+    /// Attach the projection to the child. 
+    fn into_node(self) -> Self::Node;
+}
+
+impl<I, P, N> ProjectedChild<I> for (P, N) where 
+    N: BehaviorTreeNode,
+    P: Fn(&I) -> N::Input
+{
+    type Node = InputMappedNode<N, P, I>;
+
+    fn into_node(self) -> InputMappedNode<N, P, I> {
+        InputMappedNode::new(self.0, self.1)
+    }
+}
+
+/// Trait for tuples of children paired with projections of a shared input, 
+/// which lets composites be built over children consuming different inputs. 
+///
+/// It is implemented for tuples of up to 16 projected children. 
+pub trait ProjectedTuple<I> {
+    /// Tuple of the children with their projections attached. 
+    type Nodes;
+    /// Attach the projections to the children. 
+    fn into_nodes(self) -> Self::Nodes;
+}
+
+fn step_option<C>(slot: &mut Option<C>, input: &C::Input) ->
+    Option<Statepoint<C::Nonterminal, C::Terminal>> where 
+    C: BehaviorTreeNode
+{
+    match slot.take() {
+        Option::Some(child) => match child.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                *slot = Option::Some(m);
+                Option::Some(Statepoint::Nonterminal(n))
+            },
+            NodeResult::Terminal(t) => Option::Some(Statepoint::Terminal(t))
+        },
+        Option::None => Option::None
+    }
+}
+
+fn abort_option<C>(slot: &mut Option<C>) where 
+    C: BehaviorTreeNode
+{
+    if let Option::Some(child) = slot.take() {
+        child.abort()
+    }
+}
+
+/// Trait for tuples of nodes which all externalize their state, letting 
+/// the tuple sequences and selectors over them do the same. 
+#[cfg(feature = "serde")]
+pub trait PersistTuple: NodeTuple {
+    /// Tuple of the optional states of the nodes, which are Option::None for 
+    /// the nodes which have terminated. 
+    type States: Serialize + DeserializeOwned;
+    /// Version of the layout of the states, combined from the versions of 
+    /// the nodes. 
+    const STATE_VERSION: u32;
+    /// Save the states of the nodes remaining in the slots. 
+    fn save_slots(slots: &Self::Slots) -> Self::States;
+    /// Restore previously saved states into the nodes in the slots, emptying 
+    /// the slots of nodes which had terminated. 
+    fn restore_slots(slots: &mut Self::Slots, states: Self::States);
+}
+
+#[cfg(feature = "serde")]
+fn restore_option<C>(slot: &mut Option<C>, state: Option<C::State>) where 
+    C: PersistState
+{
+    match state {
+        Option::Some(s) => if let Option::Some(ref mut child) = *slot {
+            child.restore_state(s)
+        },
+        Option::None => *slot = Option::None
+    }
+}
+
+macro_rules! node_tuple_impl {
+    ($arity:expr; $head:ident $hidx:tt $(, $name:ident $idx:tt)*) => {
+        impl<$head $(, $name)*> NodeTuple for ($head, $($name,)*) where 
+            $head: BehaviorTreeNode
+            $(, $name: BehaviorTreeNode<
+                Input=<$head as BehaviorTreeNode>::Input,
+                Nonterminal=<$head as BehaviorTreeNode>::Nonterminal,
+                Terminal=<$head as BehaviorTreeNode>::Terminal
+            >)*
+        {
+            type Input = $head::Input;
+            type Nonterminal = $head::Nonterminal;
+            type Terminal = $head::Terminal;
+            type Slots = (Option<$head>, $(Option<$name>,)*);
+
+            fn arity() -> usize {
+                $arity
+            }
+
+            fn into_slots(self) -> Self::Slots {
+                (Option::Some(self.$hidx), $(Option::Some(self.$idx),)*)
+            }
+
+            fn step_slot(slots: &mut Self::Slots, index: usize,
+                input: &Self::Input) -> Option<Statepoint<Self::Nonterminal,
+                Self::Terminal>>
+            {
+                match index {
+                    $hidx => step_option(&mut slots.$hidx, input),
+                    $($idx => step_option(&mut slots.$idx, input),)*
+                    _ => Option::None
+                }
+            }
+
+            fn abort_slot(slots: &mut Self::Slots, index: usize) {
+                match index {
+                    $hidx => abort_option(&mut slots.$hidx),
+                    $($idx => abort_option(&mut slots.$idx),)*
+                    _ => ()
+                }
+            }
+        }
+
+        impl<X, $head $(, $name)*> ProjectedTuple<X> for 
+            ($head, $($name,)*) where 
+            $head: ProjectedChild<X>
+            $(, $name: ProjectedChild<X>)*
+        {
+            type Nodes = (<$head as ProjectedChild<X>>::Node, 
+                $(<$name as ProjectedChild<X>>::Node,)*);
+
+            fn into_nodes(self) -> Self::Nodes {
+                (self.$hidx.into_node(), $(self.$idx.into_node(),)*)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<$head $(, $name)*> PersistTuple for ($head, $($name,)*) where 
+            $head: PersistState
+            $(, $name: PersistState<
+                Input=<$head as BehaviorTreeNode>::Input,
+                Nonterminal=<$head as BehaviorTreeNode>::Nonterminal,
+                Terminal=<$head as BehaviorTreeNode>::Terminal
+            >)*
+        {
+            type States = (Option<<$head as PersistState>::State>, 
+                $(Option<<$name as PersistState>::State>,)*);
+
+            const STATE_VERSION: u32 = combined_version(&[
+                <$head as PersistState>::STATE_VERSION
+                $(, <$name as PersistState>::STATE_VERSION)*
+            ]);
+
+            fn save_slots(slots: &Self::Slots) -> Self::States {
+                (slots.$hidx.as_ref().map(PersistState::save_state), 
+                    $(slots.$idx.as_ref().map(PersistState::save_state),)*)
+            }
+
+            fn restore_slots(slots: &mut Self::Slots, states: Self::States) {
+                restore_option(&mut slots.$hidx, states.$hidx);
+                $(restore_option(&mut slots.$idx, states.$idx);)*
+            }
+        }
+    };
+}
+
+node_tuple_impl!(1; A 0);
+node_tuple_impl!(2; A 0, B 1);
+node_tuple_impl!(3; A 0, B 1, C 2);
+node_tuple_impl!(4; A 0, B 1, C 2, D 3);
+node_tuple_impl!(5; A 0, B 1, C 2, D 3, E 4);
+node_tuple_impl!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+node_tuple_impl!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+node_tuple_impl!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+node_tuple_impl!(9; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+node_tuple_impl!(10; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+node_tuple_impl!(11; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+node_tuple_impl!(12; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10,
+    L 11);
+node_tuple_impl!(13; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10,
+    L 11, M 12);
+node_tuple_impl!(14; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10,
+    L 11, M 12, N 13);
+node_tuple_impl!(15; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10,
+    L 11, M 12, N 13, O 14);
+node_tuple_impl!(16; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10,
+    L 11, M 12, N 13, O 14, P 15);
+
+/// Sequence node over a tuple of nodes, run in tuple order. 
+///
+/// Like SequenceNode, it moves on to the next node whenever the current one 
+/// terminates with a Result::Ok, exiting with the success value of the last 
+/// node, and exits as soon as a node terminates with a Result::Err, along 
+/// with the index of that node. 
+pub struct TupleSequence<U, T, F> where 
+    U: NodeTuple<Terminal=Result<T, F>>
+{
+    slots: U::Slots,
+    index: usize,
+    _junk: PhantomData<(T, F)>
+}
+
+impl<U, T, F> TupleSequence<U, T, F> where 
+    U: NodeTuple<Terminal=Result<T, F>>
+{
+    /// Create a new sequence over the given tuple of nodes. 
+    pub fn new(nodes: U) -> TupleSequence<U, T, F> {
+        TupleSequence {
+            slots: nodes.into_slots(),
+            index: 0,
+            _junk: PhantomData
+        }
+    }
+
+    /// Create a new sequence over the given tuple of nodes paired with 
+    /// projections of the input of the sequence. 
+    pub fn projected<Q>(children: Q) -> TupleSequence<U, T, F> where 
+        Q: ProjectedTuple<U::Input, Nodes=U>
+    {
+        TupleSequence::new(children.into_nodes())
+    }
+}
+
+impl<U, T, F> BehaviorTreeNode for TupleSequence<U, T, F> where 
+    U: NodeTuple<Terminal=Result<T, F>>
+{
+    type Input = U::Input;
+    type Nonterminal = NontermReturn<usize, U::Nonterminal, Result<T, F>>;
+    type Terminal = Result<T, (usize, F)>;
+
+    #[inline]
+    fn step(self, input: &U::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut slots = self.slots;
+        let index = self.index;
+        match U::step_slot(&mut slots, index, input).unwrap() {
+            Statepoint::Nonterminal(n) => NodeResult::Nonterminal(
+                NontermReturn::Nonterminal(index, n),
+                TupleSequence {
+                    slots: slots,
+                    index: index,
+                    _junk: PhantomData
+                }
+            ),
+            Statepoint::Terminal(Result::Ok(t)) => if index + 1 < U::arity() {
+                NodeResult::Nonterminal(
+                    NontermReturn::Terminal(index, Result::Ok(t)),
+                    TupleSequence {
+                        slots: slots,
+                        index: index + 1,
+                        _junk: PhantomData
+                    }
+                )
+            } else {
+                NodeResult::Terminal(Result::Ok(t))
+            },
+            Statepoint::Terminal(Result::Err(f)) => {
+                NodeResult::Terminal(Result::Err((index, f)))
+            }
+        }
+    }
+
+    fn abort(self) {
+        let mut slots = self.slots;
+        U::abort_slot(&mut slots, self.index)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<U, T, F> PersistState for TupleSequence<U, T, F> where 
+    U: PersistTuple<Terminal=Result<T, F>>
+{
+    type State = (usize, U::States);
+
+    const STATE_VERSION: u32 = U::STATE_VERSION;
+
+    fn save_state(&self) -> (usize, U::States) {
+        (self.index, U::save_slots(&self.slots))
+    }
+
+    fn restore_state(&mut self, state: (usize, U::States)) {
+        self.index = state.0;
+        U::restore_slots(&mut self.slots, state.1)
+    }
+}
+
+/// Selector node over a tuple of nodes, tried in tuple order. 
+///
+/// Like SelectorNode, it moves on to the next node whenever the current one 
+/// terminates with a Result::Err, exiting with the failure value of the last 
+/// node, and exits as soon as a node terminates with a Result::Ok, along 
+/// with the index of that node. 
+pub struct TupleSelector<U, T, F> where 
+    U: NodeTuple<Terminal=Result<T, F>>
+{
+    slots: U::Slots,
+    index: usize,
+    _junk: PhantomData<(T, F)>
+}
+
+impl<U, T, F> TupleSelector<U, T, F> where 
+    U: NodeTuple<Terminal=Result<T, F>>
+{
+    /// Create a new selector over the given tuple of nodes. 
+    pub fn new(nodes: U) -> TupleSelector<U, T, F> {
+        TupleSelector {
+            slots: nodes.into_slots(),
+            index: 0,
+            _junk: PhantomData
+        }
+    }
+
+    /// Create a new selector over the given tuple of nodes paired with 
+    /// projections of the input of the selector. 
+    pub fn projected<Q>(children: Q) -> TupleSelector<U, T, F> where 
+        Q: ProjectedTuple<U::Input, Nodes=U>
+    {
+        TupleSelector::new(children.into_nodes())
+    }
+}
+
+impl<U, T, F> BehaviorTreeNode for TupleSelector<U, T, F> where 
+    U: NodeTuple<Terminal=Result<T, F>>
+{
+    type Input = U::Input;
+    type Nonterminal = NontermReturn<usize, U::Nonterminal, Result<T, F>>;
+    type Terminal = Result<(usize, T), F>;
+
+    #[inline]
+    fn step(self, input: &U::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let mut slots = self.slots;
+        let index = self.index;
+        match U::step_slot(&mut slots, index, input).unwrap() {
+            Statepoint::Nonterminal(n) => NodeResult::Nonterminal(
+                NontermReturn::Nonterminal(index, n),
+                TupleSelector {
+                    slots: slots,
+                    index: index,
+                    _junk: PhantomData
+                }
+            ),
+            Statepoint::Terminal(Result::Ok(t)) => {
+                NodeResult::Terminal(Result::Ok((index, t)))
+            },
+            Statepoint::Terminal(Result::Err(f)) => if index + 1 < U::arity() {
+                NodeResult::Nonterminal(
+                    NontermReturn::Terminal(index, Result::Err(f)),
+                    TupleSelector {
+                        slots: slots,
+                        index: index + 1,
+                        _junk: PhantomData
+                    }
+                )
+            } else {
+                NodeResult::Terminal(Result::Err(f))
+            }
+        }
+    }
+
+    fn abort(self) {
+        let mut slots = self.slots;
+        U::abort_slot(&mut slots, self.index)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<U, T, F> PersistState for TupleSelector<U, T, F> where 
+    U: PersistTuple<Terminal=Result<T, F>>
+{
+    type State = (usize, U::States);
+
+    const STATE_VERSION: u32 = U::STATE_VERSION;
+
+    fn save_state(&self) -> (usize, U::States) {
+        (self.index, U::save_slots(&self.slots))
+    }
+
+    fn restore_state(&mut self, state: (usize, U::States)) {
+        self.index = state.0;
+        U::restore_slots(&mut self.slots, state.1)
+    }
+}
+
+/// Parallel node over a tuple of nodes, which steps all of its running 
+/// nodes each step, and is joined by a JoinPolicy in the same manner as 
+/// ParallelJoinNode. 
+pub struct TupleParallel<U, P> where 
+    U: NodeTuple<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    slots: U::Slots,
+    finished: Box<[Option<P::Term>]>,
+    policy: P
+}
+
+impl<U, P> TupleParallel<U, P> where 
+    U: NodeTuple<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    /// Create a new parallel node over the given tuple of nodes. 
+    pub fn new(policy: P, nodes: U) -> TupleParallel<U, P> {
+        let finished = (0..U::arity())
+            .map(|_| Option::None)
+            .collect::<Vec<_>>();
+        TupleParallel {
+            slots: nodes.into_slots(),
+            finished: finished.into_boxed_slice(),
+            policy: policy
+        }
+    }
+
+    /// Create a new parallel node over the given tuple of nodes paired with 
+    /// projections of the input of the policy. 
+    pub fn projected<Q>(policy: P, children: Q) -> TupleParallel<U, P> where 
+        Q: ProjectedTuple<P::Input, Nodes=U>
+    {
+        TupleParallel::new(policy, children.into_nodes())
+    }
+}
+
+impl<U, P> BehaviorTreeNode for TupleParallel<U, P> where 
+    U: NodeTuple<Input=P::Input, Terminal=P::Term>,
+    P: JoinPolicy
+{
+    type Input = P::Input;
+    type Nonterminal = Box<[Option<U::Nonterminal>]>;
+    type Terminal = P::Exit;
+
+    #[inline]
+    fn step(self, input: &P::Input) -> NodeResult<Self::Nonterminal, P::Exit,
+        Self>
+    {
+        let mut slots = self.slots;
+        let mut finished = self.finished;
+        let nonterms = (0..U::arity())
+            .map(|i| match U::step_slot(&mut slots, i, input) {
+                Option::Some(Statepoint::Nonterminal(n)) => Option::Some(n),
+                Option::Some(Statepoint::Terminal(t)) => {
+                    finished[i] = Option::Some(t);
+                    Option::None
+                },
+                Option::None => Option::None
+            })
+            .collect::<Vec<_>>();
+        match self.policy.join(input, finished) {
+            Statepoint::Nonterminal(f) => NodeResult::Nonterminal(
+                nonterms.into_boxed_slice(),
+                TupleParallel {
+                    slots: slots,
+                    finished: f,
+                    policy: self.policy
+                }
+            ),
+            Statepoint::Terminal(x) => {
+                for i in 0..U::arity() {
+                    U::abort_slot(&mut slots, i);
+                }
+                NodeResult::Terminal(x)
+            }
+        }
+    }
+
+    fn abort(self) {
+        let mut slots = self.slots;
+        for i in 0..U::arity() {
+            U::abort_slot(&mut slots, i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::NontermReturn;
+
+    fn check_now(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input >= 0 {
+            Statepoint::Terminal(Result::Ok(*input))
+        } else {
+            Statepoint::Terminal(Result::Err(*input))
+        }
+    }
+
+    fn wait_nonzero(input: &i64) -> Statepoint<i64, Result<i64, i64>> {
+        if *input == 0 {
+            Statepoint::Nonterminal(0)
+        } else {
+            check_now(input)
+        }
+    }
+
+    #[test]
+    fn tuple_sequence_test() {
+        use tuple_node::TupleSequence;
+        let test_node = TupleSequence::new((
+            PredicateWait::new(check_now),
+            PredicateWait::new(|input: &i64| wait_nonzero(input))
+        ));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(i, v), n) => {
+                assert_eq!(i, 0);
+                assert_eq!(v, Result::Ok(0));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&0) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(i, _), n) => {
+                assert_eq!(i, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        match test_node_2.step(&-5) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err((1, -5))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn tuple_selector_test() {
+        use tuple_node::TupleSelector;
+        let test_node = TupleSelector::new((
+            PredicateWait::new(check_now),
+            PredicateWait::new(check_now),
+            PredicateWait::new(|_: &i64| Statepoint::Terminal(Result::Ok(9)))
+        ));
+        let test_node_1 = match test_node.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(i, _), n) => {
+                assert_eq!(i, 0);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        let test_node_2 = match test_node_1.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(i, _), n) => {
+                assert_eq!(i, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        match test_node_2.step(&-1) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Ok((2, 9))),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+
+    #[test]
+    fn tuple_projected_test() {
+        use tuple_node::{TupleParallel, TupleSequence};
+        use parallel_join::ThresholdPolicy;
+        let test_node = TupleSequence::projected((
+            (|i: &(i64, bool)| i.0, PredicateWait::new(check_now)),
+            (|i: &(i64, bool)| i.1, PredicateWait::new(
+                |i: &bool| Statepoint::Terminal(if *i {
+                    Result::Ok(1)
+                } else {
+                    Result::Err(0)
+                })
+            ))
+        ));
+        let test_node_1 = match test_node.step(&(2, false)) {
+            NodeResult::Nonterminal(NontermReturn::Terminal(0, v), n) => {
+                assert_eq!(v, Result::Ok(2));
+                n
+            },
+            _ => unreachable!("Expected subordinate terminal transition")
+        };
+        match test_node_1.step(&(-1, false)) {
+            NodeResult::Terminal(t) => assert_eq!(t, Result::Err((1, 0))),
+            _ => unreachable!("Expected terminal transition")
+        };
+        let test_node = TupleParallel::projected(ThresholdPolicy::new(2, 0), (
+            (|i: &(i64, i64)| i.0, PredicateWait::new(check_now)),
+            (|i: &(i64, i64)| i.1, PredicateWait::new(wait_nonzero))
+        ));
+        match test_node.step(&(4, 5)) {
+            NodeResult::Terminal(Result::Ok(v)) => {
+                assert_eq!(&*v, &[Option::Some(Result::Ok(4)),
+                    Option::Some(Result::Ok(5))]);
+            },
+            _ => unreachable!("Expected successful terminal transition")
+        };
+    }
+
+    #[test]
+    fn tuple_parallel_test() {
+        use tuple_node::TupleParallel;
+        use parallel_join::ThresholdPolicy;
+        let test_node = TupleParallel::new(ThresholdPolicy::new(2, 0), (
+            PredicateWait::new(check_now),
+            PredicateWait::new(|input: &i64| wait_nonzero(input))
+        ));
+        let test_node_1 = match test_node.step(&0) {
+            NodeResult::Nonterminal(v, n) => {
+                assert_eq!(&*v, &[Option::None, Option::Some(0)]);
+                n
+            },
+            _ => unreachable!("Expected nonterminal transition")
+        };
+        match test_node_1.step(&3) {
+            NodeResult::Terminal(Result::Ok(v)) => {
+                assert_eq!(&*v, &[Option::Some(Result::Ok(0)),
+                    Option::Some(Result::Ok(3))]);
+            },
+            _ => unreachable!("Expected successful terminal transition")
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,242 @@
+use behavior_tree_node::{BehaviorTreeNode, NodeResult};
+use serial_node::{EnumNode, NontermReturn};
+use num_traits::{FromPrimitive, ToPrimitive};
+
+/// Trait for the scores by which a utility selector picks its running node. 
+pub trait UtilityScorer {
+    /// Type of the enumerating discriminant. 
+    type Enum;
+    /// Type of the inputs of the subnodes. 
+    type Input;
+    /// Given a reference to the input and the discriminant of a subnode, 
+    /// return the utility of running that subnode. 
+    fn score(&self, input: &Self::Input, ord: Self::Enum) -> f64;
+}
+
+/// Node which, each step, scores all of its nodes with a UtilityScorer, and 
+/// runs the one with the highest score, switching to it if needed. 
+///
+/// To prevent thrashing between nodes with similar scores, a node only 
+/// replaces the running node if its score exceeds that of the running node 
+/// by more than the hysteresis margin. The replaced node is aborted, and 
+/// ties are broken in favor of the earlier discriminant. The selector exits 
+/// with the discriminant and terminal of whichever node terminates. 
+#[derive(Clone, PartialEq, Debug)]
+pub struct UtilitySelector<E, S> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    S: UtilityScorer<Enum=E::Discriminant, Input=E::Input>
+{
+    node: Option<E>,
+    scorer: S,
+    hysteresis: f64
+}
+
+impl<E, S> UtilitySelector<E, S> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    S: UtilityScorer<Enum=E::Discriminant, Input=E::Input>
+{
+    /// Create a new utility selector, which switches nodes whenever another 
+    /// node scores higher than the running one. 
+    pub fn new(scorer: S) -> UtilitySelector<E, S> {
+        UtilitySelector::with_hysteresis(scorer, 0.0)
+    }
+
+    /// Create a new utility selector, which only switches nodes when 
+    /// another node outscores the running one by more than the given 
+    /// margin. 
+    pub fn with_hysteresis(scorer: S, hysteresis: f64) -> UtilitySelector<E, S> {
+        UtilitySelector {
+            node: Option::None,
+            scorer: scorer,
+            hysteresis: hysteresis
+        }
+    }
+
+    fn best(&self, input: &E::Input) -> (E::Discriminant, f64) {
+        let mut index = 0;
+        let mut best = Option::None;
+        while let Option::Some(e) = E::Discriminant::from_u64(index) {
+            let score = self.scorer.score(input, e);
+            best = match best {
+                Option::Some((b, s)) if s >= score => Option::Some((b, s)),
+                _ => Option::Some((e, score))
+            };
+            index += 1;
+        }
+        best.unwrap()
+    }
+}
+
+impl<E, S> BehaviorTreeNode for UtilitySelector<E, S> where 
+    E: EnumNode,
+    E::Discriminant: FromPrimitive + ToPrimitive,
+    S: UtilityScorer<Enum=E::Discriminant, Input=E::Input>
+{
+    type Input = E::Input;
+    type Nonterminal = NontermReturn<E::Discriminant, E::Nonterminal, E::Terminal>;
+    type Terminal = (E::Discriminant, E::Terminal);
+
+    #[inline]
+    fn step(self, input: &E::Input) -> NodeResult<Self::Nonterminal,
+        Self::Terminal, Self>
+    {
+        let (best, best_score) = self.best(input);
+        let mut this = self;
+        let node = match this.node.take() {
+            Option::Some(n) => {
+                let current = n.discriminant_of();
+                let current_score = this.scorer.score(input, current);
+                if current.to_u64() != best.to_u64() &&
+                    best_score > current_score + this.hysteresis
+                {
+                    n.abort();
+                    E::new(best)
+                } else {
+                    n
+                }
+            },
+            Option::None => E::new(best)
+        };
+        let discriminant = node.discriminant_of();
+        match node.step(input) {
+            NodeResult::Nonterminal(n, m) => {
+                this.node = Option::Some(m);
+                NodeResult::Nonterminal(
+                    NontermReturn::Nonterminal(discriminant, n),
+                    this
+                )
+            },
+            NodeResult::Terminal(t) => NodeResult::Terminal((discriminant, t))
+        }
+    }
+
+    fn abort(self) {
+        if let Option::Some(n) = self.node {
+            n.abort()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_nodes::PredicateWait;
+    use behavior_tree_node::{BehaviorTreeNode, NodeResult, Statepoint};
+    use serial_node::{EnumNode, NontermReturn};
+    use utility_selector::UtilityScorer;
+    use num_derive::{FromPrimitive, ToPrimitive};
+
+    fn hold(input: &i64) -> Statepoint<i64, i64> {
+        if *input != 0 {
+            Statepoint::Nonterminal(*input)
+        } else {
+            Statepoint::Terminal(*input)
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, Eq, Debug, FromPrimitive, ToPrimitive)]
+    enum DirectionEnum {
+        East,
+        West
+    }
+
+    enum Directions {
+        East(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>),
+        West(PredicateWait<i64, i64, i64, fn(&i64) -> Statepoint<i64, i64>>)
+    }
+
+    impl BehaviorTreeNode for Directions {
+        type Input = i64;
+        type Nonterminal = i64;
+        type Terminal = i64;
+
+        fn step(self, input: &i64) -> NodeResult<i64, i64, Self> {
+            match self {
+                Directions::East(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Directions::East(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                },
+                Directions::West(n) => match n.step(input) {
+                    NodeResult::Nonterminal(r, m) => NodeResult::Nonterminal(
+                        r,
+                        Directions::West(m)
+                    ),
+                    NodeResult::Terminal(t) => NodeResult::Terminal(t)
+                }
+            }
+        }
+    }
+
+    impl EnumNode for Directions {
+        type Discriminant = DirectionEnum;
+
+        fn new(thing: DirectionEnum) -> Directions {
+            match thing {
+                DirectionEnum::East => Directions::East(PredicateWait::new(hold)),
+                DirectionEnum::West => Directions::West(PredicateWait::new(hold))
+            }
+        }
+
+        fn discriminant_of(&self) -> DirectionEnum {
+            match self {
+                Directions::East(_) => DirectionEnum::East,
+                Directions::West(_) => DirectionEnum::West
+            }
+        }
+    }
+
+    struct Heading;
+
+    impl UtilityScorer for Heading {
+        type Enum = DirectionEnum;
+        type Input = i64;
+
+        fn score(&self, input: &i64, ord: DirectionEnum) -> f64 {
+            match ord {
+                DirectionEnum::East => *input as f64,
+                DirectionEnum::West => -*input as f64
+            }
+        }
+    }
+
+    #[test]
+    fn utility_selector_test() {
+        use utility_selector::UtilitySelector;
+        let test_node = UtilitySelector::<Directions, _>::with_hysteresis(
+            Heading,
+            2.0
+        );
+        let test_node_1 = match test_node.step(&1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, DirectionEnum::East);
+                assert_eq!(v, 1);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        // West now scores higher, but not by more than the hysteresis
+        let test_node_2 = match test_node_1.step(&-1) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, _), n) => {
+                assert_eq!(e, DirectionEnum::East);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        let test_node_3 = match test_node_2.step(&-3) {
+            NodeResult::Nonterminal(NontermReturn::Nonterminal(e, v), n) => {
+                assert_eq!(e, DirectionEnum::West);
+                assert_eq!(v, -3);
+                n
+            },
+            _ => unreachable!("Expected subordinate nonterminal transition")
+        };
+        match test_node_3.step(&0) {
+            NodeResult::Terminal(t) => assert_eq!(t, (DirectionEnum::West, 0)),
+            _ => unreachable!("Expected terminal transition")
+        };
+    }
+}
\ No newline at end of file
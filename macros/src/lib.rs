@@ -1,4 +1,157 @@
-extern crate stackbt_behavior_tree;
-extern crate num_derive;
-extern crate num_traits;
+//! Procedural macros for StackBT. 
 
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Error, Expr, Fields, Ident,
+    ItemEnum, Type};
+
+/// Attribute macro for declaring an enumeration of nodes, for use with the 
+/// serial branch node and the other supernodes over an `EnumNode`. 
+///
+/// The attribute is put on a normal enum whose variants each hold one node, 
+/// all of which have the same input, nonterminals and terminals. It takes 
+/// the name of the discriminant enum to declare alongside, and each variant 
+/// may have an `#[init(...)]` attribute with the expression its node is 
+/// initialized with, defaulting to `Default::default()`. The macro then 
+/// implements `BehaviorTreeNode` and `EnumNode` for the enum. 
+///
+/// As the discriminant enum derives `FromPrimitive` and `ToPrimitive`, the 
+/// crate using the macro has to depend on `num-derive` and `num-traits`. 
+///
+/// # Example 
+/// ```ignore
+/// #[enum_node(PosNegEnum)]
+/// enum MultiMachine {
+///     #[init(PredicateWait::new(|i: &i64| Statepoint::Terminal(*i)))]
+///     Positive(PredicateWait<i64, (), i64, fn(&i64) -> Statepoint<(), i64>>),
+///     #[init(PredicateWait::new(|i: &i64| Statepoint::Terminal(-*i)))]
+///     Negative(PredicateWait<i64, (), i64, fn(&i64) -> Statepoint<(), i64>>)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn enum_node(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let discriminant = parse_macro_input!(attr as Ident);
+    let item = parse_macro_input!(item as ItemEnum);
+    match expand(discriminant, item) {
+        Result::Ok(t) => t.into(),
+        Result::Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn is_init(attr: &Attribute) -> bool {
+    attr.path().is_ident("init")
+}
+
+fn expand(discriminant: Ident, item: ItemEnum) ->
+    Result<proc_macro2::TokenStream, Error>
+{
+    let mut item = item;
+    let mut variants = Vec::new();
+    let mut types: Vec<Type> = Vec::new();
+    let mut inits = Vec::new();
+    for variant in item.variants.iter_mut() {
+        let ty = match variant.fields {
+            Fields::Unnamed(ref f) if f.unnamed.len() == 1 => {
+                f.unnamed[0].ty.clone()
+            },
+            _ => return Result::Err(Error::new_spanned(&variant.ident,
+                "Each variant must hold exactly one node"))
+        };
+        let init = match variant.attrs.iter().find(|a| is_init(a)) {
+            Option::Some(a) => a.parse_args::<Expr>()?,
+            Option::None => syn::parse_quote!(Default::default())
+        };
+        variant.attrs.retain(|a| !is_init(a));
+        variants.push(variant.ident.clone());
+        types.push(ty);
+        inits.push(init);
+    }
+    if variants.is_empty() {
+        return Result::Err(Error::new(Span::call_site(),
+            "The enum must have at least one variant"));
+    }
+
+    let node = quote!(::stackbt_behavior_tree::behavior_tree_node
+        ::BehaviorTreeNode);
+    let result = quote!(::stackbt_behavior_tree::behavior_tree_node
+        ::NodeResult);
+    let enum_node = quote!(::stackbt_behavior_tree::serial_node::EnumNode);
+    let name = &item.ident;
+    let vis = &item.vis;
+    let first = &types[0];
+    let rest = &types[1..];
+    let (impl_generics, ty_generics, where_clause) =
+        item.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned()
+        .unwrap_or_else(|| syn::parse_quote!(where));
+    if !item.generics.params.is_empty() {
+        // The nodes of a generic enum are only known to agree through bounds
+        where_clause.predicates.push(syn::parse_quote!(#first: #node));
+        for ty in rest.iter().filter(|t| *t != first) {
+            where_clause.predicates.push(syn::parse_quote!(#ty: #node<
+                Input = <#first as #node>::Input,
+                Nonterminal = <#first as #node>::Nonterminal,
+                Terminal = <#first as #node>::Terminal
+            >));
+        }
+    }
+
+    Result::Ok(quote! {
+        #item
+
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        #[derive(::num_derive::ToPrimitive, ::num_derive::FromPrimitive)]
+        #vis enum #discriminant {
+            #( #variants ),*
+        }
+
+        impl #impl_generics #node for #name #ty_generics #where_clause {
+            type Input = <#first as #node>::Input;
+            type Nonterminal = <#first as #node>::Nonterminal;
+            type Terminal = <#first as #node>::Terminal;
+
+            fn step(self, input: &Self::Input) ->
+                #result<Self::Nonterminal, Self::Terminal, Self>
+            {
+                match self {
+                    #(
+                        #name::#variants(val) => match #node::step(val, input) {
+                            #result::Nonterminal(v, o) => {
+                                #result::Nonterminal(v, #name::#variants(o))
+                            },
+                            #result::Terminal(v) => #result::Terminal(v)
+                        }
+                    ),*
+                }
+            }
+
+            fn abort(self) {
+                match self {
+                    #( #name::#variants(val) => #node::abort(val) ),*
+                }
+            }
+        }
+
+        impl #impl_generics #enum_node for #name #ty_generics #where_clause {
+            type Discriminant = #discriminant;
+
+            fn new(discriminant: #discriminant) -> Self {
+                match discriminant {
+                    #( #discriminant::#variants => #name::#variants(#inits) ),*
+                }
+            }
+
+            fn discriminant_of(&self) -> #discriminant {
+                match *self {
+                    #( #name::#variants(_) => #discriminant::#variants ),*
+                }
+            }
+        }
+    })
+}